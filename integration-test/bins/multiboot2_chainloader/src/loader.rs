@@ -2,7 +2,7 @@ use alloc::boxed::Box;
 use elf_rs::{ElfFile, ProgramHeaderEntry, ProgramType};
 use multiboot2::{
     BootLoaderNameTag, CommandLineTag, MaybeDynSized, MemoryArea, MemoryAreaType, MemoryMapTag,
-    ModuleTag, SmbiosTag,
+    SmbiosTag,
 };
 
 /// Loads the first module into memory. Assumes that the module is a ELF file.
@@ -52,17 +52,17 @@ pub fn load_module(mut modules: multiboot::information::ModuleIter) -> ! {
             0xffffffff,
             MemoryAreaType::Reserved,
         )]))
-        .add_module(ModuleTag::new(
+        .add_module(
             elf_mod.start as u32,
             elf_mod.end as u32,
             elf_mod.string.unwrap(),
-        ))
+        )
         // Test that we can add SmbiosTag multiple times.
         .add_smbios(SmbiosTag::new(1, 1, &[1, 2, 3]))
         .add_smbios(SmbiosTag::new(2, 3, &[4, 5, 6]))
         .build();
 
-    let mbi = Box::leak(mbi);
+    let mbi = Box::leak(mbi.into_boxed());
 
     log::info!(
         "Handing over to ELF: {}",
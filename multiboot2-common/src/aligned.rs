@@ -0,0 +1,58 @@
+//! Module for [`AlignedBuffer`].
+
+use crate::ALIGNMENT;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::Deref;
+use core::slice;
+
+/// Copy of arbitrary bytes in a heap buffer that is aligned to [`ALIGNMENT`],
+/// e.g., of a kernel image read from a file, so that the Multiboot2
+/// structures in it can be referenced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlignedBuffer {
+    words: Box<[u64]>,
+    len: usize,
+}
+
+impl AlignedBuffer {
+    /// Copies the bytes into a new aligned buffer.
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let words = bytes
+            .chunks(ALIGNMENT)
+            .map(|chunk| {
+                let mut word = [0; ALIGNMENT];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_ne_bytes(word)
+            })
+            .collect::<Vec<_>>();
+        Self {
+            words: words.into_boxed_slice(),
+            len: bytes.len(),
+        }
+    }
+}
+
+impl Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The words cover at least `len` initialized bytes.
+        unsafe { slice::from_raw_parts(self.words.as_ptr().cast(), self.len) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aligned_buffer() {
+        let bytes = [0_u8, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let buffer = AlignedBuffer::from_bytes(&bytes[1..]);
+        assert_eq!(buffer.as_ptr().align_offset(ALIGNMENT), 0);
+        assert_eq!(&*buffer, &bytes[1..]);
+        assert!(AlignedBuffer::from_bytes(&[]).is_empty());
+    }
+}
@@ -1,11 +1,10 @@
 //! Module for [`new_boxed`].
 
-use crate::{increase_to_alignment, Header, MaybeDynSized, ALIGNMENT};
+use crate::new_in::{prepare_layout, write_dst};
+use crate::{Header, MaybeDynSized};
 use alloc::boxed::Box;
-use core::alloc::Layout;
 use core::mem;
 use core::ops::Deref;
-use core::ptr;
 
 /// Creates a new tag implementing [`MaybeDynSized`] on the heap.
 ///
@@ -23,66 +22,45 @@ use core::ptr;
 ///   for [`Header`], but only additional payload.
 #[must_use]
 pub fn new_boxed<T: MaybeDynSized<Metadata = usize> + ?Sized>(
-    mut header: T::Header,
+    header: T::Header,
     additional_bytes_slices: &[&[u8]],
 ) -> Box<T> {
-    let additional_size = additional_bytes_slices
-        .iter()
-        .map(|b| b.len())
-        .sum::<usize>();
-
-    let tag_size = mem::size_of::<T::Header>() + additional_size;
-    header.set_size(tag_size);
+    try_new_boxed(header, additional_bytes_slices).expect("allocation should succeed")
+}
 
-    // Allocation size is multiple of alignment.
-    // See <https://doc.rust-lang.org/reference/type-layout.html>
-    let alloc_size = increase_to_alignment(tag_size);
-    let layout = Layout::from_size_align(alloc_size, ALIGNMENT).unwrap();
+/// Like [`new_boxed`], but returns `None` instead of panicking if the size
+/// overflows or the allocation fails.
+#[must_use]
+pub fn try_new_boxed<T: MaybeDynSized<Metadata = usize> + ?Sized>(
+    mut header: T::Header,
+    additional_bytes_slices: &[&[u8]],
+) -> Option<Box<T>> {
+    let layout = prepare_layout::<T>(&mut header, additional_bytes_slices)?;
     let heap_ptr = unsafe { alloc::alloc::alloc(layout) };
-    assert!(!heap_ptr.is_null());
-
-    // write header
-    {
-        let len = mem::size_of::<T::Header>();
-        let ptr = core::ptr::addr_of!(header);
-        unsafe {
-            ptr::copy_nonoverlapping(ptr.cast::<u8>(), heap_ptr, len);
-        }
-    }
-
-    // write body
-    {
-        let mut write_offset = mem::size_of::<T::Header>();
-        for &bytes in additional_bytes_slices {
-            let len = bytes.len();
-            let src = bytes.as_ptr();
-            unsafe {
-                let dst = heap_ptr.add(write_offset);
-                ptr::copy_nonoverlapping(src, dst, len);
-                write_offset += len;
-            }
-        }
+    if heap_ptr.is_null() {
+        return None;
     }
 
-    // This is a fat pointer for DSTs and a thin pointer for sized `T`s.
-    let ptr: *mut T = ptr_meta::from_raw_parts_mut(heap_ptr.cast(), T::dst_len(&header));
+    let ptr = unsafe { write_dst::<T>(&header, additional_bytes_slices, layout, heap_ptr) };
     let reference = unsafe { Box::from_raw(ptr) };
 
     // If this panic triggers, there is a fundamental flaw in my logic. This is
     // not the fault of an API user.
     assert_eq!(
         mem::size_of_val(reference.deref()),
-        alloc_size,
+        layout.size(),
         "Allocation should match Rusts expectation"
     );
 
-    reference
+    Some(reference)
 }
 
-/// Clones a [`MaybeDynSized`] by calling [`new_boxed`].
+/// Clones a [`MaybeDynSized`] by calling [`new_boxed`]. The trailing padding
+/// is not considered part of the payload, so the clone has the same size.
 #[must_use]
 pub fn clone_dyn<T: MaybeDynSized<Metadata = usize> + ?Sized>(tag: &T) -> Box<T> {
-    new_boxed(tag.header().clone(), &[tag.payload()])
+    let payload_len = tag.header().payload_len();
+    new_boxed(tag.header().clone(), &[&tag.payload()[..payload_len]])
 }
 
 #[cfg(test)]
@@ -112,6 +90,14 @@ mod tests {
         assert_eq!(tag.header().typ(), 42);
         assert_eq!(tag.payload(), &[0, 1, 2, 3]);
 
-        let _cloned = clone_dyn(tag.as_ref());
+        let cloned = clone_dyn(tag.as_ref());
+        assert_eq!(cloned.header(), tag.header());
+        assert_eq!(cloned.payload(), tag.payload());
+
+        // Trailing padding is not part of the clone's size.
+        let header = DummyTestHeader::new(DummyDstTag::ID, 0);
+        let tag = new_boxed::<DummyDstTag>(header, &[&[0, 1, 2]]);
+        let cloned = clone_dyn(tag.as_ref());
+        assert_eq!(cloned.header(), tag.header());
     }
 }
@@ -49,7 +49,8 @@ impl<'a, H: Header + 'a> Iterator for TagIter<'a, H> {
         if self.next_tag_offset == self.buffer.len() {
             return None;
         }
-        assert!(self.next_tag_offset < self.buffer.len());
+        // The header must be in bounds before it is read.
+        assert!(self.next_tag_offset + mem::size_of::<H>() <= self.buffer.len());
 
         let ptr = unsafe { self.buffer.as_ptr().add(self.next_tag_offset) }.cast::<H>();
         let tag_hdr = unsafe { &*ptr };
@@ -73,8 +74,16 @@ impl<'a, H: Header + 'a> Iterator for TagIter<'a, H> {
             &self.buffer[from..to]
         };
 
-        // unwrap: We should not fail at this point.
-        let tag = DynSizedStructure::ref_from_slice(slice).unwrap();
+        // The checks of `DynSizedStructure::ref_from_slice` are redundant
+        // here: The buffer is aligned (checked in `new`), each offset is a
+        // multiple of the alignment, and `slice` includes the padding. The
+        // bounds were checked by the indexing above. Thus, the tag is
+        // validated exactly once and accessors only need to cast it.
+        debug_assert_eq!(slice.as_ptr().align_offset(ALIGNMENT), 0);
+        debug_assert_eq!(slice.len() % ALIGNMENT, 0);
+        let ptr = ptr_meta::from_raw_parts(slice.as_ptr().cast(), tag_hdr.payload_len());
+        // SAFETY: See above.
+        let tag: &DynSizedStructure<H> = unsafe { &*ptr };
         Some(tag)
     }
 }
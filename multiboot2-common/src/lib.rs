@@ -174,14 +174,104 @@
 //! during runtime something goes wrong, for example due to malformed tags,
 //! panics guarantee that no UB will happen.
 //!
-//! # No Public API
+//! # Reuse in Other Crates
 //!
-//! Not meant as stable public API for others outside Multiboot2.
+//! The abstractions are not limited to Multiboot2. Any boot protocol, or
+//! other format, that consists of 8-byte aligned structures whose size is
+//! reported by a common header, can reuse them. The public API follows
+//! semver like the `multiboot2` and `multiboot2-header` crates.
+//!
+//! A crate has to provide:
+//! - a [`Header`] type, which reports the total size of a structure,
+//! - a `#[repr(C)]` type per structure, implementing [`MaybeDynSized`], and
+//! - [`Tag`] for the structures that are identified by an ID.
+//!
+//! DSTs need to derive `Pointee` of [`ptr_meta`], which requires `ptr_meta`
+//! as a direct dependency of the crate with the same version as this crate.
+//! Sized structures implement `Pointee` automatically.
+//!
+//! ```rust
+//! use core::mem;
+//! use multiboot2_common::{DynSizedStructure, Header, MaybeDynSized, Tag, TagIter};
+//!
+//! /// The header that all structures of the protocol have in common.
+//! #[derive(Clone, Debug, PartialEq, Eq)]
+//! #[repr(C, align(8))]
+//! struct MyHeader {
+//!     typ: u32,
+//!     size: u32,
+//! }
+//!
+//! impl Header for MyHeader {
+//!     fn payload_len(&self) -> usize {
+//!         (self.size as usize).saturating_sub(mem::size_of::<Self>())
+//!     }
+//!
+//!     fn set_size(&mut self, total_size: usize) {
+//!         self.size = total_size as u32;
+//!     }
+//! }
+//!
+//! /// A structure with a dynamically sized name.
+//! #[derive(Debug, ptr_meta::Pointee)]
+//! #[repr(C, align(8))]
+//! struct NameTag {
+//!     header: MyHeader,
+//!     name: [u8],
+//! }
+//!
+//! impl MaybeDynSized for NameTag {
+//!     type Header = MyHeader;
+//!
+//!     const BASE_SIZE: usize = mem::size_of::<MyHeader>();
+//!
+//!     fn dst_len(header: &MyHeader) -> usize {
+//!         Self::try_dst_len(header).unwrap()
+//!     }
+//!
+//!     fn try_dst_len(header: &MyHeader) -> Option<usize> {
+//!         (header.size as usize).checked_sub(Self::BASE_SIZE)
+//!     }
+//! }
+//!
+//! impl Tag for NameTag {
+//!     type IDType = u32;
+//!
+//!     const ID: u32 = 1;
+//! }
+//!
+//! #[repr(C, align(8))]
+//! struct Aligned<const N: usize>([u8; N]);
+//!
+//! // A name structure, padded to the next 8-byte boundary, followed by an
+//! // empty structure of type 0.
+//! let bytes = Aligned([
+//!     1, 0, 0, 0, 13, 0, 0, 0, b'h', b'e', b'l', b'l', b'o', 0, 0, 0,
+//!     0, 0, 0, 0, 8, 0, 0, 0,
+//! ]);
+//! let mut names = TagIter::<MyHeader>::new(&bytes.0)
+//!     .filter(|tag| tag.header().typ == NameTag::ID)
+//!     .map(|tag| &tag.try_cast::<NameTag>().unwrap().name);
+//! assert_eq!(names.next(), Some(&b"hello"[..]));
+//! assert_eq!(names.next(), None);
+//!
+//! // A single structure can also be parsed on its own.
+//! let tag = DynSizedStructure::<MyHeader>::ref_from_slice(&bytes.0[..16]).unwrap();
+//! assert_eq!(tag.cast::<NameTag>().name, *b"hello");
+//!
+//! // With the `builder` feature, structures can be built on the heap.
+//! # #[cfg(feature = "builder")]
+//! # {
+//! let header = MyHeader { typ: NameTag::ID, size: 0 };
+//! let tag = multiboot2_common::new_boxed::<NameTag>(header, &[b"hello"]);
+//! assert_eq!(tag.header().size, 13);
+//! assert_eq!(tag.as_bytes().as_ref(), &bytes.0[..16]);
+//! # }
+//! ```
 //!
 //! [`Layout`]: core::alloc::Layout
 
 #![no_std]
-#![cfg_attr(feature = "unstable", feature(error_in_core))]
 // --- BEGIN STYLE CHECKS ---
 #![deny(
     clippy::all,
@@ -205,16 +295,24 @@ extern crate alloc;
 #[allow(unused)]
 pub mod test_utils;
 
+#[cfg(feature = "alloc")]
+mod aligned;
 #[cfg(feature = "alloc")]
 mod boxed;
 mod bytes_ref;
 mod iter;
+mod new_in;
+#[cfg(kani)]
+mod proofs;
 mod tag;
 
 #[cfg(feature = "alloc")]
-pub use boxed::{clone_dyn, new_boxed};
+pub use aligned::AlignedBuffer;
+#[cfg(feature = "alloc")]
+pub use boxed::{clone_dyn, new_boxed, try_new_boxed};
 pub use bytes_ref::BytesRef;
 pub use iter::TagIter;
+pub use new_in::new_in;
 pub use tag::{MaybeDynSized, Tag};
 
 use core::fmt::Debug;
@@ -281,11 +379,14 @@ pub struct DynSizedStructure<H: Header> {
 impl<H: Header> DynSizedStructure<H> {
     /// Creates a new fat-pointer backed reference to a [`DynSizedStructure`]
     /// from the given [`BytesRef`].
-    pub fn ref_from_bytes(bytes: BytesRef<H>) -> Result<&Self, MemoryError> {
+    pub fn ref_from_bytes(bytes: BytesRef<'_, H>) -> Result<&Self, MemoryError> {
         let ptr = bytes.as_ptr().cast::<H>();
         let hdr = unsafe { &*ptr };
 
-        if hdr.payload_len() > bytes.len() {
+        // The payload follows the header, so the header must be accounted
+        // for. As the length of the bytes is a multiple of the alignment, the
+        // padding of the structure is in bounds then as well.
+        if hdr.total_size() > bytes.len() {
             return Err(MemoryError::InvalidReportedTotalSize);
         }
 
@@ -314,6 +415,11 @@ impl<H: Header> DynSizedStructure<H> {
     /// # Safety
     /// The caller must ensure that the function operates on valid memory.
     pub unsafe fn ref_from_ptr<'a>(ptr: NonNull<H>) -> Result<&'a Self, MemoryError> {
+        // Check this before creating the reference to the header, as a
+        // misaligned reference is UB on its own.
+        if ptr.as_ptr().align_offset(ALIGNMENT) != 0 {
+            return Err(MemoryError::WrongAlignment);
+        }
         let ptr = ptr.as_ptr().cast_const();
         let hdr = unsafe { &*ptr };
 
@@ -353,9 +459,7 @@ impl<H: Header> DynSizedStructure<H> {
     pub fn cast<T: MaybeDynSized<Header = H> + ?Sized>(&self) -> &T {
         let base_ptr = ptr::addr_of!(*self);
 
-        // This should be a compile-time assertion. However, this is the best
-        // location to place it for now.
-        assert!(T::BASE_SIZE >= mem::size_of::<H>());
+        const { assert!(T::BASE_SIZE >= mem::size_of::<H>()) };
 
         let t_dst_size = T::dst_len(self.header());
         let t_ptr = ptr_meta::from_raw_parts(base_ptr.cast(), t_dst_size);
@@ -365,6 +469,32 @@ impl<H: Header> DynSizedStructure<H> {
 
         t_ref
     }
+
+    /// Like [`Self::cast`], but returns
+    /// [`MemoryError::InvalidReportedTotalSize`] instead of panicking if the
+    /// size reported by the header doesn't fit `T`, e.g., if it is smaller
+    /// than the fixed-size part of `T`.
+    pub fn try_cast<T: MaybeDynSized<Header = H> + ?Sized>(&self) -> Result<&T, MemoryError> {
+        let base_ptr = ptr::addr_of!(*self);
+
+        const { assert!(T::BASE_SIZE >= mem::size_of::<H>()) };
+
+        // Rejects undersized Sized types before a reference to them exists.
+        if mem::size_of_val(self) < T::BASE_SIZE {
+            return Err(MemoryError::InvalidReportedTotalSize);
+        }
+
+        let t_dst_size =
+            T::try_dst_len(self.header()).ok_or(MemoryError::InvalidReportedTotalSize)?;
+        let t_ptr = ptr_meta::from_raw_parts(base_ptr.cast(), t_dst_size);
+        let t_ref = unsafe { &*t_ptr };
+
+        if mem::size_of_val(self) != mem::size_of_val(t_ref) {
+            return Err(MemoryError::InvalidReportedTotalSize);
+        }
+
+        Ok(t_ref)
+    }
 }
 
 /// Errors that may occur when working with memory.
@@ -386,7 +516,6 @@ pub enum MemoryError {
     InvalidReportedTotalSize,
 }
 
-#[cfg(feature = "unstable")]
 impl core::error::Error for MemoryError {}
 
 /// Increases the given size to the next alignment boundary, if it is not a
@@ -472,4 +601,78 @@ mod tests {
         assert_eq!(tag.header().typ(), 0x1337);
         assert_eq!(tag.header().size(), 18);
     }
+
+    #[test]
+    fn test_ref_from_ptr_rejects_misaligned_ptr() {
+        let bytes = AlignedBytes([0_u8; 24]);
+        // The header is never read if the pointer is misaligned.
+        let ptr = NonNull::new(bytes.0[4..].as_ptr().cast_mut())
+            .unwrap()
+            .cast::<DummyTestHeader>();
+        let res = unsafe { DynSizedStructure::ref_from_ptr(ptr) };
+        assert_eq!(res, Err(MemoryError::WrongAlignment));
+    }
+
+    #[test]
+    fn test_ref_from_slice_rejects_oversized_tag() {
+        #[rustfmt::skip]
+        let bytes = AlignedBytes(
+            [
+                0x37, 0x13, 0, 0,
+                /* Tag size: the payload alone fits into the bytes */
+                24, 0, 0, 0,
+                0, 0, 0, 0,
+                0, 0, 0, 0,
+            ],
+        );
+        assert_eq!(
+            DynSizedStructure::<DummyTestHeader>::ref_from_slice(bytes.borrow()),
+            Err(MemoryError::InvalidReportedTotalSize)
+        );
+    }
+
+    #[test]
+    fn test_try_cast_rejects_undersized_tag() {
+        #[derive(ptr_meta::Pointee)]
+        #[repr(C)]
+        struct CustomDstTag {
+            tag_header: DummyTestHeader,
+            a: u64,
+            payload: [u8],
+        }
+
+        impl MaybeDynSized for CustomDstTag {
+            type Header = DummyTestHeader;
+
+            const BASE_SIZE: usize = mem::size_of::<DummyTestHeader>() + mem::size_of::<u64>();
+
+            fn dst_len(header: &DummyTestHeader) -> usize {
+                Self::try_dst_len(header).unwrap()
+            }
+
+            fn try_dst_len(header: &DummyTestHeader) -> Option<usize> {
+                (header.size() as usize).checked_sub(Self::BASE_SIZE)
+            }
+        }
+
+        #[rustfmt::skip]
+        let bytes = AlignedBytes::new(
+            [
+                0x37, 0x13, 0, 0,
+                /* Tag size: only the header, field `a` is missing */
+                8, 0, 0, 0,
+            ],
+        );
+        let tag = DynSizedStructure::ref_from_slice(bytes.borrow()).unwrap();
+        assert!(matches!(
+            tag.try_cast::<CustomDstTag>(),
+            Err(MemoryError::InvalidReportedTotalSize)
+        ));
+        assert_eq!(
+            tag.try_cast::<DynSizedStructure<DummyTestHeader>>()
+                .unwrap()
+                .payload(),
+            &[]
+        );
+    }
 }
@@ -0,0 +1,128 @@
+//! Module for [`new_in`].
+
+use crate::{Header, MaybeDynSized, ALIGNMENT};
+use core::alloc::Layout;
+use core::mem;
+use core::ptr;
+
+/// Returns the memory layout for a [`MaybeDynSized`] with the given header and
+/// payload slices and updates the size of the header accordingly.
+///
+/// Returns `None` if the size overflows or exceeds [`isize::MAX`].
+pub fn prepare_layout<T: MaybeDynSized + ?Sized>(
+    header: &mut T::Header,
+    additional_bytes_slices: &[&[u8]],
+) -> Option<Layout> {
+    let additional_size = additional_bytes_slices
+        .iter()
+        .try_fold(0_usize, |acc, b| acc.checked_add(b.len()))?;
+
+    let tag_size = mem::size_of::<T::Header>().checked_add(additional_size)?;
+
+    // Allocation size is multiple of alignment.
+    // See <https://doc.rust-lang.org/reference/type-layout.html>
+    let alloc_size = tag_size.checked_next_multiple_of(ALIGNMENT)?;
+    let layout = Layout::from_size_align(alloc_size, ALIGNMENT).ok()?;
+    header.set_size(tag_size);
+    Some(layout)
+}
+
+/// Writes the header and the payload slices to `dst` and returns a pointer
+/// to the resulting [`MaybeDynSized`]. The padding is zeroed.
+///
+/// # Safety
+/// `dst` must be valid for writes of `layout.size()` bytes and aligned to
+/// [`ALIGNMENT`], where `layout` is the result of [`prepare_layout`].
+pub unsafe fn write_dst<T: MaybeDynSized<Metadata = usize> + ?Sized>(
+    header: &T::Header,
+    additional_bytes_slices: &[&[u8]],
+    layout: Layout,
+    dst: *mut u8,
+) -> *mut T {
+    // write header
+    let mut write_offset = mem::size_of::<T::Header>();
+    {
+        let ptr = ptr::addr_of!(*header);
+        ptr::copy_nonoverlapping(ptr.cast::<u8>(), dst, write_offset);
+    }
+
+    // write body
+    for &bytes in additional_bytes_slices {
+        let len = bytes.len();
+        ptr::copy_nonoverlapping(bytes.as_ptr(), dst.add(write_offset), len);
+        write_offset += len;
+    }
+
+    // zero padding
+    ptr::write_bytes(dst.add(write_offset), 0, layout.size() - write_offset);
+
+    // This is a fat pointer for DSTs and a thin pointer for sized `T`s.
+    ptr_meta::from_raw_parts_mut(dst.cast(), T::dst_len(header))
+}
+
+/// Like [`new_boxed`], but creates the [`MaybeDynSized`] in memory provided by
+/// the `alloc` callback instead of the global allocator.
+///
+/// This enables to place tags in a dedicated memory region, such as an
+/// early-boot arena, in environments without a heap.
+///
+/// Returns `None` if the size overflows or if `alloc` returns a null pointer.
+///
+/// # Safety
+/// If `alloc` returns a non-null pointer, it must be valid for reads and
+/// writes of `layout.size()` bytes, aligned to `layout.align()`, and must not
+/// be accessed through any other pointer for the lifetime `'a`.
+///
+/// [`new_boxed`]: crate::new_boxed
+pub unsafe fn new_in<'a, T: MaybeDynSized<Metadata = usize> + ?Sized>(
+    mut header: T::Header,
+    additional_bytes_slices: &[&[u8]],
+    alloc: &mut dyn FnMut(Layout) -> *mut u8,
+) -> Option<&'a mut T> {
+    let layout = prepare_layout::<T>(&mut header, additional_bytes_slices)?;
+    let dst = alloc(layout);
+    if dst.is_null() {
+        return None;
+    }
+    assert_eq!(dst.align_offset(layout.align()), 0, "must be aligned");
+    let ptr = write_dst::<T>(&header, additional_bytes_slices, layout, dst);
+    Some(&mut *ptr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{AlignedBytes, DummyDstTag, DummyTestHeader};
+    use crate::Tag;
+
+    #[test]
+    fn test_new_in() {
+        let mut arena = AlignedBytes::new([0xff_u8; 64]);
+        let arena_ptr = arena.0.as_mut_ptr();
+        let mut offset = 0;
+        let mut alloc = |layout: Layout| {
+            if offset + layout.size() > 64 {
+                return ptr::null_mut();
+            }
+            let ptr = unsafe { arena_ptr.add(offset) };
+            offset += layout.size();
+            ptr
+        };
+
+        let header = DummyTestHeader::new(DummyDstTag::ID, 0);
+        let tag = unsafe { new_in::<DummyDstTag>(header, &[&[0, 1], &[2]], &mut alloc) }.unwrap();
+        assert_eq!(tag.header().typ(), 42);
+        assert_eq!(tag.header().size(), 11);
+        assert_eq!(tag.payload(), &[0, 1, 2]);
+        // Padding is zeroed.
+        assert_eq!(tag.as_bytes().as_ref()[11..], [0; 5]);
+
+        let header = DummyTestHeader::new(DummyDstTag::ID, 0);
+        let tag = unsafe { new_in::<DummyDstTag>(header, &[&[0; 40]], &mut alloc) }.unwrap();
+        assert_eq!(tag.payload(), &[0; 40]);
+
+        // Arena exhausted.
+        let header = DummyTestHeader::new(DummyDstTag::ID, 0);
+        assert!(unsafe { new_in::<DummyDstTag>(header, &[&[0]], &mut alloc) }.is_none());
+    }
+}
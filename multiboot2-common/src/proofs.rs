@@ -0,0 +1,47 @@
+//! Kani proof harnesses for the memory safety of the parsing primitives.
+//!
+//! Run them with `cargo kani -p multiboot2-common`.
+
+use crate::test_utils::{AlignedBytes, DummyDstTag, DummyTestHeader};
+use crate::{increase_to_alignment, DynSizedStructure, Header, MaybeDynSized, ALIGNMENT};
+use core::mem;
+
+/// The alignment helper never overflows for sizes that have an aligned
+/// successor and returns the next multiple of [`ALIGNMENT`].
+#[kani::proof]
+fn increase_to_alignment_is_exact() {
+    let size: usize = kani::any();
+    kani::assume(size <= usize::MAX - (ALIGNMENT - 1));
+    let aligned = increase_to_alignment(size);
+    assert_eq!(aligned % ALIGNMENT, 0);
+    assert!(aligned >= size);
+    assert!(aligned - size < ALIGNMENT);
+}
+
+/// A structure created from a slice never extends beyond the slice, whatever
+/// size its header reports.
+#[kani::proof]
+fn ref_from_slice_stays_in_bounds() {
+    let bytes = AlignedBytes::<32>(kani::any());
+    let len: usize = kani::any();
+    kani::assume(len <= bytes.len());
+    // The dummy header doesn't guard against sizes smaller than itself.
+    let size = u32::from_ne_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    kani::assume(size as usize >= mem::size_of::<DummyTestHeader>());
+
+    if let Ok(structure) = DynSizedStructure::<DummyTestHeader>::ref_from_slice(&bytes[..len]) {
+        assert!(mem::size_of_val(structure) <= len);
+        assert_eq!(structure.payload().len(), structure.header().payload_len());
+    }
+}
+
+/// The length of the dynamically sized part never underflows: it is only
+/// computed for sizes that cover the sized part.
+#[kani::proof]
+fn try_dst_len_never_underflows() {
+    let header = DummyTestHeader::new(kani::any(), kani::any());
+    match DummyDstTag::try_dst_len(&header) {
+        Some(len) => assert_eq!(DummyDstTag::BASE_SIZE + len, header.size() as usize),
+        None => assert!((header.size() as usize) < DummyDstTag::BASE_SIZE),
+    }
+}
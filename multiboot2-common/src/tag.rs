@@ -40,6 +40,17 @@ pub trait MaybeDynSized: Pointee {
     /// `usize`.
     fn dst_len(header: &Self::Header) -> Self::Metadata;
 
+    /// Like [`Self::dst_len`], but returns `None` instead of panicking or
+    /// wrapping around if the size reported by the header is too small for
+    /// the type or doesn't fit its elements.
+    ///
+    /// The default implementation calls [`Self::dst_len`]. DSTs should
+    /// override this with a checked computation, e.g., with
+    /// [`usize::checked_sub`], and implement [`Self::dst_len`] on top of it.
+    fn try_dst_len(header: &Self::Header) -> Option<Self::Metadata> {
+        Some(Self::dst_len(header))
+    }
+
     /// Returns the corresponding [`Header`].
     fn header(&self) -> &Self::Header {
         let ptr = core::ptr::addr_of!(*self);
@@ -56,7 +67,7 @@ pub trait MaybeDynSized: Pointee {
     /// Returns the whole allocated bytes for this structure encapsulated in
     /// [`BytesRef`]. This includes padding bytes. To only get the "true" tag
     /// data, read the tag size from [`Self::header`] and create a sub slice.
-    fn as_bytes(&self) -> BytesRef<Self::Header> {
+    fn as_bytes(&self) -> BytesRef<'_, Self::Header> {
         let ptr = core::ptr::addr_of!(*self);
         // Actual tag size, optionally with terminating padding.
         let size = mem::size_of_val(self);
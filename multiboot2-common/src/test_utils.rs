@@ -106,7 +106,11 @@ impl MaybeDynSized for DummyDstTag {
     const BASE_SIZE: usize = mem::size_of::<DummyTestHeader>();
 
     fn dst_len(header: &Self::Header) -> Self::Metadata {
-        header.size as usize - Self::BASE_SIZE
+        Self::try_dst_len(header).expect("should have at least BASE_SIZE bytes")
+    }
+
+    fn try_dst_len(header: &Self::Header) -> Option<Self::Metadata> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
     }
 }
 
@@ -1,5 +1,7 @@
 use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use core::error::Error;
 use core::mem::size_of;
+use core::ops::Range;
 use multiboot2_common::{MaybeDynSized, Tag};
 
 /// Binary address information for non-ELF images.
@@ -85,8 +87,120 @@ impl AddressHeaderTag {
     pub const fn bss_end_addr(&self) -> u32 {
         self.bss_end_addr
     }
+
+    /// Computes the [`AddressLoadRanges`] to load an a.out-kludge image of
+    /// `image_len` bytes whose header was found at `header_offset`.
+    ///
+    /// This also validates the relationship between the addresses, as
+    /// described by the spec.
+    pub fn load_ranges(
+        &self,
+        header_offset: usize,
+        image_len: usize,
+    ) -> Result<AddressLoadRanges, AddressTagError> {
+        let header_offset = header_offset as u64;
+        let image_len = image_len as u64;
+        let header_addr = u64::from(self.header_addr);
+
+        let (file_start, load_addr) = if self.load_addr == u32::MAX {
+            let load_addr = header_addr
+                .checked_sub(header_offset)
+                .ok_or(AddressTagError::HeaderAddrTooLow)?;
+            (0, load_addr)
+        } else {
+            let load_addr = u64::from(self.load_addr);
+            let distance = header_addr
+                .checked_sub(load_addr)
+                .ok_or(AddressTagError::LoadAddrAboveHeaderAddr)?;
+            let file_start = header_offset
+                .checked_sub(distance)
+                .ok_or(AddressTagError::HeaderAddrTooHigh)?;
+            (file_start, load_addr)
+        };
+
+        let load_len = if self.load_end_addr == 0 {
+            image_len.saturating_sub(file_start)
+        } else {
+            u64::from(self.load_end_addr)
+                .checked_sub(load_addr)
+                .ok_or(AddressTagError::LoadEndBeforeLoadAddr)?
+        };
+        let file_end = file_start + load_len;
+        if file_end > image_len {
+            return Err(AddressTagError::ImageTooSmall);
+        }
+        let load_end = load_addr + load_len;
+
+        let bss_end = if self.bss_end_addr == 0 {
+            load_end
+        } else {
+            let bss_end = u64::from(self.bss_end_addr);
+            if bss_end < load_end {
+                return Err(AddressTagError::BssEndBeforeLoadEnd);
+            }
+            bss_end
+        };
+
+        Ok(AddressLoadRanges {
+            file: file_start as usize..file_end as usize,
+            load: load_addr..load_end,
+            bss: load_end..bss_end,
+        })
+    }
 }
 
+/// The ranges to load an image with an [`AddressHeaderTag`], as returned by
+/// [`AddressHeaderTag::load_ranges`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AddressLoadRanges {
+    file: Range<usize>,
+    load: Range<u64>,
+    bss: Range<u64>,
+}
+
+impl AddressLoadRanges {
+    /// Returns the range of the image file that must be copied to
+    /// [`Self::load_range`].
+    #[must_use]
+    pub fn file_range(&self) -> Range<usize> {
+        self.file.clone()
+    }
+
+    /// Returns the physical address range the image data is copied to.
+    #[must_use]
+    pub fn load_range(&self) -> Range<u64> {
+        self.load.clone()
+    }
+
+    /// Returns the physical address range of the bss segment, which must be
+    /// zeroed. It may be empty.
+    #[must_use]
+    pub fn bss_range(&self) -> Range<u64> {
+        self.bss.clone()
+    }
+}
+
+/// Errors of [`AddressHeaderTag::load_ranges`] for inconsistent addresses.
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AddressTagError {
+    /// `load_addr` is greater than `header_addr`.
+    LoadAddrAboveHeaderAddr,
+    /// `header_addr` is lower than the offset of the header in the image, so
+    /// the image would start below address zero.
+    HeaderAddrTooLow,
+    /// The distance between `header_addr` and `load_addr` is larger than the
+    /// offset of the header in the image.
+    HeaderAddrTooHigh,
+    /// `load_end_addr` is lower than `load_addr`.
+    LoadEndBeforeLoadAddr,
+    /// `bss_end_addr` is lower than the end of the loaded data.
+    BssEndBeforeLoadEnd,
+    /// The image is smaller than the data to load.
+    ImageTooSmall,
+}
+
+impl Error for AddressTagError {}
+
 impl MaybeDynSized for AddressHeaderTag {
     type Header = HeaderTagHeader;
 
@@ -102,7 +216,7 @@ impl Tag for AddressHeaderTag {
 
 #[cfg(test)]
 mod tests {
-    use crate::AddressHeaderTag;
+    use crate::{AddressHeaderTag, AddressTagError, HeaderTagFlag};
 
     #[test]
     fn test_assert_size() {
@@ -111,4 +225,57 @@ mod tests {
             2 + 2 + 4 + 4 + 4 + 4 + 4
         );
     }
+
+    #[test]
+    fn test_load_ranges() {
+        let flags = HeaderTagFlag::Required;
+        // Header at offset 0x40 of the image, which is loaded to 0x100000.
+        let tag = AddressHeaderTag::new(flags, 0x100040, 0x100000, 0x101000, 0x102000);
+        let ranges = tag.load_ranges(0x40, 0x2000).unwrap();
+        assert_eq!(ranges.file_range(), 0..0x1000);
+        assert_eq!(ranges.load_range(), 0x100000..0x101000);
+        assert_eq!(ranges.bss_range(), 0x101000..0x102000);
+
+        // Load the whole image, no bss.
+        let tag = AddressHeaderTag::new(flags, 0x100040, u32::MAX, 0, 0);
+        let ranges = tag.load_ranges(0x40, 0x2000).unwrap();
+        assert_eq!(ranges.file_range(), 0..0x2000);
+        assert_eq!(ranges.load_range(), 0x100000..0x102000);
+        assert!(ranges.bss_range().is_empty());
+
+        // Image data starts after the beginning of the file.
+        let tag = AddressHeaderTag::new(flags, 0x100040, 0x100000, 0, 0);
+        let ranges = tag.load_ranges(0x1040, 0x2000).unwrap();
+        assert_eq!(ranges.file_range(), 0x1000..0x2000);
+
+        let load = |header_addr, load_addr, load_end_addr, bss_end_addr| {
+            AddressHeaderTag::new(flags, header_addr, load_addr, load_end_addr, bss_end_addr)
+                .load_ranges(0x40, 0x2000)
+                .err()
+        };
+        assert_eq!(
+            load(0x100000, 0x100040, 0, 0),
+            Some(AddressTagError::LoadAddrAboveHeaderAddr)
+        );
+        assert_eq!(
+            load(0x20, u32::MAX, 0, 0),
+            Some(AddressTagError::HeaderAddrTooLow)
+        );
+        assert_eq!(
+            load(0x101000, 0x100000, 0, 0),
+            Some(AddressTagError::HeaderAddrTooHigh)
+        );
+        assert_eq!(
+            load(0x100040, 0x100000, 0xff000, 0),
+            Some(AddressTagError::LoadEndBeforeLoadAddr)
+        );
+        assert_eq!(
+            load(0x100040, 0x100000, 0x101000, 0x100000),
+            Some(AddressTagError::BssEndBeforeLoadEnd)
+        );
+        assert_eq!(
+            load(0x100040, 0x100000, 0x103000, 0),
+            Some(AddressTagError::ImageTooSmall)
+        );
+    }
 }
@@ -0,0 +1,221 @@
+//! `mb2header`: Inspects the Multiboot2 header of a kernel image.
+//!
+//! ```text
+//! mb2header <FILE>
+//! ```
+//!
+//! Scans the first [`Multiboot2Header::SEARCH_LIMIT`] bytes of an ELF or flat
+//! kernel image for the header, validates it, and prints all of its tags.
+//!
+//! The exit code is suitable for CI checks of kernel builds:
+//! - `0`: A valid header was found.
+//! - `1`: No valid header was found.
+//! - `2`: The header has required tags that are unknown to this crate, so a
+//!   bootloader refuses to boot the image.
+//! - `3`: Usage or I/O error.
+
+use multiboot2_common::{AlignedBuffer, MaybeDynSized};
+use multiboot2_header::{
+    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
+    GenericHeaderTag, HeaderTagHeader, HeaderTagType, InformationRequestHeaderTag, LoadError,
+    ModuleAlignHeaderTag, Multiboot2Header, RelocatableHeaderTag,
+};
+use std::fmt::{Debug, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: mb2header <FILE>";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let (Some(path), None) = (args.next(), args.next()) else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(3);
+    };
+    if path == "-h" || path == "--help" {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("mb2header: {path}: {e}");
+            return ExitCode::from(3);
+        }
+    };
+
+    // The header must be 8-byte aligned relative to the image, hence the
+    // image is copied into an aligned buffer.
+    let image = AlignedBuffer::from_bytes(&bytes);
+
+    let (output, code) = inspect(&image);
+    print!("{output}");
+    ExitCode::from(code)
+}
+
+/// Inspects the image and returns the output and the exit code.
+fn inspect(image: &[u8]) -> (String, u8) {
+    let mut out = String::new();
+    let kind = if image.starts_with(b"\x7fELF") {
+        "ELF"
+    } else {
+        "flat"
+    };
+    let _ = writeln!(out, "{kind} image, {} bytes", image.len());
+
+    match find(image, &mut out) {
+        Some((header, offset)) => {
+            print_header(&header, offset, &mut out);
+            if header.has_unsupported_required_tags() {
+                out.push_str("error: header has unsupported required tags\n");
+                (out, 2)
+            } else {
+                (out, 0)
+            }
+        }
+        None => {
+            let _ = writeln!(
+                out,
+                "error: no valid Multiboot2 header in the first {:#x} bytes",
+                Multiboot2Header::SEARCH_LIMIT
+            );
+            (out, 1)
+        }
+    }
+}
+
+/// Returns the first valid header of [`Multiboot2Header::candidates`] and
+/// reports why the candidates before it are rejected.
+fn find<'a>(image: &'a [u8], out: &mut String) -> Option<(Multiboot2Header<'a>, usize)> {
+    // The image is in an aligned buffer.
+    let candidates = Multiboot2Header::candidates(image).ok()?;
+    for (offset, candidate) in candidates {
+        match candidate {
+            Ok(header) => return Some((header, offset)),
+            Err(e) => {
+                let _ = writeln!(out, "rejected candidate at {offset:#x}: {}", describe(e));
+            }
+        }
+    }
+    None
+}
+
+fn describe(e: LoadError) -> String {
+    match e {
+        LoadError::ChecksumMismatch => "checksum mismatch".into(),
+        LoadError::MagicNotFound => "magic not found".into(),
+        LoadError::InvalidTag(offset) => format!("invalid tag at header offset {offset:#x}"),
+        LoadError::UnknownArch => "unknown architecture".into(),
+        LoadError::Memory(e) => format!("{e:?}"),
+    }
+}
+
+fn print_header(header: &Multiboot2Header, offset: usize, out: &mut String) {
+    let _ = writeln!(
+        out,
+        "header at {offset:#x}: arch {:?}, length {}, checksum {:#010x} (valid)",
+        header.arch(),
+        header.length(),
+        header.checksum()
+    );
+    for (tag_offset, tag) in header.tags_with_offsets() {
+        let tag_header = tag.header();
+        let flag = if tag_header.is_required() {
+            "required"
+        } else {
+            "optional"
+        };
+        let _ = writeln!(
+            out,
+            "{tag_offset:#06x}: {:?} ({flag}, {} bytes)",
+            tag_header.typ(),
+            tag_header.size()
+        );
+        match decode(tag) {
+            Some(decoded) => {
+                let _ = writeln!(out, "    {decoded}");
+            }
+            None => {
+                let hex = tag.payload().iter().map(|b| format!("{b:02x}"));
+                let _ = writeln!(out, "    unknown: {}", hex.collect::<Vec<_>>().join(" "));
+            }
+        }
+    }
+}
+
+/// Returns the `Debug` output of the tag as its specific type.
+fn decode(tag: &GenericHeaderTag) -> Option<String> {
+    fn debug<T: MaybeDynSized<Header = HeaderTagHeader> + Debug + ?Sized>(
+        tag: &GenericHeaderTag,
+    ) -> Option<String> {
+        tag.try_cast::<T>().ok().map(|tag| format!("{tag:?}"))
+    }
+
+    match tag.header().typ() {
+        HeaderTagType::End => debug::<EndHeaderTag>(tag),
+        HeaderTagType::InformationRequest => debug::<InformationRequestHeaderTag>(tag),
+        HeaderTagType::Address => debug::<AddressHeaderTag>(tag),
+        HeaderTagType::EntryAddress => debug::<EntryAddressHeaderTag>(tag),
+        HeaderTagType::ConsoleFlags => debug::<ConsoleHeaderTag>(tag),
+        HeaderTagType::Framebuffer => debug::<FramebufferHeaderTag>(tag),
+        HeaderTagType::ModuleAlign => debug::<ModuleAlignHeaderTag>(tag),
+        HeaderTagType::EfiBS => debug::<EfiBootServiceHeaderTag>(tag),
+        HeaderTagType::EntryAddressEFI32 => debug::<EntryEfi32HeaderTag>(tag),
+        HeaderTagType::EntryAddressEFI64 => debug::<EntryEfi64HeaderTag>(tag),
+        HeaderTagType::Relocatable => debug::<RelocatableHeaderTag>(tag),
+        HeaderTagType::Custom(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiboot2_header::{Builder, ConsoleFlags, HeaderTagFlag, HeaderTagISA};
+
+    /// Returns an image with the header at offset 16.
+    fn image(header: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0; 16 + header.len() + 32];
+        bytes[16..16 + header.len()].copy_from_slice(header);
+        bytes
+    }
+
+    #[test]
+    fn valid_header() {
+        let header = Builder::new(HeaderTagISA::I386)
+            .console_flags(HeaderTagFlag::Required, ConsoleFlags::CONSOLE_REQUIRED)
+            .build();
+        let image = AlignedBuffer::from_bytes(&image(header.as_bytes().as_ref()));
+        let (out, code) = inspect(&image);
+        assert_eq!(code, 0, "{out}");
+        assert!(out.starts_with("flat image"));
+        assert!(out.contains("header at 0x10: arch I386"));
+        assert!(out.contains("0x0010: ConsoleFlags (required, 12 bytes)\n"));
+        assert!(out.contains(": End (required, 8 bytes)\n"));
+    }
+
+    #[test]
+    fn unsupported_required_tag() {
+        let header = Builder::new(HeaderTagISA::I386)
+            .add_raw_tag(0x1337, HeaderTagFlag::Required, &[0xab; 4])
+            .build();
+        let image = AlignedBuffer::from_bytes(&image(header.as_bytes().as_ref()));
+        let (out, code) = inspect(&image);
+        assert_eq!(code, 2, "{out}");
+        assert!(out.contains("Custom(4919) (required, 12 bytes)\n    unknown: ab ab ab ab\n"));
+    }
+
+    #[test]
+    fn invalid_header() {
+        let header = Builder::new(HeaderTagISA::I386).build();
+        let mut bytes = image(header.as_bytes().as_ref());
+        // Corrupt the checksum.
+        bytes[28] ^= 1;
+        let (out, code) = inspect(&AlignedBuffer::from_bytes(&bytes));
+        assert_eq!(code, 1);
+        assert!(out.contains("rejected candidate at 0x10: checksum mismatch\n"));
+
+        let (out, code) = inspect(&[0; 64]);
+        assert_eq!(code, 1);
+        assert!(out.contains("error: no valid Multiboot2 header"));
+    }
+}
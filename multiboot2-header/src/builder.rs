@@ -1,13 +1,18 @@
 //! Exports a builder [`Builder`].
 
 use crate::{
-    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EntryAddressHeaderTag,
-    EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag, HeaderTagISA,
-    InformationRequestHeaderTag, ModuleAlignHeaderTag, Multiboot2BasicHeader, RelocatableHeaderTag,
+    AddressHeaderTag, ConsoleFlags, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
+    GenericHeaderTag, HeaderTagFlag, HeaderTagHeader, HeaderTagISA, HeaderTagType,
+    InformationRequestHeaderTag, MbiTagType, ModuleAlignHeaderTag, Multiboot2BasicHeader,
+    RelocatableHeaderTag, RelocatableHeaderTagPreference,
 };
 use alloc::boxed::Box;
 use alloc::vec::Vec;
-use multiboot2_common::{new_boxed, DynSizedStructure, MaybeDynSized};
+use multiboot2_common::{new_boxed, DynSizedStructure, MaybeDynSized, ALIGNMENT};
+
+/// Zero bytes to pad tags to [`ALIGNMENT`].
+const PADDING: [u8; ALIGNMENT - 1] = [0; ALIGNMENT - 1];
 
 /// Builder for a Multiboot2 header information.
 #[derive(Debug)]
@@ -23,7 +28,7 @@ pub struct Builder {
     efi_32_tag: Option<EntryEfi32HeaderTag>,
     efi_64_tag: Option<EntryEfi64HeaderTag>,
     relocatable_tag: Option<RelocatableHeaderTag>,
-    // TODO add support for custom tags once someone requests it.
+    custom_tags: Vec<Box<GenericHeaderTag>>,
 }
 
 impl Builder {
@@ -42,9 +47,52 @@ impl Builder {
             efi_32_tag: None,
             efi_64_tag: None,
             relocatable_tag: None,
+            custom_tags: Vec::new(),
         }
     }
 
+    /// Creates a builder with the typical tags of a kernel that is booted by
+    /// GRUB on legacy BIOS: an optional information request for the command
+    /// line, the memory map, and the modules, an optional framebuffer request
+    /// without a preferred mode, and page-aligned modules.
+    #[must_use]
+    pub fn grub_bios_default() -> Self {
+        Self::new(HeaderTagISA::I386)
+            .information_requests(HeaderTagFlag::Optional, &Self::DEFAULT_REQUESTS)
+            .framebuffer(HeaderTagFlag::Optional, 0, 0, 0)
+            .module_align_tag(ModuleAlignHeaderTag::new(HeaderTagFlag::Required))
+    }
+
+    /// Creates a builder with the typical tags of a relocatable kernel that
+    /// is booted on UEFI in 64-bit mode with the boot services still running:
+    /// the same information request as [`Self::grub_bios_default`], a
+    /// relocatable tag with the given constraints, the boot services tag, and
+    /// the EFI amd64 entry address tag.
+    ///
+    /// `entry_addr` is the link-time address of the entry point, which the
+    /// bootloader adjusts when it relocates the kernel.
+    #[must_use]
+    pub fn uefi64_relocatable(min_addr: u32, max_addr: u32, align: u32, entry_addr: u32) -> Self {
+        Self::new(HeaderTagISA::I386)
+            .information_requests(HeaderTagFlag::Optional, &Self::DEFAULT_REQUESTS)
+            .relocatable_tag(RelocatableHeaderTag::new(
+                HeaderTagFlag::Required,
+                min_addr,
+                max_addr,
+                align,
+                RelocatableHeaderTagPreference::None,
+            ))
+            .wants_boot_services(HeaderTagFlag::Required)
+            .efi_64_tag(EntryEfi64HeaderTag::new(
+                HeaderTagFlag::Required,
+                entry_addr,
+            ))
+    }
+
+    /// The information requests of the presets.
+    const DEFAULT_REQUESTS: [MbiTagType; 3] =
+        [MbiTagType::Cmdline, MbiTagType::Mmap, MbiTagType::Module];
+
     /// Set the [`InformationRequestHeaderTag`] tag.
     #[must_use]
     pub fn information_request_tag(
@@ -55,6 +103,13 @@ impl Builder {
         self
     }
 
+    /// Set the [`InformationRequestHeaderTag`] tag from a runtime list of
+    /// requested tag types.
+    #[must_use]
+    pub fn information_requests(self, flags: HeaderTagFlag, requests: &[MbiTagType]) -> Self {
+        self.information_request_tag(InformationRequestHeaderTag::from_types(flags, requests))
+    }
+
     /// Set the [`AddressHeaderTag`] tag.
     #[must_use]
     pub const fn address_tag(mut self, address_tag: AddressHeaderTag) -> Self {
@@ -76,6 +131,12 @@ impl Builder {
         self
     }
 
+    /// Set the [`ConsoleHeaderTag`] tag from the given [`ConsoleFlags`].
+    #[must_use]
+    pub const fn console_flags(self, flags: HeaderTagFlag, console_flags: ConsoleFlags) -> Self {
+        self.console_tag(ConsoleHeaderTag::new(flags, console_flags))
+    }
+
     /// Set the [`FramebufferHeaderTag`] tag.
     #[must_use]
     pub const fn framebuffer_tag(mut self, framebuffer_tag: FramebufferHeaderTag) -> Self {
@@ -83,6 +144,19 @@ impl Builder {
         self
     }
 
+    /// Set the [`FramebufferHeaderTag`] tag for the given preferred mode. Use
+    /// `0` for values without a preference.
+    #[must_use]
+    pub const fn framebuffer(
+        self,
+        flags: HeaderTagFlag,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> Self {
+        self.framebuffer_tag(FramebufferHeaderTag::new(flags, width, height, depth))
+    }
+
     /// Set the [`ModuleAlignHeaderTag`] tag.
     #[must_use]
     pub const fn module_align_tag(mut self, module_align_tag: ModuleAlignHeaderTag) -> Self {
@@ -97,6 +171,13 @@ impl Builder {
         self
     }
 
+    /// Add an [`EfiBootServiceHeaderTag`] to keep the UEFI boot services
+    /// running.
+    #[must_use]
+    pub const fn wants_boot_services(self, flags: HeaderTagFlag) -> Self {
+        self.efi_bs_tag(EfiBootServiceHeaderTag::new(flags))
+    }
+
     /// Set the [`EntryEfi32HeaderTag`] tag.
     #[must_use]
     pub const fn efi_32_tag(mut self, efi_32_tag: EntryEfi32HeaderTag) -> Self {
@@ -118,54 +199,80 @@ impl Builder {
         self
     }
 
+    /// Adds a tag with the given raw type, flags, and payload. This is meant
+    /// for tags that are unknown to this crate, for example, for experimental
+    /// agreements between a kernel and a bootloader.
+    #[must_use]
+    pub fn add_raw_tag(mut self, typ: u16, flags: HeaderTagFlag, payload: &[u8]) -> Self {
+        let header = HeaderTagHeader::new(HeaderTagType::from(typ), flags, 0);
+        self.custom_tags.push(new_boxed(header, &[payload]));
+        self
+    }
+
     /// Returns properly aligned bytes on the heap representing a valid
     /// Multiboot2 header structure.
+    ///
+    /// Each tag is padded to an 8-byte boundary and the [`EndHeaderTag`] is
+    /// appended. The total length and the checksum of the header are
+    /// computed automatically.
     #[must_use]
     pub fn build(self) -> Box<DynSizedStructure<Multiboot2BasicHeader>> {
         let header = Multiboot2BasicHeader::new(self.arch, 0);
+        let end_tag = EndHeaderTag::new();
         let mut byte_refs = Vec::new();
-        if let Some(tag) = self.information_request_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+        if let Some(tag) = self.information_request_tag.as_deref() {
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
         }
         if let Some(tag) = self.address_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
         }
         if let Some(tag) = self.entry_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
         }
         if let Some(tag) = self.console_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
         }
         if let Some(tag) = self.framebuffer_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
         }
         if let Some(tag) = self.module_align_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
         }
         if let Some(tag) = self.efi_bs_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
         }
         if let Some(tag) = self.efi_32_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
         }
         if let Some(tag) = self.efi_64_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
         }
         if let Some(tag) = self.relocatable_tag.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.size());
+        }
+        for tag in &self.custom_tags {
+            push_tag(&mut byte_refs, tag.as_bytes().as_ref(), tag.header().size());
         }
-        // TODO add support for custom tags once someone requests it.
+        push_tag(&mut byte_refs, end_tag.as_bytes().as_ref(), end_tag.size());
         new_boxed(header, byte_refs.as_slice())
     }
 }
 
+/// Pushes the first `size` bytes of `tag` and the padding to the next
+/// [`ALIGNMENT`] boundary.
+fn push_tag<'a>(byte_refs: &mut Vec<&'a [u8]>, tag: &'a [u8], size: u32) {
+    let size = size as usize;
+    byte_refs.push(&tag[..size]);
+    byte_refs.push(&PADDING[..size.next_multiple_of(ALIGNMENT) - size]);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ConsoleHeaderTagFlags::ConsoleRequired;
     use crate::HeaderTagFlag::{Optional, Required};
     use crate::RelocatableHeaderTagPreference::High;
-    use crate::{MbiTagType, Multiboot2Header};
+    use crate::{BootEnvironment, MbiTagType, Multiboot2Header};
+    use multiboot2_common::Header;
 
     #[test]
     fn build_and_parse() {
@@ -201,7 +308,7 @@ mod tests {
                 Required, 0x1000, 0x2000, 0x3000, 0x4000,
             ))
             .entry_tag(EntryAddressHeaderTag::new(Required, 0x5000))
-            .console_tag(ConsoleHeaderTag::new(Required, ConsoleRequired))
+            .console_flags(Required, ConsoleFlags::CONSOLE_REQUIRED)
             .framebuffer_tag(FramebufferHeaderTag::new(Optional, 720, 1024, 8))
             .module_align_tag(ModuleAlignHeaderTag::new(Required))
             .efi_bs_tag(EfiBootServiceHeaderTag::new(Optional))
@@ -209,7 +316,8 @@ mod tests {
             .efi_64_tag(EntryEfi64HeaderTag::new(Required, 0x8000))
             .relocatable_tag(RelocatableHeaderTag::new(
                 Required, 0x9000, 0x10000, 4096, High,
-            ));
+            ))
+            .add_raw_tag(0x1337, Optional, &[1, 2, 3, 4, 5]);
 
         let structure = builder.build();
         let header =
@@ -217,10 +325,42 @@ mod tests {
                 .unwrap();
 
         assert!(header.verify_checksum());
+        assert!(header.requires_module_alignment());
+        assert!(header.wants_boot_services());
+        assert!(!header.has_unsupported_required_tags());
+        let unknown = header.unknown_tags().collect::<Vec<_>>();
+        assert_eq!(unknown.len(), 1);
+        assert_eq!(unknown[0].header().typ(), HeaderTagType::Custom(0x1337));
+        assert_eq!(unknown[0].header().flags(), Optional);
+        assert_eq!(
+            &unknown[0].payload()[..unknown[0].header().payload_len()],
+            &[1, 2, 3, 4, 5]
+        );
+        assert_eq!(header.entry_point(BootEnvironment::Bios), Some(0x5000));
+        assert_eq!(header.entry_point(BootEnvironment::Efi32), Some(0x7000));
+        assert_eq!(header.entry_point(BootEnvironment::Efi64), Some(0x8000));
+        assert_eq!(
+            header.checksum(),
+            0_u32
+                .wrapping_sub(crate::MAGIC)
+                .wrapping_sub(header.arch() as u32)
+                .wrapping_sub(header.length())
+        );
 
         for tag in header.iter() {
             dbg!(tag);
         }
+        let debug = format!("{header:?}");
+        assert!(debug.contains("RelocatableHeaderTag"));
+        assert!(debug.contains("UnknownHeaderTag"));
+        assert!(debug.contains("payload: 0102030405"));
+        #[cfg(feature = "serde")]
+        {
+            let mut buf = [0; 512];
+            let bytes = postcard::to_slice(&header, &mut buf).unwrap();
+            // The unknown tag is serialized with its payload as hex string.
+            assert!(bytes.windows(10).any(|w| w == b"0102030405"));
+        }
 
         dbg!(header.arch());
         dbg!(header.checksum());
@@ -234,5 +374,145 @@ mod tests {
         dbg!(header.entry_address_efi32_tag());
         dbg!(header.entry_address_efi64_tag());
         dbg!(header.relocatable_tag());
+
+        assert_eq!(header.link_base_addr(), Some(0x2000));
+        let slide = header
+            .load_slide(&multiboot2::ImageLoadPhysAddrTag::new(0x102000))
+            .unwrap();
+        assert_eq!(slide.apply(0x2000), 0x102000);
+
+        #[cfg(feature = "placement")]
+        assert_eq!(
+            header.kernel_placement(),
+            Some(multiboot2::KernelPlacement::Relocatable {
+                min_addr: 0x9000,
+                max_addr: 0x10000,
+                align: 4096,
+                prefer_high: true,
+            })
+        );
+    }
+
+    /// Builds headers from all combinations of tags and checks that each of
+    /// them parses again with the expected tags, padding, and checksum.
+    #[test]
+    fn build_and_parse_all_combinations() {
+        for mask in 0_u32..(1 << 11) {
+            let has = |bit: u32| mask & (1 << bit) != 0;
+            let requests = [MbiTagType::Cmdline; 4];
+            let requests = &requests[..mask as usize % 5];
+            let payload = [0xff_u8; 8];
+            let payload = &payload[..mask as usize % 9];
+
+            let mut builder = Builder::new(HeaderTagISA::I386);
+            let mut expected = Vec::new();
+            if has(0) {
+                builder = builder.information_requests(Optional, requests);
+                expected.push((HeaderTagType::InformationRequest, 8 + 4 * requests.len()));
+            }
+            if has(1) {
+                builder = builder.address_tag(AddressHeaderTag::new(Required, 1, 2, 3, 4));
+                expected.push((HeaderTagType::Address, 24));
+            }
+            if has(2) {
+                builder = builder.entry_tag(EntryAddressHeaderTag::new(Required, 1));
+                expected.push((HeaderTagType::EntryAddress, 12));
+            }
+            if has(3) {
+                builder = builder.console_flags(Optional, ConsoleFlags::EGA_TEXT_SUPPORTED);
+                expected.push((HeaderTagType::ConsoleFlags, 12));
+            }
+            if has(4) {
+                builder = builder.framebuffer(Optional, 1, 2, 3);
+                expected.push((HeaderTagType::Framebuffer, 20));
+            }
+            if has(5) {
+                builder = builder.module_align_tag(ModuleAlignHeaderTag::new(Required));
+                expected.push((HeaderTagType::ModuleAlign, 8));
+            }
+            if has(6) {
+                builder = builder.wants_boot_services(Optional);
+                expected.push((HeaderTagType::EfiBS, 8));
+            }
+            if has(7) {
+                builder = builder.efi_32_tag(EntryEfi32HeaderTag::new(Required, 1));
+                expected.push((HeaderTagType::EntryAddressEFI32, 12));
+            }
+            if has(8) {
+                builder = builder.efi_64_tag(EntryEfi64HeaderTag::new(Required, 1));
+                expected.push((HeaderTagType::EntryAddressEFI64, 12));
+            }
+            if has(9) {
+                builder =
+                    builder.relocatable_tag(RelocatableHeaderTag::new(Required, 1, 2, 3, High));
+                expected.push((HeaderTagType::Relocatable, 24));
+            }
+            if has(10) {
+                builder = builder.add_raw_tag(0x1337, Optional, payload);
+                expected.push((HeaderTagType::Custom(0x1337), 8 + payload.len()));
+            }
+            expected.push((HeaderTagType::End, 8));
+
+            let structure = builder.build();
+            let bytes = structure.as_bytes().as_ref();
+            let header = unsafe { Multiboot2Header::load(bytes.as_ptr().cast()) }.unwrap();
+            assert!(header.verify_checksum());
+            assert_eq!(header.length() as usize, bytes.len());
+            assert_eq!(bytes.len() % ALIGNMENT, 0);
+
+            let tags = header
+                .iter()
+                .map(|tag| (tag.header().typ(), tag.header().size() as usize))
+                .collect::<Vec<_>>();
+            assert_eq!(tags, expected, "mask: {mask:#b}");
+            // The relocatable tag alone doesn't tell the link-time address.
+            assert_eq!(
+                header.link_base_addr(),
+                has(1).then_some(2),
+                "mask: {mask:#b}"
+            );
+        }
+    }
+
+    #[test]
+    fn unsupported_required_tags() {
+        let structure = Builder::new(HeaderTagISA::I386)
+            .add_raw_tag(0x1000, Optional, &[])
+            .add_raw_tag(0x1001, Required, &[0; 4])
+            .add_raw_tag(0x1002, Required, &[])
+            .build();
+        let header =
+            unsafe { Multiboot2Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }
+                .unwrap();
+        assert!(header.has_unsupported_required_tags());
+        assert!(header
+            .unsupported_required_tags()
+            .eq([HeaderTagType::Custom(0x1001), HeaderTagType::Custom(0x1002)]));
+    }
+
+    #[test]
+    fn presets() {
+        let structure = Builder::grub_bios_default().build();
+        let header =
+            unsafe { Multiboot2Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }
+                .unwrap();
+        assert!(header
+            .information_request_tag()
+            .unwrap()
+            .request_types()
+            .eq([MbiTagType::Cmdline, MbiTagType::Mmap, MbiTagType::Module]));
+        assert_eq!(header.framebuffer_tag().unwrap().preferred_width(), None);
+        assert!(header.requires_module_alignment());
+        assert_eq!(header.entry_point(BootEnvironment::Bios), None);
+
+        let structure =
+            Builder::uefi64_relocatable(0x200000, 0x40000000, 0x200000, 0x201000).build();
+        let header =
+            unsafe { Multiboot2Header::load(structure.as_bytes().as_ref().as_ptr().cast()) }
+                .unwrap();
+        assert!(header.information_request_tag().is_some());
+        assert_eq!(header.relocatable_tag().unwrap().align(), 0x200000);
+        assert!(header.wants_boot_services());
+        assert_eq!(header.entry_point(BootEnvironment::Efi64), Some(0x201000));
     }
 }
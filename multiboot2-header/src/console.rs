@@ -1,15 +1,20 @@
 use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use bitflags::bitflags;
 use core::mem;
 use multiboot2_common::{MaybeDynSized, Tag};
 
-/// Possible flags for [`ConsoleHeaderTag`].
-#[repr(u32)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum ConsoleHeaderTagFlags {
-    /// Console required.
-    ConsoleRequired = 0,
-    /// EGA text support.
-    EgaTextSupported = 1,
+bitflags! {
+    /// Flags of the [`ConsoleHeaderTag`].
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct ConsoleFlags: u32 {
+        /// At least one of the supported consoles must be present and
+        /// information about it must be available in the MBI.
+        const CONSOLE_REQUIRED = 0x1;
+
+        /// The image has support for EGA text mode.
+        const EGA_TEXT_SUPPORTED = 0x2;
+    }
 }
 
 /// Tells that a console must be available in MBI.
@@ -18,13 +23,13 @@ pub enum ConsoleHeaderTagFlags {
 #[repr(C, align(8))]
 pub struct ConsoleHeaderTag {
     header: HeaderTagHeader,
-    console_flags: ConsoleHeaderTagFlags,
+    console_flags: ConsoleFlags,
 }
 
 impl ConsoleHeaderTag {
     /// Constructs a new tag.
     #[must_use]
-    pub const fn new(flags: HeaderTagFlag, console_flags: ConsoleHeaderTagFlags) -> Self {
+    pub const fn new(flags: HeaderTagFlag, console_flags: ConsoleFlags) -> Self {
         let header =
             HeaderTagHeader::new(HeaderTagType::ConsoleFlags, flags, Self::BASE_SIZE as u32);
         Self {
@@ -51,9 +56,9 @@ impl ConsoleHeaderTag {
         self.header.size()
     }
 
-    /// Returns the [`ConsoleHeaderTagFlags`].
+    /// Returns the [`ConsoleFlags`].
     #[must_use]
-    pub const fn console_flags(&self) -> ConsoleHeaderTagFlags {
+    pub const fn console_flags(&self) -> ConsoleFlags {
         self.console_flags
     }
 }
@@ -70,3 +75,21 @@ impl Tag for ConsoleHeaderTag {
     type IDType = HeaderTagType;
     const ID: HeaderTagType = HeaderTagType::ConsoleFlags;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_console_flags() {
+        let tag = ConsoleHeaderTag::new(
+            HeaderTagFlag::Required,
+            ConsoleFlags::CONSOLE_REQUIRED | ConsoleFlags::EGA_TEXT_SUPPORTED,
+        );
+        assert_eq!(tag.size(), 12);
+        assert_eq!(tag.console_flags().bits(), 0x3);
+        assert!(tag
+            .console_flags()
+            .contains(ConsoleFlags::EGA_TEXT_SUPPORTED));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
+use core::cmp::Reverse;
 use core::mem;
 use multiboot2_common::{MaybeDynSized, Tag};
 
@@ -64,6 +65,61 @@ impl FramebufferHeaderTag {
     pub const fn depth(&self) -> u32 {
         self.depth
     }
+
+    /// Returns the preferred width, or `None` if the image has no preference.
+    #[must_use]
+    pub const fn preferred_width(&self) -> Option<u32> {
+        Self::preference(self.width)
+    }
+
+    /// Returns the preferred height, or `None` if the image has no preference.
+    #[must_use]
+    pub const fn preferred_height(&self) -> Option<u32> {
+        Self::preference(self.height)
+    }
+
+    /// Returns the preferred depth in bits per pixel, or `None` if the image
+    /// has no preference.
+    #[must_use]
+    pub const fn preferred_depth(&self) -> Option<u32> {
+        Self::preference(self.depth)
+    }
+
+    const fn preference(value: u32) -> Option<u32> {
+        if value == 0 {
+            None
+        } else {
+            Some(value)
+        }
+    }
+
+    /// Chooses the mode that matches this request best from the `(width,
+    /// height, depth)` triples of the available GOP or VBE modes and returns
+    /// its index.
+    ///
+    /// The mode closest to the preferred resolution wins, then the one
+    /// closest to the preferred depth. Where the image has no preference, or
+    /// on a tie, larger modes are preferred. Returns `None` if `modes` is
+    /// empty.
+    #[must_use]
+    pub fn best_mode(&self, modes: &[(u32, u32, u32)]) -> Option<usize> {
+        let distance = |preference: Option<u32>, value: u32| {
+            preference.map_or(0, |preference| u64::from(preference.abs_diff(value)))
+        };
+        modes
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &(width, height, depth))| {
+                (
+                    distance(self.preferred_width(), width)
+                        + distance(self.preferred_height(), height),
+                    distance(self.preferred_depth(), depth),
+                    Reverse(u64::from(width) * u64::from(height)),
+                    Reverse(depth),
+                )
+            })
+            .map(|(index, _)| index)
+    }
 }
 
 impl MaybeDynSized for FramebufferHeaderTag {
@@ -78,3 +134,36 @@ impl Tag for FramebufferHeaderTag {
     type IDType = HeaderTagType;
     const ID: HeaderTagType = HeaderTagType::Framebuffer;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [(u32, u32, u32); 4] = [
+        (640, 480, 32),
+        (1024, 768, 16),
+        (1024, 768, 32),
+        (1920, 1080, 32),
+    ];
+
+    #[test]
+    fn test_best_mode() {
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Optional, 1024, 768, 16);
+        assert_eq!(tag.preferred_width(), Some(1024));
+        assert_eq!(tag.best_mode(&MODES), Some(1));
+        assert_eq!(tag.best_mode(&[]), None);
+
+        // No depth preference.
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Optional, 1024, 768, 0);
+        assert_eq!(tag.preferred_depth(), None);
+        assert_eq!(tag.best_mode(&MODES), Some(2));
+
+        // No preference at all.
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Optional, 0, 0, 0);
+        assert_eq!(tag.best_mode(&MODES), Some(3));
+
+        // No exact match.
+        let tag = FramebufferHeaderTag::new(HeaderTagFlag::Optional, 800, 600, 32);
+        assert_eq!(tag.best_mode(&MODES), Some(0));
+    }
+}
@@ -1,15 +1,19 @@
 use crate::{
-    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EntryAddressHeaderTag,
-    EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag, HeaderTagHeader, HeaderTagISA,
-    HeaderTagType, InformationRequestHeaderTag, ModuleAlignHeaderTag, RelocatableHeaderTag,
-    TagIter,
+    AddressHeaderTag, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
+    GenericHeaderTag, HeaderTagFlag, HeaderTagHeader, HeaderTagISA, HeaderTagType,
+    InformationRequestHeaderTag, MbiTagType, ModuleAlignHeaderTag, RelocatableHeaderTag, TagIter,
 };
-#[cfg(feature = "unstable")]
 use core::error::Error;
-use core::fmt::{Debug, Formatter};
+use core::fmt::{Debug, Display, Formatter};
 use core::mem::size_of;
 use core::ptr::NonNull;
-use multiboot2_common::{DynSizedStructure, Header, MemoryError, Tag, ALIGNMENT};
+#[cfg(feature = "placement")]
+use multiboot2::KernelPlacement;
+use multiboot2::{BootInformation, ImageLoadPhysAddrTag, LoadSlide};
+use multiboot2_common::{
+    increase_to_alignment, DynSizedStructure, Header, MaybeDynSized, MemoryError, Tag, ALIGNMENT,
+};
 
 /// Magic value for a [`Multiboot2Header`], as defined by the spec.
 pub const MAGIC: u32 = 0xe85250d6;
@@ -25,6 +29,10 @@ pub const MAGIC: u32 = 0xe85250d6;
 pub struct Multiboot2Header<'a>(&'a DynSizedStructure<Multiboot2BasicHeader>);
 
 impl<'a> Multiboot2Header<'a> {
+    /// The header must be contained in this many bytes at the beginning of
+    /// the image.
+    pub const SEARCH_LIMIT: usize = 32768;
+
     /// Public constructor for this type with various validations.
     ///
     /// If the header is invalid, it returns a [`LoadError`].
@@ -32,24 +40,102 @@ impl<'a> Multiboot2Header<'a> {
     /// - `addr` is a null-pointer
     /// - `addr` isn't 8-byte aligned
     /// - the magic value of the header is not present
+    /// - the architecture is not defined by the spec
     /// - the checksum field is invalid
+    /// - a tag exceeds the header or is too small for its type
+    ///
+    /// All fields are read in the native byte order, as the header of a
+    /// big-endian MIPS image is in big-endian.
     ///
     /// # Safety
     /// This function may produce undefined behaviour, if the provided `addr` is not a valid
     /// Multiboot2 header pointer.
     pub unsafe fn load(ptr: *const Multiboot2BasicHeader) -> Result<Self, LoadError> {
         let ptr = NonNull::new(ptr.cast_mut()).ok_or(LoadError::Memory(MemoryError::Null))?;
+        if ptr.as_ptr().align_offset(ALIGNMENT) != 0 {
+            return Err(LoadError::Memory(MemoryError::WrongAlignment));
+        }
+        if (ptr.as_ref().length as usize) < size_of::<Multiboot2BasicHeader>() {
+            return Err(LoadError::Memory(MemoryError::ShorterThanHeader));
+        }
         let inner = DynSizedStructure::ref_from_ptr(ptr).map_err(LoadError::Memory)?;
-        let this = Self(inner);
+        Self(inner).validate()
+    }
 
-        let header = this.0.header();
+    /// Like [`Self::load`], but for a header at the beginning of `bytes`,
+    /// e.g., from a memory-mapped kernel file. This doesn't copy and is safe,
+    /// as the header and all tags are checked to be within `bytes`.
+    ///
+    /// `bytes` must be 8-byte aligned and may be longer than the header. Use
+    /// [`Self::tags_with_offsets`] to find out where the tags are.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, LoadError> {
+        if bytes.as_ptr().align_offset(ALIGNMENT) != 0 {
+            return Err(LoadError::Memory(MemoryError::WrongAlignment));
+        }
+        let length = bytes
+            .get(8..12)
+            .map(|length| u32::from_ne_bytes(length.try_into().unwrap()) as usize)
+            .ok_or(LoadError::Memory(MemoryError::ShorterThanHeader))?;
+        if length < size_of::<Multiboot2BasicHeader>() {
+            return Err(LoadError::Memory(MemoryError::ShorterThanHeader));
+        }
+        let bytes = bytes
+            .get(..increase_to_alignment(length))
+            .ok_or(LoadError::Memory(MemoryError::InvalidReportedTotalSize))?;
+        let inner = DynSizedStructure::ref_from_slice(bytes).map_err(LoadError::Memory)?;
+        Self(inner).validate()
+    }
+
+    /// Checks the magic, the architecture, the checksum, and the bounds and
+    /// sizes of all tags.
+    fn validate(self) -> Result<Self, LoadError> {
+        let header = self.0.header();
         if header.header_magic != MAGIC {
             return Err(LoadError::MagicNotFound);
         }
+        if HeaderTagISA::from_u32(header.arch).is_none() {
+            return Err(LoadError::UnknownArch);
+        }
         if !header.verify_checksum() {
             return Err(LoadError::ChecksumMismatch);
         }
-        Ok(this)
+
+        let tags = &self.0.payload()[..header.payload_len()];
+        let base = size_of::<Multiboot2BasicHeader>();
+        let mut offset = 0;
+        while offset < tags.len() {
+            let read = |at: usize, len: usize| tags.get(offset + at..offset + at + len);
+            let invalid = LoadError::InvalidTag(base + offset);
+            let (Some(typ), Some(size)) = (read(0, 2), read(4, 4)) else {
+                return Err(invalid);
+            };
+            let typ = HeaderTagType::from(u16::from_ne_bytes(typ.try_into().unwrap()));
+            let size = u32::from_ne_bytes(size.try_into().unwrap()) as usize;
+            if size < size_of::<HeaderTagHeader>() || read(0, size).is_none() {
+                return Err(invalid);
+            }
+            let valid = match typ {
+                HeaderTagType::InformationRequest => (size - 8) % 4 == 0,
+                HeaderTagType::Address => size >= AddressHeaderTag::BASE_SIZE,
+                HeaderTagType::EntryAddress => size >= EntryAddressHeaderTag::BASE_SIZE,
+                HeaderTagType::ConsoleFlags => size >= ConsoleHeaderTag::BASE_SIZE,
+                HeaderTagType::Framebuffer => size >= FramebufferHeaderTag::BASE_SIZE,
+                HeaderTagType::EntryAddressEFI32 => size >= EntryEfi32HeaderTag::BASE_SIZE,
+                HeaderTagType::EntryAddressEFI64 => size >= EntryEfi64HeaderTag::BASE_SIZE,
+                HeaderTagType::Relocatable => {
+                    size >= RelocatableHeaderTag::BASE_SIZE
+                        && read(20, 4).is_some_and(|preference| {
+                            u32::from_ne_bytes(preference.try_into().unwrap()) <= 2
+                        })
+                }
+                _ => true,
+            };
+            if !valid {
+                return Err(invalid);
+            }
+            offset = increase_to_alignment(offset + size);
+        }
+        Ok(self)
     }
 
     /// Find the header in a given slice.
@@ -64,9 +150,9 @@ impl<'a> Multiboot2Header<'a> {
             return Err(LoadError::Memory(MemoryError::WrongAlignment));
         }
 
-        let mut windows = buffer[0..8192].windows(4);
+        let mut windows = buffer[..buffer.len().min(Self::SEARCH_LIMIT)].windows(4);
         let magic_index = match windows.position(|vals| {
-            u32::from_le_bytes(vals.try_into().unwrap()) // yes, there's 4 bytes here
+            u32::from_ne_bytes(vals.try_into().unwrap()) // yes, there's 4 bytes here
             == MAGIC
         }) {
             Some(idx) => {
@@ -87,7 +173,7 @@ impl<'a> Multiboot2Header<'a> {
         windows.next();
         windows.next();
         windows.next();
-        let header_length: usize = u32::from_le_bytes(
+        let header_length: usize = u32::from_ne_bytes(
             windows
                 .next()
                 .ok_or(LoadError::Memory(MemoryError::MissingPadding))?
@@ -96,18 +182,94 @@ impl<'a> Multiboot2Header<'a> {
         )
         .try_into()
         .unwrap();
-        Ok(Some((
-            &buffer[magic_index..magic_index + header_length],
-            magic_index as u32,
-        )))
+        let header = buffer
+            .get(magic_index..magic_index + header_length)
+            .ok_or(LoadError::Memory(MemoryError::InvalidReportedTotalSize))?;
+        Ok(Some((header, magic_index as u32)))
+    }
+
+    /// Searches the header in a kernel image and returns it together with its
+    /// offset in the image.
+    ///
+    /// As required by the spec, only the first [`Self::SEARCH_LIMIT`] bytes
+    /// are scanned at 8-byte aligned offsets. A candidate is only accepted if
+    /// its architecture is known, its length is valid, the header is contained
+    /// in the scanned area, and its checksum is correct. Otherwise, the search
+    /// continues, as the magic value may also occur by chance.
+    ///
+    /// Returns an error if the image is not 8-byte aligned, as the header
+    /// can't be referenced in that case.
+    pub fn search(image: &'a [u8]) -> Result<Option<(Self, usize)>, LoadError> {
+        for (offset, candidate) in Self::candidates(image)? {
+            match candidate {
+                Ok(header) => return Ok(Some((header, offset))),
+                Err(e @ LoadError::InvalidTag(_)) => return Err(e),
+                Err(_) => continue,
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns every occurrence of the magic value at an 8-byte aligned
+    /// offset in the first [`Self::SEARCH_LIMIT`] bytes of a kernel image,
+    /// together with the header there or why it was rejected, e.g., to
+    /// diagnose why [`Self::search`] doesn't find a header.
+    ///
+    /// A candidate is rejected like in [`Self::from_bytes`], and if the
+    /// header exceeds the scanned area.
+    ///
+    /// Returns an error if the image is not 8-byte aligned.
+    pub fn candidates(
+        image: &'a [u8],
+    ) -> Result<impl Iterator<Item = (usize, Result<Self, LoadError>)> + 'a, LoadError> {
+        if image.as_ptr().align_offset(ALIGNMENT) != 0 {
+            return Err(LoadError::Memory(MemoryError::WrongAlignment));
+        }
+        let area = &image[..image.len().min(Self::SEARCH_LIMIT)];
+        Ok((0..area.len())
+            .step_by(ALIGNMENT)
+            .filter(|&offset| area.get(offset..offset + 4) == Some(&MAGIC.to_ne_bytes()[..]))
+            .map(|offset| (offset, Self::from_bytes(&area[offset..]))))
     }
 
     /// Returns a [`TagIter`].
     #[must_use]
-    pub fn iter(&self) -> TagIter {
+    pub fn iter(&self) -> TagIter<'_> {
         TagIter::new(self.0.payload())
     }
 
+    /// Returns an iterator over all tags together with their offset from the
+    /// beginning of the header.
+    pub fn tags_with_offsets(&self) -> impl Iterator<Item = (usize, &GenericHeaderTag)> {
+        let base = (self.0 as *const DynSizedStructure<Multiboot2BasicHeader>).cast::<u8>();
+        self.iter().map(move |tag| {
+            let ptr = (tag as *const GenericHeaderTag).cast::<u8>();
+            (ptr as usize - base as usize, tag)
+        })
+    }
+
+    /// Returns an iterator over all tags whose type is unknown to this crate,
+    /// i.e., all tags of type [`HeaderTagType::Custom`].
+    pub fn unknown_tags(&self) -> impl Iterator<Item = &GenericHeaderTag> {
+        self.iter()
+            .filter(|tag| matches!(tag.header().typ(), HeaderTagType::Custom(_)))
+    }
+
+    /// Returns the types of all tags that are unknown to this crate but marked
+    /// as required. A bootloader must refuse to boot the image if there are
+    /// any.
+    pub fn unsupported_required_tags(&self) -> impl Iterator<Item = HeaderTagType> + '_ {
+        self.unknown_tags()
+            .filter(|tag| tag.header().is_required())
+            .map(|tag| tag.header().typ())
+    }
+
+    /// Returns whether there are any [`Self::unsupported_required_tags`].
+    #[must_use]
+    pub fn has_unsupported_required_tags(&self) -> bool {
+        self.unsupported_required_tags().next().is_some()
+    }
+
     /// Wrapper around [`Multiboot2BasicHeader::verify_checksum`].
     #[must_use]
     pub const fn verify_checksum(&self) -> bool {
@@ -187,18 +349,107 @@ impl<'a> Multiboot2Header<'a> {
         self.get_tag()
     }
 
+    /// Returns whether the image requires boot modules to be page aligned,
+    /// i.e., whether a [`ModuleAlignHeaderTag`] is present.
+    #[must_use]
+    pub fn requires_module_alignment(&self) -> bool {
+        self.module_align_tag().is_some()
+    }
+
     /// Search for the [`EfiBootServiceHeaderTag`] header tag.
     #[must_use]
     pub fn efi_boot_services_tag(&self) -> Option<&EfiBootServiceHeaderTag> {
         self.get_tag()
     }
 
+    /// Returns whether the image wants the UEFI boot services to be left
+    /// running, i.e., whether an [`EfiBootServiceHeaderTag`] is present.
+    #[must_use]
+    pub fn wants_boot_services(&self) -> bool {
+        self.efi_boot_services_tag().is_some()
+    }
+
     /// Search for the [`RelocatableHeaderTag`] header tag.
     #[must_use]
     pub fn relocatable_tag(&self) -> Option<&RelocatableHeaderTag> {
         self.get_tag()
     }
 
+    /// Returns the link-time base address of the image, i.e., the load
+    /// address of the [`AddressHeaderTag`]. `None` means that the address
+    /// must be taken from the ELF file, as the header doesn't specify it.
+    #[must_use]
+    pub fn link_base_addr(&self) -> Option<u32> {
+        self.address_tag().map(|tag| tag.load_addr())
+    }
+
+    /// Returns the [`LoadSlide`] of the image, given the
+    /// [`ImageLoadPhysAddrTag`] the bootloader passed. The link-time base
+    /// address is taken from [`Self::link_base_addr`].
+    #[must_use]
+    pub fn load_slide(&self, load_base_addr: &ImageLoadPhysAddrTag) -> Option<LoadSlide> {
+        self.link_base_addr()
+            .map(|link_base_addr| load_base_addr.slide(u64::from(link_base_addr)))
+    }
+
+    /// Returns the entry address of the image for the given
+    /// [`BootEnvironment`].
+    ///
+    /// On EFI, the address of the [`EntryEfi32HeaderTag`] or
+    /// [`EntryEfi64HeaderTag`] is used if the image also wants the boot
+    /// services to be left running and is an i386 image, as required by the
+    /// spec. Otherwise, the address of the [`EntryAddressHeaderTag`] is used.
+    /// `None` means that the entry point of the ELF file must be used.
+    #[must_use]
+    pub fn entry_point(&self, env: BootEnvironment) -> Option<u32> {
+        let efi_entry = match env {
+            _ if self.arch() != HeaderTagISA::I386 => None,
+            BootEnvironment::Bios => None,
+            BootEnvironment::Efi32 => self.entry_address_efi32_tag().map(|tag| tag.entry_addr()),
+            BootEnvironment::Efi64 => self.entry_address_efi64_tag().map(|tag| tag.entry_addr()),
+        };
+        efi_entry
+            .filter(|_| self.wants_boot_services())
+            .or_else(|| self.entry_address_tag().map(|tag| tag.entry_addr()))
+    }
+
+    /// Returns the tag types requested by the information request tags of
+    /// this header that are missing in the given boot information, together
+    /// with the [`HeaderTagFlag`] of the request.
+    ///
+    /// Bootloaders can use this as a final check before jumping to the
+    /// kernel: if a [`HeaderTagFlag::Required`] request is missing, the
+    /// kernel must not be booted.
+    pub fn missing_requests<'b>(
+        &'b self,
+        mbi: &'b BootInformation<'b>,
+    ) -> impl Iterator<Item = (MbiTagType, HeaderTagFlag)> + 'b {
+        self.iter()
+            .filter(|tag| tag.header().typ() == HeaderTagType::InformationRequest)
+            .filter_map(|tag| tag.try_cast::<InformationRequestHeaderTag>().ok())
+            .flat_map(|tag| tag.request_types().map(|typ| (typ, tag.flags())))
+            .filter(|&(typ, _)| typ != MbiTagType::End)
+            .filter(|&(typ, _)| mbi.get_generic_tag(typ).is_none())
+    }
+
+    /// Returns the [`KernelPlacement`] the image requests, derived from the
+    /// relocatable tag or, if absent, from the address tag. Feed it into a
+    /// [`multiboot2::BootLayoutPlanner`].
+    #[cfg(feature = "placement")]
+    #[must_use]
+    pub fn kernel_placement(&self) -> Option<KernelPlacement> {
+        if let Some(tag) = self.relocatable_tag() {
+            return Some(KernelPlacement::Relocatable {
+                min_addr: u64::from(tag.min_addr()),
+                max_addr: u64::from(tag.max_addr()),
+                align: u64::from(tag.align()),
+                prefer_high: tag.preference() == crate::RelocatableHeaderTagPreference::High,
+            });
+        }
+        self.address_tag()
+            .map(|tag| KernelPlacement::Fixed(u64::from(tag.load_addr())))
+    }
+
     /// Searches for the specified tag by iterating the structure and returns
     /// the first occurrence, if present.
     #[must_use]
@@ -207,7 +458,7 @@ impl<'a> Multiboot2Header<'a> {
     ) -> Option<&'a T> {
         self.iter()
             .find(|tag| tag.header().typ() == T::ID)
-            .map(|tag| tag.cast::<T>())
+            .and_then(|tag| tag.try_cast::<T>().ok())
     }
 }
 
@@ -218,12 +469,89 @@ impl Debug for Multiboot2Header<'_> {
             .field("arch", &self.arch())
             .field("length", &self.length())
             .field("checksum", &self.checksum())
-            // TODO better debug impl
-            .field("tags", &"<tags iter>")
+            .field(
+                "requires_module_alignment",
+                &self.requires_module_alignment(),
+            )
+            .field("wants_boot_services", &self.wants_boot_services())
+            .field("tags", &TagsDebug(self))
             .finish()
     }
 }
 
+/// Prints all tags of a header as a list.
+struct TagsDebug<'a, 'b>(&'b Multiboot2Header<'a>);
+
+impl Debug for TagsDebug<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.0.iter().map(TagDebug)).finish()
+    }
+}
+
+/// Prints a tag as its specific type, or with its payload as hexadecimal
+/// bytes if the type is unknown.
+struct TagDebug<'a>(&'a GenericHeaderTag);
+
+impl Debug for TagDebug<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let tag = self.0;
+        match tag.header().typ() {
+            HeaderTagType::End => tag.cast::<EndHeaderTag>().fmt(f),
+            HeaderTagType::InformationRequest => tag.cast::<InformationRequestHeaderTag>().fmt(f),
+            HeaderTagType::Address => tag.cast::<AddressHeaderTag>().fmt(f),
+            HeaderTagType::EntryAddress => tag.cast::<EntryAddressHeaderTag>().fmt(f),
+            HeaderTagType::ConsoleFlags => tag.cast::<ConsoleHeaderTag>().fmt(f),
+            HeaderTagType::Framebuffer => tag.cast::<FramebufferHeaderTag>().fmt(f),
+            HeaderTagType::ModuleAlign => tag.cast::<ModuleAlignHeaderTag>().fmt(f),
+            HeaderTagType::EfiBS => tag.cast::<EfiBootServiceHeaderTag>().fmt(f),
+            HeaderTagType::EntryAddressEFI32 => tag.cast::<EntryEfi32HeaderTag>().fmt(f),
+            HeaderTagType::EntryAddressEFI64 => tag.cast::<EntryEfi64HeaderTag>().fmt(f),
+            HeaderTagType::Relocatable => tag.cast::<RelocatableHeaderTag>().fmt(f),
+            HeaderTagType::Custom(_) => f
+                .debug_struct("UnknownHeaderTag")
+                .field("type", &tag.header().typ())
+                .field("flags", &tag.header().flags())
+                .field("size", &tag.header().size())
+                .field(
+                    "payload",
+                    &HexBytes(&tag.payload()[..tag.header().payload_len()]),
+                )
+                .finish(),
+        }
+    }
+}
+
+/// Prints bytes as a hexadecimal string.
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) struct HexBytes<'a>(pub(crate) &'a [u8]);
+
+impl Display for HexBytes<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for HexBytes<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// The environment a bootloader boots an image in. See
+/// [`Multiboot2Header::entry_point`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BootEnvironment {
+    /// Legacy BIOS or any other non-EFI platform.
+    Bios,
+    /// EFI on i386.
+    Efi32,
+    /// EFI on `x86_64`.
+    Efi64,
+}
+
 /// Errors that occur when a chunk of memory can't be parsed as
 /// [`Multiboot2Header`].
 #[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -232,12 +560,17 @@ pub enum LoadError {
     ChecksumMismatch,
     /// The header does not contain the correct magic number.
     MagicNotFound,
+    /// The tag at the given offset from the beginning of the header exceeds
+    /// the header or is too small for its type.
+    #[display("InvalidTag({})", _0)]
+    InvalidTag(usize),
+    /// The architecture of the header is not defined by the spec.
+    UnknownArch,
     /// The provided memory can't be parsed as [`Multiboot2Header`].
     /// See [`MemoryError`].
     Memory(MemoryError),
 }
 
-#[cfg(feature = "unstable")]
 impl Error for LoadError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
@@ -254,7 +587,8 @@ impl Error for LoadError {
 pub struct Multiboot2BasicHeader {
     /// Must be the value of [`MAGIC`].
     header_magic: u32,
-    arch: HeaderTagISA,
+    /// Raw value of the [`HeaderTagISA`].
+    arch: u32,
     length: u32,
     checksum: u32,
     // Followed by dynamic amount of dynamically sized header tags.
@@ -269,7 +603,7 @@ impl Multiboot2BasicHeader {
         let checksum = Self::calc_checksum(magic, arch, length);
         Self {
             header_magic: magic,
-            arch,
+            arch: arch as u32,
             length,
             checksum,
         }
@@ -278,14 +612,21 @@ impl Multiboot2BasicHeader {
     /// Verifies that a Multiboot2 header is valid.
     #[must_use]
     pub const fn verify_checksum(&self) -> bool {
-        let check = Self::calc_checksum(self.header_magic, self.arch, self.length);
+        let check = Self::calc_checksum_raw(self.header_magic, self.arch, self.length);
         check == self.checksum
     }
 
     /// Calculates the checksum as described in the spec.
     #[must_use]
     pub const fn calc_checksum(magic: u32, arch: HeaderTagISA, length: u32) -> u32 {
-        (0x100000000 - magic as u64 - arch as u64 - length as u64) as u32
+        Self::calc_checksum_raw(magic, arch as u32, length)
+    }
+
+    const fn calc_checksum_raw(magic: u32, arch: u32, length: u32) -> u32 {
+        0_u32
+            .wrapping_sub(magic)
+            .wrapping_sub(arch)
+            .wrapping_sub(length)
     }
 
     /// Returns the header magic.
@@ -297,7 +638,11 @@ impl Multiboot2BasicHeader {
     /// Returns the [`HeaderTagISA`].
     #[must_use]
     pub const fn arch(&self) -> HeaderTagISA {
-        self.arch
+        match HeaderTagISA::from_u32(self.arch) {
+            Some(arch) => arch,
+            // Unreachable, as the architecture is checked when loading.
+            None => HeaderTagISA::I386,
+        }
     }
 
     /// Returns the length.
@@ -320,7 +665,7 @@ impl Header for Multiboot2BasicHeader {
 
     fn set_size(&mut self, total_size: usize) {
         self.length = total_size as u32;
-        self.checksum = Self::calc_checksum(self.header_magic, self.arch, total_size as u32);
+        self.checksum = Self::calc_checksum_raw(self.header_magic, self.arch, total_size as u32);
     }
 }
 
@@ -328,7 +673,7 @@ impl Debug for Multiboot2BasicHeader {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Multiboot2Header")
             .field("header_magic", &{ self.header_magic })
-            .field("arch", &{ self.arch })
+            .field("arch", &self.arch())
             .field("length", &{ self.length })
             .field("checksum", &{ self.checksum })
             //.field("tags", &self.iter())
@@ -338,7 +683,165 @@ impl Debug for Multiboot2BasicHeader {
 
 #[cfg(test)]
 mod tests {
-    use crate::Multiboot2BasicHeader;
+    use crate::{
+        BootEnvironment, HeaderTagISA, HeaderTagType, LoadError, Multiboot2BasicHeader,
+        Multiboot2Header, MAGIC,
+    };
+    use multiboot2_common::test_utils::AlignedBytes;
+    use multiboot2_common::MemoryError;
+
+    /// Writes a minimal header, i.e., the basic header and the end tag, to
+    /// `offset`.
+    fn write_header(bytes: &mut [u8], offset: usize, checksum_delta: u32) {
+        let length = 24;
+        let checksum = Multiboot2BasicHeader::calc_checksum(MAGIC, HeaderTagISA::I386, length)
+            .wrapping_add(checksum_delta);
+        let words = [MAGIC, 0, length, checksum, 0, 8];
+        for (i, word) in words.iter().enumerate() {
+            bytes[offset + 4 * i..offset + 4 * (i + 1)].copy_from_slice(&word.to_ne_bytes());
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let mut image = AlignedBytes::new([0_u8; 128]);
+        // Misaligned magic.
+        image.0[4..8].copy_from_slice(&MAGIC.to_ne_bytes());
+        // Invalid checksum.
+        write_header(&mut image.0, 16, 1);
+        write_header(&mut image.0, 64, 0);
+
+        let (header, offset) = Multiboot2Header::search(&image.0).unwrap().unwrap();
+        assert_eq!(offset, 64);
+        assert_eq!(header.length(), 24);
+        assert!(header.verify_checksum());
+        assert!(!header.requires_module_alignment());
+        assert!(!header.wants_boot_services());
+        assert_eq!(header.entry_point(BootEnvironment::Efi64), None);
+
+        // Truncated header.
+        assert!(Multiboot2Header::search(&image.0[..80]).unwrap().is_none());
+        // No header.
+        assert!(Multiboot2Header::search(&image.0[..8]).unwrap().is_none());
+        // Misaligned image.
+        assert_eq!(
+            Multiboot2Header::search(&image.0[4..]).err(),
+            Some(LoadError::Memory(MemoryError::WrongAlignment))
+        );
+
+        let mut candidates = Multiboot2Header::candidates(&image.0)
+            .unwrap()
+            .map(|(offset, candidate)| (offset, candidate.map(|header| header.length())));
+        assert_eq!(
+            candidates.next(),
+            Some((16, Err(LoadError::ChecksumMismatch)))
+        );
+        assert_eq!(candidates.next(), Some((64, Ok(24))));
+        assert_eq!(candidates.next(), None);
+    }
+
+    /// Header assembled by GAS from `fixtures/gas_header.S`: information
+    /// request tag (types 1-4) and end tag.
+    const GAS_HEADER: &[u8; 48] = include_bytes!("../fixtures/gas_header.bin");
+
+    const fn gas_header_bytes() -> AlignedBytes<48> {
+        AlignedBytes::new(*GAS_HEADER)
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let mut bytes = gas_header_bytes();
+        let header = unsafe { Multiboot2Header::load(bytes.0.as_ptr().cast()) }.unwrap();
+        assert!(header.verify_checksum());
+        assert_eq!(header.checksum(), 0x17ad_aefa);
+        assert_eq!(
+            Multiboot2Header::calc_checksum(MAGIC, HeaderTagISA::I386, 48),
+            header.checksum()
+        );
+
+        // Same header with MIPS as architecture, but the old checksum.
+        bytes.0[4..8].copy_from_slice(&(HeaderTagISA::MIPS32 as u32).to_ne_bytes());
+        assert_eq!(
+            unsafe { Multiboot2Header::load(bytes.0.as_ptr().cast()) }.err(),
+            Some(LoadError::ChecksumMismatch)
+        );
+        bytes.0[12..16].copy_from_slice(&0x17ad_aef6_u32.to_ne_bytes());
+        let header = unsafe { Multiboot2Header::load(bytes.0.as_ptr().cast()) }.unwrap();
+        assert!(header.verify_checksum());
+        assert_eq!(header.arch(), HeaderTagISA::MIPS32);
+
+        // Unknown architecture with a valid checksum.
+        bytes.0[4..8].copy_from_slice(&1_u32.to_ne_bytes());
+        bytes.0[12..16].copy_from_slice(&0x17ad_aef9_u32.to_ne_bytes());
+        assert_eq!(
+            unsafe { Multiboot2Header::load(bytes.0.as_ptr().cast()) }.err(),
+            Some(LoadError::UnknownArch)
+        );
+    }
+
+    #[test]
+    fn test_missing_requests() {
+        use crate::{HeaderTagFlag, MbiTagType, StaticHeader};
+        use multiboot2::{BootInformation, StaticBootInformation};
+
+        static HEADER: StaticHeader<64> = StaticHeader::new(HeaderTagISA::I386)
+            .information_request(HeaderTagFlag::Required, &[1, 6])
+            .information_request(HeaderTagFlag::Optional, &[2, 4]);
+        static MBI: StaticBootInformation<64> = StaticBootInformation::new()
+            .cmdline("")
+            .boot_loader_name("");
+
+        let header = unsafe { Multiboot2Header::load(HEADER.as_ptr()) }.unwrap();
+        let mbi = unsafe { BootInformation::load(MBI.as_ptr()) }.unwrap();
+        assert!(header.missing_requests(&mbi).eq([
+            (MbiTagType::Mmap, HeaderTagFlag::Required),
+            (MbiTagType::BasicMeminfo, HeaderTagFlag::Optional),
+        ]));
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        let mut image = AlignedBytes::new([0_u8; 64]);
+        image.0[..48].copy_from_slice(&gas_header_bytes().0);
+
+        let header = Multiboot2Header::from_bytes(&image.0).unwrap();
+        assert_eq!(header.length(), 48);
+        assert!(header
+            .tags_with_offsets()
+            .map(|(offset, tag)| (offset, tag.header().typ()))
+            .eq([
+                (16, HeaderTagType::InformationRequest),
+                (40, HeaderTagType::End)
+            ]));
+
+        // Misaligned.
+        assert_eq!(
+            Multiboot2Header::from_bytes(&image.0[4..]).err(),
+            Some(LoadError::Memory(MemoryError::WrongAlignment))
+        );
+        // Truncated.
+        assert_eq!(
+            Multiboot2Header::from_bytes(&image.0[..40]).err(),
+            Some(LoadError::Memory(MemoryError::InvalidReportedTotalSize))
+        );
+        assert_eq!(
+            Multiboot2Header::from_bytes(&image.0[..8]).err(),
+            Some(LoadError::Memory(MemoryError::ShorterThanHeader))
+        );
+        // The end tag exceeds the header.
+        image.0[44] = 16;
+        assert_eq!(
+            Multiboot2Header::from_bytes(&image.0).err(),
+            Some(LoadError::InvalidTag(40))
+        );
+        // The information request tag has an invalid size.
+        image.0[44] = 8;
+        image.0[20] = 22;
+        assert_eq!(
+            Multiboot2Header::from_bytes(&image.0).err(),
+            Some(LoadError::InvalidTag(16))
+        );
+    }
 
     #[test]
     fn test_assert_size() {
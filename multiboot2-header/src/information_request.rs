@@ -1,5 +1,5 @@
 use crate::{HeaderTagFlag, HeaderTagHeader};
-use crate::{HeaderTagType, MbiTagTypeId};
+use crate::{HeaderTagType, MbiTagType, MbiTagTypeId};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use core::mem;
@@ -9,6 +9,7 @@ use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
 use {
     alloc::boxed::Box,
+    alloc::vec::Vec,
     core::{ptr, slice},
 };
 
@@ -34,6 +35,17 @@ impl InformationRequestHeaderTag {
         new_boxed(header, &[requests])
     }
 
+    /// Creates a new object from a runtime list of requested tag types.
+    #[cfg(feature = "builder")]
+    #[must_use]
+    pub fn from_types(flags: HeaderTagFlag, requests: &[MbiTagType]) -> Box<Self> {
+        let requests = requests
+            .iter()
+            .map(|&typ| MbiTagTypeId::from(typ))
+            .collect::<Vec<_>>();
+        Self::new(flags, &requests)
+    }
+
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
@@ -57,6 +69,11 @@ impl InformationRequestHeaderTag {
     pub const fn requests(&self) -> &[MbiTagTypeId] {
         &self.requests
     }
+
+    /// Returns an iterator over the requested tag types.
+    pub fn request_types(&self) -> impl Iterator<Item = MbiTagType> + '_ {
+        self.requests.iter().map(|&id| MbiTagType::from(id))
+    }
 }
 
 impl Debug for InformationRequestHeaderTag {
@@ -76,9 +93,14 @@ impl MaybeDynSized for InformationRequestHeaderTag {
     const BASE_SIZE: usize = mem::size_of::<HeaderTagHeader>();
 
     fn dst_len(header: &Self::Header) -> Self::Metadata {
-        let dst_size = header.size() as usize - Self::BASE_SIZE;
-        assert_eq!(dst_size % mem::size_of::<MbiTagTypeId>(), 0);
-        dst_size / mem::size_of::<MbiTagTypeId>()
+        Self::try_dst_len(header)
+            .expect("tag should be BASE_SIZE bytes plus a whole number of tag type IDs")
+    }
+
+    fn try_dst_len(header: &Self::Header) -> Option<Self::Metadata> {
+        let dst_size = (header.size() as usize).checked_sub(Self::BASE_SIZE)?;
+        (dst_size % mem::size_of::<MbiTagTypeId>() == 0)
+            .then(|| dst_size / mem::size_of::<MbiTagTypeId>())
     }
 }
 
@@ -91,7 +113,6 @@ impl Tag for InformationRequestHeaderTag {
 #[cfg(feature = "builder")]
 mod tests {
     use super::*;
-    use crate::MbiTagType;
 
     #[test]
     fn creation() {
@@ -124,4 +145,17 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn request_types() {
+        let types = [
+            MbiTagType::Cmdline,
+            MbiTagType::Mmap,
+            MbiTagType::Custom(0x1337),
+        ];
+        let tag = InformationRequestHeaderTag::from_types(HeaderTagFlag::Required, &types);
+        assert_eq!(tag.size(), 8 + 3 * 4);
+        assert_eq!(tag.requests().len(), 3);
+        assert!(tag.request_types().eq(types));
+    }
 }
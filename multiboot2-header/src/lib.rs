@@ -23,10 +23,9 @@
 //!
 //! ## MSRV
 //!
-//! The MSRV is 1.75.0 stable.
+//! The MSRV is 1.81.0 stable.
 
 #![no_std]
-#![cfg_attr(feature = "unstable", feature(error_in_core))]
 // --- BEGIN STYLE CHECKS ---
 #![deny(
     clippy::all,
@@ -54,8 +53,8 @@ extern crate std;
 /// Iterator over the tags of a Multiboot2 boot information.
 pub type TagIter<'a> = multiboot2_common::TagIter<'a, HeaderTagHeader>;
 
-/// A generic version of all boot information tags.
-#[cfg(test)]
+/// A generic version of all header tags. The payload of the tag, without
+/// trailing padding, is `&tag.payload()[..tag.header().payload_len()]`.
 pub type GenericHeaderTag = multiboot2_common::DynSizedStructure<HeaderTagHeader>;
 
 mod address;
@@ -68,14 +67,21 @@ mod framebuffer;
 mod header;
 mod information_request;
 mod module_align;
+#[cfg(kani)]
+mod proofs;
 mod relocatable;
+#[cfg(feature = "serde")]
+mod ser;
+mod static_header;
 mod tags;
 mod uefi_bs;
 
 #[cfg(feature = "builder")]
 mod builder;
+#[cfg(feature = "builder")]
+mod load_plan;
 
-pub use multiboot2_common::{DynSizedStructure, MaybeDynSized, Tag};
+pub use multiboot2_common::{DynSizedStructure, MaybeDynSized, MemoryError, Tag};
 
 pub use self::address::*;
 pub use self::console::*;
@@ -88,10 +94,13 @@ pub use self::header::*;
 pub use self::information_request::*;
 pub use self::module_align::*;
 pub use self::relocatable::*;
+pub use self::static_header::StaticHeader;
 pub use self::tags::*;
 pub use self::uefi_bs::*;
 #[cfg(feature = "builder")]
 pub use builder::Builder;
+#[cfg(feature = "builder")]
+pub use load_plan::{LoadPlan, LoadPlanError, LoadSegment, LoadStrategy};
 
 /// Re-export of [`multiboot2::TagType`] from `multiboot2`-crate.
 pub use multiboot2::{TagType as MbiTagType, TagTypeId as MbiTagTypeId};
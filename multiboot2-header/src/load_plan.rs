@@ -0,0 +1,510 @@
+//! Module for [`LoadPlan`].
+
+use crate::{AddressTagError, BootEnvironment, LoadError, Multiboot2Header};
+use alloc::vec::Vec;
+use core::error::Error;
+
+/// ELF program header type of loadable segments.
+const PT_LOAD: u32 = 1;
+
+/// How a [`LoadPlan`] loads the image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadStrategy {
+    /// The loadable segments of the ELF program headers are loaded.
+    Elf,
+    /// The image is loaded as described by the [`crate::AddressHeaderTag`],
+    /// also known as the a.out kludge.
+    AddressTag,
+}
+
+/// A copy command of a [`LoadPlan`]: copy `file_size` bytes from
+/// `file_offset` of the image to `phys_addr` and zero the remaining bytes up
+/// to `mem_size`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LoadSegment {
+    file_offset: u64,
+    phys_addr: u64,
+    file_size: u64,
+    mem_size: u64,
+}
+
+impl LoadSegment {
+    /// Returns the offset of the data in the image.
+    #[must_use]
+    pub const fn file_offset(&self) -> u64 {
+        self.file_offset
+    }
+
+    /// Returns the physical address the data is copied to.
+    #[must_use]
+    pub const fn phys_addr(&self) -> u64 {
+        self.phys_addr
+    }
+
+    /// Returns the number of bytes to copy from the image.
+    #[must_use]
+    pub const fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    /// Returns the size of the segment in memory. The bytes after
+    /// [`Self::file_size`] must be zeroed.
+    #[must_use]
+    pub const fn mem_size(&self) -> u64 {
+        self.mem_size
+    }
+}
+
+/// Describes how a bootloader loads a Multiboot2 kernel image: which bytes
+/// to copy where and where to jump to afterwards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoadPlan {
+    strategy: LoadStrategy,
+    segments: Vec<LoadSegment>,
+    entry_point: u64,
+}
+
+impl LoadPlan {
+    /// Creates the load plan for the given image in the given boot
+    /// environment.
+    ///
+    /// If the header contains an [`crate::AddressHeaderTag`], the image is
+    /// loaded as described by it. Otherwise, the image must be an ELF file in
+    /// the native byte order, and its loadable segments are loaded to their
+    /// physical addresses. The entry point is taken from the header, see
+    /// [`Multiboot2Header::entry_point`], or from the ELF header.
+    ///
+    /// If `load_addr` is set, the image is moved so that it starts at that
+    /// address, which requires a [`crate::RelocatableHeaderTag`]. The image
+    /// must then fit between its minimum and maximum address and `load_addr`
+    /// must have its alignment. See
+    /// [`crate::RelocatableHeaderTag::load_range`] to choose the address.
+    ///
+    /// The image must be 8-byte aligned, as required by
+    /// [`Multiboot2Header::search`].
+    pub fn new(
+        image: &[u8],
+        env: BootEnvironment,
+        load_addr: Option<u64>,
+    ) -> Result<Self, LoadPlanError> {
+        let (header, header_offset) =
+            Multiboot2Header::search(image)?.ok_or(LoadPlanError::NoHeader)?;
+        let relocatable_tag = header.relocatable_tag();
+        if load_addr.is_some() && relocatable_tag.is_none() {
+            return Err(LoadPlanError::NotRelocatable);
+        }
+
+        let (strategy, mut segments, mut entry_point) = match header.address_tag() {
+            Some(tag) => {
+                let ranges = tag.load_ranges(header_offset, image.len())?;
+                let mem_size = ranges
+                    .bss_range()
+                    .end
+                    .checked_sub(ranges.load_range().start)
+                    .ok_or(LoadPlanError::InvalidLoadAddress)?;
+                let segment = LoadSegment {
+                    file_offset: ranges.file_range().start as u64,
+                    phys_addr: ranges.load_range().start,
+                    file_size: ranges.file_range().len() as u64,
+                    mem_size,
+                };
+                let entry_point = header
+                    .entry_point(env)
+                    .ok_or(LoadPlanError::MissingEntryAddress)?;
+                (
+                    LoadStrategy::AddressTag,
+                    alloc::vec![segment],
+                    u64::from(entry_point),
+                )
+            }
+            None => {
+                let elf = ElfFile::parse(image)?;
+                let segments = elf.load_segments()?;
+                let entry_point = header.entry_point(env).map_or(elf.entry, u64::from);
+                (LoadStrategy::Elf, segments, entry_point)
+            }
+        };
+
+        if let (Some(load_addr), Some(tag)) = (load_addr, relocatable_tag) {
+            let base = segments
+                .iter()
+                .map(LoadSegment::phys_addr)
+                .min()
+                .unwrap_or(load_addr);
+            let span = segments
+                .iter()
+                .map(|segment| {
+                    segment
+                        .phys_addr
+                        .checked_sub(base)
+                        .and_then(|offset| offset.checked_add(segment.mem_size))
+                })
+                .try_fold(0, |span, end| end.map(|end| span.max(end)))
+                .ok_or(LoadPlanError::InvalidLoadAddress)?;
+            let fits = load_addr
+                .checked_add(span)
+                .is_some_and(|end| end <= u64::from(tag.max_addr()));
+            let aligned = load_addr % u64::from(tag.align()).max(1) == 0;
+            if load_addr < u64::from(tag.min_addr()) || !fits || !aligned {
+                return Err(LoadPlanError::InvalidLoadAddress);
+            }
+            for segment in &mut segments {
+                segment.phys_addr = segment.phys_addr - base + load_addr;
+            }
+            entry_point = entry_point.wrapping_sub(base).wrapping_add(load_addr);
+        }
+
+        Ok(Self {
+            strategy,
+            segments,
+            entry_point,
+        })
+    }
+
+    /// Returns the [`LoadStrategy`].
+    #[must_use]
+    pub const fn strategy(&self) -> LoadStrategy {
+        self.strategy
+    }
+
+    /// Returns the copy commands.
+    #[must_use]
+    pub fn segments(&self) -> &[LoadSegment] {
+        &self.segments
+    }
+
+    /// Returns the address to jump to.
+    #[must_use]
+    pub const fn entry_point(&self) -> u64 {
+        self.entry_point
+    }
+}
+
+/// Errors of [`LoadPlan::new`].
+#[derive(Copy, Clone, Debug, derive_more::Display, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadPlanError {
+    /// The header couldn't be loaded.
+    Header(LoadError),
+    /// The image doesn't contain a header.
+    NoHeader,
+    /// The addresses of the [`crate::AddressHeaderTag`] are invalid.
+    AddressTag(AddressTagError),
+    /// The [`crate::AddressHeaderTag`] is present, but no entry address.
+    MissingEntryAddress,
+    /// The image is not a valid ELF file, or a segment is out of bounds.
+    InvalidElf,
+    /// The ELF file is not in the native byte order.
+    UnsupportedElf,
+    /// A load address was given, but the image is not relocatable.
+    NotRelocatable,
+    /// The load address violates the limits or the alignment of the
+    /// [`crate::RelocatableHeaderTag`], or the loaded image would exceed the
+    /// address space.
+    InvalidLoadAddress,
+}
+
+impl From<LoadError> for LoadPlanError {
+    fn from(value: LoadError) -> Self {
+        Self::Header(value)
+    }
+}
+
+impl From<AddressTagError> for LoadPlanError {
+    fn from(value: AddressTagError) -> Self {
+        Self::AddressTag(value)
+    }
+}
+
+impl Error for LoadPlanError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Header(inner) => Some(inner),
+            Self::AddressTag(inner) => Some(inner),
+            _ => None,
+        }
+    }
+}
+
+/// The parts of an ELF file that are relevant for loading.
+struct ElfFile<'a> {
+    bytes: &'a [u8],
+    is_64: bool,
+    entry: u64,
+    phoff: u64,
+    phentsize: u64,
+    phnum: u64,
+}
+
+impl<'a> ElfFile<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, LoadPlanError> {
+        if bytes.get(..4) != Some(b"\x7fELF") {
+            return Err(LoadPlanError::InvalidElf);
+        }
+        let is_64 = match bytes.get(4) {
+            Some(1) => false,
+            Some(2) => true,
+            _ => return Err(LoadPlanError::InvalidElf),
+        };
+        let native = if cfg!(target_endian = "little") { 1 } else { 2 };
+        if bytes.get(5) != Some(&native) {
+            return Err(LoadPlanError::UnsupportedElf);
+        }
+        let mut this = Self {
+            bytes,
+            is_64,
+            entry: 0,
+            phoff: 0,
+            phentsize: 0,
+            phnum: 0,
+        };
+        if is_64 {
+            this.entry = this.read(0x18, 8)?;
+            this.phoff = this.read(0x20, 8)?;
+            this.phentsize = this.read(0x36, 2)?;
+            this.phnum = this.read(0x38, 2)?;
+        } else {
+            this.entry = this.read(0x18, 4)?;
+            this.phoff = this.read(0x1c, 4)?;
+            this.phentsize = this.read(0x2a, 2)?;
+            this.phnum = this.read(0x2c, 2)?;
+        }
+        Ok(this)
+    }
+
+    /// Reads a native-endian integer of `size` bytes at `base + offset`.
+    fn read_field(&self, base: u64, offset: u64, size: usize) -> Result<u64, LoadPlanError> {
+        let offset = base.checked_add(offset).ok_or(LoadPlanError::InvalidElf)?;
+        self.read(offset, size)
+    }
+
+    /// Reads a native-endian integer of `size` bytes at `offset`.
+    fn read(&self, offset: u64, size: usize) -> Result<u64, LoadPlanError> {
+        let offset = usize::try_from(offset).map_err(|_| LoadPlanError::InvalidElf)?;
+        let bytes = offset
+            .checked_add(size)
+            .and_then(|end| self.bytes.get(offset..end))
+            .ok_or(LoadPlanError::InvalidElf)?;
+        let mut buf = [0; 8];
+        if cfg!(target_endian = "little") {
+            buf[..size].copy_from_slice(bytes);
+        } else {
+            buf[8 - size..].copy_from_slice(bytes);
+        }
+        Ok(u64::from_ne_bytes(buf))
+    }
+
+    fn load_segments(&self) -> Result<Vec<LoadSegment>, LoadPlanError> {
+        let mut segments = Vec::new();
+        for i in 0..self.phnum {
+            let ph = i
+                .checked_mul(self.phentsize)
+                .and_then(|offset| self.phoff.checked_add(offset))
+                .ok_or(LoadPlanError::InvalidElf)?;
+            if self.read(ph, 4)? != u64::from(PT_LOAD) {
+                continue;
+            }
+            let segment = if self.is_64 {
+                LoadSegment {
+                    file_offset: self.read_field(ph, 0x08, 8)?,
+                    phys_addr: self.read_field(ph, 0x18, 8)?,
+                    file_size: self.read_field(ph, 0x20, 8)?,
+                    mem_size: self.read_field(ph, 0x28, 8)?,
+                }
+            } else {
+                LoadSegment {
+                    file_offset: self.read_field(ph, 0x04, 4)?,
+                    phys_addr: self.read_field(ph, 0x0c, 4)?,
+                    file_size: self.read_field(ph, 0x10, 4)?,
+                    mem_size: self.read_field(ph, 0x14, 4)?,
+                }
+            };
+            let in_bounds = segment
+                .file_offset
+                .checked_add(segment.file_size)
+                .is_some_and(|end| end <= self.bytes.len() as u64);
+            let fits = segment.phys_addr.checked_add(segment.mem_size).is_some();
+            if !in_bounds || !fits || segment.file_size > segment.mem_size {
+                return Err(LoadPlanError::InvalidElf);
+            }
+            segments.push(segment);
+        }
+        Ok(segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HeaderTagFlag, HeaderTagISA, RelocatableHeaderTagPreference, StaticHeader};
+    use multiboot2_common::test_utils::AlignedBytes;
+
+    const HEADER_OFFSET: usize = 0x80;
+
+    fn write(image: &mut [u8], offset: usize, bytes: &[u8]) {
+        image[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// Creates an ELF file with one loadable segment and the given header.
+    fn elf(is_64: bool, header: &[u8]) -> AlignedBytes<512> {
+        let mut image = AlignedBytes::new([0; 512]);
+        let image_ref = &mut image.0;
+        write(image_ref, 0, b"\x7fELF");
+        image_ref[4] = if is_64 { 2 } else { 1 };
+        image_ref[5] = if cfg!(target_endian = "little") { 1 } else { 2 };
+        if is_64 {
+            write(image_ref, 0x18, &0x100010_u64.to_ne_bytes());
+            write(image_ref, 0x20, &0x40_u64.to_ne_bytes());
+            write(image_ref, 0x36, &56_u16.to_ne_bytes());
+            write(image_ref, 0x38, &1_u16.to_ne_bytes());
+            write(image_ref, 0x40, &PT_LOAD.to_ne_bytes());
+            write(image_ref, 0x48, &0_u64.to_ne_bytes());
+            write(image_ref, 0x58, &0x100000_u64.to_ne_bytes());
+            write(image_ref, 0x60, &0x200_u64.to_ne_bytes());
+            write(image_ref, 0x68, &0x1000_u64.to_ne_bytes());
+        } else {
+            write(image_ref, 0x18, &0x100010_u32.to_ne_bytes());
+            write(image_ref, 0x1c, &0x34_u32.to_ne_bytes());
+            write(image_ref, 0x2a, &32_u16.to_ne_bytes());
+            write(image_ref, 0x2c, &1_u16.to_ne_bytes());
+            write(image_ref, 0x34, &PT_LOAD.to_ne_bytes());
+            write(image_ref, 0x38, &0_u32.to_ne_bytes());
+            write(image_ref, 0x40, &0x100000_u32.to_ne_bytes());
+            write(image_ref, 0x44, &0x200_u32.to_ne_bytes());
+            write(image_ref, 0x48, &0x1000_u32.to_ne_bytes());
+        }
+        write(image_ref, HEADER_OFFSET, header);
+        image
+    }
+
+    #[test]
+    fn test_elf() {
+        let header = StaticHeader::<64>::new(HeaderTagISA::I386);
+        let segment = LoadSegment {
+            file_offset: 0,
+            phys_addr: 0x100000,
+            file_size: 0x200,
+            mem_size: 0x1000,
+        };
+        for is_64 in [false, true] {
+            let image = elf(is_64, header.as_bytes());
+            let plan = LoadPlan::new(&image.0, BootEnvironment::Bios, None).unwrap();
+            assert_eq!(plan.strategy(), LoadStrategy::Elf);
+            assert_eq!(plan.segments(), &[segment]);
+            assert_eq!(plan.entry_point(), 0x100010);
+            assert_eq!(
+                LoadPlan::new(&image.0, BootEnvironment::Bios, Some(0x400000)),
+                Err(LoadPlanError::NotRelocatable)
+            );
+        }
+
+        // Entry address from the header.
+        let header = header.entry_address(HeaderTagFlag::Required, 0x100020);
+        let image = elf(false, header.as_bytes());
+        let plan = LoadPlan::new(&image.0, BootEnvironment::Bios, None).unwrap();
+        assert_eq!(plan.entry_point(), 0x100020);
+
+        // Relocated.
+        let header = header.relocatable(
+            HeaderTagFlag::Required,
+            0x100000,
+            0x1000000,
+            0x1000,
+            RelocatableHeaderTagPreference::None,
+        );
+        let image = elf(false, header.as_bytes());
+        let plan = LoadPlan::new(&image.0, BootEnvironment::Bios, Some(0x400000)).unwrap();
+        assert_eq!(plan.segments()[0].phys_addr(), 0x400000);
+        assert_eq!(plan.entry_point(), 0x400020);
+        for load_addr in [0x1000, 0x400800, 0x1000000] {
+            assert_eq!(
+                LoadPlan::new(&image.0, BootEnvironment::Bios, Some(load_addr)),
+                Err(LoadPlanError::InvalidLoadAddress),
+                "{load_addr:#x}"
+            );
+        }
+        assert!(LoadPlan::new(&image.0, BootEnvironment::Bios, Some(0xfff000)).is_ok());
+
+        // Foreign byte order.
+        let mut image = elf(false, header.as_bytes());
+        image.0[5] = if cfg!(target_endian = "little") { 2 } else { 1 };
+        assert_eq!(
+            LoadPlan::new(&image.0, BootEnvironment::Bios, None),
+            Err(LoadPlanError::UnsupportedElf)
+        );
+    }
+
+    #[test]
+    fn test_malformed_elf() {
+        let header = StaticHeader::<64>::new(HeaderTagISA::I386);
+
+        // Program headers beyond the end of the address space.
+        let mut image = elf(true, header.as_bytes());
+        write(&mut image.0, 0x20, &u64::MAX.to_ne_bytes());
+        assert_eq!(
+            LoadPlan::new(&image.0, BootEnvironment::Bios, None),
+            Err(LoadPlanError::InvalidElf)
+        );
+
+        // Segment beyond the end of the address space.
+        let mut image = elf(true, header.as_bytes());
+        write(&mut image.0, 0x58, &(u64::MAX - 0xfff).to_ne_bytes());
+        assert_eq!(
+            LoadPlan::new(&image.0, BootEnvironment::Bios, None),
+            Err(LoadPlanError::InvalidElf)
+        );
+
+        // Segment with more file than memory size.
+        let mut image = elf(false, header.as_bytes());
+        write(&mut image.0, 0x48, &0x100_u32.to_ne_bytes());
+        assert_eq!(
+            LoadPlan::new(&image.0, BootEnvironment::Bios, None),
+            Err(LoadPlanError::InvalidElf)
+        );
+
+        // Program headers beyond the end of the image.
+        let mut image = elf(false, header.as_bytes());
+        write(&mut image.0, 0x2c, &u16::MAX.to_ne_bytes());
+        write(&mut image.0, 0x2a, &u16::MAX.to_ne_bytes());
+        assert_eq!(
+            LoadPlan::new(&image.0, BootEnvironment::Bios, None),
+            Err(LoadPlanError::InvalidElf)
+        );
+    }
+
+    #[test]
+    fn test_address_tag() {
+        let header = StaticHeader::<64>::new(HeaderTagISA::I386).address(
+            HeaderTagFlag::Required,
+            0x100000 + HEADER_OFFSET as u32,
+            0x100000,
+            0,
+            0x101000,
+        );
+        let mut image = AlignedBytes::new([0; 512]);
+        write(&mut image.0, HEADER_OFFSET, header.as_bytes());
+        assert_eq!(
+            LoadPlan::new(&image.0, BootEnvironment::Bios, None),
+            Err(LoadPlanError::MissingEntryAddress)
+        );
+
+        let header = header.entry_address(HeaderTagFlag::Required, 0x100100);
+        write(&mut image.0, HEADER_OFFSET, header.as_bytes());
+        let plan = LoadPlan::new(&image.0, BootEnvironment::Bios, None).unwrap();
+        assert_eq!(plan.strategy(), LoadStrategy::AddressTag);
+        assert_eq!(
+            plan.segments(),
+            &[LoadSegment {
+                file_offset: 0,
+                phys_addr: 0x100000,
+                file_size: 512,
+                mem_size: 0x1000,
+            }]
+        );
+        assert_eq!(plan.entry_point(), 0x100100);
+
+        assert_eq!(
+            LoadPlan::new(&image.0[..HEADER_OFFSET], BootEnvironment::Bios, None),
+            Err(LoadPlanError::NoHeader)
+        );
+    }
+}
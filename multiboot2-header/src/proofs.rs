@@ -0,0 +1,62 @@
+//! Kani proof harnesses for the memory safety of the header parser.
+//!
+//! Run them with `cargo kani -p multiboot2-header`.
+
+use crate::{HeaderTagISA, Multiboot2BasicHeader, Multiboot2Header};
+use multiboot2_common::test_utils::AlignedBytes;
+
+/// The size of the images. Small, to keep the loop bounds small.
+const IMAGE_SIZE: usize = 48;
+
+/// The checksum is defined for all values and sums up to zero, without
+/// overflowing.
+#[kani::proof]
+fn checksum_never_overflows() {
+    let magic: u32 = kani::any();
+    let length: u32 = kani::any();
+    let arch = if kani::any() {
+        HeaderTagISA::I386
+    } else {
+        HeaderTagISA::MIPS32
+    };
+    let checksum = Multiboot2BasicHeader::calc_checksum(magic, arch, length);
+    let sum = magic
+        .wrapping_add(arch as u32)
+        .wrapping_add(length)
+        .wrapping_add(checksum);
+    assert_eq!(sum, 0);
+}
+
+/// A header parsed from bytes and all of its tags lie within the bytes.
+#[kani::proof]
+#[kani::unwind(7)]
+fn from_bytes_stays_in_bounds() {
+    let bytes = AlignedBytes::<IMAGE_SIZE>(kani::any());
+    let Ok(header) = Multiboot2Header::from_bytes(&bytes[..]) else {
+        return;
+    };
+    assert!(header.length() as usize <= IMAGE_SIZE);
+    for (offset, tag) in header.tags_with_offsets() {
+        assert!(offset + tag.header().size() as usize <= header.length() as usize);
+    }
+}
+
+/// Searching a header never indexes out of bounds.
+#[kani::proof]
+#[kani::unwind(7)]
+fn search_stays_in_bounds() {
+    let bytes = AlignedBytes::<IMAGE_SIZE>(kani::any());
+    if let Ok(Some((header, offset))) = Multiboot2Header::search(&bytes[..]) {
+        assert!(offset + header.length() as usize <= IMAGE_SIZE);
+    }
+}
+
+/// The legacy header lookup never indexes out of bounds.
+#[kani::proof]
+#[kani::unwind(50)]
+fn find_header_stays_in_bounds() {
+    let bytes = AlignedBytes::<IMAGE_SIZE>(kani::any());
+    if let Ok(Some((header, offset))) = Multiboot2Header::find_header(&bytes[..]) {
+        assert!(offset as usize + header.len() <= IMAGE_SIZE);
+    }
+}
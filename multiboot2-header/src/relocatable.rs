@@ -2,6 +2,8 @@ use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use core::mem;
+use core::ops::Range;
+use multiboot2::{MemoryArea, MemoryAreaType};
 use multiboot2_common::{MaybeDynSized, Tag};
 
 /// It contains load address placement suggestion for bootloader.
@@ -11,6 +13,7 @@ use multiboot2_common::{MaybeDynSized, Tag};
 /// highest possible address but not higher than max addr.
 #[repr(u32)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum RelocatableHeaderTagPreference {
     /// Let boot loader decide.
     None = 0,
@@ -99,6 +102,42 @@ impl RelocatableHeaderTag {
     pub const fn preference(&self) -> RelocatableHeaderTagPreference {
         self.preference
     }
+
+    /// Computes a concrete physical address range of `image_size` bytes to
+    /// load the image to, honoring the address limits, the alignment, and the
+    /// preference of this tag.
+    ///
+    /// Only [`MemoryAreaType::Available`] areas of `memory_areas` are
+    /// considered. Without a preference, the lowest possible address is
+    /// chosen. Returns `None` if no area is large enough.
+    #[must_use]
+    pub fn load_range(&self, image_size: u64, memory_areas: &[MemoryArea]) -> Option<Range<u64>> {
+        let align = u64::from(self.align).max(1);
+        let min = u64::from(self.min_addr);
+        let max = u64::from(self.max_addr);
+        let high = self.preference == RelocatableHeaderTagPreference::High;
+
+        let candidates = memory_areas
+            .iter()
+            .filter(|area| area.typ() == MemoryAreaType::Available)
+            .filter_map(|area| {
+                let lo = area.start_address().max(min);
+                let hi = area.end_address().min(max);
+                let start = if high {
+                    hi.checked_sub(image_size)? / align * align
+                } else {
+                    lo.checked_next_multiple_of(align)?
+                };
+                let end = start.checked_add(image_size)?;
+                (start >= lo && end <= hi).then_some(start)
+            });
+        let start = if high {
+            candidates.max()
+        } else {
+            candidates.min()
+        }?;
+        Some(start..start + image_size)
+    }
 }
 
 impl Debug for RelocatableHeaderTag {
@@ -131,7 +170,8 @@ impl Tag for RelocatableHeaderTag {
 
 #[cfg(test)]
 mod tests {
-    use crate::RelocatableHeaderTag;
+    use crate::{HeaderTagFlag, RelocatableHeaderTag, RelocatableHeaderTagPreference};
+    use multiboot2::{MemoryArea, MemoryAreaType};
 
     #[test]
     fn test_assert_size() {
@@ -140,4 +180,38 @@ mod tests {
             2 + 2 + 4 + 4 + 4 + 4 + 4
         );
     }
+
+    #[test]
+    fn test_load_range() {
+        let areas = [
+            MemoryArea::new(0, 0x9fc00, MemoryAreaType::Available),
+            MemoryArea::new(0x100000, 0x1000000, MemoryAreaType::Available),
+            MemoryArea::new(0x1100000, 0x100000, MemoryAreaType::Reserved),
+            MemoryArea::new(0x2000000, 0x1000000, MemoryAreaType::Available),
+        ];
+        let tag = |preference| {
+            RelocatableHeaderTag::new(
+                HeaderTagFlag::Required,
+                0x200000,
+                0x2800000,
+                0x200000,
+                preference,
+            )
+        };
+
+        let low = tag(RelocatableHeaderTagPreference::Low);
+        assert_eq!(low.load_range(0x1000, &areas), Some(0x200000..0x201000));
+        let none = tag(RelocatableHeaderTagPreference::None);
+        assert_eq!(none.load_range(0x1000, &areas), Some(0x200000..0x201000));
+        let high = tag(RelocatableHeaderTagPreference::High);
+        assert_eq!(high.load_range(0x1000, &areas), Some(0x2600000..0x2601000));
+        assert_eq!(low.load_range(0x800000, &areas), Some(0x200000..0xa00000));
+        // The reserved area is skipped.
+        assert_eq!(
+            high.load_range(0x800000, &areas),
+            Some(0x2000000..0x2800000)
+        );
+        assert_eq!(high.load_range(0xe00000, &areas), Some(0x200000..0x1000000));
+        assert_eq!(low.load_range(0x1000000, &areas), None);
+    }
 }
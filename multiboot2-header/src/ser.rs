@@ -0,0 +1,150 @@
+//! [`Serialize`] implementations for [`Multiboot2Header`] and the header
+//! tags, behind the `serde` feature.
+//!
+//! Tags are serialized as structs with their type, flags, size, and the
+//! fields of the specific tag. Tags unknown to this crate are serialized as
+//! `UnknownHeaderTag` with their payload as hexadecimal string.
+
+use crate::header::HexBytes;
+use crate::{
+    AddressHeaderTag, ConsoleFlags, ConsoleHeaderTag, EfiBootServiceHeaderTag, EndHeaderTag,
+    EntryAddressHeaderTag, EntryEfi32HeaderTag, EntryEfi64HeaderTag, FramebufferHeaderTag,
+    GenericHeaderTag, HeaderTagType, InformationRequestHeaderTag, ModuleAlignHeaderTag,
+    Multiboot2Header, RelocatableHeaderTag,
+};
+use multiboot2_common::Header;
+use serde::ser::{SerializeSeq, SerializeStruct, Serializer};
+use serde::Serialize;
+
+/// Implements [`Serialize`] for a header tag with the given getters as
+/// additional fields.
+macro_rules! impl_serialize_tag {
+    ($tag:ty $(, $field:ident)*) => {
+        impl Serialize for $tag {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let fields: &[&str] = &[$(stringify!($field)),*];
+                let mut s = serializer.serialize_struct(stringify!($tag), 3 + fields.len())?;
+                s.serialize_field("type", &self.typ())?;
+                s.serialize_field("flags", &self.flags())?;
+                s.serialize_field("size", &self.size())?;
+                $(s.serialize_field(stringify!($field), &self.$field())?;)*
+                s.end()
+            }
+        }
+    };
+}
+
+impl_serialize_tag!(EndHeaderTag);
+impl_serialize_tag!(
+    AddressHeaderTag,
+    header_addr,
+    load_addr,
+    load_end_addr,
+    bss_end_addr
+);
+impl_serialize_tag!(EntryAddressHeaderTag, entry_addr);
+impl_serialize_tag!(ConsoleHeaderTag, console_flags);
+impl_serialize_tag!(FramebufferHeaderTag, width, height, depth);
+impl_serialize_tag!(ModuleAlignHeaderTag);
+impl_serialize_tag!(EfiBootServiceHeaderTag);
+impl_serialize_tag!(EntryEfi32HeaderTag, entry_addr);
+impl_serialize_tag!(EntryEfi64HeaderTag, entry_addr);
+impl_serialize_tag!(RelocatableHeaderTag, min_addr, max_addr, align, preference);
+
+impl Serialize for InformationRequestHeaderTag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("InformationRequestHeaderTag", 4)?;
+        s.serialize_field("type", &self.typ())?;
+        s.serialize_field("flags", &self.flags())?;
+        s.serialize_field("size", &self.size())?;
+        s.serialize_field("requests", &Requests(self))?;
+        s.end()
+    }
+}
+
+/// Serializes the requested tag types as raw values.
+struct Requests<'a>(&'a InformationRequestHeaderTag);
+
+impl Serialize for Requests<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.requests().iter().map(|&typ| u32::from(typ)))
+    }
+}
+
+impl Serialize for ConsoleFlags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.bits())
+    }
+}
+
+impl Serialize for Multiboot2Header<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Multiboot2Header", 5)?;
+        s.serialize_field("magic", &self.header_magic())?;
+        s.serialize_field("arch", &self.arch())?;
+        s.serialize_field("length", &self.length())?;
+        s.serialize_field("checksum", &self.checksum())?;
+        s.serialize_field("tags", &Tags(self))?;
+        s.end()
+    }
+}
+
+/// Serializes all tags of a header as a sequence.
+struct Tags<'a, 'b>(&'b Multiboot2Header<'a>);
+
+impl Serialize for Tags<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Formats with a length prefix need the length upfront.
+        let mut seq = serializer.serialize_seq(Some(self.0.iter().count()))?;
+        for tag in self.0.iter() {
+            seq.serialize_element(&AnyTag(tag))?;
+        }
+        seq.end()
+    }
+}
+
+/// Serializes a tag as its specific type, or with its payload as hexadecimal
+/// string if the type is unknown.
+struct AnyTag<'a>(&'a GenericHeaderTag);
+
+impl Serialize for AnyTag<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let tag = self.0;
+        match tag.header().typ() {
+            HeaderTagType::End => tag.cast::<EndHeaderTag>().serialize(serializer),
+            HeaderTagType::InformationRequest => tag
+                .cast::<InformationRequestHeaderTag>()
+                .serialize(serializer),
+            HeaderTagType::Address => tag.cast::<AddressHeaderTag>().serialize(serializer),
+            HeaderTagType::EntryAddress => {
+                tag.cast::<EntryAddressHeaderTag>().serialize(serializer)
+            }
+            HeaderTagType::ConsoleFlags => tag.cast::<ConsoleHeaderTag>().serialize(serializer),
+            HeaderTagType::Framebuffer => tag.cast::<FramebufferHeaderTag>().serialize(serializer),
+            HeaderTagType::ModuleAlign => tag.cast::<ModuleAlignHeaderTag>().serialize(serializer),
+            HeaderTagType::EfiBS => tag.cast::<EfiBootServiceHeaderTag>().serialize(serializer),
+            HeaderTagType::EntryAddressEFI32 => {
+                tag.cast::<EntryEfi32HeaderTag>().serialize(serializer)
+            }
+            HeaderTagType::EntryAddressEFI64 => {
+                tag.cast::<EntryEfi64HeaderTag>().serialize(serializer)
+            }
+            HeaderTagType::Relocatable => tag.cast::<RelocatableHeaderTag>().serialize(serializer),
+            HeaderTagType::Custom(_) => {
+                let payload = &tag.payload()[..tag.header().payload_len()];
+                let mut s = serializer.serialize_struct("UnknownHeaderTag", 4)?;
+                s.serialize_field("type", &tag.header().typ())?;
+                s.serialize_field("flags", &tag.header().flags())?;
+                s.serialize_field("size", &tag.header().size())?;
+                s.serialize_field("payload", &HexBytes(payload))?;
+                s.end()
+            }
+        }
+    }
+}
+
+impl Serialize for HexBytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
@@ -0,0 +1,379 @@
+//! Module for [`StaticHeader`] and [`crate::multiboot2_header!`].
+
+use crate::{
+    ConsoleFlags, HeaderTagFlag, HeaderTagISA, HeaderTagType, Multiboot2BasicHeader,
+    RelocatableHeaderTagPreference, MAGIC,
+};
+use core::mem::size_of;
+use multiboot2_common::increase_to_alignment;
+
+/// A Multiboot2 header that is assembled at compile time in a fixed-size,
+/// 8-byte aligned byte array.
+///
+/// This enables declaring the header of a Rust kernel in a `static` without
+/// an assembly shim, typically via [`crate::multiboot2_header!`]. After every step,
+/// the structure is a valid header including the end tag, the total length,
+/// and the checksum. Construction panics, i.e., fails to compile in const
+/// contexts, if the capacity `N` is too small.
+///
+/// All values are written in the native byte order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(C, align(8))]
+pub struct StaticHeader<const N: usize> {
+    bytes: [u8; N],
+    /// Offset of the end tag.
+    end: usize,
+}
+
+impl<const N: usize> StaticHeader<N> {
+    const END_TAG_SIZE: usize = 8;
+
+    /// Creates a header that only consists of the basic header and the end
+    /// tag.
+    #[must_use]
+    pub const fn new(arch: HeaderTagISA) -> Self {
+        Self {
+            bytes: [0; N],
+            end: size_of::<Multiboot2BasicHeader>(),
+        }
+        .write_u32(0, MAGIC)
+        .write_u32(4, arch as u32)
+        .write_end()
+    }
+
+    /// Writes the end tag and updates the length and the checksum in the
+    /// header.
+    const fn write_end(mut self) -> Self {
+        assert!(
+            self.end + Self::END_TAG_SIZE <= N,
+            "capacity of the static header exceeded"
+        );
+        let end = self.end;
+        self = self.write_tag_header(end, HeaderTagType::End, HeaderTagFlag::Required, 8);
+        let length = (end + Self::END_TAG_SIZE) as u32;
+        let arch = u32::from_ne_bytes([self.bytes[4], self.bytes[5], self.bytes[6], self.bytes[7]]);
+        let checksum = 0_u32
+            .wrapping_sub(MAGIC)
+            .wrapping_sub(arch)
+            .wrapping_sub(length);
+        self.write_u32(8, length).write_u32(12, checksum)
+    }
+
+    /// Zeroes the padding after the tag that ends at `offset`, which may
+    /// contain the previous end tag, and appends the end tag.
+    const fn finish_tag(mut self, mut offset: usize) -> Self {
+        let padded = increase_to_alignment(offset);
+        while offset < padded {
+            self.bytes[offset] = 0;
+            offset += 1;
+        }
+        self.end = padded;
+        self.write_end()
+    }
+
+    const fn write_tag_header(
+        self,
+        offset: usize,
+        typ: HeaderTagType,
+        flags: HeaderTagFlag,
+        size: u32,
+    ) -> Self {
+        self.write(offset, &typ.val().to_ne_bytes())
+            .write(offset + 2, &(flags as u16).to_ne_bytes())
+            .write_u32(offset + 4, size)
+    }
+
+    const fn write_u32(self, offset: usize, val: u32) -> Self {
+        self.write(offset, &val.to_ne_bytes())
+    }
+
+    const fn write(mut self, offset: usize, src: &[u8]) -> Self {
+        assert!(
+            offset + src.len() <= N,
+            "capacity of the static header exceeded"
+        );
+        let mut i = 0;
+        while i < src.len() {
+            self.bytes[offset + i] = src[i];
+            i += 1;
+        }
+        self
+    }
+
+    /// Appends a tag of the given type consisting of the concatenated
+    /// payload parts.
+    #[must_use]
+    pub const fn add_tag(
+        mut self,
+        typ: HeaderTagType,
+        flags: HeaderTagFlag,
+        payload: &[&[u8]],
+    ) -> Self {
+        let start = self.end;
+        let mut offset = start + 8;
+        let mut i = 0;
+        while i < payload.len() {
+            self = self.write(offset, payload[i]);
+            offset += payload[i].len();
+            i += 1;
+        }
+        self = self.write_tag_header(start, typ, flags, (offset - start) as u32);
+        self.finish_tag(offset)
+    }
+
+    /// Appends an information request tag for the given raw tag types of the
+    /// boot information.
+    #[must_use]
+    pub const fn information_request(mut self, flags: HeaderTagFlag, requests: &[u32]) -> Self {
+        let start = self.end;
+        let mut offset = start + 8;
+        let mut i = 0;
+        while i < requests.len() {
+            self = self.write_u32(offset, requests[i]);
+            offset += 4;
+            i += 1;
+        }
+        let size = (offset - start) as u32;
+        self = self.write_tag_header(start, HeaderTagType::InformationRequest, flags, size);
+        self.finish_tag(offset)
+    }
+
+    /// Appends an address tag.
+    #[must_use]
+    pub const fn address(
+        self,
+        flags: HeaderTagFlag,
+        header_addr: u32,
+        load_addr: u32,
+        load_end_addr: u32,
+        bss_end_addr: u32,
+    ) -> Self {
+        self.add_tag(
+            HeaderTagType::Address,
+            flags,
+            &[
+                &header_addr.to_ne_bytes(),
+                &load_addr.to_ne_bytes(),
+                &load_end_addr.to_ne_bytes(),
+                &bss_end_addr.to_ne_bytes(),
+            ],
+        )
+    }
+
+    /// Appends an entry address tag.
+    #[must_use]
+    pub const fn entry_address(self, flags: HeaderTagFlag, entry_addr: u32) -> Self {
+        self.add_tag(
+            HeaderTagType::EntryAddress,
+            flags,
+            &[&entry_addr.to_ne_bytes()],
+        )
+    }
+
+    /// Appends a console flags tag.
+    #[must_use]
+    pub const fn console_flags(self, flags: HeaderTagFlag, console_flags: ConsoleFlags) -> Self {
+        self.add_tag(
+            HeaderTagType::ConsoleFlags,
+            flags,
+            &[&console_flags.bits().to_ne_bytes()],
+        )
+    }
+
+    /// Appends a framebuffer tag. Use `0` for values without a preference.
+    #[must_use]
+    pub const fn framebuffer(
+        self,
+        flags: HeaderTagFlag,
+        width: u32,
+        height: u32,
+        depth: u32,
+    ) -> Self {
+        self.add_tag(
+            HeaderTagType::Framebuffer,
+            flags,
+            &[
+                &width.to_ne_bytes(),
+                &height.to_ne_bytes(),
+                &depth.to_ne_bytes(),
+            ],
+        )
+    }
+
+    /// Appends a module alignment tag.
+    #[must_use]
+    pub const fn module_align(self, flags: HeaderTagFlag) -> Self {
+        self.add_tag(HeaderTagType::ModuleAlign, flags, &[])
+    }
+
+    /// Appends an EFI boot services tag.
+    #[must_use]
+    pub const fn efi_boot_services(self, flags: HeaderTagFlag) -> Self {
+        self.add_tag(HeaderTagType::EfiBS, flags, &[])
+    }
+
+    /// Appends an EFI i386 entry address tag.
+    #[must_use]
+    pub const fn entry_address_efi32(self, flags: HeaderTagFlag, entry_addr: u32) -> Self {
+        self.add_tag(
+            HeaderTagType::EntryAddressEFI32,
+            flags,
+            &[&entry_addr.to_ne_bytes()],
+        )
+    }
+
+    /// Appends an EFI amd64 entry address tag.
+    #[must_use]
+    pub const fn entry_address_efi64(self, flags: HeaderTagFlag, entry_addr: u32) -> Self {
+        self.add_tag(
+            HeaderTagType::EntryAddressEFI64,
+            flags,
+            &[&entry_addr.to_ne_bytes()],
+        )
+    }
+
+    /// Appends a relocatable tag.
+    #[must_use]
+    pub const fn relocatable(
+        self,
+        flags: HeaderTagFlag,
+        min_addr: u32,
+        max_addr: u32,
+        align: u32,
+        preference: RelocatableHeaderTagPreference,
+    ) -> Self {
+        self.add_tag(
+            HeaderTagType::Relocatable,
+            flags,
+            &[
+                &min_addr.to_ne_bytes(),
+                &max_addr.to_ne_bytes(),
+                &align.to_ne_bytes(),
+                &(preference as u32).to_ne_bytes(),
+            ],
+        )
+    }
+
+    /// Returns the bytes of the header.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.end + Self::END_TAG_SIZE]
+    }
+
+    /// Returns the pointer to pass to [`crate::Multiboot2Header::load`].
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const Multiboot2BasicHeader {
+        self.bytes.as_ptr().cast()
+    }
+}
+
+/// Declares a [`StaticHeader`] as `static` in the `.multiboot2_header` link
+/// section and marks it as `#[used]`, so that it is kept in the kernel binary.
+///
+/// The linker script must place the section within the first 32768 bytes of
+/// the image, e.g., at the beginning of the first loadable segment.
+///
+/// # Example
+///
+/// ```rust
+/// use multiboot2_header::{
+///     multiboot2_header, ConsoleFlags, HeaderTagFlag, HeaderTagISA, StaticHeader,
+/// };
+///
+/// multiboot2_header! {
+///     static MULTIBOOT2_HEADER: StaticHeader<64> = StaticHeader::new(HeaderTagISA::I386)
+///         .console_flags(HeaderTagFlag::Optional, ConsoleFlags::EGA_TEXT_SUPPORTED)
+///         .module_align(HeaderTagFlag::Required);
+/// }
+/// ```
+#[macro_export]
+macro_rules! multiboot2_header {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $header:expr;) => {
+        $(#[$attr])*
+        #[used]
+        #[link_section = ".multiboot2_header"]
+        $vis static $name: $ty = $header;
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BootEnvironment, Multiboot2Header};
+
+    multiboot2_header! {
+        static HEADER: StaticHeader<256> = StaticHeader::new(HeaderTagISA::I386)
+            .information_request(HeaderTagFlag::Optional, &[1, 2, 6])
+            .address(HeaderTagFlag::Required, 0x100000, 0x100000, 0, 0)
+            .entry_address(HeaderTagFlag::Required, 0x100040)
+            .console_flags(HeaderTagFlag::Optional, ConsoleFlags::EGA_TEXT_SUPPORTED)
+            .framebuffer(HeaderTagFlag::Optional, 1024, 768, 32)
+            .module_align(HeaderTagFlag::Required)
+            .efi_boot_services(HeaderTagFlag::Optional)
+            .entry_address_efi32(HeaderTagFlag::Required, 0x100080)
+            .entry_address_efi64(HeaderTagFlag::Required, 0x1000c0)
+            .relocatable(
+                HeaderTagFlag::Optional,
+                0x100000,
+                0x1000000,
+                0x1000,
+                RelocatableHeaderTagPreference::Low,
+            )
+            .add_tag(HeaderTagType::Custom(0x1337), HeaderTagFlag::Optional, &[b"abc"]);
+    }
+
+    #[test]
+    fn test_static() {
+        assert_eq!(HEADER.as_ptr().align_offset(8), 0);
+        let header = unsafe { Multiboot2Header::load(HEADER.as_ptr()) }.unwrap();
+        assert!(header.verify_checksum());
+        assert_eq!(header.length() as usize, HEADER.as_bytes().len());
+        assert_eq!(header.arch(), HeaderTagISA::I386);
+        assert!(header
+            .information_request_tag()
+            .unwrap()
+            .requests()
+            .iter()
+            .map(|&id| u32::from(id))
+            .eq([1, 2, 6]));
+        assert_eq!(header.address_tag().unwrap().load_addr(), 0x100000);
+        assert_eq!(
+            header.console_flags_tag().unwrap().console_flags(),
+            ConsoleFlags::EGA_TEXT_SUPPORTED
+        );
+        assert_eq!(header.framebuffer_tag().unwrap().height(), 768);
+        assert!(header.requires_module_alignment());
+        assert_eq!(header.entry_point(BootEnvironment::Bios), Some(0x100040));
+        assert_eq!(header.entry_point(BootEnvironment::Efi32), Some(0x100080));
+        assert_eq!(header.entry_point(BootEnvironment::Efi64), Some(0x1000c0));
+        assert_eq!(header.relocatable_tag().unwrap().align(), 0x1000);
+        let custom = header.unknown_tags().next().unwrap();
+        assert_eq!(custom.header().size(), 11);
+        assert_eq!(&custom.payload()[..3], b"abc");
+        assert_eq!(header.iter().count(), 12);
+    }
+
+    #[test]
+    fn test_information_request_padding() {
+        let header = StaticHeader::<48>::new(HeaderTagISA::I386)
+            .information_request(HeaderTagFlag::Optional, &[6]);
+        let mut expected = [0_u8; 24];
+        expected[0..2].copy_from_slice(&1_u16.to_ne_bytes());
+        expected[2..4].copy_from_slice(&1_u16.to_ne_bytes());
+        expected[4..8].copy_from_slice(&12_u32.to_ne_bytes());
+        expected[8..12].copy_from_slice(&6_u32.to_ne_bytes());
+        // Zeroed padding instead of the size of the previous end tag.
+        expected[20..24].copy_from_slice(&8_u32.to_ne_bytes());
+        assert_eq!(header.as_bytes().len(), 40);
+        assert_eq!(&header.as_bytes()[16..], expected);
+    }
+
+    #[test]
+    fn test_empty() {
+        let header = StaticHeader::<24>::new(HeaderTagISA::MIPS32);
+        assert_eq!(header.as_bytes().len(), 24);
+        let header = unsafe { Multiboot2Header::load(header.as_ptr()) }.unwrap();
+        assert_eq!(header.arch(), HeaderTagISA::MIPS32);
+        assert_eq!(header.iter().count(), 1);
+    }
+}
@@ -2,12 +2,15 @@
 //! code at the end of the official Multiboot2 spec. These tags follow in memory right after
 //! [`crate::Multiboot2BasicHeader`].
 
+use core::fmt;
+use core::fmt::{Debug, Formatter};
 use core::mem;
 use multiboot2_common::Header;
 
 /// ISA/ARCH in Multiboot2 header.
 #[repr(u32)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum HeaderTagISA {
     /// Spec: "means 32-bit (protected) mode of i386".
     /// Caution: This is confusing. If you use the EFI64-tag
@@ -18,36 +21,52 @@ pub enum HeaderTagISA {
     MIPS32 = 4,
 }
 
+impl HeaderTagISA {
+    /// Returns the ISA for the given raw value, if it is defined by the spec.
+    #[must_use]
+    pub const fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::I386),
+            4 => Some(Self::MIPS32),
+            _ => None,
+        }
+    }
+}
+
 /// Possible types for header tags of a Multiboot2 header.
 ///
 /// The names and values are taken from the example C code at the bottom of the
 /// Multiboot2 specification. This value stands in the `typ` property of
 /// [`HeaderTagHeader`].
-#[repr(u16)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum HeaderTagType {
     /// Type for [`crate::EndHeaderTag`].
-    End = 0,
+    End,
     /// Type for [`crate::InformationRequestHeaderTag`].
-    InformationRequest = 1,
+    InformationRequest,
     /// Type for [`crate::AddressHeaderTag`].
-    Address = 2,
+    Address,
     /// Type for [`crate::EntryAddressHeaderTag`].
-    EntryAddress = 3,
+    EntryAddress,
     /// Type for [`crate::ConsoleHeaderTag`].
-    ConsoleFlags = 4,
+    ConsoleFlags,
     /// Type for [`crate::FramebufferHeaderTag`].
-    Framebuffer = 5,
+    Framebuffer,
     /// Type for [`crate::ModuleAlignHeaderTag`].
-    ModuleAlign = 6,
+    ModuleAlign,
     /// Type for [`crate::EfiBootServiceHeaderTag`].
-    EfiBS = 7,
+    EfiBS,
     /// Type for [`crate::EntryEfi32HeaderTag`].
-    EntryAddressEFI32 = 8,
+    EntryAddressEFI32,
     /// Type for [`crate::EntryEfi64HeaderTag`].
-    EntryAddressEFI64 = 9,
+    EntryAddressEFI64,
     /// Type for [`crate::RelocatableHeaderTag`].
-    Relocatable = 10,
+    Relocatable,
+    /// Any type that is not known to this crate, for example, from an
+    /// experimental agreement between a kernel and a bootloader. See
+    /// [`crate::GenericHeaderTag`].
+    Custom(u16),
 }
 
 impl HeaderTagType {
@@ -56,11 +75,62 @@ impl HeaderTagType {
     pub const fn count() -> u32 {
         11
     }
+
+    /// Returns the type for the given raw value.
+    #[must_use]
+    pub const fn from_u16(value: u16) -> Self {
+        match value {
+            0 => Self::End,
+            1 => Self::InformationRequest,
+            2 => Self::Address,
+            3 => Self::EntryAddress,
+            4 => Self::ConsoleFlags,
+            5 => Self::Framebuffer,
+            6 => Self::ModuleAlign,
+            7 => Self::EfiBS,
+            8 => Self::EntryAddressEFI32,
+            9 => Self::EntryAddressEFI64,
+            10 => Self::Relocatable,
+            c => Self::Custom(c),
+        }
+    }
+
+    /// Returns the raw value of the type.
+    #[must_use]
+    pub const fn val(self) -> u16 {
+        match self {
+            Self::End => 0,
+            Self::InformationRequest => 1,
+            Self::Address => 2,
+            Self::EntryAddress => 3,
+            Self::ConsoleFlags => 4,
+            Self::Framebuffer => 5,
+            Self::ModuleAlign => 6,
+            Self::EfiBS => 7,
+            Self::EntryAddressEFI32 => 8,
+            Self::EntryAddressEFI64 => 9,
+            Self::Relocatable => 10,
+            Self::Custom(c) => c,
+        }
+    }
+}
+
+impl From<u16> for HeaderTagType {
+    fn from(value: u16) -> Self {
+        Self::from_u16(value)
+    }
+}
+
+impl From<HeaderTagType> for u16 {
+    fn from(value: HeaderTagType) -> Self {
+        value.val()
+    }
 }
 
 /// Flags for Multiboot2 header tags.
 #[repr(u16)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum HeaderTagFlag {
     /// Bootloader must provide this tag. If this is not possible, the
     /// bootloader will fail loading the kernel.
@@ -71,12 +141,14 @@ pub enum HeaderTagFlag {
 
 /// The common header that all header tags share. Specific tags may have
 /// additional fields that depend on the `typ` and the `size` field.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// The type and the flags are stored as raw values, so that headers with tags
+/// unknown to this crate can be parsed.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C)]
 pub struct HeaderTagHeader {
-    typ: HeaderTagType, /* u16 */
-    // u16 value
-    flags: HeaderTagFlag, /* u16 */
+    typ: u16,
+    flags: u16,
     size: u32,
     // Followed by optional additional tag specific fields.
 }
@@ -85,19 +157,34 @@ impl HeaderTagHeader {
     /// Creates a new header.
     #[must_use]
     pub const fn new(typ: HeaderTagType, flags: HeaderTagFlag, size: u32) -> Self {
-        Self { typ, flags, size }
+        Self {
+            typ: typ.val(),
+            flags: flags as u16,
+            size,
+        }
     }
 
     /// Returns the [`HeaderTagType`].
     #[must_use]
     pub const fn typ(&self) -> HeaderTagType {
-        self.typ
+        HeaderTagType::from_u16(self.typ)
     }
 
     /// Returns the [`HeaderTagFlag`]s.
     #[must_use]
     pub const fn flags(&self) -> HeaderTagFlag {
-        self.flags
+        if self.flags & 1 == 0 {
+            HeaderTagFlag::Required
+        } else {
+            HeaderTagFlag::Optional
+        }
+    }
+
+    /// Returns whether the bootloader must refuse to boot the image if it
+    /// doesn't support this tag.
+    #[must_use]
+    pub const fn is_required(&self) -> bool {
+        matches!(self.flags(), HeaderTagFlag::Required)
     }
 
     /// Returns the size.
@@ -107,6 +194,16 @@ impl HeaderTagHeader {
     }
 }
 
+impl Debug for HeaderTagHeader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeaderTagHeader")
+            .field("typ", &self.typ())
+            .field("flags", &self.flags())
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
 impl Header for HeaderTagHeader {
     fn payload_len(&self) -> usize {
         self.size as usize - mem::size_of::<Self>()
@@ -119,10 +216,27 @@ impl Header for HeaderTagHeader {
 
 #[cfg(test)]
 mod tests {
-    use crate::HeaderTagHeader;
+    use crate::{HeaderTagFlag, HeaderTagHeader, HeaderTagType};
 
     #[test]
     fn test_assert_size() {
         assert_eq!(core::mem::size_of::<HeaderTagHeader>(), 2 + 2 + 4);
     }
+
+    #[test]
+    fn test_header_tag_type() {
+        for val in 0..=u16::from(HeaderTagType::Relocatable) {
+            assert_eq!(HeaderTagType::from(val).val(), val);
+            assert!(!matches!(
+                HeaderTagType::from(val),
+                HeaderTagType::Custom(_)
+            ));
+        }
+        assert_eq!(HeaderTagType::from(0x1337), HeaderTagType::Custom(0x1337));
+        assert_eq!(u16::from(HeaderTagType::Custom(0x1337)), 0x1337);
+        let header = HeaderTagHeader::new(HeaderTagType::Custom(42), HeaderTagFlag::Optional, 8);
+        assert_eq!(header.typ(), HeaderTagType::Custom(42));
+        assert_eq!(header.flags(), HeaderTagFlag::Optional);
+        assert!(!header.is_required());
+    }
 }
@@ -0,0 +1,540 @@
+//! Helper for end-to-end tests of Multiboot2 headers and boot information.
+//!
+//! [`TestKernel`] assembles a minimal 32-bit ELF kernel from a stub and a
+//! given Multiboot2 header. [`Qemu`] boots it with a real bootloader in QEMU.
+//! The stub dumps the boot information it receives to the QEMU debug console,
+//! from where it is captured and returned for assertions:
+//!
+//! ```no_run
+//! use multiboot2::{BootInformation, MaybeDynSized};
+//! use multiboot2_header::{Builder, HeaderTagISA};
+//! use multiboot2_qemu::{Bootloader, Qemu, TestKernel};
+//!
+//! let header = Builder::new(HeaderTagISA::I386).build();
+//! let kernel = TestKernel::new(header.as_bytes().as_ref());
+//! let mbi = Qemu::new(Bootloader::Grub)
+//!     .cmdline("foo")
+//!     .run(&kernel)
+//!     .unwrap();
+//! let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+//! assert_eq!(mbi.command_line_tag().unwrap().cmdline(), Ok("foo"));
+//! ```
+//!
+//! QEMU's `-kernel` option only boots Multiboot (1) kernels. Therefore, the
+//! kernel is either chainloaded by a Multiboot (1) kernel that speaks
+//! Multiboot2 to its module, such as the chainloader of the integration test
+//! of this repository, or booted from a GRUB image created with
+//! `grub-mkrescue`. The tools must be in `PATH`.
+//!
+//! ## MSRV
+//!
+//! The MSRV is 1.81.0 stable.
+
+// --- BEGIN STYLE CHECKS ---
+#![deny(
+    clippy::all,
+    clippy::cargo,
+    clippy::nursery,
+    clippy::must_use_candidate,
+    // clippy::restriction,
+    // clippy::pedantic
+)]
+// now allow a few rules which are denied by the above statement
+// --> They are either ridiculous, not necessary, or we can't fix them.
+#![allow(clippy::multiple_crate_versions)]
+#![deny(missing_docs)]
+#![deny(missing_debug_implementations)]
+#![deny(rustdoc::all)]
+// --- END STYLE CHECKS ---
+
+use derive_more::Display;
+use multiboot2::{BootInformationBuffer, LoadError};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use std::{fs, thread};
+
+/// Physical address at which the [`TestKernel`] is loaded.
+pub const LOAD_ADDR: u32 = 0x40_0000;
+
+/// Offset of the Multiboot2 header in the [`TestKernel`].
+const HEADER_OFFSET: usize = 0x100;
+
+/// Offset of [`STUB`] in the [`TestKernel`].
+const STUB_OFFSET: usize = 0x60;
+
+/// Size of the stack of the stub.
+const STACK_SIZE: u32 = 0x1000;
+
+/// The code of `stub.S`, linked at `LOAD_ADDR + STUB_OFFSET`. The test
+/// `stub_matches_source` checks that `stub.bin` is up to date.
+const STUB: &[u8] = include_bytes!("stub.bin");
+
+/// Offset of the stack top immediate in [`STUB`].
+const STUB_STACK_TOP: usize = 2;
+
+/// QEMU exit status of the stub after dumping the boot information.
+pub const EXIT_SUCCESS: i32 = 73;
+
+/// QEMU exit status of the stub if the bootloader magic is wrong.
+pub const EXIT_BAD_MAGIC: i32 = 3;
+
+/// Minimal 32-bit ELF kernel with a given Multiboot2 header.
+///
+/// The kernel consists of one loadable segment at [`LOAD_ADDR`] with the stub
+/// code, the header, and the stack. The header should not contain an address
+/// tag, as the ELF segment describes where to load the kernel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestKernel {
+    elf: Vec<u8>,
+}
+
+impl TestKernel {
+    /// Assembles a kernel with the given header. The header is not validated,
+    /// so that the behavior of bootloaders for broken headers can be tested.
+    ///
+    /// # Panics
+    /// Panics if the header doesn't fit into the 32 KiB that the bootloader
+    /// searches.
+    #[must_use]
+    pub fn new(header: &[u8]) -> Self {
+        assert!(
+            HEADER_OFFSET + header.len() <= 0x8000,
+            "header must be within the first 32 KiB"
+        );
+        let file_size = (HEADER_OFFSET + header.len()).next_multiple_of(16);
+        let mem_size = file_size as u32 + STACK_SIZE;
+        let entry = LOAD_ADDR + STUB_OFFSET as u32;
+
+        let mut elf = vec![0; file_size];
+        // ELF header
+        elf[..16].copy_from_slice(b"\x7fELF\x01\x01\x01\0\0\0\0\0\0\0\0\0");
+        put_u16(&mut elf, 16, 2); // executable
+        put_u16(&mut elf, 18, 3); // i386
+        put_u32(&mut elf, 20, 1); // version
+        put_u32(&mut elf, 24, entry);
+        put_u32(&mut elf, 28, 52); // program header offset
+        put_u16(&mut elf, 40, 52); // ELF header size
+        put_u16(&mut elf, 42, 32); // program header size
+        put_u16(&mut elf, 44, 1); // number of program headers
+        put_u16(&mut elf, 46, 40); // section header size
+                                   // Program header
+        put_u32(&mut elf, 52, 1); // loadable
+        put_u32(&mut elf, 56, 0); // offset
+        put_u32(&mut elf, 60, LOAD_ADDR); // virtual address
+        put_u32(&mut elf, 64, LOAD_ADDR); // physical address
+        put_u32(&mut elf, 68, file_size as u32);
+        put_u32(&mut elf, 72, mem_size);
+        put_u32(&mut elf, 76, 7); // read, write, execute
+        put_u32(&mut elf, 80, 0x1000); // alignment
+
+        elf[STUB_OFFSET..][..STUB.len()].copy_from_slice(STUB);
+        put_u32(&mut elf, STUB_OFFSET + STUB_STACK_TOP, LOAD_ADDR + mem_size);
+        elf[HEADER_OFFSET..][..header.len()].copy_from_slice(header);
+        Self { elf }
+    }
+
+    /// Returns the ELF file.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.elf
+    }
+}
+
+fn put_u16(buf: &mut [u8], offset: usize, value: u16) {
+    buf[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buf: &mut [u8], offset: usize, value: u32) {
+    buf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+/// The bootloader that boots the [`TestKernel`], see [`Qemu`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Bootloader {
+    /// GRUB, booted from an image created with `grub-mkrescue`, which needs
+    /// `xorriso`.
+    Grub,
+    /// A Multiboot (1) kernel that QEMU boots with `-kernel` and that
+    /// chainloads its first module, the [`TestKernel`], via Multiboot2. It
+    /// gets the command line `chainloader`.
+    Chainloader(PathBuf),
+}
+
+/// Error of [`Qemu::run`].
+#[derive(Display, Debug)]
+pub enum Error {
+    /// A file couldn't be written or a tool couldn't be started.
+    #[display("I/O error: {_0}")]
+    Io(io::Error),
+    /// `grub-mkrescue` failed.
+    #[display("grub-mkrescue failed: {_0}")]
+    Grub(String),
+    /// QEMU didn't exit within the timeout.
+    #[display("QEMU timed out")]
+    Timeout {
+        /// The output of the debug console.
+        output: String,
+    },
+    /// QEMU exited with an unexpected status, e.g., because the stub found a
+    /// wrong bootloader magic ([`EXIT_BAD_MAGIC`]) or the bootloader rejected
+    /// the kernel.
+    #[display("QEMU exited with status {status:?}")]
+    Exit {
+        /// The exit status, if QEMU wasn't killed by a signal.
+        status: Option<i32>,
+        /// The output of the debug console.
+        output: String,
+    },
+    /// The output contains no valid dump of the boot information.
+    #[display("invalid boot information dump")]
+    InvalidDump {
+        /// The output of the debug console.
+        output: String,
+    },
+    /// The dumped boot information is invalid.
+    #[display("invalid boot information: {_0}")]
+    Load(LoadError),
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Load(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Configuration of a QEMU run of a [`TestKernel`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Qemu {
+    bootloader: Bootloader,
+    qemu: PathBuf,
+    memory_mib: u32,
+    cmdline: String,
+    modules: Vec<(PathBuf, String)>,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+impl Qemu {
+    /// Creates a configuration for `qemu-system-x86_64` with 128 MiB RAM and
+    /// a timeout of 30 seconds.
+    #[must_use]
+    pub fn new(bootloader: Bootloader) -> Self {
+        Self {
+            bootloader,
+            qemu: PathBuf::from("qemu-system-x86_64"),
+            memory_mib: 128,
+            cmdline: String::new(),
+            modules: Vec::new(),
+            args: Vec::new(),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Sets the QEMU binary.
+    #[must_use]
+    pub fn qemu(mut self, qemu: impl Into<PathBuf>) -> Self {
+        self.qemu = qemu.into();
+        self
+    }
+
+    /// Sets the RAM size in MiB.
+    #[must_use]
+    pub const fn memory_mib(mut self, memory_mib: u32) -> Self {
+        self.memory_mib = memory_mib;
+        self
+    }
+
+    /// Sets the kernel command line. Only supported with
+    /// [`Bootloader::Grub`].
+    #[must_use]
+    pub fn cmdline(mut self, cmdline: &str) -> Self {
+        self.cmdline = cmdline.to_string();
+        self
+    }
+
+    /// Adds a module with the given command line. Only supported with
+    /// [`Bootloader::Grub`].
+    #[must_use]
+    pub fn add_module(mut self, path: impl Into<PathBuf>, cmdline: &str) -> Self {
+        self.modules.push((path.into(), cmdline.to_string()));
+        self
+    }
+
+    /// Adds an argument for QEMU, e.g., `-bios` for UEFI firmware.
+    #[must_use]
+    pub fn arg(mut self, arg: &str) -> Self {
+        self.args.push(arg.to_string());
+        self
+    }
+
+    /// Sets the timeout after which QEMU is killed.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Boots the kernel and returns the boot information it received.
+    pub fn run(&self, kernel: &TestKernel) -> Result<BootInformationBuffer, Error> {
+        let dir = TempDir::new()?;
+        let kernel_path = dir.0.join("kernel.elf");
+        fs::write(&kernel_path, kernel.as_bytes())?;
+
+        let mut qemu = Command::new(&self.qemu);
+        match &self.bootloader {
+            Bootloader::Grub => {
+                let image = self.grub_image(&dir.0, &kernel_path)?;
+                qemu.arg("-boot").arg("d").arg("-cdrom").arg(image);
+            }
+            Bootloader::Chainloader(chainloader) => {
+                qemu.arg("-kernel")
+                    .arg(chainloader)
+                    .arg("-append")
+                    .arg("chainloader")
+                    .arg("-initrd")
+                    .arg(format!("{} kernel", kernel_path.display()));
+            }
+        }
+        qemu.arg("-m")
+            .arg(format!("{}M", self.memory_mib))
+            .args(["-debugcon", "stdio", "-no-reboot", "-display", "none"])
+            .args(["-device", "isa-debug-exit,iobase=0xf4,iosize=0x04"])
+            .args(&self.args);
+
+        let (status, output) = run_with_timeout(qemu, self.timeout)?;
+        match status {
+            None => Err(Error::Timeout { output }),
+            Some(EXIT_SUCCESS) => {
+                let Some(bytes) = parse_dump(&output) else {
+                    return Err(Error::InvalidDump { output });
+                };
+                BootInformationBuffer::from_bytes(&bytes).map_err(Error::Load)
+            }
+            Some(status) => Err(Error::Exit {
+                status: (status >= 0).then_some(status),
+                output,
+            }),
+        }
+    }
+
+    /// Creates a GRUB image that boots the kernel and returns its path.
+    fn grub_image(&self, dir: &Path, kernel: &Path) -> Result<PathBuf, Error> {
+        let root = dir.join("iso");
+        fs::create_dir_all(root.join("boot/grub"))?;
+        fs::copy(kernel, root.join("boot/kernel"))?;
+        let mut config = format!(
+            "set timeout=0\nset default=0\nmenuentry \"test\" {{\n    multiboot2 /boot/kernel {}\n",
+            self.cmdline
+        );
+        for (i, (path, cmdline)) in self.modules.iter().enumerate() {
+            fs::copy(path, root.join(format!("boot/module{i}")))?;
+            config.push_str(&format!("    module2 /boot/module{i} {cmdline}\n"));
+        }
+        config.push_str("    boot\n}\n");
+        fs::write(root.join("boot/grub/grub.cfg"), config)?;
+
+        let image = dir.join("grub.iso");
+        let output = Command::new("grub-mkrescue")
+            .arg("-o")
+            .arg(&image)
+            .arg(&root)
+            .output()?;
+        if !output.status.success() {
+            return Err(Error::Grub(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            ));
+        }
+        Ok(image)
+    }
+}
+
+/// Runs the command and returns its exit status, or `None` if it was killed
+/// after the timeout, and its standard output.
+fn run_with_timeout(
+    mut command: Command,
+    timeout: Duration,
+) -> Result<(Option<i32>, String), Error> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()?;
+    let mut stdout = child.stdout.take().expect("stdout should be piped");
+    let reader = thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break Some(status.code().unwrap_or(-1));
+        }
+        if start.elapsed() > timeout {
+            child.kill()?;
+            child.wait()?;
+            break None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    let output = reader.join().unwrap_or_default();
+    Ok((status, String::from_utf8_lossy(&output).into_owned()))
+}
+
+/// Extracts the boot information from the hex dump between the
+/// `MBI-DUMP-BEGIN` and `MBI-DUMP-END` lines of the output.
+#[must_use]
+pub fn parse_dump(output: &str) -> Option<Vec<u8>> {
+    let (_, rest) = output.split_once("MBI-DUMP-BEGIN")?;
+    let (hex, _) = rest.split_once("MBI-DUMP-END")?;
+    let hex = hex
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    hex.chunks(2)
+        .map(|pair| {
+            let digit = |c: char| c.to_digit(16);
+            Some((digit(pair[0])? * 16 + digit(pair[1])?) as u8)
+        })
+        .collect()
+}
+
+/// Temporary directory that is removed on drop.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "multiboot2-qemu-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiboot2::MaybeDynSized;
+    use multiboot2_common::AlignedBuffer;
+    use multiboot2_header::{Builder, HeaderTagISA, Multiboot2Header};
+
+    fn read_u32(buf: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_kernel() {
+        let header = Builder::new(HeaderTagISA::I386).build();
+        let kernel = TestKernel::new(header.as_bytes().as_ref());
+        let elf = kernel.as_bytes();
+        assert!(elf.starts_with(b"\x7fELF\x01\x01"));
+        assert_eq!(read_u32(elf, 24), 0x40_0060);
+        assert_eq!(read_u32(elf, 68), elf.len() as u32);
+        // The stack is right after the file.
+        let stack_top = read_u32(elf, STUB_OFFSET + STUB_STACK_TOP);
+        assert_eq!(stack_top, LOAD_ADDR + elf.len() as u32 + STACK_SIZE);
+        // The markers are at the addresses that the stub prints.
+        assert_eq!(&elf[0xcf..0xdf], b"\nMBI-DUMP-BEGIN\n");
+        assert_eq!(&elf[0xdf..0xed], b"\nMBI-DUMP-END\n");
+
+        // The header is found by a bootloader.
+        let image = AlignedBuffer::from_bytes(elf);
+        let (header, offset) = Multiboot2Header::search(&image).unwrap().unwrap();
+        assert_eq!(offset, HEADER_OFFSET);
+        assert_eq!(header.arch(), HeaderTagISA::I386);
+    }
+
+    /// Assembles `stub.S` like its header comment describes. Skipped if the
+    /// GNU binutils are not installed.
+    #[test]
+    fn stub_matches_source() {
+        let dir = TempDir::new().unwrap();
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/stub.S");
+        let obj = dir.0.join("stub.o");
+        let bin = dir.0.join("stub.bin");
+        let status = match Command::new("as")
+            .arg("--32")
+            .arg(&src)
+            .arg("-o")
+            .arg(&obj)
+            .status()
+        {
+            Ok(status) => status,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                eprintln!("skipped: `as` not found");
+                return;
+            }
+            Err(e) => panic!("{e}"),
+        };
+        assert!(status.success());
+        let status = Command::new("ld")
+            .args(["-m", "elf_i386", "-Ttext=0x400060", "--oformat", "binary"])
+            .arg(&obj)
+            .arg("-o")
+            .arg(&bin)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(fs::read(&bin).unwrap(), STUB, "run the commands in stub.S");
+    }
+
+    #[test]
+    fn dump() {
+        let output =
+            "SeaBIOS\n\nMBI-DUMP-BEGIN\n1000000000000000\n00000000\n08000000\nMBI-DUMP-END\n";
+        let bytes = parse_dump(output).unwrap();
+        assert_eq!(bytes, [0x10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0]);
+        let mbi = BootInformationBuffer::from_bytes(&bytes).unwrap();
+        assert_eq!(mbi.len(), 16);
+
+        assert!(parse_dump("MBI-DUMP-BEGIN\n123\nMBI-DUMP-END").is_none());
+        assert!(parse_dump("MBI-DUMP-BEGIN\nzz\nMBI-DUMP-END").is_none());
+        assert!(parse_dump("MBI-DUMP-BEGIN\n00").is_none());
+    }
+
+    #[test]
+    #[ignore = "requires QEMU, grub-mkrescue, and xorriso"]
+    fn grub() {
+        let header = Builder::new(HeaderTagISA::I386).build();
+        let kernel = TestKernel::new(header.as_bytes().as_ref());
+        let mbi = Qemu::new(Bootloader::Grub)
+            .cmdline("foo bar")
+            .run(&kernel)
+            .unwrap();
+        let mbi = unsafe { multiboot2::BootInformation::load(mbi.as_ptr()) }.unwrap();
+        assert_eq!(mbi.command_line_tag().unwrap().cmdline(), Ok("foo bar"));
+        assert!(mbi
+            .boot_loader_name_tag()
+            .unwrap()
+            .name()
+            .unwrap()
+            .starts_with("GRUB"));
+    }
+}
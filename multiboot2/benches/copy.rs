@@ -0,0 +1,62 @@
+//! Benchmarks the relocation of the boot information with
+//! [`BootInformation::copy_to`] and [`BootInformation::to_buffer`] against a
+//! naive byte-by-byte copy.
+//!
+//! Run with `cargo bench -p multiboot2 --bench copy`.
+
+use multiboot2::{
+    BootInformation, Builder, CommandLineTag, EFIMemoryAreaType, EFIMemoryDesc, EFIMemoryMapTag,
+};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 10_000;
+
+#[repr(C, align(8))]
+struct Aligned([u8; 64 * 1024]);
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    // Warm up.
+    for _ in 0..ITERATIONS / 10 {
+        f();
+    }
+    let begin = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed: Duration = begin.elapsed() / ITERATIONS;
+    println!("{name:<16} {elapsed:>12?} per copy");
+}
+
+fn main() {
+    // A big EFI memory map dominates the size of real-world MBIs.
+    let descs = (0..512)
+        .map(|i| EFIMemoryDesc {
+            ty: EFIMemoryAreaType::CONVENTIONAL,
+            phys_start: i * 0x10000,
+            page_count: 16,
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    let mbi = Builder::new()
+        .cmdline(CommandLineTag::new("console=ttyS0"))
+        .efi_mmap(EFIMemoryMapTag::new_from_descs(&descs))
+        .build();
+    let info = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+    let src = mbi.as_bytes();
+    println!("boot information of {} bytes", info.total_size());
+
+    let mut dst = Box::new(Aligned([0; 64 * 1024]));
+
+    bench("byte loop", || {
+        for (dst, src) in dst.0.iter_mut().zip(src) {
+            *dst = black_box(*src);
+        }
+    });
+    bench("copy_to", || {
+        black_box(info.copy_to(&mut dst.0).unwrap());
+    });
+    bench("to_buffer", || {
+        black_box(info.to_buffer());
+    });
+}
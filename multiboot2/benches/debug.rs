@@ -0,0 +1,103 @@
+//! Benchmarks the `Debug` formatting of a boot information with large tags
+//! into a fixed-size `no_std`-style sink and verifies that formatting doesn't
+//! allocate.
+//!
+//! Run with `cargo bench -p multiboot2 --bench debug`.
+
+use multiboot2::{
+    BootInformation, Builder, CommandLineTag, EFIMemoryAreaType, EFIMemoryDesc, EFIMemoryMapTag,
+    ElfSectionsTag, MemoryArea, MemoryAreaType, MemoryMapTag, SmbiosTag,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::{self, Write};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 1_000;
+
+/// Counts all allocations to prove that formatting doesn't allocate.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Discards the output, like a serial console that only counts bytes.
+struct Sink(usize);
+
+impl Write for Sink {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += black_box(s).len();
+        Ok(())
+    }
+}
+
+fn bench(name: &str, value: &dyn fmt::Debug) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let mut sink = Sink(0);
+    let begin = Instant::now();
+    for _ in 0..ITERATIONS {
+        write!(sink, "{value:?}").unwrap();
+    }
+    let elapsed: Duration = begin.elapsed() / ITERATIONS;
+    assert_eq!(
+        ALLOCATIONS.load(Ordering::Relaxed),
+        allocations,
+        "formatting {name} must not allocate"
+    );
+    let bytes = sink.0 / ITERATIONS as usize;
+    println!("{name:<16} {elapsed:>12?} for {bytes} bytes");
+}
+
+fn main() {
+    let areas = (0..256)
+        .map(|i| MemoryArea::new(i * 0x10000, 0x10000, MemoryAreaType::Available))
+        .collect::<Vec<_>>();
+    let descs = (0..256)
+        .map(|i| EFIMemoryDesc {
+            ty: EFIMemoryAreaType::CONVENTIONAL,
+            phys_start: i * 0x10000,
+            page_count: 16,
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    // 64-bit section headers of type `SHT_PROGBITS`.
+    let sections = (0..64_u64)
+        .flat_map(|i| {
+            let mut header = [0_u8; 64];
+            header[4..8].copy_from_slice(&1_u32.to_ne_bytes());
+            header[16..24].copy_from_slice(&(0x10_0000 + i * 0x1000).to_ne_bytes());
+            header[32..40].copy_from_slice(&0x1000_u64.to_ne_bytes());
+            header
+        })
+        .collect::<Vec<_>>();
+    // An SMBIOS table with a single string-less structure and end-of-table.
+    let smbios = [1, 4, 0, 0, 0, 0, 127, 4, 0xff, 0xff, 0, 0];
+    let mbi = Builder::new()
+        .cmdline(CommandLineTag::new("console=ttyS0"))
+        .mmap(MemoryMapTag::new(&areas))
+        .efi_mmap(EFIMemoryMapTag::new_from_descs(&descs))
+        .elf_sections(ElfSectionsTag::new(64, 64, 0, &sections))
+        .add_smbios(SmbiosTag::new(3, 0, &smbios))
+        .build();
+    let info = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+
+    bench("memory map", &info.memory_map_tag().unwrap());
+    bench("EFI memory map", &info.efi_memory_map_tag().unwrap());
+    bench("ELF sections", &info.elf_sections().unwrap());
+    bench("SMBIOS", &info.smbios_tag().unwrap());
+    bench("boot info", &info);
+}
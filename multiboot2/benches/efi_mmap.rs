@@ -0,0 +1,65 @@
+//! Benchmarks the iteration over an EFI memory map with 500 entries against
+//! a hand-written loop without any checks, which is the lower bound.
+//!
+//! Run with `cargo bench -p multiboot2 --bench efi_mmap`. Comparing the
+//! results with older releases of this crate shows the effect of changes to
+//! the iterator.
+
+use multiboot2::{EFIMemoryAreaType, EFIMemoryDesc, EFIMemoryMapTag, MaybeDynSized};
+use std::hint::black_box;
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 100_000;
+const ENTRIES: usize = 500;
+/// Firmware typically reports descriptors that are bigger than
+/// [`EFIMemoryDesc`].
+const DESC_SIZE: usize = 48;
+
+fn bench(name: &str, mut f: impl FnMut() -> u64) {
+    let begin = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let elapsed: Duration = begin.elapsed() / ITERATIONS;
+    println!("{name:<16} {elapsed:>12?}");
+}
+
+fn main() {
+    let mut mmap = vec![0_u64; ENTRIES * DESC_SIZE / size_of::<u64>()];
+    for (i, chunk) in mmap
+        .chunks_exact_mut(DESC_SIZE / size_of::<u64>())
+        .enumerate()
+    {
+        let desc = EFIMemoryDesc {
+            ty: EFIMemoryAreaType::CONVENTIONAL,
+            phys_start: i as u64 * 0x10000,
+            page_count: 16,
+            ..Default::default()
+        };
+        unsafe { chunk.as_mut_ptr().cast::<EFIMemoryDesc>().write(desc) };
+    }
+    let mmap = unsafe { mmap.align_to::<u8>().1 };
+    let tag = EFIMemoryMapTag::new_from_map(DESC_SIZE as u32, EFIMemoryDesc::VERSION, mmap);
+
+    bench("iterator", || {
+        black_box(&tag)
+            .memory_areas()
+            .map(|desc| desc.page_count)
+            .sum()
+    });
+
+    // The descriptors start after the header, `desc_size`, and
+    // `desc_version`.
+    let bytes = &tag.as_bytes()[16..tag.header().size as usize];
+    bench("raw loop", || {
+        let mmap = black_box(bytes);
+        let mut sum = 0;
+        let mut ptr = mmap.as_ptr();
+        for _ in 0..ENTRIES {
+            sum += unsafe { &*ptr.cast::<EFIMemoryDesc>() }.page_count;
+            ptr = ptr.wrapping_add(DESC_SIZE);
+        }
+        sum
+    });
+}
@@ -0,0 +1,62 @@
+//! Benchmarks the lookup of several tags with one `*_tag()` call per tag
+//! against a single pass over [`BootInformation::generic_tags`] that
+//! downcasts the already validated tags.
+//!
+//! Run with `cargo bench -p multiboot2 --bench lookup`, optionally with
+//! `--features tag_cache`.
+
+use multiboot2::{
+    BasicMemoryInfoTag, BootInformation, BootLoaderNameTag, Builder, CommandLineTag,
+    EFIMemoryAreaType, EFIMemoryDesc, EFIMemoryMapTag, RsdpV1Tag,
+};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 100_000;
+
+fn bench(name: &str, mut f: impl FnMut() -> usize) {
+    let begin = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let elapsed: Duration = begin.elapsed() / ITERATIONS;
+    println!("{name:<16} {elapsed:>12?}");
+}
+
+fn main() {
+    let descs = (0..512)
+        .map(|i| EFIMemoryDesc {
+            ty: EFIMemoryAreaType::CONVENTIONAL,
+            phys_start: i * 0x10000,
+            page_count: 16,
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    let mbi = Builder::new()
+        .cmdline(CommandLineTag::new("console=ttyS0"))
+        .bootloader(BootLoaderNameTag::new("GRUB 2.12"))
+        .add_module(0x1000, 0x2000, "initrd")
+        .meminfo(BasicMemoryInfoTag::new(640, 0x1_0000))
+        .efi_mmap(EFIMemoryMapTag::new_from_descs(&descs))
+        .rsdpv1(RsdpV1Tag::new(0, *b"BOCHS ", 0, 0))
+        .build();
+    let info = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+
+    bench("accessors", || {
+        usize::from(info.command_line_tag().is_some())
+            + usize::from(info.boot_loader_name_tag().is_some())
+            + usize::from(info.basic_memory_info_tag().is_some())
+            + usize::from(info.rsdp_v1_tag().is_some())
+            + usize::from(info.framebuffer_tag().is_some())
+    });
+    bench("single pass", || {
+        info.generic_tags()
+            .filter(|tag| {
+                tag.downcast::<CommandLineTag>().is_some()
+                    || tag.downcast::<BootLoaderNameTag>().is_some()
+                    || tag.downcast::<BasicMemoryInfoTag>().is_some()
+                    || tag.downcast::<RsdpV1Tag>().is_some()
+            })
+            .count()
+    });
+}
@@ -0,0 +1,15 @@
+//! Feeds arbitrary bytes to [`check_robustness`], which loads them as boot
+//! information and runs all accessors. Seed it with the crate's corpus:
+//!
+//! ```sh
+//! cargo +nightly fuzz run load ../corpus
+//! ```
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use multiboot2::testing::check_robustness;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = check_robustness(data);
+});
@@ -0,0 +1,162 @@
+#ifndef MULTIBOOT2_H
+#define MULTIBOOT2_H
+
+/* Generated by cbindgen from the `ffi` feature of the multiboot2 crate. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result of [`mb2_parse`].
+ */
+typedef enum Mb2Status {
+  /**
+   * The boot information is valid.
+   */
+  MB2_STATUS_OK = 0,
+  /**
+   * The pointer is null.
+   */
+  MB2_STATUS_NULL = -1,
+  /**
+   * The pointer is not 8-byte aligned.
+   */
+  MB2_STATUS_WRONG_ALIGNMENT = -2,
+  /**
+   * The memory is shorter than the header.
+   */
+  MB2_STATUS_SHORTER_THAN_HEADER = -3,
+  /**
+   * The terminating padding is missing.
+   */
+  MB2_STATUS_MISSING_PADDING = -4,
+  /**
+   * The reported total size is invalid.
+   */
+  MB2_STATUS_INVALID_REPORTED_TOTAL_SIZE = -5,
+  /**
+   * The mandatory end tag is missing.
+   */
+  MB2_STATUS_NO_END_TAG = -6,
+  /**
+   * A tag overlaps with the next one or exceeds the total size.
+   */
+  MB2_STATUS_INVALID_LAYOUT = -7,
+  /**
+   * The boot information has too many tags or a tag is too big.
+   */
+  MB2_STATUS_LIMIT_EXCEEDED = -8,
+  /**
+   * A tag type that must occur at most once occurs multiple times.
+   */
+  MB2_STATUS_DUPLICATE_TAG = -9,
+} Mb2Status;
+
+/**
+ * A memory area of the memory map.
+ */
+typedef struct Mb2MemoryArea {
+  /**
+   * Physical start address.
+   */
+  uint64_t base_addr;
+  /**
+   * Length in bytes.
+   */
+  uint64_t length;
+  /**
+   * Type of the memory, see [`crate::MemoryAreaType`].
+   */
+  uint32_t typ;
+} Mb2MemoryArea;
+
+/**
+ * A module loaded by the bootloader.
+ */
+typedef struct Mb2Module {
+  /**
+   * Start address of the module.
+   */
+  uint32_t start;
+  /**
+   * End address of the module.
+   */
+  uint32_t end;
+  /**
+   * Command line of the module, not NUL-terminated. Null if it isn't
+   * valid UTF-8.
+   */
+  const char *cmdline;
+  /**
+   * Length of the command line in bytes.
+   */
+  size_t cmdline_len;
+} Mb2Module;
+
+/**
+ * Validates the boot information.
+ *
+ * # Safety
+ * `mbi` must be null or valid for reading.
+ */
+Mb2Status mb2_parse(const void *mbi);
+
+/**
+ * Returns the kernel command line, not NUL-terminated, and writes its
+ * length to `len`, if `len` isn't null. Returns null if there is no valid
+ * command line.
+ *
+ * # Safety
+ * `mbi` must be null or valid for reading. `len` must be null or valid for
+ * writing.
+ */
+const char *mb2_cmdline(const void *mbi, size_t *len);
+
+/**
+ * Returns the name of the bootloader, like [`mb2_cmdline`].
+ *
+ * # Safety
+ * See [`mb2_cmdline`].
+ */
+const char *mb2_boot_loader_name(const void *mbi, size_t *len);
+
+/**
+ * Returns the number of areas in the memory map, or `0` if there is none.
+ *
+ * # Safety
+ * `mbi` must be null or valid for reading.
+ */
+size_t mb2_memory_map_count(const void *mbi);
+
+/**
+ * Writes the memory area with the given index to `out`. Returns `false` if
+ * there is no such area.
+ *
+ * # Safety
+ * `mbi` must be null or valid for reading. `out` must be null or valid for
+ * writing.
+ */
+bool mb2_memory_map_entry(const void *mbi, size_t index, Mb2MemoryArea *out);
+
+/**
+ * Returns the number of modules.
+ *
+ * # Safety
+ * `mbi` must be null or valid for reading.
+ */
+size_t mb2_module_count(const void *mbi);
+
+/**
+ * Writes the module with the given index to `out`. Returns `false` if there
+ * is no such module.
+ *
+ * # Safety
+ * `mbi` must be null or valid for reading. `out` must be null or valid for
+ * writing.
+ */
+bool mb2_module(const void *mbi, size_t index, Mb2Module *out);
+
+#endif /* MULTIBOOT2_H */
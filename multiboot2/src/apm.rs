@@ -1,26 +1,33 @@
 //! Module for [`ApmTag`].
 
 use crate::{TagHeader, TagType};
+use bitflags::bitflags;
 use core::mem;
 use multiboot2_common::{MaybeDynSized, Tag};
 
-/// The Advanced Power Management (APM) tag.
-#[derive(Debug)]
+/// The Advanced Power Management (APM) tag. It contains the APM table, which
+/// is the information returned by the APM BIOS installation check and the
+/// protected mode connect interface.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C, align(8))]
 pub struct ApmTag {
     header: TagHeader,
     version: u16,
     cseg: u16,
     offset: u32,
-    cset_16: u16,
+    cseg_16: u16,
     dseg: u16,
-    flags: u16,
+    flags: ApmFlags,
     cseg_len: u16,
     cseg_16_len: u16,
     dseg_len: u16,
 }
 
 impl ApmTag {
+    /// Size of the tag as specified, without the trailing padding of the
+    /// Rust type.
+    const SIZE: usize = mem::size_of::<TagHeader>() + 20;
+
     /// Creates a new tag.
     #[allow(clippy::too_many_arguments)]
     #[must_use]
@@ -28,20 +35,20 @@ impl ApmTag {
         version: u16,
         cseg: u16,
         offset: u32,
-        cset_16: u16,
-        dset: u16,
-        flags: u16,
+        cseg_16: u16,
+        dseg: u16,
+        flags: ApmFlags,
         cseg_len: u16,
         cseg_16_len: u16,
         dseg_len: u16,
     ) -> Self {
         Self {
-            header: TagHeader::new(TagType::Apm, mem::size_of::<Self>() as u32),
+            header: TagHeader::new(Self::ID, Self::SIZE as u32),
             version,
             cseg,
             offset,
-            cset_16,
-            dseg: dset,
+            cseg_16,
+            dseg,
             flags,
             cseg_len,
             cseg_16_len,
@@ -49,13 +56,22 @@ impl ApmTag {
         }
     }
 
-    /// The version number of the APM BIOS.
+    /// The version number of the APM BIOS, encoded as BCD, e.g., `0x0102` for
+    /// version 1.2.
     #[must_use]
     pub const fn version(&self) -> u16 {
         self.version
     }
 
-    /// Contains the 16-bit code segment (CS) address for the APM entry point.
+    /// Returns the major and minor version of the APM BIOS.
+    #[must_use]
+    pub const fn version_major_minor(&self) -> (u8, u8) {
+        let bytes = self.version.to_le_bytes();
+        (bytes[1], bytes[0])
+    }
+
+    /// Contains the 32-bit protected mode code segment (CS) selector for the
+    /// APM entry point.
     #[must_use]
     pub const fn cseg(&self) -> u16 {
         self.cseg
@@ -68,11 +84,19 @@ impl ApmTag {
         self.offset
     }
 
+    /// Contains the 16-bit protected mode code segment (CS) selector used for
+    /// APM functions.
+    #[must_use]
+    pub const fn cseg_16(&self) -> u16 {
+        self.cseg_16
+    }
+
     /// Contains the 16-bit code segment (CS) address used for 16-bit protected
     /// mode APM functions.
+    #[deprecated = "use ApmTag::cseg_16 instead"]
     #[must_use]
     pub const fn cset_16(&self) -> u16 {
-        self.cset_16
+        self.cseg_16
     }
 
     /// Holds the 16-bit data segment (DS) address used by the APM BIOS for
@@ -85,12 +109,12 @@ impl ApmTag {
     /// Indicates the status and characteristics of the APM connection, such as
     /// if APM is present and its capabilities.
     #[must_use]
-    pub const fn flags(&self) -> u16 {
+    pub const fn flags(&self) -> ApmFlags {
         self.flags
     }
 
-    /// Indicates the length, in bytes, of the data segment (`dseg`) used by
-    /// the APM BIOS
+    /// Indicates the length, in bytes, of the 32-bit code segment (`cseg`)
+    /// used by the APM BIOS.
     #[must_use]
     pub const fn cseg_len(&self) -> u16 {
         self.cseg_len
@@ -124,3 +148,103 @@ impl Tag for ApmTag {
 
     const ID: TagType = TagType::Apm;
 }
+
+bitflags! {
+    /// Flags of the APM BIOS, as returned by the APM installation check.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct ApmFlags: u16 {
+        /// The 16-bit protected mode interface is supported.
+        const PROTECTED_MODE_16_BIT = 0x1;
+
+        /// The 32-bit protected mode interface is supported.
+        const PROTECTED_MODE_32_BIT = 0x2;
+
+        /// The CPU idle call reduces the processor speed.
+        const CPU_IDLE_SLOWS_CLOCK = 0x4;
+
+        /// BIOS power management is disabled.
+        const BIOS_PM_DISABLED = 0x8;
+
+        /// BIOS power management is disengaged.
+        const BIOS_PM_DISENGAGED = 0x10;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GenericTag;
+    use core::borrow::Borrow;
+    use multiboot2_common::test_utils::AlignedBytes;
+
+    #[rustfmt::skip]
+    const fn get_bytes() -> AlignedBytes<32> {
+        AlignedBytes::new([
+            TagType::Apm.val() as u8, 0, 0, 0,
+            28, 0, 0, 0,
+            /* version */
+            0x02, 0x01,
+            /* cseg */
+            0x10, 0x00,
+            /* offset */
+            0x78, 0x56, 0x34, 0x12,
+            /* cseg_16 */
+            0x18, 0x00,
+            /* dseg */
+            0x20, 0x00,
+            /* flags */
+            0x03, 0x00,
+            /* cseg_len */
+            0x00, 0x10,
+            /* cseg_16_len */
+            0x00, 0x20,
+            /* dseg_len */
+            0x00, 0x30,
+            /* padding */
+            0, 0, 0, 0,
+        ])
+    }
+
+    /// Test to parse a given tag.
+    #[test]
+    fn test_parse() {
+        let bytes = get_bytes();
+        let tag = GenericTag::ref_from_slice(bytes.borrow()).unwrap();
+        let tag = tag.downcast::<ApmTag>().unwrap();
+        assert_eq!(tag.header.typ, TagType::Apm);
+        assert_eq!(tag.version(), 0x0102);
+        assert_eq!(tag.version_major_minor(), (1, 2));
+        assert_eq!(tag.cseg(), 0x10);
+        assert_eq!(tag.offset(), 0x12345678);
+        assert_eq!(tag.cseg_16(), 0x18);
+        assert_eq!(tag.dseg(), 0x20);
+        assert_eq!(
+            tag.flags(),
+            ApmFlags::PROTECTED_MODE_16_BIT | ApmFlags::PROTECTED_MODE_32_BIT
+        );
+        assert_eq!(tag.cseg_len(), 0x1000);
+        assert_eq!(tag.cseg_16_len(), 0x2000);
+        assert_eq!(tag.dseg_len(), 0x3000);
+    }
+
+    /// Test to build a tag.
+    #[test]
+    fn test_build() {
+        let tag = ApmTag::new(
+            0x0102,
+            0x10,
+            0x12345678,
+            0x18,
+            0x20,
+            ApmFlags::PROTECTED_MODE_16_BIT | ApmFlags::PROTECTED_MODE_32_BIT,
+            0x1000,
+            0x2000,
+            0x3000,
+        );
+        let bytes = get_bytes();
+        let bytes: &[u8] = bytes.borrow();
+        assert_eq!(tag.header().size, 28);
+        assert_eq!(&tag.as_bytes().as_ref()[..28], &bytes[..28]);
+    }
+}
@@ -0,0 +1,277 @@
+//! `mb2dump`: Pretty-prints a raw dump of a Multiboot2 boot information.
+//!
+//! ```text
+//! mb2dump [--json] [--hexdump] <FILE>
+//! ```
+//!
+//! The dump is loaded with [`ParsePolicy::Lenient`], so that the output of
+//! imperfect bootloaders can still be inspected. `--hexdump` additionally
+//! prints the payload of tags that can't be decoded.
+
+use multiboot2::{
+    ApmTag, BasicMemoryInfoTag, BootInformation, BootInformationBuffer, BootLoaderNameTag,
+    BootdevTag, CommandLineTag, EFIBootServicesNotExitedTag, EFIImageHandle32Tag,
+    EFIImageHandle64Tag, EFIMemoryMapTag, EFISdt32Tag, EFISdt64Tag, ElfSectionsTag, EndTag,
+    FramebufferTag, GenericTag, ImageLoadPhysAddrTag, MemoryMapTag, ModuleTag, NetworkTag,
+    ParsePolicy, RsdpV1Tag, RsdpV2Tag, SmbiosTag, Tag, TagHeader, TagType, VBEInfoTag,
+};
+use std::fmt::{Debug, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: mb2dump [--json] [--hexdump] <FILE>";
+
+/// A decoded tag of the dump.
+struct Entry {
+    offset: usize,
+    typ: TagType,
+    size: u32,
+    /// The `Debug` output of the specific tag type, if it could be decoded.
+    decoded: Option<String>,
+    /// The payload, if it should be hexdumped.
+    payload: Option<Vec<u8>>,
+}
+
+fn main() -> ExitCode {
+    let mut json = false;
+    let mut hexdump = false;
+    let mut path = None;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--hexdump" => hexdump = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => {
+                eprintln!("{USAGE}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("mb2dump: {path}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match dump(&bytes, json, hexdump) {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("mb2dump: {path}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Decodes the dump and formats it as text or JSON.
+fn dump(bytes: &[u8], json: bool, hexdump: bool) -> Result<String, String> {
+    let buffer = BootInformationBuffer::from_bytes_with_policy(bytes, ParsePolicy::Lenient)
+        .map_err(|e| e.to_string())?;
+    // SAFETY: The buffer was validated with the same policy.
+    let mbi = unsafe { BootInformation::load_with_policy(buffer.as_ptr(), ParsePolicy::Lenient) }
+        .map_err(|e| e.to_string())?;
+
+    let entries = mbi
+        .generic_tags()
+        .map(|tag| {
+            let decoded = decode(tag);
+            let payload = (hexdump && decoded.is_none()).then(|| tag.payload().to_vec());
+            Entry {
+                offset: tag as *const GenericTag as *const u8 as usize - mbi.start_address(),
+                typ: tag.typ(),
+                size: tag.size(),
+                decoded,
+                payload,
+            }
+        })
+        .collect::<Vec<_>>();
+    let warnings = mbi.warnings().map(|w| w.to_string()).collect::<Vec<_>>();
+
+    Ok(if json {
+        format_json(mbi.total_size(), &entries, &warnings)
+    } else {
+        format_text(mbi.total_size(), &entries, &warnings)
+    })
+}
+
+/// Returns the `Debug` output of the tag as its specific type.
+fn decode(tag: &GenericTag) -> Option<String> {
+    fn debug<T: Tag<IDType = TagType, Header = TagHeader> + Debug + ?Sized>(
+        tag: &GenericTag,
+    ) -> Option<String> {
+        tag.downcast::<T>().map(|tag| format!("{tag:?}"))
+    }
+
+    match tag.typ() {
+        TagType::End => debug::<EndTag>(tag),
+        TagType::Cmdline => debug::<CommandLineTag>(tag),
+        TagType::BootLoaderName => debug::<BootLoaderNameTag>(tag),
+        TagType::Module => debug::<ModuleTag>(tag),
+        TagType::BasicMeminfo => debug::<BasicMemoryInfoTag>(tag),
+        TagType::Bootdev => debug::<BootdevTag>(tag),
+        TagType::Mmap => debug::<MemoryMapTag>(tag),
+        // The VBE mode info contains an enum that must not be read from
+        // arbitrary bytes, so only the mode is decoded.
+        TagType::Vbe => tag
+            .downcast::<VBEInfoTag>()
+            .map(|tag| format!("VBEInfoTag {{ mode: {:#x} }}", tag.mode())),
+        TagType::Framebuffer => debug::<FramebufferTag>(tag),
+        TagType::ElfSections => debug::<ElfSectionsTag>(tag),
+        TagType::Apm => debug::<ApmTag>(tag),
+        TagType::Efi32 => debug::<EFISdt32Tag>(tag),
+        TagType::Efi64 => debug::<EFISdt64Tag>(tag),
+        TagType::Smbios => debug::<SmbiosTag>(tag),
+        TagType::AcpiV1 => debug::<RsdpV1Tag>(tag),
+        TagType::AcpiV2 => debug::<RsdpV2Tag>(tag),
+        TagType::Network => debug::<NetworkTag>(tag),
+        TagType::EfiMmap => debug::<EFIMemoryMapTag>(tag),
+        TagType::EfiBs => debug::<EFIBootServicesNotExitedTag>(tag),
+        TagType::Efi32Ih => debug::<EFIImageHandle32Tag>(tag),
+        TagType::Efi64Ih => debug::<EFIImageHandle64Tag>(tag),
+        TagType::LoadBaseAddr => debug::<ImageLoadPhysAddrTag>(tag),
+        TagType::Custom(_) => None,
+    }
+}
+
+fn format_text(total_size: usize, entries: &[Entry], warnings: &[String]) -> String {
+    let mut out = format!(
+        "boot information: {total_size:#x} bytes, {} tags\n",
+        entries.len()
+    );
+    for entry in entries {
+        let _ = writeln!(
+            out,
+            "{:#06x}: {:?} ({} bytes)",
+            entry.offset, entry.typ, entry.size
+        );
+        match &entry.decoded {
+            Some(decoded) => {
+                let _ = writeln!(out, "    {decoded}");
+            }
+            None => out.push_str("    <not decoded>\n"),
+        }
+        if let Some(payload) = &entry.payload {
+            for (i, line) in payload.chunks(16).enumerate() {
+                let hex = line.iter().map(|b| format!("{b:02x}")).collect::<Vec<_>>();
+                let _ = writeln!(out, "    {:04x}  {}", i * 16, hex.join(" "));
+            }
+        }
+    }
+    for warning in warnings {
+        let _ = writeln!(out, "warning: {warning}");
+    }
+    out
+}
+
+fn format_json(total_size: usize, entries: &[Entry], warnings: &[String]) -> String {
+    let mut out = format!("{{\"total_size\":{total_size},\"tags\":[");
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(
+            out,
+            "{{\"offset\":{},\"type\":{},\"type_id\":{},\"size\":{}",
+            entry.offset,
+            json_string(&format!("{:?}", entry.typ)),
+            entry.typ.val(),
+            entry.size
+        );
+        if let Some(decoded) = &entry.decoded {
+            let _ = write!(out, ",\"decoded\":{}", json_string(decoded));
+        }
+        if let Some(payload) = &entry.payload {
+            let hex = payload
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<String>();
+            let _ = write!(out, ",\"payload\":\"{hex}\"");
+        }
+        out.push('}');
+    }
+    out.push_str("],\"warnings\":[");
+    let warnings = warnings.iter().map(|w| json_string(w)).collect::<Vec<_>>();
+    out.push_str(&warnings.join(","));
+    out.push_str("]}\n");
+    out
+}
+
+/// Formats the string as JSON string literal.
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    const DUMP: [u8; 40] = [
+        40, 0, 0, 0, // total_size
+        0, 0, 0, 0, // reserved
+        1, 0, 0, 0, // cmdline tag type
+        12, 0, 0, 0, // cmdline tag size
+        b'a', b'"', b'c', 0, // cmdline
+        0, 0, 0, 0, // padding
+        0x37, 0x13, 0, 0, // custom tag type
+        8, 0, 0, 0, // custom tag size
+        0, 0, 0, 0, // end tag type
+        8, 0, 0, 0, // end tag size
+    ];
+
+    #[test]
+    fn text() {
+        let out = dump(&DUMP, false, true).unwrap();
+        assert!(out.starts_with("boot information: 0x28 bytes, 3 tags\n"));
+        assert!(out.contains("0x0008: Cmdline (12 bytes)\n"));
+        assert!(out.contains("0x0018: Custom(4919) (8 bytes)\n    <not decoded>\n"));
+        assert!(!out.contains("warning"));
+    }
+
+    #[test]
+    fn json() {
+        let out = dump(&DUMP, true, true).unwrap();
+        assert!(out.starts_with("{\"total_size\":40,\"tags\":[{\"offset\":8,\"type\":\"Cmdline\",\"type_id\":1,\"size\":12,\"decoded\":"));
+        assert!(out.contains("\\\"c"));
+        assert!(out.contains("\"type_id\":4919,\"size\":8,\"payload\":\"\"}"));
+        assert!(out.ends_with("\"warnings\":[]}\n"));
+    }
+
+    #[test]
+    fn missing_end_tag() {
+        let mut dump_bytes = DUMP;
+        dump_bytes[32] = 1;
+        let out = dump(&dump_bytes, false, false).unwrap();
+        assert!(out.contains("warning: missing end tag\n"));
+        assert!(dump(&DUMP[..16], false, false).is_err());
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(json_string("a\"\\\n\u{1}"), "\"a\\\"\\\\\\n\\u0001\"");
+    }
+}
@@ -1,20 +1,37 @@
 //! Module for [`BootInformation`].
 
 use crate::framebuffer::UnknownFramebufferType;
-use crate::tag::TagHeader;
+use crate::tag::{TagHeader, TagParseError};
+#[cfg(feature = "tag_cache")]
+use crate::tag_cache::TagCache;
+use crate::util::copy_words;
+#[cfg(feature = "builder")]
+use crate::BootInformationBuffer;
+#[cfg(feature = "net")]
+use crate::NetworkTag;
+#[cfg(feature = "smbios")]
+use crate::SmbiosTag;
+#[cfg(feature = "vbe")]
+use crate::VBEInfoTag;
 use crate::{
-    module, BasicMemoryInfoTag, BootLoaderNameTag, CommandLineTag, EFIBootServicesNotExitedTag,
-    EFIImageHandle32Tag, EFIImageHandle64Tag, EFIMemoryMapTag, EFISdt32Tag, EFISdt64Tag,
-    ElfSectionIter, ElfSectionsTag, EndTag, FramebufferTag, ImageLoadPhysAddrTag, MemoryMapTag,
-    ModuleIter, RsdpV1Tag, RsdpV2Tag, SmbiosTag, TagIter, TagType, VBEInfoTag,
+    module, ApmTag, BasicMemoryInfoTag, BootLoaderNameTag, BootdevTag, CommandLineTag,
+    EFIBootServicesNotExitedTag, EFIMemoryMapTag, EndTag, FramebufferTag, GenericTag,
+    GenericTagIter, ImageLoadPhysAddrTag, MemoryMapTag, ModuleIter, RsdpV1Tag, RsdpV2Tag, TagIter,
+    TagType, TagTypeId,
 };
-#[cfg(feature = "unstable")]
+#[cfg(feature = "efi")]
+use crate::{EFIImageHandle32Tag, EFIImageHandle64Tag, EFISdt32Tag, EFISdt64Tag};
+#[cfg(feature = "elf")]
+use crate::{ElfSectionIter, ElfSectionsTag};
 use core::error::Error;
 use core::fmt;
 use core::mem;
 use core::ptr::NonNull;
+use core::slice;
 use derive_more::Display;
-use multiboot2_common::{DynSizedStructure, Header, MaybeDynSized, MemoryError, Tag};
+use multiboot2_common::{
+    increase_to_alignment, DynSizedStructure, Header, MemoryError, Tag, ALIGNMENT,
+};
 
 /// Errors that occur when a chunk of memory can't be parsed as
 /// [`BootInformation`].
@@ -25,18 +42,199 @@ pub enum LoadError {
     Memory(MemoryError),
     /// Missing mandatory end tag.
     NoEndTag,
+    /// The tags are not laid out properly. See [`LayoutError`].
+    Layout(LayoutError),
+    /// The boot information exceeds a [`LoadLimits`]. See [`LimitError`].
+    LimitExceeded(LimitError),
+    /// A tag type that must occur at most once occurs multiple times. Only
+    /// reported with [`ParsePolicy::Strict`].
+    #[display("duplicate tag at offset {offset:#x} (tag type {typ:?})")]
+    DuplicateTag {
+        /// The offset of the duplicate relative to the beginning of the boot
+        /// information.
+        offset: usize,
+        /// The type of the tag.
+        typ: TagTypeId,
+    },
 }
 
-#[cfg(feature = "unstable")]
 impl Error for LoadError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::Memory(inner) => Some(inner),
             Self::NoEndTag => None,
+            Self::Layout(inner) => Some(inner),
+            Self::LimitExceeded(inner) => Some(inner),
+            Self::DuplicateTag { .. } => None,
+        }
+    }
+}
+
+impl From<ParseWarning> for LoadError {
+    fn from(value: ParseWarning) -> Self {
+        match value {
+            ParseWarning::MissingEndTag => Self::NoEndTag,
+            ParseWarning::DuplicateTag { offset, typ } => Self::DuplicateTag { offset, typ },
         }
     }
 }
 
+/// How strictly [`BootInformation::load_with_policy`] enforces the spec.
+///
+/// Real bootloaders are imperfect. With [`ParsePolicy::Lenient`], violations
+/// that don't prevent a safe parsing are tolerated and can be inspected via
+/// [`BootInformation::warnings`]. Tags that overlap or exceed the total size
+/// are rejected with either policy, as they can't be accessed safely.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParsePolicy {
+    /// Every [`ParseWarning`] is a hard error. This is the default.
+    #[default]
+    Strict,
+    /// [`ParseWarning`]s are tolerated.
+    Lenient,
+}
+
+/// A violation of the spec that [`ParsePolicy::Lenient`] tolerates, as
+/// reported by [`BootInformation::warnings`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParseWarning {
+    /// The mandatory end tag is missing. The tags are parsed until the
+    /// total size is reached.
+    #[display("missing end tag")]
+    MissingEndTag,
+    /// A tag type that must occur at most once occurs multiple times. Getters
+    /// return the first tag of the type.
+    #[display("duplicate tag at offset {offset:#x} (tag type {typ:?})")]
+    DuplicateTag {
+        /// The offset of the duplicate relative to the beginning of the boot
+        /// information.
+        offset: usize,
+        /// The type of the tag.
+        typ: TagTypeId,
+    },
+}
+
+/// Iterator over the [`ParseWarning`]s of a [`BootInformation`], see
+/// [`BootInformation::warnings`].
+#[derive(Clone, Debug)]
+pub struct ParseWarningIter<'a> {
+    tags: TagIter<'a>,
+    start_address: usize,
+    /// Bitmap of the singleton tag types seen so far.
+    seen: u32,
+    missing_end_tag: bool,
+}
+
+impl Iterator for ParseWarningIter<'_> {
+    type Item = ParseWarning;
+
+    fn next(&mut self) -> Option<ParseWarning> {
+        for tag in self.tags.by_ref() {
+            let typ = tag.header().typ;
+            // Unknown tag types are custom tags that may repeat. Thus, all
+            // remaining types fit into the bitmap.
+            if TagType::from(typ).may_repeat() {
+                continue;
+            }
+            let bit = 1 << typ.val();
+            if self.seen & bit != 0 {
+                return Some(ParseWarning::DuplicateTag {
+                    offset: (tag as *const DynSizedStructure<TagHeader>).cast::<u8>() as usize
+                        - self.start_address,
+                    typ,
+                });
+            }
+            self.seen |= bit;
+        }
+        if self.missing_end_tag {
+            self.missing_end_tag = false;
+            return Some(ParseWarning::MissingEndTag);
+        }
+        None
+    }
+}
+
+/// Caps that [`BootInformation::load_with_limits`] enforces, so that a
+/// hostile boot information, e.g., provided by an untrusted hypervisor or
+/// over kexec, can't cause excessive walking or absurd slices.
+///
+/// The [`Default`] limits are generous and far beyond what any real
+/// bootloader produces.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LoadLimits {
+    /// The maximum number of tags, including the end tag.
+    pub max_tags: usize,
+    /// The maximum size of a single tag in bytes, as reported by its header.
+    pub max_tag_size: usize,
+}
+
+impl LoadLimits {
+    /// The default limits: 65536 tags and 16 MiB per tag.
+    pub const DEFAULT: Self = Self {
+        max_tags: 0x1_0000,
+        max_tag_size: 0x100_0000,
+    };
+}
+
+impl Default for LoadLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// A [`LoadLimits`] that a [`BootInformation`] exceeds, as reported by
+/// [`BootInformation::load_with_limits`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LimitError {
+    /// The boot information has more than [`LoadLimits::max_tags`] tags.
+    #[display("more than {max} tags")]
+    TooManyTags {
+        /// The exceeded limit.
+        max: usize,
+    },
+    /// A tag is bigger than [`LoadLimits::max_tag_size`].
+    #[display("tag of size {size:#x} at offset {offset:#x} (tag type {typ:?})")]
+    TagTooLarge {
+        /// The offset of the tag relative to the beginning of the boot
+        /// information.
+        offset: usize,
+        /// The type of the tag.
+        typ: TagTypeId,
+        /// The reported size of the tag.
+        size: usize,
+    },
+}
+
+impl Error for LimitError {}
+
+/// The first tag of a [`BootInformation`] that violates the layout of the
+/// tags, as reported by [`BootInformation::validate_layout`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display("{violation} at offset {offset:#x} (tag type {typ:?})")]
+pub struct LayoutError {
+    /// The offset of the tag relative to the beginning of the boot
+    /// information.
+    pub offset: usize,
+    /// The type of the tag.
+    pub typ: TagTypeId,
+    /// The kind of the violation.
+    pub violation: LayoutViolation,
+}
+
+impl Error for LayoutError {}
+
+/// The ways a tag can violate the layout of the tags. See [`LayoutError`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutViolation {
+    /// The reported size of the tag is smaller than its header. Thus, the
+    /// tag overlaps with the next one and the offsets of the tags don't
+    /// advance.
+    Overlapping,
+    /// The tag, including its padding to the next 8-byte boundary, exceeds
+    /// the total size of the boot information.
+    OutOfBounds,
+}
+
 /// The basic header of a [`BootInformation`] as sized Rust type.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C, align(8))]
@@ -65,7 +263,9 @@ impl BootInformationHeader {
 
 impl Header for BootInformationHeader {
     fn payload_len(&self) -> usize {
-        self.total_size as usize - mem::size_of::<Self>()
+        // A total size smaller than the header is rejected in
+        // `BootInformation::load`.
+        (self.total_size as usize).saturating_sub(mem::size_of::<Self>())
     }
 
     fn set_size(&mut self, total_size: usize) {
@@ -74,8 +274,16 @@ impl Header for BootInformationHeader {
 }
 
 /// A Multiboot 2 Boot Information (MBI) accessor.
-#[repr(transparent)]
-pub struct BootInformation<'a>(&'a DynSizedStructure<BootInformationHeader>);
+///
+/// With the `tag_cache` feature, the offsets of all tags are recorded on the
+/// first tag lookup, so that subsequent lookups don't scan the whole
+/// structure again. This requires atomic compare-and-swap support of the
+/// target.
+#[cfg_attr(not(feature = "tag_cache"), repr(transparent))]
+pub struct BootInformation<'a>(
+    &'a DynSizedStructure<BootInformationHeader>,
+    #[cfg(feature = "tag_cache")] TagCache,
+);
 
 impl<'a> BootInformation<'a> {
     /// Loads the [`BootInformation`] from a pointer. The pointer must be valid
@@ -102,16 +310,152 @@ impl<'a> BootInformation<'a> {
     /// * The memory at `ptr` must not be modified after calling `load` or the
     ///   program may observe unsynchronized mutation.
     pub unsafe fn load(ptr: *const BootInformationHeader) -> Result<Self, LoadError> {
+        let this = Self::load_without_end_tag(ptr)?;
+        if !this.has_valid_end_tag() {
+            return Err(LoadError::NoEndTag);
+        }
+        Ok(this)
+    }
+
+    /// Like [`Self::load`], but doesn't require the end tag.
+    unsafe fn load_without_end_tag(ptr: *const BootInformationHeader) -> Result<Self, LoadError> {
         let ptr = NonNull::new(ptr.cast_mut()).ok_or(LoadError::Memory(MemoryError::Null))?;
         let inner = DynSizedStructure::ref_from_ptr(ptr).map_err(LoadError::Memory)?;
+        if (inner.header().total_size() as usize) < mem::size_of::<BootInformationHeader>() {
+            return Err(LoadError::Memory(MemoryError::InvalidReportedTotalSize));
+        }
 
-        let this = Self(inner);
-        if !this.has_valid_end_tag() {
-            return Err(LoadError::NoEndTag);
+        Ok(Self(
+            inner,
+            #[cfg(feature = "tag_cache")]
+            TagCache::new(),
+        ))
+    }
+
+    /// Like [`Self::load`], but additionally checks the [`LoadLimits::DEFAULT`]
+    /// and validates the layout of all tags with [`Self::validate_layout`].
+    ///
+    /// Use this for boot information that may be corrupt. Accessors of a
+    /// [`BootInformation`] whose tags are not laid out properly may panic.
+    ///
+    /// ## Safety
+    /// See [`Self::load`].
+    pub unsafe fn load_checked(ptr: *const BootInformationHeader) -> Result<Self, LoadError> {
+        Self::load_with_limits(ptr, LoadLimits::DEFAULT)
+    }
+
+    /// Like [`Self::load_checked`], but with custom [`LoadLimits`].
+    ///
+    /// ## Safety
+    /// See [`Self::load`].
+    pub unsafe fn load_with_limits(
+        ptr: *const BootInformationHeader,
+        limits: LoadLimits,
+    ) -> Result<Self, LoadError> {
+        let this = Self::load(ptr)?;
+        this.walk_tags(Some(limits))?;
+        Ok(this)
+    }
+
+    /// Like [`Self::load_checked`], but the [`ParsePolicy`] decides whether
+    /// the [`ParseWarning`]s are hard errors.
+    ///
+    /// With [`ParsePolicy::Lenient`], the warnings are available afterwards
+    /// via [`Self::warnings`].
+    ///
+    /// ## Safety
+    /// See [`Self::load`].
+    pub unsafe fn load_with_policy(
+        ptr: *const BootInformationHeader,
+        policy: ParsePolicy,
+    ) -> Result<Self, LoadError> {
+        let this = Self::load_without_end_tag(ptr)?;
+        this.walk_tags(Some(LoadLimits::DEFAULT))?;
+        if policy == ParsePolicy::Strict {
+            if let Some(warning) = this.warnings().next() {
+                return Err(warning.into());
+            }
         }
         Ok(this)
     }
 
+    /// Returns the violations of the spec that [`ParsePolicy::Lenient`]
+    /// tolerates, in the order of the tags.
+    ///
+    /// The warnings are determined by walking the tags, so this doesn't need
+    /// to allocate.
+    #[must_use]
+    pub fn warnings(&self) -> ParseWarningIter<'_> {
+        ParseWarningIter {
+            tags: self.raw_tags(),
+            start_address: self.start_address(),
+            seen: 0,
+            missing_end_tag: !self.has_valid_end_tag(),
+        }
+    }
+
+    /// Validates that every tag lies fully within [`Self::total_size`], that
+    /// no tag overlaps with the next one, and thus, that the offsets of the
+    /// tags advance monotonically.
+    ///
+    /// Returns the offset and type of the first tag that violates the
+    /// layout.
+    pub fn validate_layout(&self) -> Result<(), LayoutError> {
+        match self.walk_tags(None) {
+            Err(LoadError::Layout(e)) => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Walks the tags without relying on their reported sizes and reports the
+    /// first tag that exceeds the `limits` or violates the layout. This is the
+    /// only walk that doesn't trust the tags; all other ones require it to
+    /// pass first.
+    fn walk_tags(&self, limits: Option<LoadLimits>) -> Result<(), LoadError> {
+        let bytes = self.padded_bytes();
+        let total_size = self.total_size();
+        let mut offset = mem::size_of::<BootInformationHeader>();
+        let mut tags = 0;
+        while offset < total_size {
+            // SAFETY: `offset` is a multiple of eight that is smaller than
+            // the total size, and the bytes are padded to the next multiple of
+            // eight. Hence, the header is aligned and in bounds.
+            let header = unsafe { &*bytes.as_ptr().add(offset).cast::<TagHeader>() };
+            let size = header.size as usize;
+
+            tags += 1;
+            if let Some(limits) = limits {
+                if tags > limits.max_tags {
+                    let max = limits.max_tags;
+                    return Err(LoadError::LimitExceeded(LimitError::TooManyTags { max }));
+                }
+                if size > limits.max_tag_size {
+                    return Err(LoadError::LimitExceeded(LimitError::TagTooLarge {
+                        offset,
+                        typ: header.typ,
+                        size,
+                    }));
+                }
+            }
+
+            let error = |violation| {
+                LoadError::Layout(LayoutError {
+                    offset,
+                    typ: header.typ,
+                    violation,
+                })
+            };
+            if size < mem::size_of::<TagHeader>() {
+                return Err(error(LayoutViolation::Overlapping));
+            }
+            if size > total_size - offset || increase_to_alignment(offset + size) > total_size {
+                return Err(error(LayoutViolation::OutOfBounds));
+            }
+            offset = increase_to_alignment(offset + size);
+        }
+        Ok(())
+    }
+
     /// Checks if the MBI has a valid end tag by checking the end of the mbi's
     /// bytes.
     fn has_valid_end_tag(&self) -> bool {
@@ -129,6 +473,44 @@ impl<'a> BootInformation<'a> {
         end_tag.typ == EndTag::ID && end_tag.size as usize == mem::size_of::<EndTag>()
     }
 
+    /// Copies the boot information to `dst` and returns the
+    /// [`BootInformation`] of the copy.
+    ///
+    /// This is useful to relocate the structure before the memory the
+    /// bootloader placed it in is reused. The copy is done in aligned 8-byte
+    /// words, hence `dst` must be aligned to an 8-byte boundary and cover
+    /// [`Self::total_size`] rounded up to the next multiple of eight.
+    pub fn copy_to<'b>(&self, dst: &'b mut [u8]) -> Result<BootInformation<'b>, LoadError> {
+        let src = self.padded_bytes();
+        if dst.as_ptr().align_offset(ALIGNMENT) != 0 {
+            return Err(LoadError::Memory(MemoryError::WrongAlignment));
+        }
+        let dst = dst
+            .get_mut(..src.len())
+            .ok_or(LoadError::Memory(MemoryError::InvalidReportedTotalSize))?;
+        // SAFETY: Both slices are 8-byte aligned and of the same length, which
+        // is a multiple of eight.
+        unsafe { copy_words(src.as_ptr(), dst.as_mut_ptr(), src.len()) };
+        let dst: &'b [u8] = dst;
+        // SAFETY: `dst` is borrowed for `'b` and holds a valid copy.
+        unsafe { BootInformation::load(dst.as_ptr().cast()) }
+    }
+
+    /// Returns an owned copy of the boot information, made with aligned
+    /// 8-byte words like [`Self::copy_to`].
+    #[cfg(feature = "builder")]
+    #[must_use]
+    pub fn to_buffer(&self) -> BootInformationBuffer {
+        BootInformationBuffer::from_padded_bytes(self.padded_bytes())
+    }
+
+    /// Returns the bytes of the boot information including the padding to
+    /// the next 8-byte boundary, which the structure is guaranteed to have.
+    fn padded_bytes(&self) -> &[u8] {
+        let len = mem::size_of_val(self.0);
+        unsafe { slice::from_raw_parts(self.as_ptr().cast::<u8>(), len) }
+    }
+
     /// Get the start address of the boot info.
     #[must_use]
     pub fn start_address(&self) -> usize {
@@ -165,10 +547,11 @@ impl<'a> BootInformation<'a> {
     // ######################################################
     // ### BEGIN OF TAG GETTERS (in alphabetical order)
 
-    /*fn apm(&self) {
-        // also add to debug output
-        todo!()
-    }*/
+    /// Search for the APM tag.
+    #[must_use]
+    pub fn apm_tag(&self) -> Option<&ApmTag> {
+        self.get_tag::<ApmTag>()
+    }
 
     /// Search for the basic memory info tag.
     #[must_use]
@@ -182,10 +565,11 @@ impl<'a> BootInformation<'a> {
         self.get_tag::<BootLoaderNameTag>()
     }
 
-    /*fn bootdev(&self) {
-        // also add to debug output
-        todo!()
-    }*/
+    /// Search for the BIOS boot device tag.
+    #[must_use]
+    pub fn bootdev_tag(&self) -> Option<&BootdevTag> {
+        self.get_tag::<BootdevTag>()
+    }
 
     /// Search for the Command line tag.
     #[must_use]
@@ -193,6 +577,33 @@ impl<'a> BootInformation<'a> {
         self.get_tag::<CommandLineTag>()
     }
 
+    /// Returns the kernel command line, or `""` if there is no command line
+    /// tag.
+    ///
+    /// This never fails: A missing terminating NUL is tolerated, and if the
+    /// command line isn't valid UTF-8, only the part before the first invalid
+    /// byte is returned, as the borrowed `&str` can't contain replacement
+    /// characters. Use [`CommandLineTag::cmdline_bytes`] to decode the rest,
+    /// or [`CommandLineTag::cmdline`] to detect these cases.
+    ///
+    /// ```rust,no_run
+    /// # use multiboot2::{BootInformation, BootInformationHeader};
+    /// # let ptr = 0xdeadbeef as *const BootInformationHeader;
+    /// # let boot_info = unsafe { BootInformation::load(ptr).unwrap() };
+    /// let quiet = boot_info.cmdline().split(' ').any(|arg| arg == "quiet");
+    /// ```
+    #[must_use]
+    pub fn cmdline(&self) -> &str {
+        let Some(tag) = self.command_line_tag() else {
+            return "";
+        };
+        let bytes = tag.cmdline_bytes();
+        core::str::from_utf8(bytes).unwrap_or_else(|e| {
+            // The prefix is valid UTF-8 by definition.
+            core::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default()
+        })
+    }
+
     /// Search for the EFI boot services not exited tag.
     #[must_use]
     pub fn efi_bs_not_exited_tag(&self) -> Option<&EFIBootServicesNotExitedTag> {
@@ -217,24 +628,28 @@ impl<'a> BootInformation<'a> {
     }
 
     /// Search for the EFI 32-bit SDT tag.
+    #[cfg(feature = "efi")]
     #[must_use]
     pub fn efi_sdt32_tag(&self) -> Option<&EFISdt32Tag> {
         self.get_tag::<EFISdt32Tag>()
     }
 
     /// Search for the EFI 64-bit SDT tag.
+    #[cfg(feature = "efi")]
     #[must_use]
     pub fn efi_sdt64_tag(&self) -> Option<&EFISdt64Tag> {
         self.get_tag::<EFISdt64Tag>()
     }
 
     /// Search for the EFI 32-bit image handle pointer tag.
+    #[cfg(feature = "efi")]
     #[must_use]
     pub fn efi_ih32_tag(&self) -> Option<&EFIImageHandle32Tag> {
         self.get_tag::<EFIImageHandle32Tag>()
     }
 
     /// Search for the EFI 64-bit image handle pointer tag.
+    #[cfg(feature = "efi")]
     #[must_use]
     pub fn efi_ih64_tag(&self) -> Option<&EFIImageHandle64Tag> {
         self.get_tag::<EFIImageHandle64Tag>()
@@ -257,13 +672,11 @@ impl<'a> BootInformation<'a> {
     ///     }
     /// }
     /// ```
+    #[cfg(feature = "elf")]
     #[must_use]
-    pub fn elf_sections(&self) -> Option<ElfSectionIter> {
-        let tag = self.get_tag::<ElfSectionsTag>();
-        tag.map(|t| {
-            assert!((t.entry_size() * t.shndx()) <= t.header().size);
-            t.sections_iter()
-        })
+    pub fn elf_sections(&self) -> Option<ElfSectionIter<'_>> {
+        self.get_tag::<ElfSectionsTag>()
+            .map(ElfSectionsTag::sections_iter)
     }
 
     /// Search for the VBE framebuffer tag. The result is `Some(Err(e))`, if the
@@ -293,14 +706,16 @@ impl<'a> BootInformation<'a> {
 
     /// Get an iterator of all module tags.
     #[must_use]
-    pub fn module_tags(&self) -> ModuleIter {
-        module::module_iter(self.tags())
+    pub fn module_tags(&self) -> ModuleIter<'_> {
+        module::module_iter(self.raw_tags())
     }
 
-    /*fn network_tag(&self) {
-        // also add to debug output
-        todo!()
-    }*/
+    /// Search for the Network tag.
+    #[cfg(feature = "net")]
+    #[must_use]
+    pub fn network_tag(&self) -> Option<&NetworkTag> {
+        self.get_tag::<NetworkTag>()
+    }
 
     /// Search for the (ACPI 1.0) RSDP tag.
     #[must_use]
@@ -315,12 +730,14 @@ impl<'a> BootInformation<'a> {
     }
 
     /// Search for the SMBIOS tag.
+    #[cfg(feature = "smbios")]
     #[must_use]
     pub fn smbios_tag(&self) -> Option<&SmbiosTag> {
         self.get_tag::<SmbiosTag>()
     }
 
     /// Search for the VBE information tag.
+    #[cfg(feature = "vbe")]
     #[must_use]
     pub fn vbe_info_tag(&self) -> Option<&VBEInfoTag> {
         self.get_tag::<VBEInfoTag>()
@@ -332,12 +749,17 @@ impl<'a> BootInformation<'a> {
     /// Public getter to find any Multiboot tag by its type, including
     /// specified and custom ones.
     ///
+    /// Returns `None` if the first tag of that type reports a size that
+    /// doesn't fit `T`, e.g., one smaller than
+    /// [`crate::MaybeDynSized::BASE_SIZE`]. Use [`Self::try_get_tag`] to tell
+    /// such a corrupt tag apart from a missing one.
+    ///
     /// # Specified or Custom Tags
     /// The Multiboot2 specification specifies a list of tags, see [`TagType`].
     /// However, it doesn't forbid to use custom tags. Because of this, there
     /// exists the [`TagType`] abstraction. It is recommended to use this
     /// getter only for custom tags. For specified tags, use getters, such as
-    /// [`Self::efi_ih64_tag`].
+    /// [`Self::memory_map_tag`].
     ///
     /// ## Use Custom Tags
     /// The following example shows how you may use this interface to parse
@@ -372,8 +794,13 @@ impl<'a> BootInformation<'a> {
     ///     // This differs for DSTs and normal structs. See function
     ///     // documentation.
     ///     fn dst_len(header: &TagHeader) -> usize {
-    ///         assert!(header.size >= Self::BASE_SIZE as u32);
-    ///         header.size as usize - Self::BASE_SIZE
+    ///         Self::try_dst_len(header).unwrap()
+    ///     }
+    ///
+    ///     // Lets the library skip tags that are too small instead of
+    ///     // panicking.
+    ///     fn try_dst_len(header: &TagHeader) -> Option<usize> {
+    ///         (header.size as usize).checked_sub(Self::BASE_SIZE)
     ///     }
     /// }
     ///
@@ -397,42 +824,146 @@ impl<'a> BootInformation<'a> {
     pub fn get_tag<T: Tag<IDType = TagType, Header = TagHeader> + ?Sized + 'a>(
         &'a self,
     ) -> Option<&'a T> {
+        self.try_get_tag().ok().flatten()
+    }
+
+    /// Like [`Self::get_tag`], but reports the error if the first tag of that
+    /// type can't be cast to `T`, instead of hiding it behind `None`.
+    pub fn try_get_tag<T: Tag<IDType = TagType, Header = TagHeader> + ?Sized + 'a>(
+        &'a self,
+    ) -> Result<Option<&'a T>, TagParseError> {
+        self.find_tag(T::ID.into())
+            .map(|tag| tag.try_cast::<T>().map_err(TagParseError::Memory))
+            .transpose()
+    }
+
+    /// Searches for the first tag of the given type and returns it as
+    /// [`GenericTag`]. This works for all tags, including tags that are
+    /// unknown to this crate.
+    #[must_use]
+    pub fn get_generic_tag(&self, typ: impl Into<TagTypeId>) -> Option<&GenericTag> {
+        self.find_tag(typ.into()).map(|tag| tag.cast())
+    }
+
+    /// Returns whether there is a tag of the given type, without casting it.
+    ///
+    /// Only the type is checked, so [`Self::get_tag`] may still return `None`
+    /// if the tag is too small for `T`.
+    ///
+    /// ```rust,no_run
+    /// # use multiboot2::{BootInformation, BootInformationHeader, FramebufferTag};
+    /// # let ptr = 0xdeadbeef as *const BootInformationHeader;
+    /// # let boot_info = unsafe { BootInformation::load(ptr).unwrap() };
+    /// if boot_info.contains::<FramebufferTag>() {
+    ///     // Set up the graphical console.
+    /// }
+    /// ```
+    #[must_use]
+    pub fn contains<T: Tag<IDType = TagType, Header = TagHeader> + ?Sized>(&self) -> bool {
+        self.find_tag(T::ID.into()).is_some()
+    }
+
+    /// Returns the number of tags, including the end tag. This equals
+    /// `self.tags().count()`.
+    #[must_use]
+    pub fn tag_count(&self) -> usize {
+        self.raw_tags().count()
+    }
+
+    /// Returns an iterator over all tags as [`GenericTag`]s.
+    ///
+    /// Use [`GenericTagIter::filter_type`] and [`GenericTagIter::typed`] to
+    /// narrow it down:
+    ///
+    /// ```rust,no_run
+    /// # use multiboot2::{BootInformation, BootInformationHeader, ModuleTag, TagType};
+    /// # let ptr = 0xdeadbeef as *const BootInformationHeader;
+    /// # let boot_info = unsafe { BootInformation::load(ptr).unwrap() };
+    /// let vendor_tags = boot_info.tags().filter_type(TagType::Custom(0x1337)).count();
+    /// for module in boot_info.tags().typed::<ModuleTag>() {
+    ///     println!("{:?}", module.cmdline());
+    /// }
+    /// // Equivalent to `boot_info.tags()`.
+    /// for tag in &boot_info {
+    ///     println!("{:?}", tag.typ());
+    /// }
+    /// ```
+    #[must_use]
+    pub fn tags(&self) -> GenericTagIter<'_> {
+        GenericTagIter::new(self.raw_tags())
+    }
+
+    /// Returns an iterator over all tags as [`GenericTag`]s. Same as
+    /// [`Self::tags`].
+    #[must_use]
+    pub fn generic_tags(&self) -> GenericTagIter<'_> {
         self.tags()
-            .find(|tag| tag.header().typ == T::ID)
-            .map(|tag| tag.cast::<T>())
+    }
+
+    /// Returns the first tag of the given type.
+    fn find_tag(&self, typ: TagTypeId) -> Option<&DynSizedStructure<TagHeader>> {
+        #[cfg(feature = "tag_cache")]
+        if let Some(offset) = self.1.lookup(typ, self.0.payload()) {
+            return offset.and_then(|offset| TagIter::new(&self.0.payload()[offset..]).next());
+        }
+        self.raw_tags().find(|tag| tag.header().typ == typ)
     }
 
     /// Returns an iterator over all tags.
-    pub(crate) fn tags(&self) -> TagIter {
+    pub(crate) fn raw_tags(&self) -> TagIter<'_> {
         TagIter::new(self.0.payload())
     }
 }
 
+impl<'a> IntoIterator for &'a BootInformation<'_> {
+    type Item = &'a GenericTag;
+    type IntoIter = GenericTagIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tags()
+    }
+}
+
 impl fmt::Debug for BootInformation<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut debug = f.debug_struct("Multiboot2BootInformation");
+        debug
+            .field("start_address", &self.start_address())
+            .field("end_address", &self.end_address())
+            .field("total_size", &self.total_size());
+        #[cfg(feature = "debug_impls")]
+        self.debug_tags(&mut debug);
+        debug.finish()
+    }
+}
+
+impl BootInformation<'_> {
+    /// Adds the tags in alphabetical order to the `Debug` output.
+    #[cfg(feature = "debug_impls")]
+    fn debug_tags(&self, debug: &mut fmt::DebugStruct) {
         /// Limit how many Elf-Sections should be debug-formatted.
         /// Can be thousands of sections for a Rust binary => this is useless output.
         /// If the user really wants this, they should debug-format the field directly.
+        #[cfg(feature = "elf")]
         const ELF_SECTIONS_LIMIT: usize = 7;
 
-        let mut debug = f.debug_struct("Multiboot2BootInformation");
         debug
-            .field("start_address", &self.start_address())
-            .field("end_address", &self.end_address())
-            .field("total_size", &self.total_size())
-            // now tags in alphabetical order
+            .field("apm", &self.apm_tag())
             .field("basic_memory_info", &(self.basic_memory_info_tag()))
             .field("boot_loader_name", &self.boot_loader_name_tag())
-            // .field("bootdev", &self.bootdev_tag())
+            .field("bootdev", &self.bootdev_tag())
             .field("command_line", &self.command_line_tag())
             .field("efi_bs_not_exited", &self.efi_bs_not_exited_tag())
-            .field("efi_memory_map", &self.efi_memory_map_tag())
+            .field("efi_memory_map", &self.efi_memory_map_tag());
+        #[cfg(feature = "efi")]
+        debug
             .field("efi_sdt32", &self.efi_sdt32_tag())
             .field("efi_sdt64", &self.efi_sdt64_tag())
             .field("efi_ih32", &self.efi_ih32_tag())
             .field("efi_ih64", &self.efi_ih64_tag());
 
         // usually this is REALLY big (thousands of tags) => skip it here
+        #[cfg(feature = "elf")]
         {
             let elf_sections_tag_entries_count =
                 self.elf_sections().map(|x| x.count()).unwrap_or(0);
@@ -448,12 +979,15 @@ impl fmt::Debug for BootInformation<'_> {
             .field("framebuffer", &self.framebuffer_tag())
             .field("load_base_addr", &self.load_base_addr_tag())
             .field("memory_map", &self.memory_map_tag())
-            .field("modules", &self.module_tags())
-            // .field("network", &self.network_tag())
+            .field("modules", &self.module_tags());
+        #[cfg(feature = "net")]
+        debug.field("network", &self.network_tag());
+        debug
             .field("rsdp_v1", &self.rsdp_v1_tag())
-            .field("rsdp_v2", &self.rsdp_v2_tag())
-            .field("smbios_tag", &self.smbios_tag())
-            .field("vbe_info_tag", &self.vbe_info_tag())
-            .finish()
+            .field("rsdp_v2", &self.rsdp_v2_tag());
+        #[cfg(feature = "smbios")]
+        debug.field("smbios_tag", &self.smbios_tag());
+        #[cfg(feature = "vbe")]
+        debug.field("vbe_info_tag", &self.vbe_info_tag());
     }
 }
@@ -1,12 +1,18 @@
 //! Module for [`BootLoaderNameTag`].
 
 use crate::tag::TagHeader;
-use crate::{parse_slice_as_string, StringError, TagType};
+use crate::util::string_bytes;
+use crate::{parse_slice_as_string, parse_slice_as_string_lenient, StringError, TagType};
 use core::fmt::{Debug, Formatter};
 use core::mem;
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, multiboot2_common::new_boxed};
+use {
+    crate::builder::{check_string, check_tag_size},
+    crate::BuilderError,
+    alloc::boxed::Box,
+    multiboot2_common::{new_boxed, try_new_boxed},
+};
 
 /// The bootloader name tag.
 #[derive(ptr_meta::Pointee, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -31,6 +37,17 @@ impl BootLoaderNameTag {
         }
     }
 
+    /// Like [`Self::new`], but returns an error instead of panicking or
+    /// producing a malformed tag if the string contains a NUL byte before its
+    /// end, if it is too long, or if the allocation fails.
+    #[cfg(feature = "builder")]
+    pub fn try_new(name: &str) -> Result<Box<Self>, BuilderError> {
+        let bytes = check_string(name)?;
+        check_tag_size(bytes.len() + 1)?;
+        let header = TagHeader::new(Self::ID, 0);
+        try_new_boxed(header, &[bytes, &[0]]).ok_or(BuilderError::OutOfMemory)
+    }
+
     /// Returns the underlying [`TagType`].
     #[must_use]
     pub fn typ(&self) -> TagType {
@@ -63,6 +80,63 @@ impl BootLoaderNameTag {
     pub fn name(&self) -> Result<&str, StringError> {
         parse_slice_as_string(&self.name)
     }
+
+    /// Like [`Self::name`], but treats the whole payload as the name if the
+    /// terminating NUL is missing, instead of returning
+    /// [`StringError::MissingNul`].
+    pub fn name_lenient(&self) -> Result<&str, StringError> {
+        parse_slice_as_string_lenient(&self.name)
+    }
+
+    /// Returns the raw bytes of the name without the terminating NUL and
+    /// without checking for valid UTF-8. If the NUL is missing, this is the
+    /// whole payload.
+    #[must_use]
+    pub fn name_bytes(&self) -> &[u8] {
+        string_bytes(&self.name)
+    }
+
+    /// Returns the vendor part of [`Self::name`], e.g., `"GRUB"` for
+    /// `"GRUB 2.06"`. This is the whole name if there is no version.
+    ///
+    /// The name is split at the first word that starts with a digit, or with
+    /// `v` followed by a digit. A `version` word in between is dropped, so
+    /// `"GNU GRUB version 0.97"` has the vendor `"GNU GRUB"`.
+    pub fn vendor(&self) -> Result<&str, StringError> {
+        self.name().map(|name| split_name(name).0)
+    }
+
+    /// Returns the version part of [`Self::name`], e.g., `"2.06"` for
+    /// `"GRUB 2.06"` and `"6.04"` for `"PXELINUX 6.04 EDD"`. See
+    /// [`Self::vendor`] for how the name is split.
+    ///
+    /// Returns `Ok(None)` if the name has no version, as the one of QEMU's
+    /// direct kernel boot.
+    pub fn version(&self) -> Result<Option<&str>, StringError> {
+        self.name().map(|name| split_name(name).1)
+    }
+}
+
+/// Splits the name of a bootloader into the vendor and the version. See
+/// [`BootLoaderNameTag::vendor`].
+fn split_name(name: &str) -> (&str, Option<&str>) {
+    let mut vendor_end = 0;
+    let mut offset = 0;
+    for word in name.split(' ') {
+        let start = offset;
+        offset += word.len() + 1;
+        let version = match word.strip_prefix(['v', 'V']) {
+            Some(version) if version.starts_with(|c: char| c.is_ascii_digit()) => version,
+            _ => word,
+        };
+        if version.starts_with(|c: char| c.is_ascii_digit()) {
+            return (&name[..vendor_end], Some(version));
+        }
+        if !word.is_empty() && !word.eq_ignore_ascii_case("version") {
+            vendor_end = start + word.len();
+        }
+    }
+    (&name[..vendor_end], None)
 }
 
 impl Debug for BootLoaderNameTag {
@@ -81,8 +155,11 @@ impl MaybeDynSized for BootLoaderNameTag {
     const BASE_SIZE: usize = mem::size_of::<TagHeader>();
 
     fn dst_len(header: &TagHeader) -> usize {
-        assert!(header.size as usize >= Self::BASE_SIZE);
-        header.size as usize - Self::BASE_SIZE
+        Self::try_dst_len(header).expect("tag should be at least BASE_SIZE bytes")
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
     }
 }
 
@@ -95,12 +172,12 @@ impl Tag for BootLoaderNameTag {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::GenericInfoTag;
+    use crate::GenericTag;
     use core::borrow::Borrow;
     use multiboot2_common::test_utils::AlignedBytes;
 
     #[rustfmt::skip]
-    fn get_bytes() -> AlignedBytes<16> {
+    const fn get_bytes() -> AlignedBytes<16> {
         AlignedBytes::new([
             TagType::BootLoaderName.val() as u8, 0, 0, 0,
             14, 0, 0, 0,
@@ -114,10 +191,39 @@ mod tests {
     #[test]
     fn test_parse_str() {
         let bytes = get_bytes();
-        let tag = GenericInfoTag::ref_from_slice(bytes.borrow()).unwrap();
-        let tag = tag.cast::<BootLoaderNameTag>();
+        let tag = GenericTag::ref_from_slice(bytes.borrow()).unwrap();
+        let tag = tag.downcast::<BootLoaderNameTag>().unwrap();
         assert_eq!(tag.header.typ, TagType::BootLoaderName);
         assert_eq!(tag.name(), Ok("hello"));
+        assert_eq!(tag.name_bytes(), b"hello");
+    }
+
+    #[test]
+    fn test_split_name() {
+        for (name, vendor, version) in [
+            ("GRUB 2.06", "GRUB", Some("2.06")),
+            ("GRUB 2.02~beta3-5", "GRUB", Some("2.02~beta3-5")),
+            ("GRUB 2.12-1ubuntu7", "GRUB", Some("2.12-1ubuntu7")),
+            ("GNU GRUB  version 0.97", "GNU GRUB", Some("0.97")),
+            ("PXELINUX 6.04", "PXELINUX", Some("6.04")),
+            ("SYSLINUX 6.04 EDD 6.04-pre1", "SYSLINUX", Some("6.04")),
+            ("Limine v7.0.0", "Limine", Some("7.0.0")),
+            ("qemu", "qemu", None),
+            ("", "", None),
+        ] {
+            assert_eq!(split_name(name), (vendor, version), "{name}");
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_vendor_version() {
+        let tag = BootLoaderNameTag::new("GRUB 2.06");
+        assert_eq!(tag.vendor(), Ok("GRUB"));
+        assert_eq!(tag.version(), Ok(Some("2.06")));
+        let tag = BootLoaderNameTag::new("qemu");
+        assert_eq!(tag.vendor(), Ok("qemu"));
+        assert_eq!(tag.version(), Ok(None));
     }
 
     /// Test to generate a tag from a given string.
@@ -4,8 +4,9 @@ use crate::{TagHeader, TagType};
 use core::mem;
 use multiboot2_common::{MaybeDynSized, Tag};
 
-/// The end tag ends the information struct.
-#[derive(Debug)]
+/// The BIOS boot device tag indicates which BIOS disk device the bootloader
+/// loaded the OS image from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C, align(8))]
 pub struct BootdevTag {
     header: TagHeader,
@@ -15,11 +16,18 @@ pub struct BootdevTag {
 }
 
 impl BootdevTag {
+    /// Value of the `slice` and `part` fields if they are not used.
+    pub const UNUSED: u32 = 0xffff_ffff;
+
+    /// Size of the tag as specified, without the trailing padding of the
+    /// Rust type.
+    const SIZE: usize = mem::size_of::<TagHeader>() + 3 * mem::size_of::<u32>();
+
     /// Creates a new tag.
     #[must_use]
     pub fn new(biosdev: u32, slice: u32, part: u32) -> Self {
         Self {
-            header: TagHeader::new(TagType::Apm, mem::size_of::<Self>() as u32),
+            header: TagHeader::new(Self::ID, Self::SIZE as u32),
             biosdev,
             slice,
             part,
@@ -35,6 +43,12 @@ impl BootdevTag {
         self.biosdev
     }
 
+    /// Classifies the [`Self::biosdev`] drive number.
+    #[must_use]
+    pub const fn drive_kind(&self) -> BiosDriveKind {
+        BiosDriveKind::from_drive_number(self.biosdev)
+    }
+
     /// The slice field identifies the partition (also known as a "slice" in BSD
     /// terminology) on the BIOS device from which the operating system was
     /// booted.
@@ -43,6 +57,17 @@ impl BootdevTag {
         self.slice
     }
 
+    /// Like [`Self::slice`] but returns `None` if the field is unused, i.e.,
+    /// the OS was not booted from a partition.
+    #[must_use]
+    pub const fn partition(&self) -> Option<u32> {
+        if self.slice == Self::UNUSED {
+            None
+        } else {
+            Some(self.slice)
+        }
+    }
+
     /// The part field denotes the subpartition or logical partition within the
     /// primary partition (if applicable) from which the operating system was
     /// booted.
@@ -50,6 +75,16 @@ impl BootdevTag {
     pub const fn part(&self) -> u32 {
         self.part
     }
+
+    /// Like [`Self::part`] but returns `None` if the field is unused.
+    #[must_use]
+    pub const fn sub_partition(&self) -> Option<u32> {
+        if self.part == Self::UNUSED {
+            None
+        } else {
+            Some(self.part)
+        }
+    }
 }
 
 impl MaybeDynSized for BootdevTag {
@@ -65,3 +100,93 @@ impl Tag for BootdevTag {
 
     const ID: TagType = TagType::Bootdev;
 }
+
+/// The kind of a BIOS drive, derived from the BIOS drive number by the
+/// conventional ranges.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BiosDriveKind {
+    /// Floppy disk (`0x00..=0x7f`).
+    Floppy,
+    /// Hard disk (`0x80..=0xdf`).
+    HardDisk,
+    /// CD-ROM, typically booted via El Torito (`0xe0..=0xff`).
+    CdRom,
+    /// Not a valid BIOS drive number.
+    Unknown,
+}
+
+impl BiosDriveKind {
+    /// Classifies the given BIOS drive number.
+    #[must_use]
+    pub const fn from_drive_number(drive: u32) -> Self {
+        match drive {
+            0x00..=0x7f => Self::Floppy,
+            0x80..=0xdf => Self::HardDisk,
+            0xe0..=0xff => Self::CdRom,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GenericTag;
+    use core::borrow::Borrow;
+    use multiboot2_common::test_utils::AlignedBytes;
+
+    #[rustfmt::skip]
+    const fn get_bytes() -> AlignedBytes<24> {
+        AlignedBytes::new([
+            TagType::Bootdev.val() as u8, 0, 0, 0,
+            20, 0, 0, 0,
+            /* biosdev */
+            0x80, 0, 0, 0,
+            /* slice */
+            1, 0, 0, 0,
+            /* part */
+            0xff, 0xff, 0xff, 0xff,
+            /* padding */
+            0, 0, 0, 0,
+        ])
+    }
+
+    /// Test to parse a given tag.
+    #[test]
+    fn test_parse() {
+        let bytes = get_bytes();
+        let tag = GenericTag::ref_from_slice(bytes.borrow()).unwrap();
+        let tag = tag.downcast::<BootdevTag>().unwrap();
+        assert_eq!(tag.header.typ, TagType::Bootdev);
+        assert_eq!(tag.biosdev(), 0x80);
+        assert_eq!(tag.drive_kind(), BiosDriveKind::HardDisk);
+        assert_eq!(tag.slice(), 1);
+        assert_eq!(tag.partition(), Some(1));
+        assert_eq!(tag.part(), BootdevTag::UNUSED);
+        assert_eq!(tag.sub_partition(), None);
+    }
+
+    /// Test to build a tag.
+    #[test]
+    fn test_build() {
+        let tag = BootdevTag::new(0x80, 1, BootdevTag::UNUSED);
+        let bytes = get_bytes();
+        let bytes: &[u8] = bytes.borrow();
+        assert_eq!(tag.header().size, 20);
+        assert_eq!(&tag.as_bytes().as_ref()[..20], &bytes[..20]);
+    }
+
+    #[test]
+    fn test_drive_kind() {
+        assert_eq!(BiosDriveKind::from_drive_number(0), BiosDriveKind::Floppy);
+        assert_eq!(
+            BiosDriveKind::from_drive_number(0x81),
+            BiosDriveKind::HardDisk
+        );
+        assert_eq!(BiosDriveKind::from_drive_number(0xe0), BiosDriveKind::CdRom);
+        assert_eq!(
+            BiosDriveKind::from_drive_number(0x100),
+            BiosDriveKind::Unknown
+        );
+    }
+}
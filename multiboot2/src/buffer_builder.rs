@@ -0,0 +1,356 @@
+//! Module for [`BufferBuilder`].
+
+use crate::{BootInformationHeader, EndTag, TagHeader, TagTypeId};
+use core::mem;
+use derive_more::Display;
+use multiboot2_common::{increase_to_alignment, MaybeDynSized, ALIGNMENT};
+
+/// Error when the buffer of a [`BufferBuilder`] is too small, or when a tag
+/// or the boot information exceeds the `u32` size field.
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[display(
+    "out of space: {} bytes required but only {} bytes available",
+    required,
+    available
+)]
+pub struct OutOfSpace {
+    required: usize,
+    available: usize,
+}
+
+impl OutOfSpace {
+    /// Returns the number of bytes the operation needed in total.
+    #[must_use]
+    pub const fn required(&self) -> usize {
+        self.required
+    }
+
+    /// Returns the size of the buffer.
+    #[must_use]
+    pub const fn available(&self) -> usize {
+        self.available
+    }
+}
+
+impl core::error::Error for OutOfSpace {}
+
+/// Errors of a [`BufferBuilder`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BufferBuilderError {
+    /// The buffer is too small. See [`OutOfSpace`].
+    OutOfSpace(OutOfSpace),
+    /// The buffer is not aligned to an 8-byte boundary.
+    #[display("buffer is not 8-byte aligned")]
+    WrongAlignment,
+    /// The size field of a tag is smaller than the tag header or larger than
+    /// the tag.
+    #[display("tag reports an invalid size of {_0} bytes")]
+    InvalidTagSize(u32),
+}
+
+impl core::error::Error for BufferBuilderError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::OutOfSpace(inner) => Some(inner),
+            Self::WrongAlignment | Self::InvalidTagSize(_) => None,
+        }
+    }
+}
+
+impl From<OutOfSpace> for BufferBuilderError {
+    fn from(value: OutOfSpace) -> Self {
+        Self::OutOfSpace(value)
+    }
+}
+
+/// Builder for a Multiboot2 boot information that serializes the tags directly
+/// into a caller-provided buffer.
+///
+/// Unlike [`Builder`], this doesn't need the `alloc` feature, which makes it
+/// usable in loaders without a heap.
+///
+/// Tags are written in the order they are added. The end tag and the total
+/// size are written by [`Self::finish`].
+///
+/// The buffer must be aligned to an 8-byte boundary, as this is required for
+/// a boot information to be loadable.
+///
+/// # Example
+///
+/// ```rust
+/// use multiboot2::{BasicMemoryInfoTag, BufferBuilder, TagType};
+///
+/// #[repr(C, align(8))]
+/// struct Aligned([u8; 64]);
+///
+/// let mut buffer = Aligned([0; 64]);
+/// let len = BufferBuilder::new(&mut buffer.0)?
+///     .add_tag(&BasicMemoryInfoTag::new(640, 7 * 1024))?
+///     .add_tag_parts(TagType::Cmdline, &[b"hello\0"])?
+///     .finish()?;
+/// assert_eq!(len, 48);
+/// # Ok::<(), multiboot2::BufferBuilderError>(())
+/// ```
+///
+/// [`Builder`]: crate::Builder
+#[derive(Debug)]
+pub struct BufferBuilder<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> BufferBuilder<'a> {
+    /// Creates a new builder that writes into `buffer`. The buffer must be
+    /// 8-byte aligned and at least be big enough for the boot information
+    /// header.
+    pub fn new(buffer: &'a mut [u8]) -> Result<Self, BufferBuilderError> {
+        if buffer.as_ptr().align_offset(ALIGNMENT) != 0 {
+            return Err(BufferBuilderError::WrongAlignment);
+        }
+        let mut this = Self { buffer, len: 0 };
+        this.reserve(mem::size_of::<BootInformationHeader>())?;
+        this.len = mem::size_of::<BootInformationHeader>();
+        Ok(this)
+    }
+
+    /// Ensures that `additional` more bytes fit into the buffer.
+    fn reserve(&self, additional: usize) -> Result<(), OutOfSpace> {
+        let required = self.len + additional;
+        if required > self.buffer.len() {
+            Err(OutOfSpace {
+                required,
+                available: self.buffer.len(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Converts `size` for a `u32` size field, which limits the size of the
+    /// boot information and its tags. `required` is reported on failure.
+    fn size_field(&self, size: usize, required: usize) -> Result<u32, OutOfSpace> {
+        u32::try_from(size).map_err(|_| OutOfSpace {
+            required,
+            available: self.buffer.len().min(u32::MAX as usize),
+        })
+    }
+
+    /// Writes a tag consisting of a header and the concatenated `parts` as
+    /// payload, followed by zeroed padding.
+    fn write_tag(&mut self, header: TagHeader, parts: &[&[u8]]) -> Result<(), OutOfSpace> {
+        let size = header.size as usize;
+        let padded_size = increase_to_alignment(size);
+        self.reserve(padded_size)?;
+
+        let tag = &mut self.buffer[self.len..self.len + padded_size];
+        tag[0..4].copy_from_slice(&u32::from(header.typ).to_ne_bytes());
+        tag[4..8].copy_from_slice(&header.size.to_ne_bytes());
+        let mut offset = mem::size_of::<TagHeader>();
+        for part in parts {
+            tag[offset..offset + part.len()].copy_from_slice(part);
+            offset += part.len();
+        }
+        tag[offset..].fill(0);
+
+        self.len += padded_size;
+        Ok(())
+    }
+
+    /// Adds a typed tag, such as [`BasicMemoryInfoTag`].
+    ///
+    /// Returns [`BufferBuilderError::InvalidTagSize`] if the size field of the
+    /// tag doesn't match the tag, e.g., for a [`GenericTag`] from untrusted
+    /// bytes.
+    ///
+    /// [`BasicMemoryInfoTag`]: crate::BasicMemoryInfoTag
+    /// [`GenericTag`]: crate::GenericTag
+    pub fn add_tag<T: MaybeDynSized<Header = TagHeader> + ?Sized>(
+        mut self,
+        tag: &T,
+    ) -> Result<Self, BufferBuilderError> {
+        let header = *tag.header();
+        let payload = (header.size as usize)
+            .checked_sub(mem::size_of::<TagHeader>())
+            .and_then(|len| tag.payload().get(..len))
+            .ok_or(BufferBuilderError::InvalidTagSize(header.size))?;
+        self.write_tag(header, &[payload])?;
+        Ok(self)
+    }
+
+    /// Adds a tag of the given type whose payload is the concatenation of
+    /// `parts`. This enables to add tags with a dynamic size, such as a
+    /// [`CommandLineTag`], without a heap.
+    ///
+    /// [`CommandLineTag`]: crate::CommandLineTag
+    pub fn add_tag_parts(
+        mut self,
+        typ: impl Into<TagTypeId>,
+        parts: &[&[u8]],
+    ) -> Result<Self, BufferBuilderError> {
+        let payload_len = parts.iter().map(|part| part.len()).sum::<usize>();
+        let size = mem::size_of::<TagHeader>().saturating_add(payload_len);
+        let size = self.size_field(size, self.len.saturating_add(size))?;
+        self.write_tag(TagHeader::new(typ, size), parts)?;
+        Ok(self)
+    }
+
+    /// Appends the end tag, writes the boot information header, and returns
+    /// the total size of the boot information in the buffer.
+    pub fn finish(self) -> Result<usize, BufferBuilderError> {
+        let end_tag = EndTag::default();
+        let this = self.add_tag(&end_tag)?;
+        let total_size = this.size_field(this.len, this.len)?;
+        this.buffer[0..4].copy_from_slice(&total_size.to_ne_bytes());
+        this.buffer[4..8].fill(0);
+        Ok(this.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "builder")]
+    use crate::CommandLineTag;
+    use crate::{BasicMemoryInfoTag, BootInformation, TagType};
+    use multiboot2_common::test_utils::AlignedBytes;
+
+    #[test]
+    fn test_build_and_parse() {
+        let mut buffer = AlignedBytes::new([0_u8; 128]);
+        let len = BufferBuilder::new(&mut buffer.0)
+            .unwrap()
+            .add_tag(&BasicMemoryInfoTag::new(640, 7 * 1024))
+            .unwrap()
+            .add_tag_parts(TagType::Cmdline, &[b"hello", b" world\0"])
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert_eq!(len, 8 + 16 + 24 + 8);
+
+        let info = unsafe { BootInformation::load(buffer.0.as_ptr().cast()) }.unwrap();
+        assert_eq!(info.total_size(), len);
+        assert_eq!(
+            info.basic_memory_info_tag().unwrap().memory_upper(),
+            7 * 1024
+        );
+        assert_eq!(
+            info.command_line_tag().unwrap().cmdline(),
+            Ok("hello world")
+        );
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_tag_size_overflow() {
+        let mut buffer = AlignedBytes::new([0_u8; 64]);
+        static PART: [u8; 1 << 20] = [0; 1 << 20];
+        let parts = [&PART[..]; 4096];
+        let err = BufferBuilder::new(&mut buffer.0)
+            .unwrap()
+            .add_tag_parts(TagType::Custom(0x1337), &parts)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            BufferBuilderError::OutOfSpace(OutOfSpace {
+                required: 8 + 8 + (1 << 32),
+                available: 64
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_add_dst_tag() {
+        let mut buffer = AlignedBytes::new([0_u8; 64]);
+        let len = BufferBuilder::new(&mut buffer.0)
+            .unwrap()
+            .add_tag(&*CommandLineTag::new("hello"))
+            .unwrap()
+            .finish()
+            .unwrap();
+        let info = unsafe { BootInformation::load(buffer.0.as_ptr().cast()) }.unwrap();
+        assert_eq!(info.total_size(), len);
+        assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("hello"));
+    }
+
+    #[test]
+    fn test_out_of_space() {
+        let out_of_space = |required, available| {
+            BufferBuilderError::OutOfSpace(OutOfSpace {
+                required,
+                available,
+            })
+        };
+
+        let mut buffer = AlignedBytes::new([0_u8; 4]);
+        assert_eq!(
+            BufferBuilder::new(&mut buffer.0).unwrap_err(),
+            out_of_space(8, 4)
+        );
+
+        let mut buffer = AlignedBytes::new([0_u8; 16]);
+        let err = BufferBuilder::new(&mut buffer.0)
+            .unwrap()
+            .add_tag(&BasicMemoryInfoTag::new(640, 7 * 1024))
+            .unwrap_err();
+        assert_eq!(err, out_of_space(24, 16));
+
+        let mut buffer = AlignedBytes::new([0_u8; 24]);
+        let err = BufferBuilder::new(&mut buffer.0)
+            .unwrap()
+            .add_tag(&BasicMemoryInfoTag::new(640, 7 * 1024))
+            .unwrap()
+            .finish()
+            .unwrap_err();
+        assert_eq!(err, out_of_space(32, 24));
+    }
+
+    #[test]
+    fn test_wrong_alignment() {
+        let mut buffer = AlignedBytes::new([0_u8; 32]);
+        assert_eq!(
+            BufferBuilder::new(&mut buffer.0[4..]).unwrap_err(),
+            BufferBuilderError::WrongAlignment
+        );
+    }
+
+    #[test]
+    fn test_invalid_tag_size() {
+        /// A tag whose size field can be set arbitrarily.
+        #[repr(C, align(8))]
+        struct RawTag {
+            header: TagHeader,
+            payload: [u8; 8],
+        }
+
+        impl MaybeDynSized for RawTag {
+            type Header = TagHeader;
+
+            const BASE_SIZE: usize = mem::size_of::<Self>();
+
+            fn dst_len(_header: &TagHeader) {}
+        }
+
+        let raw_tag = |size| RawTag {
+            header: TagHeader::new(TagType::Custom(0x1337), size),
+            payload: [0xab; 8],
+        };
+        let mut buffer = AlignedBytes::new([0_u8; 64]);
+        for size in [0, 7, 17, u32::MAX] {
+            let err = BufferBuilder::new(&mut buffer.0)
+                .unwrap()
+                .add_tag(&raw_tag(size))
+                .unwrap_err();
+            assert_eq!(err, BufferBuilderError::InvalidTagSize(size));
+        }
+
+        let len = BufferBuilder::new(&mut buffer.0)
+            .unwrap()
+            .add_tag(&raw_tag(12))
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert_eq!(len, 8 + 16 + 8);
+        assert_eq!(&buffer.0[16..24], [0xab, 0xab, 0xab, 0xab, 0, 0, 0, 0]);
+    }
+}
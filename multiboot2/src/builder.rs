@@ -2,43 +2,221 @@
 
 use crate::apm::ApmTag;
 use crate::bootdev::BootdevTag;
-use crate::network::NetworkTag;
+use crate::util::copy_words;
+#[cfg(feature = "elf")]
+use crate::ElfSectionsTag;
+#[cfg(feature = "net")]
+use crate::NetworkTag;
+#[cfg(feature = "smbios")]
+use crate::SmbiosTag;
+#[cfg(feature = "vbe")]
+use crate::VBEInfoTag;
 use crate::{
-    BasicMemoryInfoTag, BootInformationHeader, BootLoaderNameTag, CommandLineTag,
-    EFIBootServicesNotExitedTag, EFIImageHandle32Tag, EFIImageHandle64Tag, EFIMemoryMapTag,
-    EFISdt32Tag, EFISdt64Tag, ElfSectionsTag, EndTag, FramebufferTag, ImageLoadPhysAddrTag,
-    MemoryMapTag, ModuleTag, RsdpV1Tag, RsdpV2Tag, SmbiosTag, TagHeader, TagType, VBEInfoTag,
+    BasicMemoryInfoTag, BootInformation, BootInformationHeader, BootLoaderNameTag, CommandLineTag,
+    E820Entry, EFIBootServicesNotExitedTag, EFIMemoryDesc, EFIMemoryMapTag, EndTag, FramebufferTag,
+    GenericTag, ImageLoadPhysAddrTag, LoadError, MemoryArea, MemoryMapTag, ModuleTag, ParsePolicy,
+    RsdpV1Tag, RsdpV2Tag, TagHeader, TagType, TagTypeId,
 };
+#[cfg(feature = "efi")]
+use crate::{EFIImageHandle32Tag, EFIImageHandle64Tag, EFISdt32Tag, EFISdt64Tag};
 use alloc::boxed::Box;
 use alloc::vec::Vec;
-use multiboot2_common::{new_boxed, DynSizedStructure, MaybeDynSized};
+use core::alloc::Layout;
+use core::convert::Infallible;
+use core::mem;
+use derive_more::Display;
+use multiboot2_common::{
+    new_boxed, try_new_boxed, DynSizedStructure, MaybeDynSized, MemoryError, ALIGNMENT,
+};
+
+/// Zero bytes to pad a tag to the next [`ALIGNMENT`] boundary.
+const PADDING: [u8; ALIGNMENT] = [0; ALIGNMENT];
+
+/// The order in which the [`Builder`] serializes the tags.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TagOrder {
+    /// Tags are sorted by their type. Tags of the same type keep the order in
+    /// which they were added. This is the default.
+    #[default]
+    ByType,
+    /// Tags are serialized in the order in which they were added.
+    Insertion,
+}
+
+/// What the [`Builder`] does if a tag is added while a tag of the same type
+/// is already present.
+///
+/// By default, tags of the types for which [`TagType::may_repeat`] holds use
+/// [`DuplicatePolicy::AllowMultiple`] while all other tags use
+/// [`DuplicatePolicy::Replace`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DuplicatePolicy {
+    /// The duplicate is not added and [`Builder::try_build`] reports a
+    /// [`BuilderError::DuplicateTag`].
+    Reject,
+    /// The new tag replaces the existing one at its position.
+    Replace,
+    /// All tags of the type are kept.
+    AllowMultiple,
+}
+
+/// A problem found by [`Builder::validate`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValidationProblem {
+    /// An end tag was added explicitly, although the builder appends it
+    /// automatically.
+    ExplicitEndTag,
+    /// A tag type that must occur at most once occurs multiple times.
+    DuplicateTag(TagType),
+    /// The entry size of a memory map tag doesn't match its entries.
+    InconsistentMemoryMap(TagType),
+    /// The string of a tag is not NUL-terminated.
+    MissingNul(TagType),
+    /// The tag is smaller than its fixed-size fields.
+    Truncated(TagType),
+    /// The total size in bytes doesn't fit into the `u32` size field.
+    TooLarge(usize),
+}
+
+/// Errors of the fallible constructors, such as [`Builder::try_build`] or
+/// [`CommandLineTag::try_new`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BuilderError {
+    /// The size in bytes doesn't fit into the `u32` size field.
+    #[display("SizeOverflow({})", _0)]
+    SizeOverflow(usize),
+    /// The string contains a NUL byte before its end and would be truncated.
+    InvalidString,
+    /// The end address of a module is not above its start address.
+    EmptyModule,
+    /// The memory allocation failed.
+    OutOfMemory,
+    /// A tag was added while a tag of the same type was already present and
+    /// its [`DuplicatePolicy`] is [`DuplicatePolicy::Reject`].
+    #[display("DuplicateTag({:?})", _0)]
+    DuplicateTag(TagType),
+}
+
+impl core::error::Error for BuilderError {}
+
+/// Errors of [`Builder::write_to`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WriteError<E> {
+    /// The boot information can't be serialized. See [`BuilderError`].
+    Builder(BuilderError),
+    /// Writing to the guest memory failed.
+    Memory(E),
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for WriteError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Builder(inner) => Some(inner),
+            Self::Memory(inner) => Some(inner),
+        }
+    }
+}
+
+/// Memory of a guest, addressed by guest physical addresses (GPAs), which
+/// [`Builder::write_to`] writes the boot information to.
+///
+/// Implement this for the guest memory abstraction of a VMM, such as the
+/// `GuestMemory` of `vm-memory`, to materialize the boot information in guest
+/// RAM without an intermediate host buffer.
+pub trait GuestMemoryWrite {
+    /// Error when a write fails, e.g., because it is out of bounds.
+    type Error;
+
+    /// Writes `bytes` to the guest physical address `gpa`.
+    fn write(&mut self, gpa: u64, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Declares [`BuilderTag`] with one variant per typed tag of the builder.
+macro_rules! builder_tags {
+    ($($(#[$attr:meta])* $variant:ident($typ:ty),)+) => {
+        /// A tag of the [`Builder`]. It is kept as it was passed to the
+        /// builder and only copied when the boot information is serialized.
+        #[derive(Debug)]
+        enum BuilderTag {
+            $($(#[$attr])* $variant($typ),)+
+        }
+
+        impl BuilderTag {
+            /// Returns the header of the tag.
+            fn header(&self) -> &TagHeader {
+                match self {
+                    $($(#[$attr])* Self::$variant(tag) => tag.header(),)+
+                }
+            }
+
+            /// Returns the bytes of the tag, excluding the padding. The
+            /// padding of sized tags is not necessarily initialized.
+            fn as_bytes(&self) -> &[u8] {
+                let bytes: &[u8] = match self {
+                    $($(#[$attr])* Self::$variant(tag) => tag.as_bytes().as_ref(),)+
+                };
+                &bytes[..self.header().size as usize]
+            }
+        }
+    };
+}
+
+builder_tags! {
+    Cmdline(Box<CommandLineTag>),
+    BootLoaderName(Box<BootLoaderNameTag>),
+    Module(Box<ModuleTag>),
+    BasicMeminfo(BasicMemoryInfoTag),
+    Bootdev(BootdevTag),
+    Mmap(Box<MemoryMapTag>),
+    #[cfg(feature = "vbe")]
+    Vbe(Box<VBEInfoTag>),
+    Framebuffer(Box<FramebufferTag>),
+    #[cfg(feature = "elf")]
+    ElfSections(Box<ElfSectionsTag>),
+    Apm(ApmTag),
+    #[cfg(feature = "efi")]
+    Efi32(EFISdt32Tag),
+    #[cfg(feature = "efi")]
+    Efi64(EFISdt64Tag),
+    #[cfg(feature = "smbios")]
+    Smbios(Box<SmbiosTag>),
+    RsdpV1(RsdpV1Tag),
+    RsdpV2(RsdpV2Tag),
+    EfiMmap(Box<EFIMemoryMapTag>),
+    #[cfg(feature = "net")]
+    Network(Box<NetworkTag>),
+    EfiBs(EFIBootServicesNotExitedTag),
+    #[cfg(feature = "efi")]
+    Efi32Ih(EFIImageHandle32Tag),
+    #[cfg(feature = "efi")]
+    Efi64Ih(EFIImageHandle64Tag),
+    ImageLoadAddr(ImageLoadPhysAddrTag),
+    Custom(Box<DynSizedStructure<TagHeader>>),
+    Raw(Box<GenericTag>),
+}
+
+impl BuilderTag {
+    /// Returns the type of the tag.
+    fn typ(&self) -> TagType {
+        self.header().typ.into()
+    }
+
+    /// Returns the payload of the tag without the padding.
+    fn payload(&self) -> &[u8] {
+        self.as_bytes()
+            .get(mem::size_of::<TagHeader>()..)
+            .unwrap_or_default()
+    }
+}
 
 /// Builder for a Multiboot2 header information.
-// #[derive(Debug)]
 #[derive(Debug)]
 pub struct Builder {
-    cmdline: Option<Box<CommandLineTag>>,
-    bootloader: Option<Box<BootLoaderNameTag>>,
-    modules: Vec<Box<ModuleTag>>,
-    meminfo: Option<BasicMemoryInfoTag>,
-    bootdev: Option<BootdevTag>,
-    mmap: Option<Box<MemoryMapTag>>,
-    vbe: Option<VBEInfoTag>,
-    framebuffer: Option<Box<FramebufferTag>>,
-    elf_sections: Option<Box<ElfSectionsTag>>,
-    apm: Option<ApmTag>,
-    efi32: Option<EFISdt32Tag>,
-    efi64: Option<EFISdt64Tag>,
-    smbios: Vec<Box<SmbiosTag>>,
-    rsdpv1: Option<RsdpV1Tag>,
-    rsdpv2: Option<RsdpV2Tag>,
-    network: Option<Box<NetworkTag>>,
-    efi_mmap: Option<Box<EFIMemoryMapTag>>,
-    efi_bs: Option<EFIBootServicesNotExitedTag>,
-    efi32_ih: Option<EFIImageHandle32Tag>,
-    efi64_ih: Option<EFIImageHandle64Tag>,
-    image_load_addr: Option<ImageLoadPhysAddrTag>,
-    custom_tags: Vec<Box<DynSizedStructure<TagHeader>>>,
+    tags: Vec<BuilderTag>,
+    order: TagOrder,
+    policies: Vec<(TagTypeId, DuplicatePolicy)>,
+    /// The first tag type of which a duplicate was rejected.
+    rejected: Option<TagTypeId>,
 }
 
 impl Default for Builder {
@@ -52,279 +230,665 @@ impl Builder {
     #[must_use]
     pub const fn new() -> Self {
         Self {
-            cmdline: None,
-            bootloader: None,
-            modules: vec![],
-            meminfo: None,
-            bootdev: None,
-            mmap: None,
-            vbe: None,
-            framebuffer: None,
-            elf_sections: None,
-            apm: None,
-            efi32: None,
-            efi64: None,
-            smbios: vec![],
-            rsdpv1: None,
-            rsdpv2: None,
-            efi_mmap: None,
-            network: None,
-            efi_bs: None,
-            efi32_ih: None,
-            efi64_ih: None,
-            image_load_addr: None,
-            custom_tags: vec![],
+            tags: vec![],
+            order: TagOrder::ByType,
+            policies: vec![],
+            rejected: None,
         }
     }
 
-    /// Sets the [`CommandLineTag`] tag.
+    /// Creates a builder that contains all tags of the given boot information,
+    /// including unknown ones, byte-for-byte and in their original order.
+    ///
+    /// This enables a chainloader to parse, modify, and re-emit the boot
+    /// information without data loss. The tag order is set to
+    /// [`TagOrder::Insertion`]. Tags added afterwards follow the usual
+    /// [`DuplicatePolicy`], e.g., setting a new memory map replaces the
+    /// imported one in place while additional modules are appended.
     #[must_use]
-    pub fn cmdline(mut self, cmdline: Box<CommandLineTag>) -> Self {
-        self.cmdline = Some(cmdline);
+    pub fn from_boot_information(info: &BootInformation) -> Self {
+        let tags = info
+            .generic_tags()
+            .filter(|tag| tag.typ() != TagType::End)
+            .map(|tag| BuilderTag::Raw(GenericTag::new(tag.header().typ, tag.payload())))
+            .collect();
+        Self {
+            tags,
+            order: TagOrder::Insertion,
+            policies: vec![],
+            rejected: None,
+        }
+    }
+
+    /// Sets the order in which the tags are serialized.
+    #[must_use]
+    pub const fn tag_order(mut self, order: TagOrder) -> Self {
+        self.order = order;
         self
     }
 
-    /// Sets the [`BootLoaderNameTag`] tag.
+    /// Sets the [`DuplicatePolicy`] for tags of the given type. This only
+    /// affects tags added afterwards.
     #[must_use]
-    pub fn bootloader(mut self, bootloader: Box<BootLoaderNameTag>) -> Self {
-        self.bootloader = Some(bootloader);
+    pub fn duplicate_policy(mut self, typ: impl Into<TagTypeId>, policy: DuplicatePolicy) -> Self {
+        let typ = typ.into();
+        self.policies.retain(|(t, _)| *t != typ);
+        self.policies.push((typ, policy));
         self
     }
 
-    /// Adds a [`ModuleTag`] tag.
+    /// Returns the [`DuplicatePolicy`] for tags of the given type.
     #[must_use]
-    pub fn add_module(mut self, module: Box<ModuleTag>) -> Self {
-        self.modules.push(module);
+    pub fn policy_of(&self, typ: impl Into<TagTypeId>) -> DuplicatePolicy {
+        let typ = typ.into();
+        self.policies.iter().find(|(t, _)| *t == typ).map_or_else(
+            || {
+                if TagType::from(typ).may_repeat() {
+                    DuplicatePolicy::AllowMultiple
+                } else {
+                    DuplicatePolicy::Replace
+                }
+            },
+            |(_, policy)| *policy,
+        )
+    }
+
+    /// Adds the tag according to the [`DuplicatePolicy`] of its type.
+    ///
+    /// A rejected duplicate is recorded and reported when building.
+    fn insert(mut self, tag: BuilderTag) -> Self {
+        let typ = tag.header().typ;
+        let existing = self.tags.iter().position(|t| t.header().typ == typ);
+        match (self.policy_of(typ), existing) {
+            (DuplicatePolicy::AllowMultiple, _) | (_, None) => self.tags.push(tag),
+            (DuplicatePolicy::Replace, Some(idx)) => self.tags[idx] = tag,
+            (DuplicatePolicy::Reject, Some(_)) => {
+                self.rejected.get_or_insert(typ);
+            }
+        }
         self
     }
 
+    /// Sets the [`CommandLineTag`] tag.
+    #[must_use]
+    pub fn cmdline(self, cmdline: Box<CommandLineTag>) -> Self {
+        self.insert(BuilderTag::Cmdline(cmdline))
+    }
+
+    /// Sets the [`BootLoaderNameTag`] tag.
+    #[must_use]
+    pub fn bootloader(self, bootloader: Box<BootLoaderNameTag>) -> Self {
+        self.insert(BuilderTag::BootLoaderName(bootloader))
+    }
+
+    /// Adds the [`ModuleTag`] tag.
+    #[must_use]
+    pub fn add_module_tag(self, module: Box<ModuleTag>) -> Self {
+        self.insert(BuilderTag::Module(module))
+    }
+
+    /// Adds a [`ModuleTag`] for the module at `start..end` with the given
+    /// command line. Modules keep the order in which they were added.
+    #[must_use]
+    pub fn add_module(self, start: u32, end: u32, cmdline: &str) -> Self {
+        self.add_module_tag(ModuleTag::new(start, end, cmdline))
+    }
+
+    /// Adds a [`ModuleTag`] for each `(start, end, cmdline)` triple, keeping
+    /// their order.
+    #[must_use]
+    pub fn add_modules<S: AsRef<str>>(
+        self,
+        modules: impl IntoIterator<Item = (u32, u32, S)>,
+    ) -> Self {
+        modules
+            .into_iter()
+            .fold(self, |builder, (start, end, cmdline)| {
+                builder.add_module(start, end, cmdline.as_ref())
+            })
+    }
+
     /// Sets the [`BasicMemoryInfoTag`] tag.
     #[must_use]
-    pub const fn meminfo(mut self, meminfo: BasicMemoryInfoTag) -> Self {
-        self.meminfo = Some(meminfo);
-        self
+    pub fn meminfo(self, meminfo: BasicMemoryInfoTag) -> Self {
+        self.insert(BuilderTag::BasicMeminfo(meminfo))
     }
 
     /// Sets the [`BootdevTag`] tag.
     #[must_use]
-    pub const fn bootdev(mut self, bootdev: BootdevTag) -> Self {
-        self.bootdev = Some(bootdev);
-        self
+    pub fn bootdev(self, bootdev: BootdevTag) -> Self {
+        self.insert(BuilderTag::Bootdev(bootdev))
+    }
+
+    /// Sets the [`BasicMemoryInfoTag`] and the [`MemoryMapTag`] derived from
+    /// raw E820 output. See [`E820Entry::to_memory_areas`] for how the entries
+    /// are sanitized.
+    #[must_use]
+    pub fn e820(self, entries: &[E820Entry]) -> Self {
+        let areas = E820Entry::to_memory_areas(entries);
+        self.meminfo(BasicMemoryInfoTag::from_memory_areas(&areas))
+            .mmap(MemoryMapTag::new(&areas))
     }
 
     /// Sets the [`MemoryMapTag`] tag.
     #[must_use]
-    pub fn mmap(mut self, mmap: Box<MemoryMapTag>) -> Self {
-        self.mmap = Some(mmap);
-        self
+    pub fn mmap(self, mmap: Box<MemoryMapTag>) -> Self {
+        self.insert(BuilderTag::Mmap(mmap))
     }
 
     /// Sets the [`VBEInfoTag`] tag.
+    #[cfg(feature = "vbe")]
     #[must_use]
-    pub const fn vbe(mut self, vbe: VBEInfoTag) -> Self {
-        self.vbe = Some(vbe);
-        self
+    pub fn vbe(self, vbe: VBEInfoTag) -> Self {
+        self.insert(BuilderTag::Vbe(Box::new(vbe)))
     }
 
     /// Sets the [`FramebufferTag`] tag.
     #[must_use]
-    pub fn framebuffer(mut self, framebuffer: Box<FramebufferTag>) -> Self {
-        self.framebuffer = Some(framebuffer);
-        self
+    pub fn framebuffer(self, framebuffer: Box<FramebufferTag>) -> Self {
+        self.insert(BuilderTag::Framebuffer(framebuffer))
     }
 
     /// Sets the [`ElfSectionsTag`] tag.
+    #[cfg(feature = "elf")]
     #[must_use]
-    pub fn elf_sections(mut self, elf_sections: Box<ElfSectionsTag>) -> Self {
-        self.elf_sections = Some(elf_sections);
-        self
+    pub fn elf_sections(self, elf_sections: Box<ElfSectionsTag>) -> Self {
+        self.insert(BuilderTag::ElfSections(elf_sections))
     }
 
     /// Sets the [`ApmTag`] tag.
     #[must_use]
-    pub const fn apm(mut self, apm: ApmTag) -> Self {
-        self.apm = Some(apm);
-        self
+    pub fn apm(self, apm: ApmTag) -> Self {
+        self.insert(BuilderTag::Apm(apm))
     }
 
     /// Sets the [`EFISdt32Tag`] tag.
+    #[cfg(feature = "efi")]
     #[must_use]
-    pub const fn efi32(mut self, efi32: EFISdt32Tag) -> Self {
-        self.efi32 = Some(efi32);
-        self
+    pub fn efi32(self, efi32: EFISdt32Tag) -> Self {
+        self.insert(BuilderTag::Efi32(efi32))
     }
 
     /// Sets the [`EFISdt64Tag`] tag.
+    #[cfg(feature = "efi")]
     #[must_use]
-    pub const fn efi64(mut self, efi64: EFISdt64Tag) -> Self {
-        self.efi64 = Some(efi64);
-        self
+    pub fn efi64(self, efi64: EFISdt64Tag) -> Self {
+        self.insert(BuilderTag::Efi64(efi64))
     }
 
-    /// Adds a [`SmbiosTag`] tag.
+    /// Adds the [`SmbiosTag`] tag.
+    #[cfg(feature = "smbios")]
     #[must_use]
-    pub fn add_smbios(mut self, smbios: Box<SmbiosTag>) -> Self {
-        self.smbios.push(smbios);
-        self
+    pub fn add_smbios(self, smbios: Box<SmbiosTag>) -> Self {
+        self.insert(BuilderTag::Smbios(smbios))
     }
 
     /// Sets the [`RsdpV1Tag`] tag.
     #[must_use]
-    pub const fn rsdpv1(mut self, rsdpv1: RsdpV1Tag) -> Self {
-        self.rsdpv1 = Some(rsdpv1);
-        self
+    pub fn rsdpv1(self, rsdpv1: RsdpV1Tag) -> Self {
+        self.insert(BuilderTag::RsdpV1(rsdpv1))
     }
 
     /// Sets the [`RsdpV2Tag`] tag.
     #[must_use]
-    pub const fn rsdpv2(mut self, rsdpv2: RsdpV2Tag) -> Self {
-        self.rsdpv2 = Some(rsdpv2);
-        self
+    pub fn rsdpv2(self, rsdpv2: RsdpV2Tag) -> Self {
+        self.insert(BuilderTag::RsdpV2(rsdpv2))
     }
 
     /// Sets the [`EFIMemoryMapTag`] tag.
     #[must_use]
-    pub fn efi_mmap(mut self, efi_mmap: Box<EFIMemoryMapTag>) -> Self {
-        self.efi_mmap = Some(efi_mmap);
-        self
+    pub fn efi_mmap(self, efi_mmap: Box<EFIMemoryMapTag>) -> Self {
+        self.insert(BuilderTag::EfiMmap(efi_mmap))
     }
 
-    /// Sets the [`NetworkTag`] tag.
+    /// Adds a [`NetworkTag`] tag, one per network card.
+    #[cfg(feature = "net")]
     #[must_use]
-    pub fn network(mut self, network: Box<NetworkTag>) -> Self {
-        self.network = Some(network);
-        self
+    pub fn network(self, network: Box<NetworkTag>) -> Self {
+        self.insert(BuilderTag::Network(network))
     }
 
     /// Sets the [`EFIBootServicesNotExitedTag`] tag.
     #[must_use]
-    pub const fn efi_bs(mut self, efi_bs: EFIBootServicesNotExitedTag) -> Self {
-        self.efi_bs = Some(efi_bs);
-        self
+    pub fn efi_bs(self, efi_bs: EFIBootServicesNotExitedTag) -> Self {
+        self.insert(BuilderTag::EfiBs(efi_bs))
     }
 
     /// Sets the [`EFIImageHandle32Tag`] tag.
+    #[cfg(feature = "efi")]
     #[must_use]
-    pub const fn efi32_ih(mut self, efi32_ih: EFIImageHandle32Tag) -> Self {
-        self.efi32_ih = Some(efi32_ih);
-        self
+    pub fn efi32_ih(self, efi32_ih: EFIImageHandle32Tag) -> Self {
+        self.insert(BuilderTag::Efi32Ih(efi32_ih))
     }
 
     /// Sets the [`EFIImageHandle64Tag`] tag.
+    #[cfg(feature = "efi")]
     #[must_use]
-    pub const fn efi64_ih(mut self, efi64_ih: EFIImageHandle64Tag) -> Self {
-        self.efi64_ih = Some(efi64_ih);
-        self
+    pub fn efi64_ih(self, efi64_ih: EFIImageHandle64Tag) -> Self {
+        self.insert(BuilderTag::Efi64Ih(efi64_ih))
     }
 
     /// Sets the [`ImageLoadPhysAddrTag`] tag.
     #[must_use]
-    pub const fn image_load_addr(mut self, image_load_addr: ImageLoadPhysAddrTag) -> Self {
-        self.image_load_addr = Some(image_load_addr);
-        self
+    pub fn image_load_addr(self, image_load_addr: ImageLoadPhysAddrTag) -> Self {
+        self.insert(BuilderTag::ImageLoadAddr(image_load_addr))
     }
 
     /// Adds a custom tag.
     #[must_use]
-    pub fn add_custom_tag(mut self, custom_tag: Box<DynSizedStructure<TagHeader>>) -> Self {
+    pub fn add_custom_tag(self, custom_tag: Box<DynSizedStructure<TagHeader>>) -> Self {
         if let TagType::Custom(_c) = custom_tag.header().typ.into() {
-            self.custom_tags.push(custom_tag);
+            self.insert(BuilderTag::Custom(custom_tag))
         } else {
             panic!("Only for custom types!");
         }
-        self
     }
 
-    /// Returns properly aligned bytes on the heap representing a valid
-    /// Multiboot2 header structure.
+    /// Adds a [`GenericTag`] of a custom type, e.g., a vendor-specific tag.
     #[must_use]
-    pub fn build(self) -> Box<DynSizedStructure<BootInformationHeader>> {
-        let header = BootInformationHeader::new(0);
-        let mut byte_refs = Vec::new();
-        if let Some(tag) = self.cmdline.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
-        }
-        if let Some(tag) = self.bootloader.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
-        }
-        for i in &self.modules {
-            byte_refs.push(i.as_bytes().as_ref());
-        }
-        if let Some(tag) = self.meminfo.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
-        }
-        if let Some(tag) = self.bootdev.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
-        }
-        if let Some(tag) = self.mmap.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
-        }
-        if let Some(tag) = self.vbe.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
-        }
-        if let Some(tag) = self.framebuffer.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
-        }
-        if let Some(tag) = self.elf_sections.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
-        }
-        if let Some(tag) = self.apm.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+    pub fn add_generic_tag(self, tag: &GenericTag) -> Self {
+        let tag = new_boxed::<DynSizedStructure<TagHeader>>(*tag.header(), &[tag.payload()]);
+        self.add_custom_tag(tag)
+    }
+
+    /// Adds a tag of any type from its raw payload, i.e., everything after the
+    /// tag header. Unlike [`Self::add_custom_tag`], this also accepts types
+    /// defined by the spec, which is useful to forward tags of a boot
+    /// information unchanged, e.g., when chainloading.
+    #[must_use]
+    pub fn add_raw_tag(self, typ: impl Into<TagTypeId>, payload: &[u8]) -> Self {
+        self.insert(BuilderTag::Raw(GenericTag::new(typ, payload)))
+    }
+
+    /// Returns the tags in the order of serialization, excluding the end tag.
+    fn ordered_tags(&self) -> Vec<&BuilderTag> {
+        let mut tags = self.tags.iter().collect::<Vec<_>>();
+        if self.order == TagOrder::ByType {
+            tags.sort_by_key(|tag| u32::from(tag.header().typ));
         }
-        if let Some(tag) = self.efi32.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+        tags
+    }
+
+    /// Returns the exact size in bytes of the boot information that
+    /// [`Self::build`] will produce, including the header, the padding of all
+    /// tags, and the end tag.
+    ///
+    /// This is useful to check upfront if a certain memory region is big
+    /// enough to hold the boot information.
+    #[must_use]
+    pub fn required_size(&self) -> usize {
+        let tags_size = self
+            .tags
+            .iter()
+            .map(|tag| tag.as_bytes().len().next_multiple_of(ALIGNMENT))
+            .sum::<usize>();
+        mem::size_of::<BootInformationHeader>() + tags_size + EndTag::default().as_bytes().len()
+    }
+
+    /// Returns the [`Self::required_size`] and its value for the `u32` size
+    /// field, or an error if the boot information can't be serialized.
+    fn checked_size(&self) -> Result<(usize, u32), BuilderError> {
+        if let Some(typ) = self.rejected {
+            return Err(BuilderError::DuplicateTag(typ.into()));
         }
-        if let Some(tag) = self.efi64.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+        let total_size = self.required_size();
+        let size_field =
+            u32::try_from(total_size).map_err(|_| BuilderError::SizeOverflow(total_size))?;
+        Ok((total_size, size_field))
+    }
+
+    /// Checks the tags for problems that would result in a malformed boot
+    /// information and returns all problems found.
+    ///
+    /// This checks that:
+    /// - there is no explicit end tag, as it is appended automatically,
+    /// - tags that are defined to occur at most once occur at most once,
+    /// - the entry sizes of memory maps are consistent with their entries,
+    /// - strings are NUL-terminated, and
+    /// - the total size fits into the `u32` size field.
+    pub fn validate(&self) -> Result<(), Vec<ValidationProblem>> {
+        let mut problems = Vec::new();
+
+        for (i, tag) in self.tags.iter().enumerate() {
+            let typ = tag.typ();
+            let payload = tag.payload();
+            let is_first_of_type = !self.tags[..i].iter().any(|t| t.typ() == typ);
+            let count = self.tags.iter().filter(|t| t.typ() == typ).count();
+            if typ == TagType::End {
+                if is_first_of_type {
+                    problems.push(ValidationProblem::ExplicitEndTag);
+                }
+                continue;
+            }
+            if !typ.may_repeat() && count > 1 && is_first_of_type {
+                problems.push(ValidationProblem::DuplicateTag(typ));
+            }
+
+            // Offset of the string in the payload, if the tag has one.
+            let string_offset = match typ {
+                TagType::Cmdline | TagType::BootLoaderName => Some(0),
+                TagType::Module => Some(8),
+                _ => None,
+            };
+            if let Some(offset) = string_offset {
+                match payload.get(offset..) {
+                    None => problems.push(ValidationProblem::Truncated(typ)),
+                    Some(string) if !string.contains(&0) => {
+                        problems.push(ValidationProblem::MissingNul(typ));
+                    }
+                    _ => {}
+                }
+            }
+
+            // Minimum entry size of memory maps.
+            let min_entry_size = match typ {
+                TagType::Mmap => Some(mem::size_of::<MemoryArea>()),
+                TagType::EfiMmap => Some(mem::size_of::<EFIMemoryDesc>()),
+                _ => None,
+            };
+            if let Some(min_entry_size) = min_entry_size {
+                if payload.len() < 8 {
+                    problems.push(ValidationProblem::Truncated(typ));
+                } else {
+                    let entry_size =
+                        u32::from_ne_bytes([payload[0], payload[1], payload[2], payload[3]])
+                            as usize;
+                    // The memory map has a fixed entry size, the EFI memory
+                    // map allows bigger descriptors.
+                    let size_ok = if typ == TagType::Mmap {
+                        entry_size == min_entry_size
+                    } else {
+                        entry_size >= min_entry_size
+                    };
+                    if !size_ok || (payload.len() - 8) % entry_size != 0 {
+                        problems.push(ValidationProblem::InconsistentMemoryMap(typ));
+                    }
+                }
+            }
         }
-        for i in &self.smbios {
-            byte_refs.push(i.as_bytes().as_ref());
+
+        if let Some(typ) = self.rejected {
+            problems.push(ValidationProblem::DuplicateTag(typ.into()));
         }
-        if let Some(tag) = self.rsdpv1.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+
+        let size = self.required_size();
+        if u32::try_from(size).is_err() {
+            problems.push(ValidationProblem::TooLarge(size));
         }
-        if let Some(tag) = self.rsdpv2.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
         }
-        if let Some(tag) = self.efi_mmap.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+    }
+
+    /// Serializes the boot information to the physical address `dest_phys`
+    /// by streaming it chunk by chunk to `write`, which receives the physical
+    /// destination address and the bytes to write there. Returns the total
+    /// size of the boot information.
+    ///
+    /// Unlike [`Self::build`], this doesn't need an intermediate buffer for
+    /// the whole boot information. This is useful for loaders and hypervisors
+    /// that place the boot information in guest memory or low memory. See
+    /// [`Self::write_to`] for a variant with fallible writes.
+    ///
+    /// Returns an error without writing anything if the boot information is
+    /// too large for its `u32` size field.
+    ///
+    /// # Panics
+    /// Panics if `dest_phys` is not aligned to an 8-byte boundary, as
+    /// required by the spec.
+    pub fn build_at(
+        &self,
+        dest_phys: u64,
+        write: impl FnMut(u64, &[u8]),
+    ) -> Result<usize, BuilderError> {
+        struct FnWriter<F>(F);
+
+        impl<F: FnMut(u64, &[u8])> GuestMemoryWrite for FnWriter<F> {
+            type Error = Infallible;
+
+            fn write(&mut self, gpa: u64, bytes: &[u8]) -> Result<(), Infallible> {
+                (self.0)(gpa, bytes);
+                Ok(())
+            }
         }
-        if let Some(tag) = self.efi_bs.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+
+        self.write_to(&mut FnWriter(write), dest_phys)
+            .map_err(|e| match e {
+                WriteError::Builder(e) => e,
+                WriteError::Memory(e) => match e {},
+            })
+    }
+
+    /// Serializes the boot information, including all tags and the end tag,
+    /// directly into guest memory at the guest physical address `gpa`.
+    /// Returns the total size of the boot information or the first error.
+    /// Nothing is written if the boot information can't be serialized.
+    ///
+    /// # Panics
+    /// Panics if `gpa` is not aligned to an 8-byte boundary, as required by
+    /// the spec.
+    pub fn write_to<M: GuestMemoryWrite + ?Sized>(
+        &self,
+        mem: &mut M,
+        gpa: u64,
+    ) -> Result<usize, WriteError<M::Error>> {
+        assert_eq!(
+            gpa % ALIGNMENT as u64,
+            0,
+            "The boot information must be 8-byte aligned"
+        );
+        let (total_size, size_field) = self.checked_size().map_err(WriteError::Builder)?;
+
+        let mut offset = 0;
+        let mut write_chunk = |bytes: &[u8]| {
+            mem.write(gpa + offset as u64, bytes)
+                .map_err(WriteError::Memory)?;
+            offset += bytes.len();
+            Ok(())
+        };
+        // Header: total size followed by the reserved field.
+        write_chunk(&size_field.to_ne_bytes())?;
+        write_chunk(&[0; 4])?;
+        for tag in self.ordered_tags() {
+            let bytes = tag.as_bytes();
+            write_chunk(bytes)?;
+            write_chunk(&PADDING[..bytes.len().next_multiple_of(ALIGNMENT) - bytes.len()])?;
         }
-        if let Some(tag) = self.efi32_ih.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+        write_chunk(EndTag::default().as_bytes().as_ref())?;
+
+        debug_assert_eq!(offset, total_size);
+        Ok(total_size)
+    }
+
+    /// Returns properly aligned bytes on the heap representing a valid
+    /// Multiboot2 header structure.
+    ///
+    /// # Panics
+    /// Panics if [`Self::try_build`] fails.
+    #[must_use]
+    pub fn build(self) -> BootInformationBuffer {
+        match self.try_build() {
+            Ok(buffer) => buffer,
+            Err(e) => panic!("Failed to build the boot information: {e}"),
         }
-        if let Some(tag) = self.efi64_ih.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+    }
+
+    /// Like [`Self::build`], but returns an error if the boot information is
+    /// too large for its `u32` size field or if the allocation fails.
+    pub fn try_build(self) -> Result<BootInformationBuffer, BuilderError> {
+        let (total_size, _) = self.checked_size()?;
+        let header = BootInformationHeader::new(0);
+        let end_tag = EndTag::default();
+        let mut byte_refs = Vec::new();
+        for tag in self.ordered_tags() {
+            let bytes = tag.as_bytes();
+            byte_refs.push(bytes);
+            byte_refs.push(&PADDING[..bytes.len().next_multiple_of(ALIGNMENT) - bytes.len()]);
         }
-        if let Some(tag) = self.image_load_addr.as_ref() {
-            byte_refs.push(tag.as_bytes().as_ref());
+        byte_refs.push(end_tag.as_bytes().as_ref());
+        let structure: Box<DynSizedStructure<_>> =
+            try_new_boxed(header, byte_refs.as_slice()).ok_or(BuilderError::OutOfMemory)?;
+        debug_assert_eq!(structure.header().total_size() as usize, total_size);
+        Ok(BootInformationBuffer(structure))
+    }
+}
+
+/// Checks that a tag of the given payload size fits into the `u32` size field
+/// of the [`TagHeader`].
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn check_tag_size(payload_size: usize) -> Result<(), BuilderError> {
+    let total_size = mem::size_of::<TagHeader>().saturating_add(payload_size);
+    match u32::try_from(total_size) {
+        Ok(_) => Ok(()),
+        Err(_) => Err(BuilderError::SizeOverflow(total_size)),
+    }
+}
+
+/// Returns the bytes of a Multiboot2 string without an optional trailing NUL
+/// byte. Fails if the string contains another NUL byte, which would truncate
+/// it.
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn check_string(string: &str) -> Result<&[u8], BuilderError> {
+    let bytes = string.as_bytes();
+    let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+    if bytes.contains(&0) {
+        Err(BuilderError::InvalidString)
+    } else {
+        Ok(bytes)
+    }
+}
+
+/// The boot information created by [`Builder::build`].
+///
+/// The buffer is guaranteed to be 8-byte aligned, as required by the spec,
+/// so it can be passed to a kernel or to [`BootInformation::load`] as is.
+///
+/// [`BootInformation::load`]: crate::BootInformation::load
+#[derive(Debug)]
+pub struct BootInformationBuffer(Box<DynSizedStructure<BootInformationHeader>>);
+
+impl BootInformationBuffer {
+    /// Copies a raw boot information, e.g., received from another machine,
+    /// into a properly aligned buffer. Trailing bytes beyond the reported
+    /// total size are ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LoadError> {
+        let buffer = Self::copy_from(bytes)?;
+        // SAFETY: The buffer is valid and properly aligned.
+        unsafe { BootInformation::load(buffer.as_ptr()) }?;
+        Ok(buffer)
+    }
+
+    /// Like [`Self::from_bytes`], but validates the copy with
+    /// [`BootInformation::load_with_policy`], e.g., to inspect the output of
+    /// imperfect bootloaders with [`ParsePolicy::Lenient`].
+    ///
+    /// [`BootInformation::load_with_policy`]: crate::BootInformation::load_with_policy
+    pub fn from_bytes_with_policy(bytes: &[u8], policy: ParsePolicy) -> Result<Self, LoadError> {
+        let buffer = Self::copy_from(bytes)?;
+        // SAFETY: The buffer is valid and properly aligned.
+        unsafe { BootInformation::load_with_policy(buffer.as_ptr(), policy) }?;
+        Ok(buffer)
+    }
+
+    /// Copies the bytes up to the reported total size without validating the
+    /// tags.
+    fn copy_from(bytes: &[u8]) -> Result<Self, LoadError> {
+        let header_size = mem::size_of::<BootInformationHeader>();
+        let total_size = bytes
+            .get(..4)
+            .map(|size| u32::from_ne_bytes(size.try_into().unwrap()) as usize)
+            .ok_or(LoadError::Memory(MemoryError::ShorterThanHeader))?;
+        if total_size < header_size + mem::size_of::<EndTag>() || total_size > bytes.len() {
+            return Err(LoadError::Memory(MemoryError::InvalidReportedTotalSize));
         }
-        for i in &self.custom_tags {
-            byte_refs.push(i.as_bytes().as_ref());
+        let header = BootInformationHeader::new(0);
+        Ok(Self(new_boxed(header, &[&bytes[header_size..total_size]])))
+    }
+
+    /// Copies the bytes of a valid boot information, including the padding
+    /// to the next 8-byte boundary, word by word into a new buffer.
+    pub(crate) fn from_padded_bytes(bytes: &[u8]) -> Self {
+        let layout = Layout::from_size_align(bytes.len(), ALIGNMENT).unwrap();
+        let ptr = unsafe { alloc::alloc::alloc(layout) };
+        if ptr.is_null() {
+            alloc::alloc::handle_alloc_error(layout);
         }
-        let end_tag = EndTag::default();
-        byte_refs.push(end_tag.as_bytes().as_ref());
-        new_boxed(header, byte_refs.as_slice())
+        // SAFETY: The allocation is 8-byte aligned and as big as `bytes`,
+        // which is 8-byte aligned and padded to a multiple of eight.
+        unsafe { copy_words(bytes.as_ptr(), ptr, bytes.len()) };
+        let total_size = unsafe { &*ptr.cast::<BootInformationHeader>() }.total_size();
+        let payload_len = total_size as usize - mem::size_of::<BootInformationHeader>();
+        let ptr = ptr_meta::from_raw_parts_mut(ptr.cast(), payload_len);
+        Self(unsafe { Box::from_raw(ptr) })
+    }
+
+    /// Returns the raw bytes of the boot information.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        let bytes: &[u8] = self.0.as_bytes().as_ref();
+        &bytes[..self.len()]
+    }
+
+    /// Returns the pointer to the boot information, which is the value a
+    /// bootloader passes to the kernel.
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const BootInformationHeader {
+        self.0.header()
+    }
+
+    /// Returns the total size of the boot information in bytes.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.0.header().total_size() as usize
+    }
+
+    /// Always returns `false`, as the boot information consists at least of
+    /// the header and the end tag.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the underlying heap allocation, e.g., to leak it.
+    #[must_use]
+    pub fn into_boxed(self) -> Box<DynSizedStructure<BootInformationHeader>> {
+        self.0
+    }
+}
+
+impl AsRef<[u8]> for BootInformationBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        BootInformation, FramebufferType, MemoryArea, MemoryAreaType, VBEControlInfo, VBEModeInfo,
-    };
-    use uefi_raw::table::boot::MemoryDescriptor;
+    use crate::EFIMemoryDesc as MemoryDescriptor;
+    use crate::{ApmFlags, BootInformation, MemoryArea, MemoryAreaType, VendorTagId};
+    #[cfg(feature = "vbe")]
+    use crate::{FramebufferType, VBEControlInfo, VBEModeInfo};
+    use alloc::borrow::ToOwned;
+    use multiboot2_common::test_utils::AlignedBytes;
 
     #[test]
+    #[cfg(all(
+        feature = "efi",
+        feature = "elf",
+        feature = "net",
+        feature = "smbios",
+        feature = "vbe"
+    ))]
     fn build_and_parse() {
         let builder = Builder::new()
             .cmdline(CommandLineTag::new("this is a command line"))
             .bootloader(BootLoaderNameTag::new("this is the bootloader"))
-            .add_module(ModuleTag::new(0x1000, 0x2000, "module 1"))
-            .add_module(ModuleTag::new(0x3000, 0x4000, "module 2"))
+            .add_module(0x1000, 0x2000, "module 1")
+            .add_module(0x3000, 0x4000, "module 2")
             .meminfo(BasicMemoryInfoTag::new(0x4000, 0x5000))
             .bootdev(BootdevTag::new(0x00, 0x00, 0x00))
             .mmap(MemoryMapTag::new(&[MemoryArea::new(
@@ -340,7 +904,6 @@ mod tests {
                 VBEControlInfo::default(),
                 VBEModeInfo::default(),
             ))
-            // Currently causes UB.
             .framebuffer(FramebufferTag::new(
                 0x1000,
                 1,
@@ -350,7 +913,7 @@ mod tests {
                 FramebufferType::Text,
             ))
             .elf_sections(ElfSectionsTag::new(0, 32, 0, &[]))
-            .apm(ApmTag::new(0, 0, 0, 0, 0, 0, 0, 0, 0))
+            .apm(ApmTag::new(0, 0, 0, 0, 0, ApmFlags::empty(), 0, 0, 0))
             .efi32(EFISdt32Tag::new(0x1000))
             .efi64(EFISdt64Tag::new(0x1000))
             .add_smbios(SmbiosTag::new(0, 0, &[1, 2, 3]))
@@ -371,12 +934,455 @@ mod tests {
                 &[],
             ));
 
+        let required_size = builder.required_size();
         let structure = builder.build();
+        assert_eq!(structure.len(), required_size);
+        assert_eq!(structure.as_ptr().align_offset(8), 0);
 
         let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
-        for tag in info.tags() {
+        for tag in info.raw_tags() {
             // Mainly a test for Miri.
             dbg!(tag.header(), tag.payload().len());
         }
     }
+
+    /// Round-trip of the fixed-size tags that are rarely emitted by loaders.
+    #[test]
+    fn build_and_parse_legacy_tags() {
+        let apm = ApmTag::new(
+            0x0102,
+            0x10,
+            0x1234,
+            0x18,
+            0x20,
+            ApmFlags::PROTECTED_MODE_32_BIT,
+            0x1000,
+            0x2000,
+            0x3000,
+        );
+        let bootdev = BootdevTag::new(0xe0, BootdevTag::UNUSED, BootdevTag::UNUSED);
+        let load_addr = ImageLoadPhysAddrTag::new(0x200000);
+
+        let structure = Builder::new()
+            .apm(apm)
+            .bootdev(bootdev)
+            .image_load_addr(load_addr)
+            .build();
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        assert_eq!(info.apm_tag(), Some(&apm));
+        assert_eq!(info.bootdev_tag(), Some(&bootdev));
+        assert_eq!(info.load_base_addr_tag(), Some(&load_addr));
+        assert_eq!(info.apm_tag().unwrap().header().size, 28);
+        assert_eq!(info.bootdev_tag().unwrap().header().size, 20);
+        assert_eq!(info.load_base_addr_tag().unwrap().header().size, 12);
+    }
+
+    #[test]
+    fn build_and_parse_raw_tags() {
+        let source = Builder::new()
+            .cmdline(CommandLineTag::new("forwarded"))
+            .add_generic_tag(&GenericTag::new(TagType::Custom(0x1337), b"unknown"))
+            .build();
+        let source = unsafe { BootInformation::load(source.as_bytes().as_ptr().cast()) }.unwrap();
+
+        // Forward all tags of the source boot information.
+        let builder = source
+            .generic_tags()
+            .filter(|tag| tag.typ() != TagType::End)
+            .fold(Builder::new(), |builder, tag| {
+                builder.add_raw_tag(tag.typ(), tag.payload())
+            });
+        let structure = builder.build();
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("forwarded"));
+        assert_eq!(
+            info.get_generic_tag(TagType::Custom(0x1337))
+                .unwrap()
+                .payload(),
+            b"unknown"
+        );
+        assert_eq!(info.total_size(), source.total_size());
+    }
+
+    #[test]
+    fn compare_built_and_parsed_tags() {
+        let cmdline = CommandLineTag::new("cmdline");
+        let module = ModuleTag::new(0x1000, 0x2000, "module");
+        let structure = Builder::new()
+            .cmdline(cmdline.to_owned())
+            .add_module_tag(module.to_owned())
+            .add_module_tag(module.to_owned())
+            .build();
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        assert_eq!(cmdline, info.command_line_tag().unwrap());
+        assert_eq!(info.command_line_tag().unwrap(), cmdline);
+        assert!(info.module_tags().all(|tag| module == tag));
+
+        // Duplicate a parsed tag.
+        let parsed = info.command_line_tag().unwrap().to_owned();
+        assert_eq!(parsed, cmdline);
+    }
+
+    #[test]
+    fn buffer_from_bytes() {
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .build();
+        let mut bytes = mbi.as_bytes().to_vec();
+        bytes.extend([0xff; 3]);
+        let copy = BootInformationBuffer::from_bytes(&bytes[1..][..bytes.len() - 1]);
+        assert!(copy.is_err());
+        let copy = BootInformationBuffer::from_bytes(&bytes).unwrap();
+        assert_eq!(copy.as_bytes(), mbi.as_bytes());
+
+        assert_eq!(
+            BootInformationBuffer::from_bytes(&bytes[..4]).err(),
+            Some(LoadError::Memory(MemoryError::InvalidReportedTotalSize))
+        );
+        assert_eq!(
+            BootInformationBuffer::from_bytes(&bytes[..2]).err(),
+            Some(LoadError::Memory(MemoryError::ShorterThanHeader))
+        );
+
+        // Replace the end tag by an empty custom tag.
+        let end = mbi.len() - mem::size_of::<EndTag>();
+        bytes[end] = 0x37;
+        assert_eq!(
+            BootInformationBuffer::from_bytes(&bytes).err(),
+            Some(LoadError::NoEndTag)
+        );
+        let copy = BootInformationBuffer::from_bytes_with_policy(&bytes, ParsePolicy::Lenient);
+        assert_eq!(copy.unwrap().as_bytes(), &bytes[..mbi.len()]);
+        assert_eq!(
+            BootInformationBuffer::from_bytes_with_policy(&bytes, ParsePolicy::Strict).err(),
+            Some(LoadError::NoEndTag)
+        );
+    }
+
+    #[test]
+    fn copy_to_and_to_buffer() {
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(0x1000, 0x2000, "module")
+            .build();
+        let info = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+
+        let owned = info.to_buffer();
+        assert_eq!(owned.as_bytes(), mbi.as_bytes());
+        assert_ne!(owned.as_ptr(), mbi.as_ptr());
+
+        let mut dst = AlignedBytes([0_u8; 128]);
+        let copy = info.copy_to(&mut dst.0).unwrap();
+        assert_eq!(copy.total_size(), info.total_size());
+        assert_eq!(copy.command_line_tag().unwrap().cmdline(), Ok("cmdline"));
+        assert_eq!(copy.module_tags().count(), 1);
+
+        assert_eq!(
+            info.copy_to(&mut dst.0[..info.total_size() - 8]).err(),
+            Some(LoadError::Memory(MemoryError::InvalidReportedTotalSize))
+        );
+        assert_eq!(
+            info.copy_to(&mut dst.0[4..]).err(),
+            Some(LoadError::Memory(MemoryError::WrongAlignment))
+        );
+    }
+
+    #[test]
+    fn add_modules_keeps_order() {
+        let structure = Builder::new()
+            .add_module(0x1000, 0x2000, "first")
+            .add_modules([(0x3000, 0x4000, "second"), (0x2000, 0x3000, "third")])
+            .cmdline(CommandLineTag::new("cmdline"))
+            .build();
+        let info = unsafe { BootInformation::load(structure.as_ptr()) }.unwrap();
+        let cmdlines = info
+            .module_tags()
+            .map(|module| module.cmdline().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(cmdlines, ["first", "second", "third"]);
+    }
+
+    #[cfg(feature = "smbios")]
+    #[test]
+    fn rebuild_from_boot_information() {
+        let source = Builder::new()
+            .tag_order(TagOrder::Insertion)
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_raw_tag(TagType::Custom(0x1337), b"unknown")
+            .add_module(0x1000, 0x2000, "a")
+            .add_smbios(SmbiosTag::new(3, 0, &[1, 2, 3]))
+            .add_smbios(SmbiosTag::new(3, 0, &[4, 5, 6]))
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0,
+                0x1000,
+                MemoryAreaType::Available,
+            )]))
+            .build();
+        let source_bytes = source.as_bytes();
+        let source = unsafe { BootInformation::load(source.as_ptr()) }.unwrap();
+
+        // Unmodified: byte-for-byte identical.
+        let rebuilt = Builder::from_boot_information(&source).build();
+        assert_eq!(rebuilt.as_bytes(), source_bytes);
+
+        // Modified: patch the memory map and append a module.
+        let rebuilt = Builder::from_boot_information(&source)
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0,
+                0x2000,
+                MemoryAreaType::Available,
+            )]))
+            .add_module(0x3000, 0x4000, "b")
+            .build();
+        let info = unsafe { BootInformation::load(rebuilt.as_ptr()) }.unwrap();
+        let types = info.generic_tags().map(|tag| tag.typ()).collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                TagType::Cmdline,
+                TagType::Custom(0x1337),
+                TagType::Module,
+                TagType::Smbios,
+                TagType::Smbios,
+                TagType::Mmap,
+                TagType::Module,
+                TagType::End,
+            ]
+        );
+        assert_eq!(
+            info.memory_map_tag().unwrap().memory_areas()[0].size(),
+            0x2000
+        );
+    }
+
+    #[test]
+    fn try_build() {
+        let builder = Builder::new().cmdline(CommandLineTag::try_new("cmdline").unwrap());
+        let size = builder.required_size();
+        assert_eq!(builder.try_build().unwrap().len(), size);
+        assert_eq!(
+            check_tag_size(usize::MAX),
+            Err(BuilderError::SizeOverflow(usize::MAX))
+        );
+        assert_eq!(
+            check_tag_size(u32::MAX as usize),
+            Err(BuilderError::SizeOverflow(u32::MAX as usize + 8))
+        );
+        assert_eq!(check_tag_size(u32::MAX as usize - 8), Ok(()));
+    }
+
+    #[test]
+    fn build_at() {
+        let builder = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(0x1000, 0x2000, "module");
+
+        // Simulated guest memory.
+        const GUEST_BASE: u64 = 0x10000;
+        let mut guest = AlignedBytes::new([0_u8; 128]);
+        let size = builder
+            .build_at(GUEST_BASE + 8, |addr, bytes| {
+                let offset = (addr - GUEST_BASE) as usize;
+                guest.0[offset..offset + bytes.len()].copy_from_slice(bytes);
+            })
+            .unwrap();
+        assert_eq!(size, builder.required_size());
+
+        let structure = builder.build();
+        assert_eq!(&guest.0[8..8 + size], structure.as_bytes());
+    }
+
+    #[test]
+    fn write_to_guest_memory() {
+        struct GuestMemory {
+            base: u64,
+            bytes: Vec<u8>,
+        }
+
+        impl GuestMemoryWrite for GuestMemory {
+            type Error = u64;
+
+            fn write(&mut self, gpa: u64, bytes: &[u8]) -> Result<(), u64> {
+                let offset = gpa.checked_sub(self.base).ok_or(gpa)? as usize;
+                self.bytes
+                    .get_mut(offset..offset + bytes.len())
+                    .ok_or(gpa)?
+                    .copy_from_slice(bytes);
+                Ok(())
+            }
+        }
+
+        let builder = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(0x1000, 0x2000, "module");
+        let mut guest = GuestMemory {
+            base: 0x10000,
+            bytes: vec![0; 128],
+        };
+        let size = builder.write_to(&mut guest, 0x10008).unwrap();
+        assert_eq!(&guest.bytes[8..8 + size], builder.build().as_bytes());
+
+        // The boot information doesn't fit.
+        let builder = Builder::new().cmdline(CommandLineTag::new("cmdline"));
+        assert_eq!(
+            builder.write_to(&mut guest, 0x10070),
+            Err(WriteError::Memory(0x10078))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "aligned")]
+    fn build_at_unaligned() {
+        let _ = Builder::new().build_at(0x1004, |_, _| {});
+    }
+
+    #[test]
+    fn validate() {
+        let builder = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(0x1000, 0x2000, "a")
+            .add_module(0x3000, 0x4000, "b")
+            .add_raw_tag(TagType::Network, &[0; 8])
+            .add_raw_tag(TagType::Network, &[0; 8])
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0x1000000,
+                0x1000,
+                MemoryAreaType::Available,
+            )]))
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[
+                MemoryDescriptor::default(),
+                MemoryDescriptor::default(),
+            ]));
+        assert_eq!(builder.validate(), Ok(()));
+
+        let builder = builder
+            .duplicate_policy(TagType::Cmdline, DuplicatePolicy::AllowMultiple)
+            .cmdline(CommandLineTag::new("second"))
+            .add_raw_tag(TagType::BootLoaderName, b"no nul")
+            .add_raw_tag(TagType::Module, &[0, 0])
+            .add_raw_tag(TagType::Mmap, &[24, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3])
+            .add_raw_tag(TagType::End, &[]);
+        assert_eq!(
+            builder.validate(),
+            Err(vec![
+                ValidationProblem::DuplicateTag(TagType::Cmdline),
+                ValidationProblem::InconsistentMemoryMap(TagType::Mmap),
+                ValidationProblem::MissingNul(TagType::BootLoaderName),
+                ValidationProblem::Truncated(TagType::Module),
+                ValidationProblem::ExplicitEndTag,
+            ])
+        );
+    }
+
+    #[test]
+    fn tag_order() {
+        let builder = || {
+            Builder::new()
+                .add_raw_tag(TagType::Custom(0x1337), &[])
+                .meminfo(BasicMemoryInfoTag::new(640, 7 * 1024))
+                .cmdline(CommandLineTag::new("cmdline"))
+        };
+
+        let types = |order| {
+            let structure = builder().tag_order(order).build();
+            let info =
+                unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+            info.generic_tags().map(|tag| tag.typ()).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            types(TagOrder::ByType),
+            [
+                TagType::Cmdline,
+                TagType::BasicMeminfo,
+                TagType::Custom(0x1337),
+                TagType::End
+            ]
+        );
+        assert_eq!(
+            types(TagOrder::Insertion),
+            [
+                TagType::Custom(0x1337),
+                TagType::BasicMeminfo,
+                TagType::Cmdline,
+                TagType::End
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_policy() {
+        let structure = Builder::new()
+            .cmdline(CommandLineTag::new("first"))
+            .cmdline(CommandLineTag::new("second"))
+            .add_module(0x1000, 0x2000, "a")
+            .add_module(0x3000, 0x4000, "b")
+            .build();
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("second"));
+        assert_eq!(info.module_tags().count(), 2);
+
+        let builder = Builder::new()
+            .duplicate_policy(TagType::Cmdline, DuplicatePolicy::AllowMultiple)
+            .duplicate_policy(TagType::Module, DuplicatePolicy::Replace);
+        assert_eq!(
+            builder.policy_of(TagType::Cmdline),
+            DuplicatePolicy::AllowMultiple
+        );
+        assert_eq!(builder.policy_of(TagType::Mmap), DuplicatePolicy::Replace);
+        assert_eq!(
+            builder.policy_of(TagType::Network),
+            DuplicatePolicy::AllowMultiple
+        );
+        let structure = builder
+            .cmdline(CommandLineTag::new("first"))
+            .cmdline(CommandLineTag::new("second"))
+            .add_module(0x1000, 0x2000, "a")
+            .add_module(0x3000, 0x4000, "b")
+            .build();
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("first"));
+        assert_eq!(info.module_tags().count(), 1);
+        assert_eq!(info.module_tags().next().unwrap().cmdline(), Ok("b"));
+    }
+
+    #[test]
+    fn duplicate_policy_reject() {
+        let builder = Builder::new()
+            .duplicate_policy(TagType::Cmdline, DuplicatePolicy::Reject)
+            .cmdline(CommandLineTag::new("first"))
+            .cmdline(CommandLineTag::new("second"));
+        assert_eq!(
+            builder.validate(),
+            Err(vec![ValidationProblem::DuplicateTag(TagType::Cmdline)])
+        );
+        assert_eq!(
+            builder.build_at(0, |_, _| {}),
+            Err(BuilderError::DuplicateTag(TagType::Cmdline))
+        );
+        assert_eq!(
+            builder.try_build().unwrap_err(),
+            BuilderError::DuplicateTag(TagType::Cmdline)
+        );
+    }
+
+    #[test]
+    fn build_and_parse_generic_tags() {
+        let vendor_id = VendorTagId::new(0x1337, 1);
+        let structure = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_generic_tag(&GenericTag::new(vendor_id, b"hello"))
+            .build();
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+
+        let tag = info.get_generic_tag(vendor_id).unwrap();
+        assert_eq!(tag.payload(), b"hello");
+        assert_eq!(tag.vendor_id(), Some(vendor_id));
+        assert_eq!(
+            info.generic_tags().map(|tag| tag.typ()).collect::<Vec<_>>(),
+            [TagType::Cmdline, vendor_id.into(), TagType::End]
+        );
+    }
 }
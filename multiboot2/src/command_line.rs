@@ -1,13 +1,19 @@
 //! Module for [`CommandLineTag`].
 
 use crate::tag::TagHeader;
-use crate::{parse_slice_as_string, StringError, TagType};
+use crate::util::string_bytes;
+use crate::{parse_slice_as_string, parse_slice_as_string_lenient, StringError, TagType};
 use core::fmt::{Debug, Formatter};
 use core::mem;
 use core::str;
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, multiboot2_common::new_boxed};
+use {
+    crate::builder::{check_string, check_tag_size},
+    crate::BuilderError,
+    alloc::boxed::Box,
+    multiboot2_common::{new_boxed, try_new_boxed},
+};
 
 /// This tag contains the command line string.
 ///
@@ -35,6 +41,17 @@ impl CommandLineTag {
         }
     }
 
+    /// Like [`Self::new`], but returns an error instead of panicking or
+    /// producing a malformed tag if the string contains a NUL byte before its
+    /// end, if it is too long, or if the allocation fails.
+    #[cfg(feature = "builder")]
+    pub fn try_new(command_line: &str) -> Result<Box<Self>, BuilderError> {
+        let bytes = check_string(command_line)?;
+        check_tag_size(bytes.len() + 1)?;
+        let header = TagHeader::new(Self::ID, 0);
+        try_new_boxed(header, &[bytes, &[0]]).ok_or(BuilderError::OutOfMemory)
+    }
+
     /// Reads the command line of the kernel as Rust string slice without
     /// the null-byte.
     ///
@@ -57,6 +74,21 @@ impl CommandLineTag {
     pub fn cmdline(&self) -> Result<&str, StringError> {
         parse_slice_as_string(&self.cmdline)
     }
+
+    /// Like [`Self::cmdline`], but treats the whole payload as the command
+    /// line if the terminating NUL is missing, instead of returning
+    /// [`StringError::MissingNul`].
+    pub fn cmdline_lenient(&self) -> Result<&str, StringError> {
+        parse_slice_as_string_lenient(&self.cmdline)
+    }
+
+    /// Returns the raw bytes of the command line without the terminating NUL and
+    /// without checking for valid UTF-8. If the NUL is missing, this is the
+    /// whole payload.
+    #[must_use]
+    pub fn cmdline_bytes(&self) -> &[u8] {
+        string_bytes(&self.cmdline)
+    }
 }
 
 impl Debug for CommandLineTag {
@@ -75,8 +107,11 @@ impl MaybeDynSized for CommandLineTag {
     const BASE_SIZE: usize = mem::size_of::<TagHeader>();
 
     fn dst_len(header: &TagHeader) -> usize {
-        assert!(header.size as usize >= Self::BASE_SIZE);
-        header.size as usize - Self::BASE_SIZE
+        Self::try_dst_len(header).expect("tag should be at least BASE_SIZE bytes")
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
     }
 }
 
@@ -89,12 +124,12 @@ impl Tag for CommandLineTag {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::GenericInfoTag;
+    use crate::GenericTag;
     use core::borrow::Borrow;
     use multiboot2_common::test_utils::AlignedBytes;
 
     #[rustfmt::skip]
-    fn get_bytes() -> AlignedBytes<16> {
+    const fn get_bytes() -> AlignedBytes<16> {
         AlignedBytes::new([
             TagType::Cmdline.val() as u8, 0, 0, 0,
             14, 0, 0, 0,
@@ -108,12 +143,37 @@ mod tests {
     #[test]
     fn test_parse_str() {
         let bytes = get_bytes();
-        let tag = GenericInfoTag::ref_from_slice(bytes.borrow()).unwrap();
-        let tag = tag.cast::<CommandLineTag>();
+        let tag = GenericTag::ref_from_slice(bytes.borrow()).unwrap();
+        let tag = tag.downcast::<CommandLineTag>().unwrap();
         assert_eq!(tag.header.typ, TagType::Cmdline);
         assert_eq!(tag.cmdline(), Ok("hello"));
     }
 
+    /// Tests a tag whose string lacks the terminating null byte.
+    #[test]
+    fn test_parse_str_missing_nul() {
+        let mut bytes = get_bytes();
+        // The size excludes the null byte, so the padding is not considered.
+        bytes.0[4] = 13;
+        let tag = GenericTag::ref_from_slice(bytes.borrow()).unwrap();
+        let tag = tag.downcast::<CommandLineTag>().unwrap();
+        assert!(matches!(tag.cmdline(), Err(StringError::MissingNul(_))));
+        assert_eq!(tag.cmdline_lenient(), Ok("hello"));
+        assert_eq!(tag.cmdline_bytes(), b"hello");
+    }
+
+    /// Tests a tag that is too small to contain a string.
+    #[test]
+    fn test_parse_str_truncated() {
+        let mut bytes = get_bytes();
+        bytes.0[4] = 8;
+        let tag = GenericTag::ref_from_slice(&bytes.0[..8]).unwrap();
+        let tag = tag.downcast::<CommandLineTag>().unwrap();
+        assert_eq!(tag.cmdline(), Err(StringError::Truncated));
+        assert_eq!(tag.cmdline_lenient(), Ok(""));
+        assert_eq!(tag.cmdline_bytes(), b"");
+    }
+
     /// Test to generate a tag from a given string.
     #[test]
     #[cfg(feature = "builder")]
@@ -137,4 +197,21 @@ mod tests {
         let tag = CommandLineTag::new("AbCdEfGhUjK YEAH".repeat(42).as_str());
         assert_eq!(tag.cmdline(), Ok("AbCdEfGhUjK YEAH".repeat(42).as_str()));
     }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_try_new() {
+        assert_eq!(
+            CommandLineTag::try_new("hello").unwrap(),
+            CommandLineTag::new("hello")
+        );
+        assert_eq!(
+            CommandLineTag::try_new("hello\0").unwrap(),
+            CommandLineTag::new("hello")
+        );
+        assert_eq!(
+            CommandLineTag::try_new("hel\0lo"),
+            Err(crate::BuilderError::InvalidString)
+        );
+    }
 }
@@ -0,0 +1,275 @@
+//! Module for [`E820Entry`].
+
+use crate::free_memory::{efi_is_available, EFI_PAGE_SIZE};
+use crate::{BootInformation, EFIMemoryAreaType, EFIMemoryDesc, MemoryArea, MemoryAreaType};
+use alloc::vec::Vec;
+
+/// An entry of the memory map returned by the BIOS function INT 15h,
+/// AX=E820h, as written by the caller's real-mode stub.
+///
+/// The BIOS output may be unordered and contain overlapping or empty entries.
+/// Use [`E820Entry::to_memory_areas`] to sanitize it.
+///
+/// For kernels and tools that still speak E820, the memory map of a boot
+/// information can be exported with [`BootInformation::e820_entries`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(C, packed)]
+pub struct E820Entry {
+    base_addr: u64,
+    length: u64,
+    typ: u32,
+}
+
+impl E820Entry {
+    /// Constructs a new entry.
+    #[must_use]
+    pub const fn new(base_addr: u64, length: u64, typ: u32) -> Self {
+        Self {
+            base_addr,
+            length,
+            typ,
+        }
+    }
+
+    /// Returns the base address.
+    #[must_use]
+    pub const fn base_addr(&self) -> u64 {
+        self.base_addr
+    }
+
+    /// Returns the length.
+    #[must_use]
+    pub const fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Returns the raw E820 type. The types 1 to 5 match the Multiboot2
+    /// memory area types.
+    #[must_use]
+    pub const fn typ(&self) -> u32 {
+        self.typ
+    }
+
+    /// Returns the Multiboot2 memory area type. Unknown types are reported as
+    /// reserved, like GRUB does.
+    #[must_use]
+    pub const fn area_type(&self) -> MemoryAreaType {
+        match self.typ {
+            1 => MemoryAreaType::Available,
+            3 => MemoryAreaType::AcpiAvailable,
+            4 => MemoryAreaType::ReservedHibernate,
+            5 => MemoryAreaType::Defective,
+            _ => MemoryAreaType::Reserved,
+        }
+    }
+
+    /// Converts an EFI memory descriptor into an entry for a kernel that exited
+    /// the boot services: boot services memory becomes available memory.
+    /// Loader memory stays reserved, as the bootloader may have placed the
+    /// kernel, the modules, or the boot information there.
+    #[must_use]
+    pub fn from_efi_desc(desc: &EFIMemoryDesc) -> Self {
+        let typ = match desc.ty {
+            ty if efi_is_available(ty) => MemoryAreaType::Available,
+            EFIMemoryAreaType::ACPI_RECLAIM => MemoryAreaType::AcpiAvailable,
+            EFIMemoryAreaType::ACPI_NON_VOLATILE => MemoryAreaType::ReservedHibernate,
+            EFIMemoryAreaType::UNUSABLE => MemoryAreaType::Defective,
+            _ => MemoryAreaType::Reserved,
+        };
+        Self::from_memory_area(&MemoryArea::new(
+            desc.phys_start,
+            desc.page_count.saturating_mul(EFI_PAGE_SIZE),
+            typ,
+        ))
+    }
+
+    /// Converts a Multiboot2 memory area into an entry.
+    #[must_use]
+    pub fn from_memory_area(area: &MemoryArea) -> Self {
+        Self::new(area.start_address(), area.size(), area.typ().into())
+    }
+
+    /// Returns the exclusive end address, saturated at `u64::MAX`.
+    const fn end_addr(&self) -> u64 {
+        self.base_addr.saturating_add(self.length)
+    }
+
+    /// Converts the raw E820 output into a sorted memory map without
+    /// overlaps, like GRUB does:
+    ///
+    /// - empty entries are dropped,
+    /// - where entries overlap, the more restrictive type wins, i.e., available
+    ///   memory has the lowest and defective memory the highest priority, and
+    /// - adjacent areas of the same type are merged.
+    #[must_use]
+    pub fn to_memory_areas(entries: &[Self]) -> Vec<MemoryArea> {
+        let priority = |typ: MemoryAreaType| match typ {
+            MemoryAreaType::Available => 1,
+            MemoryAreaType::AcpiAvailable => 2,
+            MemoryAreaType::Defective => 4,
+            _ => 3,
+        };
+
+        let mut bounds = entries
+            .iter()
+            .filter(|entry| entry.length != 0)
+            .flat_map(|entry| [entry.base_addr, entry.end_addr()])
+            .collect::<Vec<_>>();
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        let mut areas = Vec::<MemoryArea>::new();
+        for window in bounds.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            let typ = entries
+                .iter()
+                .filter(|entry| entry.base_addr <= start && end <= entry.end_addr())
+                .map(Self::area_type)
+                .max_by_key(|&typ| priority(typ));
+            let Some(typ) = typ else {
+                // Hole in the memory map.
+                continue;
+            };
+            match areas.last_mut() {
+                Some(last)
+                    if last.end_address() == start && MemoryAreaType::from(last.typ()) == typ =>
+                {
+                    *last = MemoryArea::new(last.start_address(), end - last.start_address(), typ);
+                }
+                _ => areas.push(MemoryArea::new(start, end - start, typ)),
+            }
+        }
+        areas
+    }
+}
+
+impl BootInformation<'_> {
+    /// Exports the memory map as classic E820 table, for kernels bridging to
+    /// Linux-style subsystems or kexec-like tooling.
+    ///
+    /// The EFI memory map is preferred if the boot services were exited.
+    /// Otherwise, the Multiboot2 memory map is used. Returns `None` if there
+    /// is neither. The entries are sanitized by
+    /// [`E820Entry::to_memory_areas`], so the table is sorted, without
+    /// overlaps, and unknown types are reported as reserved.
+    #[must_use]
+    pub fn e820_entries(&self) -> Option<Vec<E820Entry>> {
+        let entries = if let Some(tag) = self.efi_memory_map_tag() {
+            tag.memory_areas()
+                .map(|desc| E820Entry::from_efi_desc(&desc))
+                .collect::<Vec<_>>()
+        } else {
+            self.memory_map_tag()?
+                .memory_areas()
+                .iter()
+                .map(E820Entry::from_memory_area)
+                .collect::<Vec<_>>()
+        };
+        let areas = E820Entry::to_memory_areas(&entries);
+        Some(areas.iter().map(E820Entry::from_memory_area).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BasicMemoryInfoTag, Builder, EFIMemoryMapTag, MemoryMapTag};
+
+    #[test]
+    fn test_to_memory_areas() {
+        let entries = [
+            // Unordered.
+            E820Entry::new(0x100000, 0x7f00000, 1),
+            E820Entry::new(0x0, 0x9fc00, 1),
+            E820Entry::new(0x9fc00, 0x400, 2),
+            E820Entry::new(0xf0000, 0x10000, 2),
+            // Empty.
+            E820Entry::new(0x500000, 0, 2),
+            // Overlapping with available memory.
+            E820Entry::new(0x7fe0000, 0x20000, 3),
+            // Adjacent to the previous one.
+            E820Entry::new(0x8000000, 0x1000, 3),
+            // Unknown type.
+            E820Entry::new(0xfffc0000, 0x40000, 12),
+        ];
+        let areas = E820Entry::to_memory_areas(&entries);
+        assert_eq!(
+            areas,
+            [
+                MemoryArea::new(0x0, 0x9fc00, MemoryAreaType::Available),
+                MemoryArea::new(0x9fc00, 0x400, MemoryAreaType::Reserved),
+                MemoryArea::new(0xf0000, 0x10000, MemoryAreaType::Reserved),
+                MemoryArea::new(0x100000, 0x7ee0000, MemoryAreaType::Available),
+                MemoryArea::new(0x7fe0000, 0x21000, MemoryAreaType::AcpiAvailable),
+                MemoryArea::new(0xfffc0000, 0x40000, MemoryAreaType::Reserved),
+            ]
+        );
+
+        let meminfo = BasicMemoryInfoTag::from_memory_areas(&areas);
+        assert_eq!(meminfo.memory_lower(), 639);
+        assert_eq!(meminfo.memory_upper(), 0x7ee0000 / 1024);
+    }
+
+    #[test]
+    fn test_e820_entries() {
+        let mbi = Builder::new()
+            .mmap(MemoryMapTag::new(&[
+                MemoryArea::new(0x100000, 0x1000, MemoryAreaType::Available),
+                MemoryArea::new(0x0, 0x9fc00, MemoryAreaType::Available),
+                MemoryArea::new(0x101000, 0x1000, MemoryAreaType::Available),
+                MemoryArea::new(0x9fc00, 0x400, MemoryAreaType::Custom(12)),
+            ]))
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        assert_eq!(
+            mbi.e820_entries().unwrap(),
+            [
+                E820Entry::new(0x0, 0x9fc00, 1),
+                E820Entry::new(0x9fc00, 0x400, 2),
+                E820Entry::new(0x100000, 0x2000, 1),
+            ]
+        );
+
+        let mbi = Builder::new()
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[
+                EFIMemoryDesc {
+                    ty: EFIMemoryAreaType::CONVENTIONAL,
+                    phys_start: 0x1000,
+                    page_count: 1,
+                    ..Default::default()
+                },
+                EFIMemoryDesc {
+                    ty: EFIMemoryAreaType::BOOT_SERVICES_DATA,
+                    phys_start: 0x2000,
+                    page_count: 2,
+                    ..Default::default()
+                },
+                EFIMemoryDesc {
+                    ty: EFIMemoryAreaType::ACPI_NON_VOLATILE,
+                    phys_start: 0x4000,
+                    page_count: 1,
+                    ..Default::default()
+                },
+                EFIMemoryDesc {
+                    ty: EFIMemoryAreaType::LOADER_DATA,
+                    phys_start: 0x5000,
+                    page_count: 1,
+                    ..Default::default()
+                },
+            ]))
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        assert_eq!(
+            mbi.e820_entries().unwrap(),
+            [
+                E820Entry::new(0x1000, 0x3000, 1),
+                E820Entry::new(0x4000, 0x1000, 4),
+                E820Entry::new(0x5000, 0x1000, 2),
+            ]
+        );
+
+        let mbi = Builder::new().build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        assert_eq!(mbi.e820_entries(), None);
+    }
+}
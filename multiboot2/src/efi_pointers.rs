@@ -0,0 +1,251 @@
+//! All tags that carry the pointers to the EFI system table and to the
+//! UEFI image handle of the bootloader:
+//!
+//! - [`EFISdt32Tag`]
+//! - [`EFISdt64Tag`]
+//! - [`EFIImageHandle32Tag`]
+//! - [`EFIImageHandle64Tag`]
+
+use crate::tag::{parse_sized_tag, TagHeader, TagParseError};
+use crate::TagType;
+use core::mem::size_of;
+use multiboot2_common::{MaybeDynSized, Tag};
+
+/// EFI system table in 32 bit mode tag.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C, align(8))]
+pub struct EFISdt32Tag {
+    header: TagHeader,
+    pointer: u32,
+}
+
+impl EFISdt32Tag {
+    const BASE_SIZE: usize = size_of::<TagHeader>() + size_of::<u32>();
+
+    /// Create a new tag to pass the EFI32 System Table pointer.
+    #[must_use]
+    pub const fn new(pointer: u32) -> Self {
+        Self {
+            header: TagHeader::new_const(Self::ID, Self::BASE_SIZE as u32),
+            pointer,
+        }
+    }
+
+    /// Parses the tag by value from unaligned bytes that include its padding.
+    pub const fn from_bytes(bytes: &[u8]) -> Result<Self, TagParseError> {
+        parse_sized_tag(bytes)
+    }
+
+    /// The physical address of a i386 EFI system table.
+    #[must_use]
+    pub const fn sdt_address(&self) -> usize {
+        self.pointer as usize
+    }
+}
+
+impl MaybeDynSized for EFISdt32Tag {
+    type Header = TagHeader;
+
+    const BASE_SIZE: usize = size_of::<Self>();
+
+    fn dst_len(_: &TagHeader) {}
+}
+
+impl Tag for EFISdt32Tag {
+    type IDType = TagType;
+
+    const ID: TagType = TagType::Efi32;
+}
+
+/// EFI system table in 64 bit mode tag.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C, align(8))]
+pub struct EFISdt64Tag {
+    header: TagHeader,
+    pointer: u64,
+}
+
+impl EFISdt64Tag {
+    /// Create a new tag to pass the EFI64 System Table pointer.
+    #[must_use]
+    pub const fn new(pointer: u64) -> Self {
+        Self {
+            header: TagHeader::new_const(Self::ID, size_of::<Self>() as u32),
+            pointer,
+        }
+    }
+
+    /// Parses the tag by value from unaligned bytes that include its padding.
+    pub const fn from_bytes(bytes: &[u8]) -> Result<Self, TagParseError> {
+        parse_sized_tag(bytes)
+    }
+
+    /// The physical address of a x86_64 EFI system table.
+    #[must_use]
+    pub const fn sdt_address(&self) -> usize {
+        self.pointer as usize
+    }
+}
+
+impl MaybeDynSized for EFISdt64Tag {
+    type Header = TagHeader;
+
+    const BASE_SIZE: usize = size_of::<Self>();
+
+    fn dst_len(_: &TagHeader) {}
+}
+
+impl Tag for EFISdt64Tag {
+    type IDType = TagType;
+
+    const ID: TagType = TagType::Efi64;
+}
+
+/// Tag that contains the pointer to the boot loader's UEFI image handle
+/// (32-bit).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C, align(8))]
+pub struct EFIImageHandle32Tag {
+    header: TagHeader,
+    pointer: u32,
+}
+
+impl EFIImageHandle32Tag {
+    const BASE_SIZE: usize = size_of::<TagHeader>() + size_of::<u32>();
+
+    /// Constructs a new tag.
+    #[must_use]
+    pub const fn new(pointer: u32) -> Self {
+        Self {
+            header: TagHeader::new_const(Self::ID, Self::BASE_SIZE as u32),
+            pointer,
+        }
+    }
+
+    /// Parses the tag by value from unaligned bytes that include its padding.
+    pub const fn from_bytes(bytes: &[u8]) -> Result<Self, TagParseError> {
+        parse_sized_tag(bytes)
+    }
+
+    /// Returns the physical address of the EFI image handle.
+    #[must_use]
+    pub const fn image_handle(&self) -> usize {
+        self.pointer as usize
+    }
+}
+
+impl MaybeDynSized for EFIImageHandle32Tag {
+    type Header = TagHeader;
+
+    const BASE_SIZE: usize = size_of::<Self>();
+
+    fn dst_len(_: &TagHeader) {}
+}
+
+impl Tag for EFIImageHandle32Tag {
+    type IDType = TagType;
+
+    const ID: TagType = TagType::Efi32Ih;
+}
+
+/// Tag that contains the pointer to the boot loader's UEFI image handle
+/// (64-bit).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C, align(8))]
+pub struct EFIImageHandle64Tag {
+    header: TagHeader,
+    pointer: u64,
+}
+
+impl EFIImageHandle64Tag {
+    /// Constructs a new tag.
+    #[must_use]
+    pub const fn new(pointer: u64) -> Self {
+        Self {
+            header: TagHeader::new_const(Self::ID, size_of::<Self>() as u32),
+            pointer,
+        }
+    }
+
+    /// Parses the tag by value from unaligned bytes that include its padding.
+    pub const fn from_bytes(bytes: &[u8]) -> Result<Self, TagParseError> {
+        parse_sized_tag(bytes)
+    }
+
+    /// Returns the physical address of the EFI image handle.
+    #[must_use]
+    pub const fn image_handle(&self) -> usize {
+        self.pointer as usize
+    }
+}
+
+impl MaybeDynSized for EFIImageHandle64Tag {
+    type Header = TagHeader;
+
+    const BASE_SIZE: usize = size_of::<Self>();
+
+    fn dst_len(_: &TagHeader) {}
+}
+
+impl Tag for EFIImageHandle64Tag {
+    type IDType = TagType;
+
+    const ID: TagType = TagType::Efi64Ih;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDR: usize = 0xABCDEF;
+
+    #[test]
+    fn test_build_eftsdt32() {
+        let tag = EFISdt32Tag::new(ADDR.try_into().unwrap());
+        assert_eq!(tag.sdt_address(), ADDR);
+    }
+
+    #[test]
+    fn test_build_eftsdt64() {
+        let tag = EFISdt64Tag::new(ADDR.try_into().unwrap());
+        assert_eq!(tag.sdt_address(), ADDR);
+    }
+
+    #[test]
+    fn test_build_eftih32() {
+        let tag = EFIImageHandle32Tag::new(ADDR.try_into().unwrap());
+        assert_eq!(tag.image_handle(), ADDR);
+    }
+
+    #[test]
+    fn test_build_eftih64() {
+        let tag = EFIImageHandle64Tag::new(ADDR.try_into().unwrap());
+        assert_eq!(tag.image_handle(), ADDR);
+    }
+
+    #[test]
+    fn test_from_bytes() {
+        use multiboot2_common::MaybeDynSized;
+
+        let sdt32 = EFISdt32Tag::new(0xABCDEF);
+        assert_eq!(
+            EFISdt32Tag::from_bytes(sdt32.as_bytes().as_ref()),
+            Ok(sdt32)
+        );
+        let sdt64 = EFISdt64Tag::new(0xABCDEF);
+        assert_eq!(
+            EFISdt64Tag::from_bytes(sdt64.as_bytes().as_ref()),
+            Ok(sdt64)
+        );
+        let ih32 = EFIImageHandle32Tag::new(0xABCDEF);
+        assert_eq!(
+            EFIImageHandle32Tag::from_bytes(ih32.as_bytes().as_ref()),
+            Ok(ih32)
+        );
+        let ih64 = EFIImageHandle64Tag::new(0xABCDEF);
+        assert_eq!(
+            EFIImageHandle64Tag::from_bytes(ih64.as_bytes().as_ref()),
+            Ok(ih64)
+        );
+    }
+}
@@ -1,10 +1,11 @@
 //! Module for [`ElfSectionsTag`].
 
-use crate::{TagHeader, TagType};
+use crate::util::parse_fixed_string;
+use crate::{StringError, TagHeader, TagType};
+use bitflags::bitflags;
 use core::fmt::{Debug, Formatter};
 use core::marker::PhantomData;
-use core::mem;
-use core::str::Utf8Error;
+use core::{mem, ptr};
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
 use {alloc::boxed::Box, multiboot2_common::new_boxed};
@@ -39,13 +40,28 @@ impl ElfSectionsTag {
 
     /// Get an iterator of loaded ELF sections.
     #[must_use]
-    pub(crate) const fn sections_iter(&self) -> ElfSectionIter {
-        let string_section_offset = (self.shndx * self.entry_size) as isize;
-        let string_section_ptr =
-            unsafe { self.sections.as_ptr().offset(string_section_offset) as *const _ };
+    pub(crate) const fn sections_iter(&self) -> ElfSectionIter<'_> {
+        // Never point beyond the payload, even if the reported number of
+        // sections or the string table index are too large. Unknown entry
+        // sizes yield no sections at all.
+        let entry_size = self.entry_size as usize;
+        let available_sections = match entry_size {
+            40 | 64 => self.sections.len() / entry_size,
+            _ => 0,
+        };
+        let remaining_sections = if (self.number_of_sections as usize) < available_sections {
+            self.number_of_sections
+        } else {
+            available_sections as u32
+        };
+        let string_section_ptr = if (self.shndx as usize) < available_sections {
+            unsafe { self.sections.as_ptr().add(self.shndx as usize * entry_size) }
+        } else {
+            ptr::null()
+        };
         ElfSectionIter {
             current_section: self.sections.as_ptr(),
-            remaining_sections: self.number_of_sections,
+            remaining_sections,
             entry_size: self.entry_size,
             string_section: string_section_ptr,
             _phantom_data: PhantomData,
@@ -77,8 +93,11 @@ impl MaybeDynSized for ElfSectionsTag {
     const BASE_SIZE: usize = mem::size_of::<TagHeader>() + 3 * mem::size_of::<u32>();
 
     fn dst_len(header: &TagHeader) -> usize {
-        assert!(header.size as usize >= Self::BASE_SIZE);
-        header.size as usize - Self::BASE_SIZE
+        Self::try_dst_len(header).expect("tag should be at least BASE_SIZE bytes")
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
     }
 }
 
@@ -123,7 +142,7 @@ impl<'a> Iterator for ElfSectionIter<'a> {
                 _phantom: PhantomData,
             };
 
-            self.current_section = unsafe { self.current_section.offset(self.entry_size as isize) };
+            self.current_section = unsafe { self.current_section.add(self.entry_size as usize) };
             self.remaining_sections -= 1;
 
             if section.section_type() != ElfSectionType::Unused {
@@ -136,11 +155,7 @@ impl<'a> Iterator for ElfSectionIter<'a> {
 
 impl<'a> Debug for ElfSectionIter<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        let mut debug = f.debug_list();
-        self.clone().for_each(|ref e| {
-            debug.entry(e);
-        });
-        debug.finish()
+        f.debug_list().entries(self.clone()).finish()
     }
 }
 
@@ -219,9 +234,21 @@ impl<'a> ElfSection<'a> {
     }
 
     /// Read the name of the section.
-    pub fn name(&self) -> Result<&str, Utf8Error> {
-        use core::{slice, str};
+    ///
+    /// Returns an empty name if the string table index of the tag doesn't
+    /// refer to a section within the tag.
+    pub fn name(&self) -> Result<&str, StringError> {
+        parse_fixed_string(self.name_bytes())
+    }
 
+    /// The raw bytes of [`Self::name`], without the terminating NUL.
+    #[must_use]
+    pub fn name_bytes(&self) -> &[u8] {
+        use core::slice;
+
+        if self.string_section.is_null() {
+            return &[];
+        }
         let name_ptr = unsafe { self.string_table().offset(self.get().name_index() as isize) };
 
         // strlen without null byte
@@ -233,7 +260,7 @@ impl<'a> ElfSection<'a> {
             len as usize
         };
 
-        str::from_utf8(unsafe { slice::from_raw_parts(name_ptr, strlen) })
+        unsafe { slice::from_raw_parts(name_ptr, strlen) }
     }
 
     /// Get the physical start address of the section.
@@ -0,0 +1,286 @@
+//! `extern "C"` interface for C kernels and mixed-language projects.
+//!
+//! The functions take the pointer to the boot information as handle and
+//! validate it on every call, so no state needs to be kept on the C side.
+//! The corresponding C header is `include/multiboot2.h`, which can be
+//! regenerated with `cbindgen`.
+
+use crate::{BootInformation, BootInformationHeader, LoadError, StringError};
+use core::ffi::c_char;
+use multiboot2_common::MemoryError;
+
+/// Result of [`mb2_parse`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub enum Mb2Status {
+    /// The boot information is valid.
+    Ok = 0,
+    /// The pointer is null.
+    Null = -1,
+    /// The pointer is not 8-byte aligned.
+    WrongAlignment = -2,
+    /// The memory is shorter than the header.
+    ShorterThanHeader = -3,
+    /// The terminating padding is missing.
+    MissingPadding = -4,
+    /// The reported total size is invalid.
+    InvalidReportedTotalSize = -5,
+    /// The mandatory end tag is missing.
+    NoEndTag = -6,
+    /// A tag overlaps with the next one or exceeds the total size.
+    InvalidLayout = -7,
+    /// The boot information has too many tags or a tag is too big.
+    LimitExceeded = -8,
+    /// A tag type that must occur at most once occurs multiple times.
+    DuplicateTag = -9,
+}
+
+impl From<LoadError> for Mb2Status {
+    fn from(value: LoadError) -> Self {
+        match value {
+            LoadError::Memory(MemoryError::Null) => Self::Null,
+            LoadError::Memory(MemoryError::WrongAlignment) => Self::WrongAlignment,
+            LoadError::Memory(MemoryError::ShorterThanHeader) => Self::ShorterThanHeader,
+            LoadError::Memory(MemoryError::MissingPadding) => Self::MissingPadding,
+            LoadError::Memory(MemoryError::InvalidReportedTotalSize) => {
+                Self::InvalidReportedTotalSize
+            }
+            LoadError::NoEndTag => Self::NoEndTag,
+            LoadError::Layout(_) => Self::InvalidLayout,
+            LoadError::LimitExceeded(_) => Self::LimitExceeded,
+            LoadError::DuplicateTag { .. } => Self::DuplicateTag,
+        }
+    }
+}
+
+/// A memory area of the memory map.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct Mb2MemoryArea {
+    /// Physical start address.
+    pub base_addr: u64,
+    /// Length in bytes.
+    pub length: u64,
+    /// Type of the memory, see [`crate::MemoryAreaType`].
+    pub typ: u32,
+}
+
+/// A module loaded by the bootloader.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct Mb2Module {
+    /// Start address of the module.
+    pub start: u32,
+    /// End address of the module.
+    pub end: u32,
+    /// Command line of the module, not NUL-terminated. Null if it isn't
+    /// valid UTF-8.
+    pub cmdline: *const c_char,
+    /// Length of the command line in bytes.
+    pub cmdline_len: usize,
+}
+
+/// Loads the boot information or returns `None` for invalid input.
+unsafe fn load<'a>(mbi: *const BootInformationHeader) -> Option<BootInformation<'a>> {
+    BootInformation::load_checked(mbi).ok()
+}
+
+/// Writes the length of the string and returns its pointer, or null.
+unsafe fn string_out(string: Option<Result<&str, StringError>>, len: *mut usize) -> *const c_char {
+    let string = string.and_then(Result::ok);
+    if let Some(len) = len.as_mut() {
+        *len = string.map_or(0, str::len);
+    }
+    string.map_or(core::ptr::null(), |string| string.as_ptr().cast())
+}
+
+/// Validates the boot information.
+///
+/// # Safety
+/// `mbi` must be null or valid for reading.
+#[no_mangle]
+pub unsafe extern "C" fn mb2_parse(mbi: *const BootInformationHeader) -> Mb2Status {
+    BootInformation::load_checked(mbi).map_or_else(Mb2Status::from, |_| Mb2Status::Ok)
+}
+
+/// Returns the kernel command line, not NUL-terminated, and writes its
+/// length to `len`, if `len` isn't null. Returns null if there is no valid
+/// command line.
+///
+/// # Safety
+/// `mbi` must be null or valid for reading. `len` must be null or valid for
+/// writing.
+#[no_mangle]
+pub unsafe extern "C" fn mb2_cmdline(
+    mbi: *const BootInformationHeader,
+    len: *mut usize,
+) -> *const c_char {
+    let info = load(mbi);
+    let cmdline = info
+        .as_ref()
+        .and_then(|info| info.command_line_tag())
+        .map(|tag| tag.cmdline());
+    string_out(cmdline, len)
+}
+
+/// Returns the name of the bootloader, like [`mb2_cmdline`].
+///
+/// # Safety
+/// See [`mb2_cmdline`].
+#[no_mangle]
+pub unsafe extern "C" fn mb2_boot_loader_name(
+    mbi: *const BootInformationHeader,
+    len: *mut usize,
+) -> *const c_char {
+    let info = load(mbi);
+    let name = info
+        .as_ref()
+        .and_then(|info| info.boot_loader_name_tag())
+        .map(|tag| tag.name());
+    string_out(name, len)
+}
+
+/// Returns the number of areas in the memory map, or `0` if there is none.
+///
+/// # Safety
+/// `mbi` must be null or valid for reading.
+#[no_mangle]
+pub unsafe extern "C" fn mb2_memory_map_count(mbi: *const BootInformationHeader) -> usize {
+    load(mbi)
+        .and_then(|info| info.memory_map_tag().map(|tag| tag.memory_areas().len()))
+        .unwrap_or(0)
+}
+
+/// Writes the memory area with the given index to `out`. Returns `false` if
+/// there is no such area.
+///
+/// # Safety
+/// `mbi` must be null or valid for reading. `out` must be null or valid for
+/// writing.
+#[no_mangle]
+pub unsafe extern "C" fn mb2_memory_map_entry(
+    mbi: *const BootInformationHeader,
+    index: usize,
+    out: *mut Mb2MemoryArea,
+) -> bool {
+    let Some(info) = load(mbi) else {
+        return false;
+    };
+    let area = info
+        .memory_map_tag()
+        .and_then(|tag| tag.memory_areas().get(index));
+    match (area, out.as_mut()) {
+        (Some(area), Some(out)) => {
+            *out = Mb2MemoryArea {
+                base_addr: area.start_address(),
+                length: area.size(),
+                typ: area.typ().into(),
+            };
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Returns the number of modules.
+///
+/// # Safety
+/// `mbi` must be null or valid for reading.
+#[no_mangle]
+pub unsafe extern "C" fn mb2_module_count(mbi: *const BootInformationHeader) -> usize {
+    load(mbi).map_or(0, |info| info.module_tags().count())
+}
+
+/// Writes the module with the given index to `out`. Returns `false` if there
+/// is no such module.
+///
+/// # Safety
+/// `mbi` must be null or valid for reading. `out` must be null or valid for
+/// writing.
+#[no_mangle]
+pub unsafe extern "C" fn mb2_module(
+    mbi: *const BootInformationHeader,
+    index: usize,
+    out: *mut Mb2Module,
+) -> bool {
+    let Some(info) = load(mbi) else {
+        return false;
+    };
+    let module = info.module_tags().nth(index);
+    match (module, out.as_mut()) {
+        (Some(module), Some(out)) => {
+            let mut cmdline_len = 0;
+            let cmdline = string_out(Some(module.cmdline()), &mut cmdline_len);
+            *out = Mb2Module {
+                start: module.start_address(),
+                end: module.end_address(),
+                cmdline,
+                cmdline_len,
+            };
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{
+        BootLoaderNameTag, Builder, CommandLineTag, MemoryArea, MemoryAreaType, MemoryMapTag,
+    };
+    use core::ptr;
+    use core::slice;
+
+    #[test]
+    fn test_ffi() {
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("console=ttyS0"))
+            .bootloader(BootLoaderNameTag::new("GRUB"))
+            .add_module(0x1000, 0x2000, "initrd")
+            .mmap(MemoryMapTag::new(&[
+                MemoryArea::new(0, 0x9_fc00, MemoryAreaType::Available),
+                MemoryArea::new(0x9_fc00, 0x400, MemoryAreaType::Reserved),
+            ]))
+            .build();
+        let mbi = mbi.as_ptr();
+        unsafe {
+            assert_eq!(mb2_parse(mbi), Mb2Status::Ok);
+            assert_eq!(mb2_parse(ptr::null()), Mb2Status::Null);
+
+            let mut len = 0;
+            let cmdline = mb2_cmdline(mbi, &mut len);
+            assert_eq!(
+                slice::from_raw_parts(cmdline.cast::<u8>(), len),
+                b"console=ttyS0"
+            );
+            let name = mb2_boot_loader_name(mbi, &mut len);
+            assert_eq!(slice::from_raw_parts(name.cast::<u8>(), len), b"GRUB");
+            assert!(mb2_cmdline(ptr::null(), &mut len).is_null());
+            assert_eq!(len, 0);
+
+            assert_eq!(mb2_memory_map_count(mbi), 2);
+            let mut area = Mb2MemoryArea::default();
+            assert!(mb2_memory_map_entry(mbi, 1, &mut area));
+            assert_eq!(
+                area,
+                Mb2MemoryArea {
+                    base_addr: 0x9_fc00,
+                    length: 0x400,
+                    typ: 2,
+                }
+            );
+            assert!(!mb2_memory_map_entry(mbi, 2, &mut area));
+
+            assert_eq!(mb2_module_count(mbi), 1);
+            let mut module = core::mem::zeroed::<Mb2Module>();
+            assert!(mb2_module(mbi, 0, &mut module));
+            assert_eq!((module.start, module.end), (0x1000, 0x2000));
+            assert_eq!(
+                slice::from_raw_parts(module.cmdline.cast::<u8>(), module.cmdline_len),
+                b"initrd"
+            );
+            assert!(!mb2_module(mbi, 1, &mut module));
+        }
+    }
+}
@@ -1,9 +1,11 @@
 //! Module for [`FramebufferTag`].
 
+use crate::free_memory::{efi_desc_range, efi_is_available, mmap_area_range};
 use crate::tag::TagHeader;
-use crate::TagType;
+use crate::{BootInformation, MemoryAreaType, PhysAddr, PhysRange, TagType};
 use core::fmt::Debug;
 use core::mem;
+use core::ops::Range;
 use core::slice;
 use derive_more::Display;
 use multiboot2_common::{MaybeDynSized, Tag};
@@ -22,22 +24,18 @@ impl<'a> Reader<'a> {
         Self { buffer, off: 0 }
     }
 
-    fn read_u8(&mut self) -> u8 {
-        let val = self
-            .buffer
-            .get(self.off)
-            .cloned()
-            // This is not a solution I'm proud of, but at least it is safe.
-            // The whole framebuffer tag code originally is not from me.
-            // I hope someone from the community wants to improve this overall
-            // functionality someday.
-            .expect("Embedded framebuffer info should be properly sized and available");
+    fn read_u8(&mut self) -> Option<u8> {
+        let val = self.buffer.get(self.off).copied()?;
         self.off += 1;
-        val
+        Some(val)
     }
 
-    fn read_u16(&mut self) -> u16 {
-        self.read_u8() as u16 | (self.read_u8() as u16) << 8
+    fn read_u16(&mut self) -> Option<u16> {
+        Some(self.read_u8()? as u16 | (self.read_u8()? as u16) << 8)
+    }
+
+    const fn remaining(&self) -> usize {
+        self.buffer.len().saturating_sub(self.off)
     }
 
     const fn current_ptr(&self) -> *const u8 {
@@ -70,12 +68,10 @@ pub struct FramebufferTag {
     /// Contains number of bits per pixel.
     bpp: u8,
 
-    /// The type of framebuffer. See [`FramebufferTypeId`].
-    // TODO: Strictly speaking this causes UB for invalid values. However, no
-    //  sane bootloader puts something illegal there at the moment. When we
-    //  refactor this (newtype pattern?), we should also streamline other
-    //  parts in the code base accordingly.
-    framebuffer_type: FramebufferTypeId,
+    /// The raw type of framebuffer. See [`FramebufferTypeId`]. This is not a
+    /// [`FramebufferTypeId`], as a reference to a tag with an unknown value
+    /// would be UB.
+    framebuffer_type: u8,
 
     _padding: u16,
 
@@ -128,6 +124,14 @@ impl FramebufferTag {
         self.address
     }
 
+    /// The physical memory of the framebuffer, from [`Self::address`] for
+    /// [`Self::height`] lines of [`Self::pitch`] bytes.
+    #[must_use]
+    pub const fn range(&self) -> PhysRange {
+        let size = self.pitch as u64 * self.height as u64;
+        PhysRange::from_start_size(PhysAddr::new(self.address), size)
+    }
+
     /// Contains the pitch in bytes.
     #[must_use]
     pub const fn pitch(&self) -> u32 {
@@ -153,24 +157,26 @@ impl FramebufferTag {
     }
 
     /// The type of framebuffer, one of: `Indexed`, `RGB` or `Text`.
-    pub fn buffer_type(&self) -> Result<FramebufferType, UnknownFramebufferType> {
+    ///
+    /// Type information that does not fit into the tag is reported as
+    /// [`UnknownFramebufferType`].
+    pub fn buffer_type(&self) -> Result<FramebufferType<'_>, UnknownFramebufferType> {
         let mut reader = Reader::new(&self.buffer);
+        let truncated = UnknownFramebufferType(self.framebuffer_type);
 
-        // TODO: We should use the newtype pattern instead or so to properly
-        //  solve this.
-        let fb_type_raw = self.framebuffer_type as u8;
-        let fb_type = FramebufferTypeId::try_from(fb_type_raw)?;
+        let fb_type = FramebufferTypeId::try_from(self.framebuffer_type)?;
 
         match fb_type {
             FramebufferTypeId::Indexed => {
                 // TODO we can create a struct for this and implement
                 //  DynSizedStruct for it to leverage the already existing
                 //  functionality
-                let num_colors = reader.read_u16();
+                let num_colors = reader.read_u16().ok_or(truncated)?;
 
                 let palette = {
-                    // Ensure the slice can be created without causing UB
-                    assert_eq!(mem::size_of::<FramebufferColor>(), 3);
+                    if reader.remaining() < num_colors as usize * 3 {
+                        return Err(truncated);
+                    }
 
                     unsafe {
                         slice::from_raw_parts(
@@ -182,12 +188,12 @@ impl FramebufferTag {
                 Ok(FramebufferType::Indexed { palette })
             }
             FramebufferTypeId::RGB => {
-                let red_pos = reader.read_u8(); // These refer to the bit positions of the LSB of each field
-                let red_mask = reader.read_u8(); // And then the length of the field from LSB to MSB
-                let green_pos = reader.read_u8();
-                let green_mask = reader.read_u8();
-                let blue_pos = reader.read_u8();
-                let blue_mask = reader.read_u8();
+                let red_pos = reader.read_u8().ok_or(truncated)?; // These refer to the bit positions of the LSB of each field
+                let red_mask = reader.read_u8().ok_or(truncated)?; // And then the length of the field from LSB to MSB
+                let green_pos = reader.read_u8().ok_or(truncated)?;
+                let green_mask = reader.read_u8().ok_or(truncated)?;
+                let blue_pos = reader.read_u8().ok_or(truncated)?;
+                let blue_mask = reader.read_u8().ok_or(truncated)?;
                 Ok(FramebufferType::RGB {
                     red: FramebufferField {
                         position: red_pos,
@@ -218,8 +224,11 @@ impl MaybeDynSized for FramebufferTag {
         + mem::size_of::<u16>();
 
     fn dst_len(header: &TagHeader) -> usize {
-        assert!(header.size as usize >= Self::BASE_SIZE);
-        header.size as usize - Self::BASE_SIZE
+        Self::try_dst_len(header).expect("tag should be at least BASE_SIZE bytes")
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
     }
 }
 
@@ -389,26 +398,131 @@ pub struct FramebufferColor {
     pub blue: u8,
 }
 
-/// Error when an unknown [`FramebufferTypeId`] is found.
+// Palettes are created as slices of this type directly from the tag bytes.
+const _: () = assert!(mem::size_of::<FramebufferColor>() == 3);
+
+/// Error when an unknown framebuffer type is found, see
+/// [`FramebufferTag::buffer_type`].
 #[derive(Debug, Copy, Clone, Display, PartialEq, Eq)]
 #[display("Unknown framebuffer type {}", _0)]
 pub struct UnknownFramebufferType(u8);
 
-#[cfg(feature = "unstable")]
 impl core::error::Error for UnknownFramebufferType {}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// A problem with the framebuffer found by
+/// [`BootInformation::check_framebuffer`].
+///
+/// Bootloaders that pass stale VBE data may report a framebuffer that
+/// overlaps memory in use. Kernels may then decide to ignore the framebuffer.
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FramebufferFinding {
+    /// The pitch is too small for a line of `width` pixels, or the end of the
+    /// framebuffer doesn't fit into the physical address space.
+    InvalidGeometry,
+    /// The framebuffer overlaps a memory area that is reported as available
+    /// RAM.
+    #[display("OverlapsAvailableRam({start:#x}..{end:#x})")]
+    OverlapsAvailableRam {
+        /// The physical start address of the memory area.
+        start: u64,
+        /// The physical end address (exclusive) of the memory area.
+        end: u64,
+    },
+    /// The framebuffer overlaps the boot information itself.
+    OverlapsBootInformation,
+    /// The framebuffer overlaps a boot module.
+    #[display("OverlapsModule({start:#x}..{end:#x})")]
+    OverlapsModule {
+        /// The physical start address of the module.
+        start: u64,
+        /// The physical end address (exclusive) of the module.
+        end: u64,
+    },
+}
 
-    // Compile time test
-    #[test]
-    fn test_size() {
-        assert_eq!(mem::size_of::<FramebufferColor>(), 3)
+impl core::error::Error for FramebufferFinding {}
+
+impl FramebufferTag {
+    /// Returns the physical address range of the framebuffer, which is
+    /// `pitch * height` bytes long.
+    fn phys_range(&self) -> Result<Range<u64>, FramebufferFinding> {
+        let line_bits = u64::from(self.width) * u64::from(self.bpp);
+        if u64::from(self.pitch) * 8 < line_bits {
+            return Err(FramebufferFinding::InvalidGeometry);
+        }
+        let size = u64::from(self.pitch) * u64::from(self.height);
+        let end = self
+            .address
+            .checked_add(size)
+            .ok_or(FramebufferFinding::InvalidGeometry)?;
+        Ok(self.address..end)
+    }
+}
+
+impl BootInformation<'_> {
+    /// Cross-validates the address and the geometry of the framebuffer
+    /// against the memory map, the boot information, and the modules.
+    ///
+    /// This is opt-in, as [`Self::framebuffer_tag`] doesn't perform any
+    /// checks. Returns the first [`FramebufferFinding`], if any. The check
+    /// passes if there is no framebuffer tag.
+    ///
+    /// Both the Multiboot2 and the EFI memory map are considered. EFI boot
+    /// services memory counts as available RAM. The boot information is
+    /// assumed to be identity-mapped.
+    pub fn check_framebuffer(&self) -> Result<(), FramebufferFinding> {
+        let Some(tag) = self.get_tag::<FramebufferTag>() else {
+            return Ok(());
+        };
+        let fb = tag.phys_range()?;
+        let overlaps = |start: u64, end: u64| start < fb.end && fb.start < end;
+
+        if let Some(mmap) = self.memory_map_tag() {
+            let available = mmap
+                .memory_areas()
+                .iter()
+                .filter(|area| MemoryAreaType::from(area.typ()) == MemoryAreaType::Available);
+            for area in available.map(mmap_area_range) {
+                if overlaps(area.start, area.end) {
+                    return Err(FramebufferFinding::OverlapsAvailableRam {
+                        start: area.start,
+                        end: area.end,
+                    });
+                }
+            }
+        }
+
+        if let Some(mmap) = self.efi_memory_map_tag() {
+            let available = mmap.memory_areas().filter(|desc| efi_is_available(desc.ty));
+            for desc in available {
+                let Range { start, end } = efi_desc_range(desc);
+                if overlaps(start, end) {
+                    return Err(FramebufferFinding::OverlapsAvailableRam { start, end });
+                }
+            }
+        }
+
+        if overlaps(self.start_address() as u64, self.end_address() as u64) {
+            return Err(FramebufferFinding::OverlapsBootInformation);
+        }
+
+        for module in self.module_tags() {
+            let start = u64::from(module.start_address());
+            let end = u64::from(module.end_address());
+            if overlaps(start, end) {
+                return Err(FramebufferFinding::OverlapsModule { start, end });
+            }
+        }
+
+        Ok(())
     }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
 
     #[test]
-    #[cfg(feature = "builder")]
     fn create_new() {
         let tag = FramebufferTag::new(0x1000, 1, 1024, 1024, 8, FramebufferType::Text);
         // Good test for Miri
@@ -462,4 +576,94 @@ mod tests {
         // Good test for Miri
         dbg!(tag);
     }
+
+    #[test]
+    fn check_framebuffer() {
+        use crate::{Builder, MemoryArea, MemoryMapTag};
+
+        let check = |address: u64, pitch: u32| {
+            let mmap = MemoryMapTag::new(&[
+                MemoryArea::new(0, 0x9fc00, MemoryAreaType::Available),
+                MemoryArea::new(0x10_0000, 0x7f0_0000, MemoryAreaType::Available),
+                MemoryArea::new(0xfd00_0000, 0x100_0000, MemoryAreaType::Reserved),
+            ]);
+            let mbi = Builder::new()
+                .mmap(mmap)
+                .add_module(0xe000_0000, 0xe010_0000, "initrd")
+                .framebuffer(FramebufferTag::new(
+                    address,
+                    pitch,
+                    1024,
+                    768,
+                    32,
+                    FramebufferType::Text,
+                ))
+                .build();
+            let bi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+            bi.check_framebuffer()
+        };
+
+        assert_eq!(check(0xfd00_0000, 4096), Ok(()));
+        assert_eq!(
+            check(0x20_0000, 4096),
+            Err(FramebufferFinding::OverlapsAvailableRam {
+                start: 0x10_0000,
+                end: 0x800_0000
+            })
+        );
+        assert_eq!(
+            check(0xe000_0000, 4096),
+            Err(FramebufferFinding::OverlapsModule {
+                start: 0xe000_0000,
+                end: 0xe010_0000
+            })
+        );
+        assert_eq!(
+            check(0xfd00_0000, 1024),
+            Err(FramebufferFinding::InvalidGeometry)
+        );
+        assert_eq!(
+            check(u64::MAX - 4096, 4096),
+            Err(FramebufferFinding::InvalidGeometry)
+        );
+    }
+
+    #[test]
+    fn check_framebuffer_efi_mmap() {
+        use crate::{Builder, EFIMemoryAreaType, EFIMemoryDesc, EFIMemoryMapTag};
+
+        let check = |address: u64| {
+            let desc = |ty, phys_start, page_count| EFIMemoryDesc {
+                ty,
+                phys_start,
+                page_count,
+                ..Default::default()
+            };
+            let mbi = Builder::new()
+                .efi_mmap(EFIMemoryMapTag::new_from_descs(&[
+                    desc(EFIMemoryAreaType::BOOT_SERVICES_DATA, 0x10_0000, 0x100),
+                    desc(EFIMemoryAreaType::LOADER_DATA, 0x20_0000, 0x1000),
+                ]))
+                .framebuffer(FramebufferTag::new(
+                    address,
+                    4096,
+                    1024,
+                    768,
+                    32,
+                    FramebufferType::Text,
+                ))
+                .build();
+            let bi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+            bi.check_framebuffer()
+        };
+
+        assert_eq!(
+            check(0x10_0000),
+            Err(FramebufferFinding::OverlapsAvailableRam {
+                start: 0x10_0000,
+                end: 0x20_0000
+            })
+        );
+        assert_eq!(check(0x20_0000), Ok(()));
+    }
 }
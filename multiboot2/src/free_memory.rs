@@ -0,0 +1,80 @@
+//! Helpers shared by all functionality that looks for free memory in the
+//! memory maps, so that it agrees on what is free.
+
+use crate::{EFIMemoryAreaType, EFIMemoryDesc, MemoryArea};
+use core::ops::Range;
+
+/// Size of a page in the EFI memory map.
+pub const EFI_PAGE_SIZE: u64 = 4096;
+
+/// Returns whether memory of the given EFI type is free once the boot services
+/// were exited.
+///
+/// Loader memory is not free, as the bootloader may have placed the kernel,
+/// the modules, or the boot information there.
+pub const fn efi_is_available(ty: EFIMemoryAreaType) -> bool {
+    matches!(
+        ty,
+        EFIMemoryAreaType::CONVENTIONAL
+            | EFIMemoryAreaType::BOOT_SERVICES_CODE
+            | EFIMemoryAreaType::BOOT_SERVICES_DATA
+    )
+}
+
+/// Returns the physical memory range of an EFI memory descriptor, saturated at
+/// `u64::MAX`.
+pub const fn efi_desc_range(desc: EFIMemoryDesc) -> Range<u64> {
+    let size = desc.page_count.saturating_mul(EFI_PAGE_SIZE);
+    desc.phys_start..desc.phys_start.saturating_add(size)
+}
+
+/// Returns the physical memory range of a memory area, saturated at
+/// `u64::MAX`.
+pub const fn mmap_area_range(area: &MemoryArea) -> Range<u64> {
+    area.start_address()..area.start_address().saturating_add(area.size())
+}
+
+/// Iterator that splits a memory range into the parts that overlap any of the
+/// used ranges and the parts that don't. Yields each part together with
+/// whether it is used.
+#[cfg(any(feature = "bootloader_api", feature = "heap"))]
+pub struct SplitRanges<F> {
+    range: Range<u64>,
+    used: F,
+}
+
+#[cfg(any(feature = "bootloader_api", feature = "heap"))]
+impl<F: Fn() -> I, I: Iterator<Item = Range<u64>>> SplitRanges<F> {
+    /// Creates a new iterator. `used` returns the used ranges, which may
+    /// overlap each other.
+    pub const fn new(range: Range<u64>, used: F) -> Self {
+        Self { range, used }
+    }
+}
+
+#[cfg(any(feature = "bootloader_api", feature = "heap"))]
+impl<F: Fn() -> I, I: Iterator<Item = Range<u64>>> Iterator for SplitRanges<F> {
+    type Item = (Range<u64>, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None;
+        }
+        let cursor = self.range.start;
+        // The used range that overlaps the remainder first.
+        let next = (self.used)()
+            .filter(|used| used.start < self.range.end && used.end > cursor)
+            .map(|used| used.start.max(cursor)..used.end.min(self.range.end))
+            .min_by_key(|used| used.start);
+        let end = match next {
+            Some(used) if used.start == cursor => {
+                self.range.start = used.end;
+                return Some((used, true));
+            }
+            Some(used) => used.start,
+            None => self.range.end,
+        };
+        self.range.start = end;
+        Some((cursor..end, false))
+    }
+}
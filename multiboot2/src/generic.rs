@@ -0,0 +1,382 @@
+//! Module for [`GenericTag`] and [`VendorTagId`].
+
+use crate::{TagHeader, TagIter, TagType, TagTypeId};
+use core::fmt::{Debug, Formatter};
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr;
+use multiboot2_common::{DynSizedStructure, MaybeDynSized, MemoryError, Tag};
+use ptr_meta::Pointee;
+#[cfg(feature = "builder")]
+use {alloc::boxed::Box, multiboot2_common::new_boxed};
+
+/// A tag of any type, exposing its raw payload.
+///
+/// This is useful to inspect tags this crate doesn't know about, such as
+/// vendor-specific tags (see [`VendorTagId`]), and to build them.
+#[derive(Pointee, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C, align(8))]
+pub struct GenericTag {
+    header: TagHeader,
+    payload: [u8],
+}
+
+impl GenericTag {
+    /// Creates a new tag of the given type with the given payload.
+    #[cfg(feature = "builder")]
+    #[must_use]
+    pub fn new(typ: impl Into<TagTypeId>, payload: &[u8]) -> Box<Self> {
+        let header = TagHeader::new(typ, 0);
+        new_boxed(header, &[payload])
+    }
+
+    /// Creates a reference to a tag from the given bytes. They must be 8-byte
+    /// aligned and contain the whole tag, including its padding.
+    pub fn ref_from_slice(bytes: &[u8]) -> Result<&Self, MemoryError> {
+        DynSizedStructure::<TagHeader>::ref_from_slice(bytes)?.try_cast()
+    }
+
+    /// Returns the type of the tag.
+    #[must_use]
+    pub fn typ(&self) -> TagType {
+        self.header.typ.into()
+    }
+
+    /// Returns the size of the tag, including the header but without
+    /// trailing padding.
+    #[must_use]
+    pub const fn size(&self) -> u32 {
+        self.header.size
+    }
+
+    /// Returns the raw payload of the tag, i.e., everything after the header.
+    #[must_use]
+    pub const fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Returns the [`VendorTagId`], if the type of the tag is in the vendor
+    /// namespace.
+    #[must_use]
+    pub fn vendor_id(&self) -> Option<VendorTagId> {
+        VendorTagId::from_tag_type(self.header.typ)
+    }
+
+    /// Returns the tag as the specific tag type `T`, if it has the type of
+    /// `T`.
+    ///
+    /// The tags emitted by [`BootInformation::generic_tags`] are already
+    /// validated handles, whose bounds and alignment were checked once while
+    /// iterating. This only checks the type and the size of `T`.
+    ///
+    /// [`BootInformation::generic_tags`]: crate::BootInformation::generic_tags
+    #[must_use]
+    pub fn downcast<T: Tag<IDType = TagType, Header = TagHeader> + ?Sized>(&self) -> Option<&T> {
+        if self.header.typ != T::ID {
+            return None;
+        }
+        let ptr = ptr_meta::from_raw_parts::<DynSizedStructure<TagHeader>>(
+            ptr::addr_of!(*self).cast(),
+            self.payload.len(),
+        );
+        // SAFETY: Both types consist of the header followed by the payload.
+        let tag = unsafe { &*ptr };
+        tag.try_cast::<T>().ok()
+    }
+}
+
+impl Debug for GenericTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GenericTag")
+            .field("typ", &self.header.typ)
+            .field("size", &self.header.size)
+            .field("payload_len", &self.payload.len())
+            .finish()
+    }
+}
+
+impl MaybeDynSized for GenericTag {
+    type Header = TagHeader;
+
+    const BASE_SIZE: usize = mem::size_of::<TagHeader>();
+
+    fn dst_len(header: &TagHeader) -> usize {
+        (header.size as usize).saturating_sub(Self::BASE_SIZE)
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
+    }
+}
+
+/// Convention for tag types of vendor-specific tags, such as experimental
+/// channels between a certain bootloader and a certain kernel.
+///
+/// The Multiboot2 spec neither reserves nor forbids tag types beyond the ones
+/// it defines. To prevent collisions with future tags of the spec and between
+/// vendors, this convention splits the `u32` tag type into a 16-bit vendor
+/// number (upper half) and a 16-bit tag number (lower half). Vendor `0` is
+/// reserved for the spec, so every vendor tag type is at least `0x1_0000`.
+///
+/// There is no central registry. Vendors should pick a vendor number randomly
+/// and document it, e.g., in their crate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VendorTagId {
+    vendor: u16,
+    tag: u16,
+}
+
+impl VendorTagId {
+    /// Creates a new vendor tag ID.
+    ///
+    /// # Panics
+    /// Panics if `vendor` is `0`, as this namespace belongs to the spec.
+    #[must_use]
+    pub const fn new(vendor: u16, tag: u16) -> Self {
+        assert!(vendor != 0, "vendor 0 is reserved for the spec");
+        Self { vendor, tag }
+    }
+
+    /// Parses the given tag type as vendor tag ID. Returns `None` if the
+    /// tag type is not in the vendor namespace.
+    #[must_use]
+    pub fn from_tag_type(typ: impl Into<TagTypeId>) -> Option<Self> {
+        let typ = u32::from(typ.into());
+        let vendor = (typ >> 16) as u16;
+        (vendor != 0).then_some(Self {
+            vendor,
+            tag: typ as u16,
+        })
+    }
+
+    /// Returns the vendor number.
+    #[must_use]
+    pub const fn vendor(&self) -> u16 {
+        self.vendor
+    }
+
+    /// Returns the tag number within the namespace of the vendor.
+    #[must_use]
+    pub const fn tag(&self) -> u16 {
+        self.tag
+    }
+
+    /// Returns the raw `u32` tag type.
+    #[must_use]
+    pub const fn val(&self) -> u32 {
+        ((self.vendor as u32) << 16) | self.tag as u32
+    }
+}
+
+impl From<VendorTagId> for TagTypeId {
+    fn from(value: VendorTagId) -> Self {
+        Self::new(value.val())
+    }
+}
+
+impl From<VendorTagId> for TagType {
+    fn from(value: VendorTagId) -> Self {
+        Self::Custom(value.val())
+    }
+}
+
+/// Iterator over all tags of a boot information as [`GenericTag`]s.
+#[derive(Clone)]
+pub struct GenericTagIter<'a> {
+    iter: TagIter<'a>,
+}
+
+impl<'a> GenericTagIter<'a> {
+    pub(crate) const fn new(iter: TagIter<'a>) -> Self {
+        Self { iter }
+    }
+
+    /// Only yields the tags of the given type.
+    #[must_use]
+    pub const fn filter_type(self, typ: TagType) -> TagTypeFilter<'a> {
+        TagTypeFilter { iter: self, typ }
+    }
+
+    /// Only yields the tags of type `T`, cast to `T`.
+    ///
+    /// Tags with the ID of `T` whose size doesn't fit `T` are skipped, like
+    /// [`GenericTag::downcast`] rejects them.
+    #[must_use]
+    pub const fn typed<T: Tag<IDType = TagType, Header = TagHeader> + ?Sized>(
+        self,
+    ) -> TypedTagIter<'a, T> {
+        TypedTagIter {
+            iter: self,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for GenericTagIter<'a> {
+    type Item = &'a GenericTag;
+
+    fn next(&mut self) -> Option<&'a GenericTag> {
+        self.iter.next().map(|tag| tag.cast())
+    }
+}
+
+impl Debug for GenericTagIter<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// Iterator over the tags of a given type, see
+/// [`GenericTagIter::filter_type`].
+#[derive(Clone, Debug)]
+pub struct TagTypeFilter<'a> {
+    iter: GenericTagIter<'a>,
+    typ: TagType,
+}
+
+impl<'a> Iterator for TagTypeFilter<'a> {
+    type Item = &'a GenericTag;
+
+    fn next(&mut self) -> Option<&'a GenericTag> {
+        let typ = self.typ;
+        self.iter.find(|tag| tag.typ() == typ)
+    }
+}
+
+/// Iterator over the tags of type `T`, see [`GenericTagIter::typed`].
+pub struct TypedTagIter<'a, T: ?Sized> {
+    iter: GenericTagIter<'a>,
+    _t: PhantomData<T>,
+}
+
+impl<T: ?Sized> Clone for TypedTagIter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: Tag<IDType = TagType, Header = TagHeader> + ?Sized + 'a> Iterator
+    for TypedTagIter<'a, T>
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.find_map(GenericTag::downcast)
+    }
+}
+
+impl<'a, T: Tag<IDType = TagType, Header = TagHeader> + Debug + ?Sized + 'a> Debug
+    for TypedTagIter<'a, T>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::borrow::Borrow;
+    use multiboot2_common::test_utils::AlignedBytes;
+
+    #[rustfmt::skip]
+    const fn get_bytes() -> AlignedBytes<16> {
+        AlignedBytes::new([
+            0x01, 0x00, 0x37, 0x13,
+            11, 0, 0, 0,
+            /* payload */
+            b'a', b'b', b'c',
+            /* padding */
+            0, 0, 0, 0, 0,
+        ])
+    }
+
+    /// Test to parse a given tag.
+    #[test]
+    fn test_parse() {
+        let bytes = get_bytes();
+        let tag = GenericTag::ref_from_slice(bytes.borrow()).unwrap();
+        assert_eq!(tag.typ(), TagType::Custom(0x1337_0001));
+        assert_eq!(tag.size(), 11);
+        assert_eq!(tag.payload(), b"abc");
+        assert_eq!(tag.vendor_id(), Some(VendorTagId::new(0x1337, 1)));
+    }
+
+    /// Test to build a tag.
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_build() {
+        let tag = GenericTag::new(VendorTagId::new(0x1337, 1), b"abc");
+        let bytes = tag.as_bytes().as_ref();
+        assert_eq!(bytes, &get_bytes()[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_downcast() {
+        use crate::{BootInformation, Builder, CommandLineTag, ModuleTag};
+
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(0x1000, 0x2000, "module")
+            .build();
+        let info = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        let mut tags = info.generic_tags();
+        let cmdline = tags.next().unwrap();
+        assert!(cmdline.downcast::<ModuleTag>().is_none());
+        assert_eq!(
+            cmdline.downcast::<CommandLineTag>().unwrap().cmdline(),
+            Ok("cmdline")
+        );
+        let module = tags.next().unwrap().downcast::<ModuleTag>().unwrap();
+        assert_eq!(module.start_address(), 0x1000);
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_combinators() {
+        use crate::{BootInformation, Builder, CommandLineTag, ModuleTag};
+
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(0x1000, 0x2000, "a")
+            .add_module(0x3000, 0x4000, "b")
+            .build();
+        let info = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+
+        let types = (&info).into_iter().map(GenericTag::typ);
+        assert!(types.eq([
+            TagType::Cmdline,
+            TagType::Module,
+            TagType::Module,
+            TagType::End
+        ]));
+        assert_eq!(info.tags().filter_type(TagType::Module).count(), 2);
+        assert_eq!(info.tags().filter_type(TagType::Mmap).count(), 0);
+        let modules = info
+            .tags()
+            .typed::<ModuleTag>()
+            .map(|module| module.cmdline().unwrap());
+        assert!(modules.eq(["a", "b"]));
+
+        let mut count = 0;
+        for tag in &info {
+            assert_eq!(tag.size() as usize, tag.payload().len() + 8);
+            count += 1;
+        }
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn test_vendor_tag_id() {
+        let id = VendorTagId::new(0xabcd, 0x42);
+        assert_eq!(id.val(), 0xabcd_0042);
+        assert_eq!(TagType::from(id), TagType::Custom(0xabcd_0042));
+        assert_eq!(VendorTagId::from_tag_type(id), Some(id));
+        assert_eq!(VendorTagId::from_tag_type(TagType::Custom(0x1337)), None);
+        assert_eq!(VendorTagId::from_tag_type(TagType::Cmdline), None);
+    }
+}
@@ -0,0 +1,331 @@
+//! Differential tests against the structures of GRUB's `include/multiboot2.h`,
+//! the reference implementation of the spec.
+//!
+//! The [`grub`] module declares the structures of the header, see there for
+//! the GRUB version. The tests compare the sizes
+//! with this crate and read every tag of arbitrary boot informations through
+//! both, to catch a silent divergence of the layout.
+
+use crate::testing::{arbitrary_boot_information, Gen};
+use crate::{
+    ApmTag, BasicMemoryInfoTag, BootInformation, BootLoaderNameTag, BootdevTag, CommandLineTag,
+    EFIImageHandle32Tag, EFIImageHandle64Tag, EFIMemoryMapTag, EFISdt32Tag, EFISdt64Tag,
+    ElfSectionsTag, FramebufferField, FramebufferTag, FramebufferType, GenericTag,
+    ImageLoadPhysAddrTag, MemoryArea, MemoryMapTag, ModuleTag, NetworkTag, RsdpV1Tag, RsdpV2Tag,
+    SmbiosTag, TagType, VBEInfoTag,
+};
+use core::ffi::CStr;
+use core::mem::{offset_of, size_of};
+use multiboot2_common::{increase_to_alignment, MaybeDynSized};
+
+#[allow(non_camel_case_types, dead_code)]
+mod grub;
+
+use grub::*;
+
+#[test]
+fn layouts() {
+    // Tags with a dynamically sized part: it starts where GRUB's flexible
+    // array member starts.
+    assert_eq!(
+        CommandLineTag::BASE_SIZE,
+        offset_of!(multiboot_tag_string, string)
+    );
+    assert_eq!(
+        BootLoaderNameTag::BASE_SIZE,
+        offset_of!(multiboot_tag_string, string)
+    );
+    assert_eq!(
+        ModuleTag::BASE_SIZE,
+        offset_of!(multiboot_tag_module, cmdline)
+    );
+    assert_eq!(
+        MemoryMapTag::BASE_SIZE,
+        offset_of!(multiboot_tag_mmap, entries)
+    );
+    assert_eq!(size_of::<MemoryArea>(), size_of::<multiboot_mmap_entry>());
+    assert_eq!(
+        ElfSectionsTag::BASE_SIZE,
+        offset_of!(multiboot_tag_elf_sections, sections)
+    );
+    assert_eq!(
+        SmbiosTag::BASE_SIZE,
+        offset_of!(multiboot_tag_smbios, tables)
+    );
+    assert_eq!(
+        NetworkTag::BASE_SIZE,
+        offset_of!(multiboot_tag_network, dhcpack)
+    );
+    assert_eq!(
+        EFIMemoryMapTag::BASE_SIZE,
+        offset_of!(multiboot_tag_efi_mmap, efi_mmap)
+    );
+    // The type-specific part of the framebuffer tag is part of the dynamically
+    // sized part.
+    assert_eq!(
+        FramebufferTag::BASE_SIZE,
+        size_of::<multiboot_tag_framebuffer_common>()
+    );
+    assert_eq!(GenericTag::BASE_SIZE, size_of::<multiboot_tag>());
+
+    // Sized tags. Their Rust types include the padding to the next tag.
+    let padded = |size| increase_to_alignment(size);
+    assert_eq!(
+        BasicMemoryInfoTag::BASE_SIZE,
+        padded(size_of::<multiboot_tag_basic_meminfo>())
+    );
+    assert_eq!(
+        BootdevTag::BASE_SIZE,
+        padded(size_of::<multiboot_tag_bootdev>())
+    );
+    assert_eq!(
+        VBEInfoTag::BASE_SIZE,
+        padded(size_of::<multiboot_tag_vbe>())
+    );
+    assert_eq!(ApmTag::BASE_SIZE, padded(size_of::<multiboot_tag_apm>()));
+    assert_eq!(
+        EFISdt32Tag::BASE_SIZE,
+        padded(size_of::<multiboot_tag_efi32>())
+    );
+    assert_eq!(
+        EFISdt64Tag::BASE_SIZE,
+        padded(size_of::<multiboot_tag_efi64>())
+    );
+    assert_eq!(
+        EFIImageHandle32Tag::BASE_SIZE,
+        padded(size_of::<multiboot_tag_efi32_ih>())
+    );
+    assert_eq!(
+        EFIImageHandle64Tag::BASE_SIZE,
+        padded(size_of::<multiboot_tag_efi64_ih>())
+    );
+    assert_eq!(
+        ImageLoadPhysAddrTag::BASE_SIZE,
+        padded(size_of::<multiboot_tag_load_base_addr>())
+    );
+}
+
+/// Returns the NUL-terminated string at `ptr`.
+///
+/// # Safety
+/// The string must be terminated within the tag.
+const unsafe fn c_str<'a>(ptr: *const core::ffi::c_char) -> &'a [u8] {
+    CStr::from_ptr(ptr).to_bytes()
+}
+
+/// Returns the size of the tags that GRUB declares without a flexible array
+/// member.
+const fn sized_tag_size(typ: TagType) -> Option<usize> {
+    let size = match typ {
+        TagType::BasicMeminfo => size_of::<multiboot_tag_basic_meminfo>(),
+        TagType::Bootdev => size_of::<multiboot_tag_bootdev>(),
+        TagType::Vbe => size_of::<multiboot_tag_vbe>(),
+        TagType::Apm => size_of::<multiboot_tag_apm>(),
+        TagType::Efi32 => size_of::<multiboot_tag_efi32>(),
+        TagType::Efi64 => size_of::<multiboot_tag_efi64>(),
+        TagType::Efi32Ih => size_of::<multiboot_tag_efi32_ih>(),
+        TagType::Efi64Ih => size_of::<multiboot_tag_efi64_ih>(),
+        TagType::LoadBaseAddr => size_of::<multiboot_tag_load_base_addr>(),
+        _ => return None,
+    };
+    Some(size)
+}
+
+/// Reads every tag through GRUB's structures and compares the fields with the
+/// accessors of this crate.
+fn compare(bi: &BootInformation) {
+    for tag in bi.generic_tags() {
+        let ptr = (tag as *const GenericTag).cast::<u8>();
+        let raw = unsafe { &*ptr.cast::<multiboot_tag>() };
+        assert_eq!(raw.type_, u32::from(tag.typ()));
+        assert_eq!(raw.size, tag.size());
+        if let Some(size) = sized_tag_size(tag.typ()) {
+            assert_eq!(raw.size as usize, size, "size of {:?}", tag.typ());
+        }
+
+        match tag.typ() {
+            TagType::Cmdline => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_string>() };
+                let ours = tag.downcast::<CommandLineTag>().unwrap();
+                let string = unsafe { c_str(grub.string.as_ptr()) };
+                assert_eq!(ours.cmdline().map(str::as_bytes), Ok(string));
+            }
+            TagType::BootLoaderName => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_string>() };
+                let ours = tag.downcast::<BootLoaderNameTag>().unwrap();
+                let string = unsafe { c_str(grub.string.as_ptr()) };
+                assert_eq!(ours.name().map(str::as_bytes), Ok(string));
+            }
+            TagType::Module => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_module>() };
+                let ours = tag.downcast::<ModuleTag>().unwrap();
+                assert_eq!(ours.start_address(), grub.mod_start);
+                assert_eq!(ours.end_address(), grub.mod_end);
+                let cmdline = unsafe { c_str(grub.cmdline.as_ptr()) };
+                assert_eq!(ours.cmdline().map(str::as_bytes), Ok(cmdline));
+            }
+            TagType::BasicMeminfo => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_basic_meminfo>() };
+                let ours = tag.downcast::<BasicMemoryInfoTag>().unwrap();
+                assert_eq!(ours.memory_lower(), grub.mem_lower);
+                assert_eq!(ours.memory_upper(), grub.mem_upper);
+            }
+            TagType::Bootdev => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_bootdev>() };
+                let ours = tag.downcast::<BootdevTag>().unwrap();
+                assert_eq!(ours.biosdev(), grub.biosdev);
+                assert_eq!(ours.slice(), grub.slice);
+                assert_eq!(ours.part(), grub.part);
+            }
+            TagType::Mmap => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_mmap>() };
+                let ours = tag.downcast::<MemoryMapTag>().unwrap();
+                assert_eq!(ours.entry_size(), grub.entry_size);
+                assert_eq!(ours.entry_version(), grub.entry_version);
+                let count = (grub.size as usize - size_of::<multiboot_tag_mmap>())
+                    / grub.entry_size as usize;
+                let entries = unsafe { core::slice::from_raw_parts(grub.entries.as_ptr(), count) };
+                assert_eq!(ours.memory_areas().len(), entries.len());
+                for (area, entry) in ours.memory_areas().iter().zip(entries) {
+                    assert_eq!(area.start_address(), entry.addr);
+                    assert_eq!(area.size(), entry.len);
+                    assert_eq!(u32::from(area.typ()), entry.type_);
+                }
+            }
+            TagType::Framebuffer => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_framebuffer>() };
+                let ours = tag.downcast::<FramebufferTag>().unwrap();
+                let common = &grub.common;
+                assert_eq!(ours.address(), common.framebuffer_addr);
+                assert_eq!(ours.pitch(), common.framebuffer_pitch);
+                assert_eq!(ours.width(), common.framebuffer_width);
+                assert_eq!(ours.height(), common.framebuffer_height);
+                assert_eq!(ours.bpp(), common.framebuffer_bpp);
+                match (ours.buffer_type(), common.framebuffer_type) {
+                    (Ok(FramebufferType::Indexed { palette }), 0) => {
+                        let indexed = unsafe { &grub.__bindgen_anon_1.indexed };
+                        let colors = unsafe {
+                            core::slice::from_raw_parts(
+                                indexed.framebuffer_palette.as_ptr(),
+                                indexed.framebuffer_palette_num_colors as usize,
+                            )
+                        };
+                        assert_eq!(palette.len(), colors.len());
+                        for (ours, grub) in palette.iter().zip(colors) {
+                            assert_eq!(
+                                (ours.red, ours.green, ours.blue),
+                                (grub.red, grub.green, grub.blue)
+                            );
+                        }
+                    }
+                    (Ok(FramebufferType::RGB { red, green, blue }), 1) => {
+                        let rgb = unsafe { &grub.__bindgen_anon_1.rgb };
+                        let field = |position, size| FramebufferField { position, size };
+                        assert_eq!(
+                            red,
+                            field(
+                                rgb.framebuffer_red_field_position,
+                                rgb.framebuffer_red_mask_size
+                            )
+                        );
+                        assert_eq!(
+                            green,
+                            field(
+                                rgb.framebuffer_green_field_position,
+                                rgb.framebuffer_green_mask_size
+                            )
+                        );
+                        assert_eq!(
+                            blue,
+                            field(
+                                rgb.framebuffer_blue_field_position,
+                                rgb.framebuffer_blue_mask_size
+                            )
+                        );
+                    }
+                    (Ok(FramebufferType::Text), 2) => {}
+                    (ours, grub) => panic!("framebuffer type {grub} parsed as {ours:?}"),
+                }
+            }
+            TagType::ElfSections => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_elf_sections>() };
+                let ours = tag.downcast::<ElfSectionsTag>().unwrap();
+                assert_eq!(ours.number_of_sections(), grub.num);
+                assert_eq!(ours.entry_size(), grub.entsize);
+                assert_eq!(ours.shndx(), grub.shndx);
+            }
+            TagType::Apm => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_apm>() };
+                let ours = tag.downcast::<ApmTag>().unwrap();
+                assert_eq!(ours.version(), grub.version);
+                assert_eq!(ours.cseg(), grub.cseg);
+                assert_eq!(ours.offset(), grub.offset);
+                assert_eq!(ours.cseg_16(), grub.cseg_16);
+                assert_eq!(ours.dseg(), grub.dseg);
+                assert_eq!(ours.flags().bits(), grub.flags);
+                assert_eq!(ours.cseg_len(), grub.cseg_len);
+                assert_eq!(ours.cseg_16_len(), grub.cseg_16_len);
+                assert_eq!(ours.dseg_len(), grub.dseg_len);
+            }
+            TagType::Efi32 => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_efi32>() };
+                let ours = tag.downcast::<EFISdt32Tag>().unwrap();
+                assert_eq!(ours.sdt_address(), grub.pointer as usize);
+            }
+            TagType::Efi64 => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_efi64>() };
+                let ours = tag.downcast::<EFISdt64Tag>().unwrap();
+                assert_eq!(ours.sdt_address(), grub.pointer as usize);
+            }
+            TagType::Smbios => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_smbios>() };
+                let ours = tag.downcast::<SmbiosTag>().unwrap();
+                assert_eq!(ours.major(), grub.major);
+                assert_eq!(ours.minor(), grub.minor);
+                assert_eq!(ours.tables().as_ptr(), grub.tables.as_ptr());
+            }
+            TagType::AcpiV1 => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_old_acpi>() };
+                let ours = tag.downcast::<RsdpV1Tag>().unwrap();
+                let signature = unsafe { core::slice::from_raw_parts(grub.rsdp.as_ptr(), 8) };
+                assert_eq!(ours.signature().map(str::as_bytes), Ok(signature));
+            }
+            TagType::AcpiV2 => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_new_acpi>() };
+                let ours = tag.downcast::<RsdpV2Tag>().unwrap();
+                let signature = unsafe { core::slice::from_raw_parts(grub.rsdp.as_ptr(), 8) };
+                assert_eq!(ours.signature().map(str::as_bytes), Ok(signature));
+            }
+            TagType::Network => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_network>() };
+                let ours = tag.downcast::<NetworkTag>().unwrap();
+                assert_eq!(ours.dhcp_ack().as_ptr(), grub.dhcpack.as_ptr());
+            }
+            TagType::Efi32Ih => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_efi32_ih>() };
+                let ours = tag.downcast::<EFIImageHandle32Tag>().unwrap();
+                assert_eq!(ours.image_handle(), grub.pointer as usize);
+            }
+            TagType::Efi64Ih => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_efi64_ih>() };
+                let ours = tag.downcast::<EFIImageHandle64Tag>().unwrap();
+                assert_eq!(ours.image_handle(), grub.pointer as usize);
+            }
+            TagType::LoadBaseAddr => {
+                let grub = unsafe { &*ptr.cast::<multiboot_tag_load_base_addr>() };
+                let ours = tag.downcast::<ImageLoadPhysAddrTag>().unwrap();
+                assert_eq!(ours.load_base_addr(), grub.load_base_addr);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn arbitrary_boot_informations() {
+    for seed in 0..256 {
+        let mbi = arbitrary_boot_information(&mut Gen::new(seed));
+        let bi = unsafe { BootInformation::load(mbi.as_ptr().cast()) }.unwrap();
+        compare(&bi);
+    }
+}
@@ -0,0 +1,237 @@
+//! Tag structures of `include/multiboot2.h` of GRUB 2.12.
+//!
+//! The declarations are transcribed by hand from the header. They use the
+//! names of the C types and fields, with flexible array members as
+//! zero-length arrays and anonymous unions named like bindgen names them.
+//! Changes of the header must be transcribed manually.
+
+pub type multiboot_uint8_t = u8;
+pub type multiboot_uint16_t = u16;
+pub type multiboot_uint32_t = u32;
+pub type multiboot_uint64_t = u64;
+
+#[repr(C)]
+pub struct multiboot_color {
+    pub red: multiboot_uint8_t,
+    pub green: multiboot_uint8_t,
+    pub blue: multiboot_uint8_t,
+}
+
+#[repr(C)]
+pub struct multiboot_mmap_entry {
+    pub addr: multiboot_uint64_t,
+    pub len: multiboot_uint64_t,
+    pub type_: multiboot_uint32_t,
+    pub zero: multiboot_uint32_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_string {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub string: [core::ffi::c_char; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_module {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub mod_start: multiboot_uint32_t,
+    pub mod_end: multiboot_uint32_t,
+    pub cmdline: [core::ffi::c_char; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_basic_meminfo {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub mem_lower: multiboot_uint32_t,
+    pub mem_upper: multiboot_uint32_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_bootdev {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub biosdev: multiboot_uint32_t,
+    pub slice: multiboot_uint32_t,
+    pub part: multiboot_uint32_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_mmap {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub entry_size: multiboot_uint32_t,
+    pub entry_version: multiboot_uint32_t,
+    pub entries: [multiboot_mmap_entry; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_vbe_info_block {
+    pub external_specification: [multiboot_uint8_t; 512],
+}
+
+#[repr(C)]
+pub struct multiboot_vbe_mode_info_block {
+    pub external_specification: [multiboot_uint8_t; 256],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_vbe {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub vbe_mode: multiboot_uint16_t,
+    pub vbe_interface_seg: multiboot_uint16_t,
+    pub vbe_interface_off: multiboot_uint16_t,
+    pub vbe_interface_len: multiboot_uint16_t,
+    pub vbe_control_info: multiboot_vbe_info_block,
+    pub vbe_mode_info: multiboot_vbe_mode_info_block,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_framebuffer_common {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub framebuffer_addr: multiboot_uint64_t,
+    pub framebuffer_pitch: multiboot_uint32_t,
+    pub framebuffer_width: multiboot_uint32_t,
+    pub framebuffer_height: multiboot_uint32_t,
+    pub framebuffer_bpp: multiboot_uint8_t,
+    pub framebuffer_type: multiboot_uint8_t,
+    pub reserved: multiboot_uint16_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_framebuffer__bindgen_ty_1__bindgen_ty_1 {
+    pub framebuffer_palette_num_colors: multiboot_uint16_t,
+    pub framebuffer_palette: [multiboot_color; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_framebuffer__bindgen_ty_1__bindgen_ty_2 {
+    pub framebuffer_red_field_position: multiboot_uint8_t,
+    pub framebuffer_red_mask_size: multiboot_uint8_t,
+    pub framebuffer_green_field_position: multiboot_uint8_t,
+    pub framebuffer_green_mask_size: multiboot_uint8_t,
+    pub framebuffer_blue_field_position: multiboot_uint8_t,
+    pub framebuffer_blue_mask_size: multiboot_uint8_t,
+}
+
+#[repr(C)]
+pub union multiboot_tag_framebuffer__bindgen_ty_1 {
+    pub indexed: core::mem::ManuallyDrop<multiboot_tag_framebuffer__bindgen_ty_1__bindgen_ty_1>,
+    pub rgb: core::mem::ManuallyDrop<multiboot_tag_framebuffer__bindgen_ty_1__bindgen_ty_2>,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_framebuffer {
+    pub common: multiboot_tag_framebuffer_common,
+    pub __bindgen_anon_1: multiboot_tag_framebuffer__bindgen_ty_1,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_elf_sections {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub num: multiboot_uint32_t,
+    pub entsize: multiboot_uint32_t,
+    pub shndx: multiboot_uint32_t,
+    pub sections: [core::ffi::c_char; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_apm {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub version: multiboot_uint16_t,
+    pub cseg: multiboot_uint16_t,
+    pub offset: multiboot_uint32_t,
+    pub cseg_16: multiboot_uint16_t,
+    pub dseg: multiboot_uint16_t,
+    pub flags: multiboot_uint16_t,
+    pub cseg_len: multiboot_uint16_t,
+    pub cseg_16_len: multiboot_uint16_t,
+    pub dseg_len: multiboot_uint16_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_efi32 {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub pointer: multiboot_uint32_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_efi64 {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub pointer: multiboot_uint64_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_smbios {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub major: multiboot_uint8_t,
+    pub minor: multiboot_uint8_t,
+    pub reserved: [multiboot_uint8_t; 6],
+    pub tables: [multiboot_uint8_t; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_old_acpi {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub rsdp: [multiboot_uint8_t; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_new_acpi {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub rsdp: [multiboot_uint8_t; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_network {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub dhcpack: [multiboot_uint8_t; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_efi_mmap {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub descr_size: multiboot_uint32_t,
+    pub descr_vers: multiboot_uint32_t,
+    pub efi_mmap: [multiboot_uint8_t; 0],
+}
+
+#[repr(C)]
+pub struct multiboot_tag_efi32_ih {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub pointer: multiboot_uint32_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_efi64_ih {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub pointer: multiboot_uint64_t,
+}
+
+#[repr(C)]
+pub struct multiboot_tag_load_base_addr {
+    pub type_: multiboot_uint32_t,
+    pub size: multiboot_uint32_t,
+    pub load_base_addr: multiboot_uint32_t,
+}
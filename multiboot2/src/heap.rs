@@ -0,0 +1,109 @@
+//! Module for [`BootInformation::heap_region`].
+
+use crate::free_memory::{efi_desc_range, efi_is_available, mmap_area_range, SplitRanges};
+use crate::{BootInformation, MemoryAreaType};
+use core::ops::Range;
+
+/// Memory below this address is never used for the heap, as it contains
+/// legacy BIOS structures and is needed, e.g., for AP trampolines.
+const LOW_MEMORY_END: u64 = 0x10_0000;
+
+impl BootInformation<'_> {
+    /// Picks the region for the first heap of a kernel, e.g., to initialize a
+    /// `linked_list_allocator` or `talc` heap, and returns its start address
+    /// and size.
+    ///
+    /// The largest free chunk of memory is chosen, with its start aligned to
+    /// `align`. Of equally large chunks, the lowest one is chosen. Free memory
+    /// is taken from the EFI memory map if the boot services were exited and
+    /// from the Multiboot2 memory map otherwise.
+    /// Memory below 1 MiB, the boot information itself, the modules, and the
+    /// `exclude` ranges, such as the kernel image, are never used.
+    ///
+    /// Returns `None` if no chunk has at least `min_size` bytes.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two.
+    #[must_use]
+    pub fn heap_region(
+        &self,
+        min_size: u64,
+        align: u64,
+        exclude: &[Range<u64>],
+    ) -> Option<(u64, u64)> {
+        assert!(align.is_power_of_two(), "align must be a power of two");
+        let mbi = self.start_address() as u64..self.end_address() as u64;
+        let modules = self
+            .module_tags()
+            .map(|tag| u64::from(tag.start_address())..u64::from(tag.end_address()));
+        let used = || {
+            core::iter::once(0..LOW_MEMORY_END)
+                .chain(core::iter::once(mbi.clone()))
+                .chain(modules.clone())
+                .chain(exclude.iter().cloned())
+        };
+
+        let efi = self.efi_memory_map_tag();
+        let mmap = efi.map_or_else(|| self.memory_map_tag(), |_| None);
+        let efi = efi.into_iter().flat_map(|tag| {
+            tag.memory_areas()
+                .filter(|desc| efi_is_available(desc.ty))
+                .map(efi_desc_range)
+        });
+        let mmap = mmap.into_iter().flat_map(|tag| {
+            tag.memory_areas()
+                .iter()
+                .filter(|area| MemoryAreaType::from(area.typ()) == MemoryAreaType::Available)
+                .map(mmap_area_range)
+        });
+
+        efi.chain(mmap)
+            .flat_map(|area| SplitRanges::new(area, used))
+            .filter(|(_, used)| !used)
+            .filter_map(|(chunk, _)| {
+                let start = chunk.start.checked_next_multiple_of(align)?;
+                let size = chunk.end.checked_sub(start)?;
+                (size >= min_size && size > 0).then_some((start, size))
+            })
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use crate::{BootInformation, Builder, MemoryArea, MemoryAreaType, MemoryMapTag};
+
+    #[test]
+    fn heap_region() {
+        let mbi = Builder::new()
+            .mmap(MemoryMapTag::new(&[
+                MemoryArea::new(0, 0x9_fc00, MemoryAreaType::Available),
+                MemoryArea::new(0x10_0000, 0x70_0000, MemoryAreaType::Available),
+                MemoryArea::new(0x80_0000, 0x10_0000, MemoryAreaType::Reserved),
+                MemoryArea::new(0x90_0000, 0x30_0000, MemoryAreaType::Available),
+            ]))
+            .add_module(0x40_0000, 0x50_0000, "initrd")
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+
+        let kernel = 0x10_0000..0x20_0000;
+        let exclude = core::slice::from_ref(&kernel);
+        // Free chunks: 2 MiB after the kernel, 3 MiB after the module, and
+        // 3 MiB above the reserved memory. The lower one wins the tie.
+        assert_eq!(
+            mbi.heap_region(0x1000, 0x1000, exclude),
+            Some((0x50_0000, 0x30_0000))
+        );
+        let exclude = [kernel.clone(), 0x50_0000..0x50_1000];
+        assert_eq!(
+            mbi.heap_region(0x1000, 0x1000, &exclude),
+            Some((0x90_0000, 0x30_0000))
+        );
+        // With a 2 MiB alignment, all chunks shrink to 2 MiB.
+        assert_eq!(
+            mbi.heap_region(0x1000, 0x20_0000, &[kernel.clone()][..]),
+            Some((0x20_0000, 0x20_0000))
+        );
+        assert_eq!(mbi.heap_region(0x40_0000, 0x1000, &[]), None);
+    }
+}
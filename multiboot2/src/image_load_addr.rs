@@ -1,8 +1,7 @@
-//! Module for [`ImageLoadPhysAddrTag`].
+//! Module for [`ImageLoadPhysAddrTag`] and [`LoadSlide`].
 
-use crate::tag::TagHeader;
-use crate::TagType;
-#[cfg(feature = "builder")]
+use crate::tag::{parse_sized_tag, TagHeader, TagParseError};
+use crate::{PhysAddr, TagType};
 use core::mem::size_of;
 use multiboot2_common::{MaybeDynSized, Tag};
 
@@ -21,18 +20,36 @@ impl ImageLoadPhysAddrTag {
 
     /// Constructs a new tag.
     #[must_use]
-    pub fn new(load_base_addr: u32) -> Self {
+    pub const fn new(load_base_addr: u32) -> Self {
         Self {
-            header: TagHeader::new(Self::ID, Self::BASE_SIZE as u32),
+            header: TagHeader::new_const(Self::ID, Self::BASE_SIZE as u32),
             load_base_addr,
         }
     }
 
+    /// Parses the tag by value from unaligned bytes that include its padding.
+    pub const fn from_bytes(bytes: &[u8]) -> Result<Self, TagParseError> {
+        parse_sized_tag(bytes)
+    }
+
     /// Returns the load base address.
     #[must_use]
     pub const fn load_base_addr(&self) -> u32 {
         self.load_base_addr
     }
+
+    /// Like [`Self::load_base_addr`], but as [`PhysAddr`].
+    #[must_use]
+    pub const fn load_base_phys(&self) -> PhysAddr {
+        PhysAddr::new(self.load_base_addr as u64)
+    }
+
+    /// Returns the [`LoadSlide`] of the image relative to the given link-time
+    /// base address of the image.
+    #[must_use]
+    pub const fn slide(&self, link_base_addr: u64) -> LoadSlide {
+        LoadSlide::new(link_base_addr, self.load_base_addr as u64)
+    }
 }
 impl MaybeDynSized for ImageLoadPhysAddrTag {
     type Header = TagHeader;
@@ -48,9 +65,51 @@ impl Tag for ImageLoadPhysAddrTag {
     const ID: TagType = TagType::LoadBaseAddr;
 }
 
-#[cfg(all(test, feature = "builder"))]
+/// The offset between the address an image was linked for and the address it
+/// was actually loaded to.
+///
+/// Relocatable kernels need it to translate link-time addresses into physical
+/// addresses, e.g., before setting up page tables.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LoadSlide {
+    offset: i64,
+}
+
+impl LoadSlide {
+    /// Creates the slide from the link-time base address and the actual load
+    /// base address of the image.
+    #[must_use]
+    pub const fn new(link_base_addr: u64, load_base_addr: u64) -> Self {
+        Self {
+            offset: load_base_addr.wrapping_sub(link_base_addr) as i64,
+        }
+    }
+
+    /// Returns the signed offset between load and link address.
+    #[must_use]
+    pub const fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    /// Translates a link-time address into the corresponding address of the
+    /// loaded image.
+    #[must_use]
+    pub const fn apply(&self, addr: u64) -> u64 {
+        addr.wrapping_add_signed(self.offset)
+    }
+
+    /// Translates an address of the loaded image back into the corresponding
+    /// link-time address.
+    #[must_use]
+    pub const fn revert(&self, addr: u64) -> u64 {
+        addr.wrapping_sub(self.offset as u64)
+    }
+}
+
+#[cfg(test)]
 mod tests {
-    use super::ImageLoadPhysAddrTag;
+    use super::{ImageLoadPhysAddrTag, LoadSlide};
+    use multiboot2_common::MaybeDynSized;
 
     const ADDR: u32 = 0xABCDEF;
 
@@ -58,5 +117,31 @@ mod tests {
     fn test_build_load_addr() {
         let tag = ImageLoadPhysAddrTag::new(ADDR);
         assert_eq!(tag.load_base_addr(), ADDR);
+
+        #[rustfmt::skip]
+        let expected = [
+            21, 0, 0, 0,
+            12, 0, 0, 0,
+            0xef, 0xcd, 0xab, 0x00,
+        ];
+        assert_eq!(tag.header().size, 12);
+        assert_eq!(&tag.as_bytes().as_ref()[..12], &expected);
+    }
+
+    #[test]
+    fn test_slide() {
+        let tag = ImageLoadPhysAddrTag::new(0x200000);
+        let slide = tag.slide(0x100000);
+        assert_eq!(slide.offset(), 0x100000);
+        assert_eq!(slide.apply(0x101234), 0x201234);
+        assert_eq!(slide.revert(0x201234), 0x101234);
+
+        // Loaded below the link address.
+        let slide = LoadSlide::new(0x400000, 0x100000);
+        assert_eq!(slide.offset(), -0x300000);
+        assert_eq!(slide.apply(0x401000), 0x101000);
+        assert_eq!(slide.revert(0x101000), 0x401000);
+
+        assert_eq!(LoadSlide::new(0x1000, 0x1000), LoadSlide::default());
     }
 }
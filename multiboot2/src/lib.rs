@@ -1,5 +1,4 @@
 #![no_std]
-#![cfg_attr(feature = "unstable", feature(error_in_core))]
 // --- BEGIN STYLE CHECKS ---
 #![deny(
     clippy::all,
@@ -42,10 +41,10 @@
 //! ```
 //!
 //! ## MSRV
-//! The MSRV is 1.75.0 stable.
+//! The MSRV is 1.81.0 stable.
 
-#[cfg_attr(feature = "builder", macro_use)]
-#[cfg(feature = "builder")]
+#[cfg_attr(all(feature = "builder", not(test)), macro_use)]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
 // this crate can use std in tests only
@@ -53,73 +52,155 @@ extern crate alloc;
 #[cfg(test)]
 extern crate std;
 
-#[macro_use]
-extern crate bitflags;
-
 #[cfg(feature = "builder")]
 mod builder;
+#[cfg(feature = "builder")]
+mod e820;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "heap")]
+mod heap;
+#[cfg(feature = "limine")]
+mod limine;
+#[cfg(feature = "bootloader_api")]
+mod memory_regions;
+#[cfg(feature = "builder")]
+mod planner;
+#[cfg(feature = "snapshot")]
+mod snapshot;
+#[cfg(feature = "tag_cache")]
+mod tag_cache;
+#[cfg(any(feature = "testing", all(test, feature = "builder")))]
+pub mod testing;
+#[cfg(feature = "uefi")]
+mod uefi;
+#[cfg(feature = "vmm")]
+mod vmm;
+#[cfg(feature = "x86_64")]
+mod x86_64_addr;
 
 /// Iterator over the tags of a Multiboot2 boot information.
 pub type TagIter<'a> = multiboot2_common::TagIter<'a, TagHeader>;
 
-/// A generic version of all boot information tags.
-#[cfg(test)]
-pub type GenericInfoTag = multiboot2_common::DynSizedStructure<TagHeader>;
-
 mod apm;
 mod boot_information;
 mod boot_loader_name;
 mod bootdev;
+mod buffer_builder;
 mod command_line;
 mod efi;
+#[cfg(feature = "efi")]
+mod efi_pointers;
+#[cfg(feature = "elf")]
 mod elf_sections;
 mod end;
 mod framebuffer;
+mod free_memory;
+mod generic;
+#[cfg(all(
+    test,
+    feature = "builder",
+    feature = "efi",
+    feature = "elf",
+    feature = "net",
+    feature = "smbios",
+    feature = "vbe"
+))]
+mod grub_diff;
 mod image_load_addr;
 mod memory_map;
 mod module;
+pub mod multiboot1;
+#[cfg(feature = "net")]
 mod network;
+mod phys_addr;
+#[cfg(kani)]
+mod proofs;
 mod rsdp;
+#[cfg(feature = "smbios")]
 mod smbios;
+mod spec_compat;
+mod stable_format;
+mod static_boot_information;
 mod tag;
 mod tag_type;
 pub(crate) mod util;
+#[cfg(feature = "vbe")]
 mod vbe_info;
 
-pub use multiboot2_common::{DynSizedStructure, MaybeDynSized, Tag};
+pub use multiboot2_common::{DynSizedStructure, MaybeDynSized, MemoryError, Tag};
 
-pub use apm::ApmTag;
-pub use boot_information::{BootInformation, BootInformationHeader, LoadError};
+pub use apm::{ApmFlags, ApmTag};
+pub use boot_information::{
+    BootInformation, BootInformationHeader, LayoutError, LayoutViolation, LimitError, LoadError,
+    LoadLimits, ParsePolicy, ParseWarning, ParseWarningIter,
+};
 pub use boot_loader_name::BootLoaderNameTag;
-pub use bootdev::BootdevTag;
+pub use bootdev::{BiosDriveKind, BootdevTag};
+pub use buffer_builder::{BufferBuilder, BufferBuilderError, OutOfSpace};
 #[cfg(feature = "builder")]
-pub use builder::Builder;
-pub use command_line::CommandLineTag;
-pub use efi::{
-    EFIBootServicesNotExitedTag, EFIImageHandle32Tag, EFIImageHandle64Tag, EFISdt32Tag, EFISdt64Tag,
+pub use builder::{
+    BootInformationBuffer, Builder, BuilderError, DuplicatePolicy, GuestMemoryWrite, TagOrder,
+    ValidationProblem, WriteError,
 };
+pub use command_line::CommandLineTag;
+#[cfg(feature = "builder")]
+pub use e820::E820Entry;
+pub use efi::EFIBootServicesNotExitedTag;
+#[cfg(feature = "efi")]
+pub use efi_pointers::{EFIImageHandle32Tag, EFIImageHandle64Tag, EFISdt32Tag, EFISdt64Tag};
+#[cfg(feature = "elf")]
 pub use elf_sections::{
     ElfSection, ElfSectionFlags, ElfSectionIter, ElfSectionType, ElfSectionsTag,
 };
 pub use end::EndTag;
-pub use framebuffer::{FramebufferColor, FramebufferField, FramebufferTag, FramebufferType};
-pub use image_load_addr::ImageLoadPhysAddrTag;
+pub use framebuffer::{
+    FramebufferColor, FramebufferField, FramebufferFinding, FramebufferTag, FramebufferType,
+    UnknownFramebufferType,
+};
+pub use generic::{GenericTag, GenericTagIter, TagTypeFilter, TypedTagIter, VendorTagId};
+pub use image_load_addr::{ImageLoadPhysAddrTag, LoadSlide};
+#[cfg(feature = "limine")]
+pub use limine::{
+    LimineFile, LimineFramebuffer, LimineHandoff, LimineMemmapEntry, LimineMemoryType,
+};
 pub use memory_map::{
     BasicMemoryInfoTag, EFIMemoryAreaType, EFIMemoryAttribute, EFIMemoryDesc, EFIMemoryMapTag,
     MemoryArea, MemoryAreaType, MemoryAreaTypeId, MemoryMapTag,
 };
+#[cfg(feature = "bootloader_api")]
+pub use memory_regions::MemoryRegionsError;
 pub use module::{ModuleIter, ModuleTag};
-pub use network::NetworkTag;
+#[cfg(feature = "net")]
+pub use network::{BootpPacket, DhcpOption, DhcpOptionIter, Ipv4AddrIter, NetworkTag, TftpServer};
+pub use phys_addr::{PhysAddr, PhysRange};
+#[cfg(feature = "builder")]
+pub use planner::{BootLayout, BootLayoutPlanner, KernelPlacement, PlanError};
 pub use ptr_meta::Pointee;
 pub use rsdp::{RsdpV1Tag, RsdpV2Tag};
-pub use smbios::SmbiosTag;
-pub use tag::TagHeader;
+#[cfg(feature = "smbios")]
+pub use smbios::{
+    SmbiosEntryPoint, SmbiosStringIter, SmbiosStructure, SmbiosStructureIter, SmbiosTag,
+};
+#[cfg(feature = "snapshot")]
+pub use snapshot::Snapshot;
+pub use spec_compat::{SpecCompat, SpecFinding, SpecRevision};
+pub use stable_format::{StableFormat, StableFormatVersion};
+pub use static_boot_information::StaticBootInformation;
+pub use tag::{TagHeader, TagParseError};
 pub use tag_type::{TagType, TagTypeId};
-pub use util::{parse_slice_as_string, StringError};
+#[cfg(feature = "uefi")]
+pub use uefi::{
+    UefiCollector, UefiMemoryMap, UefiSystemTable, ACPI2_GUID, ACPI_GUID, SMBIOS3_GUID, SMBIOS_GUID,
+};
+pub use util::{parse_slice_as_string, parse_slice_as_string_lenient, StringError};
+#[cfg(feature = "vbe")]
 pub use vbe_info::{
     VBECapabilities, VBEControlInfo, VBEDirectColorAttributes, VBEField, VBEInfoTag,
     VBEMemoryModel, VBEModeAttributes, VBEModeInfo, VBEWindowAttributes,
 };
+#[cfg(feature = "vmm")]
+pub use vmm::{DirectBootPreset, RsdpError};
 
 /// Magic number that a Multiboot2-compliant boot loader will use to identify
 /// the handoff. The location depends on the architecture and the targeted
@@ -168,6 +249,7 @@ mod tests {
         assert_eq!(addr, bi.start_address());
         assert_eq!(addr + bytes.0.len(), bi.end_address());
         assert_eq!(bytes.0.len(), bi.total_size());
+        #[cfg(feature = "elf")]
         assert!(bi.elf_sections().is_none());
         assert!(bi.memory_map_tag().is_none());
         assert!(bi.module_tags().next().is_none());
@@ -191,6 +273,7 @@ mod tests {
         assert_eq!(addr, bi.start_address());
         assert_eq!(addr + bytes.0.len(), bi.end_address());
         assert_eq!(bytes.0.len(), bi.total_size());
+        #[cfg(feature = "elf")]
         assert!(bi.elf_sections().is_none());
         assert!(bi.memory_map_tag().is_none());
         assert!(bi.module_tags().next().is_none());
@@ -214,6 +297,7 @@ mod tests {
         assert_eq!(addr, bi.start_address());
         assert_eq!(addr + bytes.0.len(), bi.end_address());
         assert_eq!(bytes.0.len(), bi.total_size());
+        #[cfg(feature = "elf")]
         assert!(bi.elf_sections().is_none());
         assert!(bi.memory_map_tag().is_none());
         assert!(bi.module_tags().next().is_none());
@@ -221,6 +305,150 @@ mod tests {
         assert!(bi.command_line_tag().is_none());
     }
 
+    #[test]
+    fn load_limits() {
+        let bytes = AlignedBytes([
+            40, 0, 0, 0, // total_size
+            0, 0, 0, 0, // reserved
+            1, 0, 0, 0, // cmdline tag type
+            12, 0, 0, 0, // cmdline tag size
+            b'a', b'b', b'c', 0, // cmdline
+            0, 0, 0, 0, // padding
+            1, 0, 0, 0, // cmdline tag type
+            8, 0, 0, 0, // cmdline tag size
+            0, 0, 0, 0, // end tag type
+            8, 0, 0, 0, // end tag size
+        ]);
+        let ptr = bytes.0.as_ptr().cast();
+        let limits = LoadLimits {
+            max_tags: 3,
+            max_tag_size: 12,
+        };
+        assert!(unsafe { BootInformation::load_with_limits(ptr, limits) }.is_ok());
+        assert!(unsafe { BootInformation::load_checked(ptr) }.is_ok());
+
+        let err = unsafe {
+            BootInformation::load_with_limits(
+                ptr,
+                LoadLimits {
+                    max_tags: 2,
+                    ..limits
+                },
+            )
+        };
+        assert_eq!(
+            err.unwrap_err(),
+            LoadError::LimitExceeded(LimitError::TooManyTags { max: 2 })
+        );
+
+        let err = unsafe {
+            BootInformation::load_with_limits(
+                ptr,
+                LoadLimits {
+                    max_tag_size: 11,
+                    ..limits
+                },
+            )
+        };
+        assert_eq!(
+            err.unwrap_err(),
+            LoadError::LimitExceeded(LimitError::TagTooLarge {
+                offset: 8,
+                typ: TagType::Cmdline.into(),
+                size: 12,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_policy() {
+        let mut bytes = AlignedBytes([
+            48, 0, 0, 0, // total_size
+            0, 0, 0, 0, // reserved
+            1, 0, 0, 0, // cmdline tag type
+            12, 0, 0, 0, // cmdline tag size
+            b'a', b'b', b'c', 0, // cmdline
+            0, 0, 0, 0, // padding
+            3, 0, 0, 0, // module tag type, may repeat
+            8, 0, 0, 0, // module tag size
+            3, 0, 0, 0, // module tag type, may repeat
+            8, 0, 0, 0, // module tag size
+            1, 0, 0, 0, // cmdline tag type
+            8, 0, 0, 0, // cmdline tag size
+        ]);
+        let ptr = bytes.0.as_ptr().cast();
+        let duplicate = ParseWarning::DuplicateTag {
+            offset: 40,
+            typ: TagType::Cmdline.into(),
+        };
+
+        // The last tag is no end tag.
+        let mbi = unsafe { BootInformation::load_with_policy(ptr, ParsePolicy::Lenient) }.unwrap();
+        assert_eq!(
+            mbi.warnings().collect::<std::vec::Vec<_>>(),
+            [duplicate, ParseWarning::MissingEndTag]
+        );
+        assert_eq!(mbi.command_line_tag().unwrap().cmdline(), Ok("abc"));
+        assert_eq!(
+            unsafe { BootInformation::load_with_policy(ptr, ParsePolicy::Strict) }.unwrap_err(),
+            LoadError::from(duplicate)
+        );
+
+        // Turn the duplicate into an end tag.
+        bytes.0[40] = 0;
+        let ptr = bytes.0.as_ptr().cast();
+        let mbi = unsafe { BootInformation::load_with_policy(ptr, ParsePolicy::Strict) }.unwrap();
+        assert_eq!(mbi.warnings().next(), None);
+
+        // Layout violations are rejected with either policy.
+        bytes.0[12] = 4;
+        let ptr = bytes.0.as_ptr().cast();
+        assert!(matches!(
+            unsafe { BootInformation::load_with_policy(ptr, ParsePolicy::Lenient) },
+            Err(LoadError::Layout(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_layout() {
+        let mut bytes = AlignedBytes([
+            32, 0, 0, 0, // total_size
+            0, 0, 0, 0, // reserved
+            1, 0, 0, 0, // cmdline tag type
+            16, 0, 0, 0, // cmdline tag size
+            b'a', b'b', b'c', 0, // cmdline
+            0, 0, 0, 0, // padding
+            0, 0, 0, 0, // end tag type
+            8, 0, 0, 0, // end tag size
+        ]);
+        let ptr = bytes.0.as_ptr();
+        let bi = unsafe { BootInformation::load_checked(ptr.cast()) }.unwrap();
+        assert_eq!(bi.validate_layout(), Ok(()));
+        assert_eq!(bi.command_line_tag().unwrap().cmdline(), Ok("abc"));
+
+        // The tag is smaller than its header.
+        bytes.0[12] = 4;
+        let err = unsafe { BootInformation::load_checked(bytes.0.as_ptr().cast()) }.unwrap_err();
+        let expected = LayoutError {
+            offset: 8,
+            typ: TagType::Cmdline.into(),
+            violation: LayoutViolation::Overlapping,
+        };
+        assert_eq!(err, LoadError::Layout(expected));
+
+        // The padded tag exceeds the total size.
+        bytes.0[12] = 25;
+        let bi = unsafe { BootInformation::load(bytes.0.as_ptr().cast()) }.unwrap();
+        assert_eq!(
+            bi.validate_layout(),
+            Err(LayoutError {
+                offset: 8,
+                typ: TagType::Cmdline.into(),
+                violation: LayoutViolation::OutOfBounds,
+            })
+        );
+    }
+
     #[test]
     fn name_tag() {
         let bytes = AlignedBytes([
@@ -240,6 +468,7 @@ mod tests {
         assert_eq!(addr, bi.start_address());
         assert_eq!(addr + bytes.0.len(), bi.end_address());
         assert_eq!(bytes.0.len(), bi.total_size());
+        #[cfg(feature = "elf")]
         assert!(bi.elf_sections().is_none());
         assert!(bi.memory_map_tag().is_none());
         assert!(bi.module_tags().next().is_none());
@@ -385,6 +614,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "vbe")]
     #[test]
     #[allow(clippy::cognitive_complexity)]
     fn vbe_info_tag() {
@@ -543,6 +773,7 @@ mod tests {
         assert_eq!({ vbe.mode_info().offscreen_memory_size }, 0);
     }
 
+    #[cfg(feature = "vbe")]
     #[test]
     /// Compile time test for [`VBEInfoTag`].
     fn vbe_info_tag_size() {
@@ -552,6 +783,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "elf")]
     /// Tests to parse a MBI that was statically extracted from a test run with
     /// GRUB as bootloader.
     #[test]
@@ -816,12 +1048,36 @@ mod tests {
         let bi = bi.unwrap();
         test_grub2_boot_info(&bi, addr, string_addr, &bytes.0, &string_bytes.0);
 
+        // The size of each tag excludes its padding, e.g., of the command line
+        // with a size of 9 or of the boot loader name with a size of 26.
+        assert_eq!(bi.validate_layout(), Ok(()));
+        let mut offset = mem::size_of::<BootInformationHeader>();
+        for tag in bi.generic_tags() {
+            let tag_addr = core::ptr::addr_of!(*tag).cast::<u8>() as usize;
+            assert_eq!(tag_addr - addr, offset);
+            assert_eq!(
+                tag.payload().len() + mem::size_of::<TagHeader>(),
+                tag.header().size as usize
+            );
+            offset += (tag.header().size as usize).next_multiple_of(8);
+        }
+        assert_eq!(offset, bi.total_size());
+        assert_eq!(bi.command_line_tag().unwrap().cmdline(), Ok(""));
+
+        // Rebuilding the MBI reproduces the padding byte for byte.
+        #[cfg(feature = "builder")]
+        assert_eq!(
+            Builder::from_boot_information(&bi).build().as_bytes(),
+            &bytes.0[..]
+        );
+
         // Check that the MBI's debug output can be printed without SEGFAULT.
         // If this works, it is a good indicator than transitively a lot of
         // stuff works.
         println!("{bi:#?}");
     }
 
+    #[cfg(feature = "elf")]
     /// Helper for [`grub2`].
     #[allow(clippy::cognitive_complexity)]
     fn test_grub2_boot_info(
@@ -837,6 +1093,7 @@ mod tests {
         let mut es = bi.elf_sections().unwrap();
         let s1 = es.next().expect("Should have one more section");
         assert_eq!(".rodata", s1.name().expect("Should be valid utf-8"));
+        assert_eq!(b".rodata", s1.name_bytes());
         assert_eq!(0xFFFF_8000_0010_0000, s1.start_address());
         assert_eq!(0xFFFF_8000_0010_3000, s1.end_address());
         assert_eq!(0x0000_0000_0000_3000, s1.size());
@@ -959,6 +1216,7 @@ mod tests {
         assert_eq!(fbi.buffer_type(), Ok(FramebufferType::Text));
     }
 
+    #[cfg(feature = "elf")]
     #[test]
     fn elf_sections() {
         let mut bytes = AlignedBytes([
@@ -1033,6 +1291,125 @@ mod tests {
         assert!(es.next().is_none());
     }
 
+    /// The reported number of sections and the string table index must not
+    /// lead to reads beyond the tag.
+    #[test]
+    #[cfg(all(feature = "builder", feature = "elf"))]
+    fn elf_sections_out_of_bounds() {
+        let mut sections = [0_u8; 64];
+        sections[4..8].copy_from_slice(&1_u32.to_ne_bytes());
+        let mbi = Builder::new()
+            .elf_sections(ElfSectionsTag::new(1000, 64, 1000, &sections))
+            .build();
+        let bi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        let mut iter = bi.elf_sections().unwrap();
+        let section = iter.next().unwrap();
+        assert_eq!(section.section_type_raw(), 1);
+        assert_eq!(section.name(), Ok(""));
+        assert!(iter.next().is_none());
+    }
+
+    /// Exercises every DST tag of a boot information that is provided in a
+    /// misaligned buffer. Under Miri, this checks the casts and accessors for
+    /// UB.
+    #[test]
+    #[cfg(all(
+        feature = "builder",
+        feature = "efi",
+        feature = "elf",
+        feature = "net",
+        feature = "smbios"
+    ))]
+    fn dst_tags_from_misaligned_buffer() {
+        let string_table = AlignedBytes(*b"\0.shstrtab\0\0\0\0\0");
+        // A null section and the section header string table.
+        let mut sections = [0_u8; 128];
+        sections[64..68].copy_from_slice(&1_u32.to_ne_bytes());
+        sections[68..72].copy_from_slice(&3_u32.to_ne_bytes());
+        let string_table_addr = string_table.0.as_ptr() as u64;
+        sections[80..88].copy_from_slice(&string_table_addr.to_ne_bytes());
+        sections[96..104].copy_from_slice(&11_u64.to_ne_bytes());
+
+        let palette = [FramebufferColor {
+            red: 1,
+            green: 2,
+            blue: 3,
+        }];
+        let source = Builder::new()
+            .cmdline(CommandLineTag::new("root=/dev/sda1"))
+            .bootloader(BootLoaderNameTag::new("GRUB 2.12"))
+            .add_module(0x1000, 0x2000, "initrd")
+            .add_module(0x3000, 0x4000, "")
+            .mmap(MemoryMapTag::new(&[MemoryArea::new(
+                0,
+                0x9fc00,
+                MemoryAreaType::Available,
+            )]))
+            .framebuffer(FramebufferTag::new(
+                0xfd00_0000,
+                1024,
+                1024,
+                768,
+                8,
+                FramebufferType::Indexed { palette: &palette },
+            ))
+            .elf_sections(ElfSectionsTag::new(2, 64, 1, &sections))
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[EFIMemoryDesc {
+                ty: EFIMemoryAreaType::CONVENTIONAL,
+                phys_start: 0x1000,
+                page_count: 4,
+                ..Default::default()
+            }]))
+            .add_smbios(SmbiosTag::new(3, 0, &[1, 2, 3, 4, 5]))
+            .network(NetworkTag::new(&[0x02; 300]))
+            .build();
+
+        let mut buffer = AlignedBytes([0_u8; 1024]);
+        let misaligned = &mut buffer.0[1..][..source.len()];
+        misaligned.copy_from_slice(source.as_bytes());
+        assert_eq!(
+            unsafe { BootInformation::load(misaligned.as_ptr().cast()) }.err(),
+            Some(LoadError::Memory(MemoryError::WrongAlignment))
+        );
+
+        let copy = BootInformationBuffer::from_bytes(misaligned).unwrap();
+        let bi = unsafe { BootInformation::load_checked(copy.as_ptr()) }.unwrap();
+
+        assert_eq!(
+            bi.command_line_tag().unwrap().cmdline(),
+            Ok("root=/dev/sda1")
+        );
+        assert_eq!(bi.boot_loader_name_tag().unwrap().name(), Ok("GRUB 2.12"));
+        let cmdlines = bi
+            .module_tags()
+            .map(|module| module.cmdline().unwrap())
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(cmdlines, ["initrd", ""]);
+        assert_eq!(
+            bi.memory_map_tag().unwrap().memory_areas()[0].size(),
+            0x9fc00
+        );
+        assert_eq!(
+            bi.framebuffer_tag().unwrap().unwrap().buffer_type(),
+            Ok(FramebufferType::Indexed { palette: &palette })
+        );
+        let section = bi.elf_sections().unwrap().next().unwrap();
+        assert_eq!(section.name(), Ok(".shstrtab"));
+        assert_eq!(section.size(), 11);
+        let desc = bi
+            .efi_memory_map_tag()
+            .unwrap()
+            .memory_areas()
+            .next()
+            .unwrap();
+        assert_eq!(desc.page_count, 4);
+        assert_eq!(bi.smbios_tag().unwrap().tables(), &[1, 2, 3, 4, 5]);
+        assert_eq!(bi.network_tag().unwrap().dhcp_ack(), &[0x02; 300]);
+
+        // Reads all fields of all tags.
+        let _ = format!("{bi:?}");
+    }
+
     #[test]
     fn efi_memory_map() {
         // test that the EFI memory map is detected.
@@ -1100,11 +1477,130 @@ mod tests {
     }
 
     #[test]
-    #[cfg(feature = "unstable")]
     /// This test succeeds if it compiles.
     fn mbi_load_error_implements_error() {
         fn consumer<E: core::error::Error>(_e: E) {}
-        consumer(LoadError::NoEndTag)
+        consumer(LoadError::NoEndTag);
+        consumer(MemoryError::Null);
+        consumer(framebuffer::FramebufferTypeId::try_from(7).unwrap_err());
+        #[cfg(feature = "builder")]
+        consumer(BuilderError::OutOfMemory);
+    }
+
+    #[test]
+    fn mbi_load_error_source() {
+        use core::error::Error;
+        let err = LoadError::Memory(MemoryError::WrongAlignment);
+        let source = err.source().unwrap().downcast_ref::<MemoryError>();
+        assert_eq!(source, Some(&MemoryError::WrongAlignment));
+        assert!(LoadError::NoEndTag.source().is_none());
+    }
+
+    /// Feeds tags with sizes around their fixed-size part and pseudo-random
+    /// payloads through the getters. Undersized tags must be reported as
+    /// absent instead of panicking or wrapping around.
+    #[test]
+    fn fuzz_undersized_tags() {
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next_byte = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        };
+
+        let types = [
+            (TagType::Cmdline, CommandLineTag::BASE_SIZE, true),
+            (TagType::BootLoaderName, BootLoaderNameTag::BASE_SIZE, true),
+            (TagType::Module, ModuleTag::BASE_SIZE, true),
+            (TagType::Mmap, MemoryMapTag::BASE_SIZE, true),
+            (TagType::Framebuffer, FramebufferTag::BASE_SIZE, true),
+            (TagType::Apm, ApmTag::BASE_SIZE, false),
+            (TagType::Bootdev, BootdevTag::BASE_SIZE, false),
+            (TagType::BasicMeminfo, BasicMemoryInfoTag::BASE_SIZE, false),
+            (
+                TagType::LoadBaseAddr,
+                ImageLoadPhysAddrTag::BASE_SIZE,
+                false,
+            ),
+            #[cfg(feature = "elf")]
+            (TagType::ElfSections, ElfSectionsTag::BASE_SIZE, true),
+            #[cfg(feature = "net")]
+            (TagType::Network, NetworkTag::BASE_SIZE, true),
+            #[cfg(feature = "smbios")]
+            (TagType::Smbios, SmbiosTag::BASE_SIZE, true),
+            #[cfg(feature = "efi")]
+            (TagType::EfiMmap, EFIMemoryMapTag::BASE_SIZE, true),
+            #[cfg(feature = "efi")]
+            (TagType::Efi64, EFISdt64Tag::BASE_SIZE, false),
+        ];
+
+        for (typ, base_size, is_dst) in types {
+            for size in mem::size_of::<TagHeader>()..base_size + 16 {
+                let mut bytes = AlignedBytes([0_u8; 128]);
+                let padded_size = size.next_multiple_of(8);
+                let total_size = 8 + padded_size + 8;
+                bytes.0[0..4].copy_from_slice(&(total_size as u32).to_le_bytes());
+                bytes.0[8..12].copy_from_slice(&u32::from(typ).to_le_bytes());
+                bytes.0[12..16].copy_from_slice(&(size as u32).to_le_bytes());
+                for byte in &mut bytes.0[16..8 + size] {
+                    *byte = next_byte();
+                }
+                // End tag.
+                bytes.0[8 + padded_size + 4] = 8;
+
+                let bi = unsafe { BootInformation::load(bytes.0.as_ptr().cast()) }.unwrap();
+                let present = match typ {
+                    TagType::Cmdline => bi.command_line_tag().is_some(),
+                    TagType::BootLoaderName => bi.boot_loader_name_tag().is_some(),
+                    TagType::Module => bi.module_tags().next().is_some(),
+                    TagType::Mmap => bi.memory_map_tag().is_some(),
+                    TagType::Framebuffer => bi.get_tag::<FramebufferTag>().is_some(),
+                    TagType::Apm => bi.apm_tag().is_some(),
+                    TagType::Bootdev => bi.bootdev_tag().is_some(),
+                    TagType::BasicMeminfo => bi.basic_memory_info_tag().is_some(),
+                    TagType::LoadBaseAddr => bi.load_base_addr_tag().is_some(),
+                    #[cfg(feature = "elf")]
+                    TagType::ElfSections => bi.get_tag::<ElfSectionsTag>().is_some(),
+                    #[cfg(feature = "net")]
+                    TagType::Network => bi.network_tag().is_some(),
+                    #[cfg(feature = "smbios")]
+                    TagType::Smbios => bi.smbios_tag().is_some(),
+                    #[cfg(feature = "efi")]
+                    TagType::EfiMmap => bi.get_tag::<EFIMemoryMapTag>().is_some(),
+                    #[cfg(feature = "efi")]
+                    TagType::Efi64 => bi.efi_sdt64_tag().is_some(),
+                    _ => unreachable!(),
+                };
+                // Sized tags are compared with their size including padding.
+                let checked_size = if is_dst { size } else { padded_size };
+                if checked_size < base_size {
+                    assert!(!present, "{typ:?} with size {size}");
+                }
+                assert!(bi.get_generic_tag(typ).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn try_get_tag_reports_corrupt_tag() {
+        #[rustfmt::skip]
+        let bytes = AlignedBytes([
+            24, 0, 0, 0, // total size
+            0, 0, 0, 0, // reserved
+            5, 0, 0, 0, // bootdev tag type
+            8, 0, 0, 0, // bootdev tag size, too small for the payload
+            0, 0, 0, 0, // end tag type
+            8, 0, 0, 0, // end tag size
+        ]);
+        let bi = unsafe { BootInformation::load(bytes.0.as_ptr().cast()) }.unwrap();
+
+        assert!(bi.get_tag::<BootdevTag>().is_none());
+        assert_eq!(
+            bi.try_get_tag::<BootdevTag>(),
+            Err(TagParseError::Memory(MemoryError::InvalidReportedTotalSize))
+        );
+        assert_eq!(bi.try_get_tag::<ApmTag>(), Ok(None));
     }
 
     /// Example for a custom tag.
@@ -1305,4 +1801,43 @@ mod tests {
 
         let _tag = bi.get_tag::<CommandLineTag>().unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn contains_and_tag_count() {
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("cmdline"))
+            .add_module(0x1000, 0x2000, "a")
+            .add_module(0x3000, 0x4000, "b")
+            .build();
+        let bi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+
+        assert!(bi.contains::<CommandLineTag>());
+        assert!(bi.contains::<ModuleTag>());
+        assert!(!bi.contains::<BootLoaderNameTag>());
+        assert!(!bi.contains::<FramebufferTag>());
+        assert_eq!(bi.tag_count(), 4);
+        assert_eq!(bi.tag_count(), bi.tags().count());
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn cmdline() {
+        for (payload, cmdline) in [
+            (&b"quiet splash\0"[..], "quiet splash"),
+            (b"no nul", "no nul"),
+            (b"ab\xffcd\0", "ab"),
+            (b"", ""),
+        ] {
+            let mbi = Builder::new()
+                .add_raw_tag(TagType::Cmdline, payload)
+                .build();
+            let bi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+            assert_eq!(bi.cmdline(), cmdline);
+        }
+
+        let mbi = Builder::new().build();
+        let bi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        assert_eq!(bi.cmdline(), "");
+    }
 }
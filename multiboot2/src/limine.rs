@@ -0,0 +1,291 @@
+//! Module for [`LimineHandoff`].
+
+use crate::free_memory::EFI_PAGE_SIZE;
+use crate::tag::TagHeader;
+use crate::{
+    BootInformation, EFIMemoryAreaType, FramebufferType, MemoryAreaType, RsdpV1Tag, RsdpV2Tag,
+};
+use core::mem::size_of;
+
+/// Type of a [`LimineMemmapEntry`], with the values of the Limine protocol.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u64)]
+pub enum LimineMemoryType {
+    /// Free memory.
+    Usable = 0,
+    /// Memory that must not be used.
+    Reserved = 1,
+    /// Memory holding ACPI tables that can be reclaimed once they are parsed.
+    AcpiReclaimable = 2,
+    /// ACPI non-volatile storage.
+    AcpiNvs = 3,
+    /// Defective memory.
+    BadMemory = 4,
+    /// Memory used by the bootloader that can be reclaimed once the
+    /// information it holds is no longer needed.
+    BootloaderReclaimable = 5,
+    /// Memory holding the kernel image and the modules.
+    KernelAndModules = 6,
+    /// The framebuffer.
+    Framebuffer = 7,
+}
+
+impl From<MemoryAreaType> for LimineMemoryType {
+    fn from(value: MemoryAreaType) -> Self {
+        match value {
+            MemoryAreaType::Available => Self::Usable,
+            MemoryAreaType::AcpiAvailable => Self::AcpiReclaimable,
+            MemoryAreaType::ReservedHibernate => Self::AcpiNvs,
+            MemoryAreaType::Defective => Self::BadMemory,
+            MemoryAreaType::Reserved | MemoryAreaType::Custom(_) => Self::Reserved,
+        }
+    }
+}
+
+impl From<EFIMemoryAreaType> for LimineMemoryType {
+    fn from(value: EFIMemoryAreaType) -> Self {
+        match value {
+            EFIMemoryAreaType::CONVENTIONAL
+            | EFIMemoryAreaType::BOOT_SERVICES_CODE
+            | EFIMemoryAreaType::BOOT_SERVICES_DATA => Self::Usable,
+            EFIMemoryAreaType::LOADER_CODE | EFIMemoryAreaType::LOADER_DATA => {
+                Self::BootloaderReclaimable
+            }
+            EFIMemoryAreaType::ACPI_RECLAIM => Self::AcpiReclaimable,
+            EFIMemoryAreaType::ACPI_NON_VOLATILE => Self::AcpiNvs,
+            EFIMemoryAreaType::UNUSABLE => Self::BadMemory,
+            _ => Self::Reserved,
+        }
+    }
+}
+
+/// An entry of the Limine memory map response.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct LimineMemmapEntry {
+    /// Physical start address.
+    pub base: u64,
+    /// Length in bytes.
+    pub length: u64,
+    /// Type of the memory.
+    pub typ: LimineMemoryType,
+}
+
+/// A module, like a file of the Limine module response.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LimineFile<'a> {
+    /// Physical address of the module.
+    pub address: u64,
+    /// Size of the module in bytes.
+    pub size: u64,
+    /// Command line of the module. Empty if it isn't valid UTF-8.
+    pub cmdline: &'a str,
+}
+
+/// A framebuffer of the Limine framebuffer response.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LimineFramebuffer {
+    /// Physical address of the framebuffer.
+    pub address: u64,
+    /// Width in pixels.
+    pub width: u64,
+    /// Height in pixels.
+    pub height: u64,
+    /// Bytes per line.
+    pub pitch: u64,
+    /// Bits per pixel.
+    pub bpp: u16,
+    /// Memory model. Always `1` (RGB), the only model Limine defines.
+    pub memory_model: u8,
+    /// Size of the red mask in bits.
+    pub red_mask_size: u8,
+    /// Position of the red mask.
+    pub red_mask_shift: u8,
+    /// Size of the green mask in bits.
+    pub green_mask_size: u8,
+    /// Position of the green mask.
+    pub green_mask_shift: u8,
+    /// Size of the blue mask in bits.
+    pub blue_mask_size: u8,
+    /// Position of the blue mask.
+    pub blue_mask_shift: u8,
+}
+
+/// One-way translation of a [`BootInformation`] into structures modelled
+/// after the responses of the Limine boot protocol.
+///
+/// This helps kernels written for Limine to accept a Multiboot2 handoff with
+/// little glue. Each getter corresponds to one Limine request and returns
+/// `None` if the boot information lacks the corresponding tag.
+#[derive(Copy, Clone, Debug)]
+pub struct LimineHandoff<'a> {
+    info: &'a BootInformation<'a>,
+}
+
+impl<'a> LimineHandoff<'a> {
+    /// Wraps the boot information.
+    #[must_use]
+    pub const fn new(info: &'a BootInformation<'a>) -> Self {
+        Self { info }
+    }
+
+    /// The memory map, taken from the EFI memory map if the boot services
+    /// were exited and from the Multiboot2 memory map otherwise.
+    ///
+    /// Unlike Limine, the memory of the modules is not reported separately,
+    /// but is part of the surrounding area.
+    pub fn memmap(&self) -> impl Iterator<Item = LimineMemmapEntry> + 'a {
+        let efi = self.info.efi_memory_map_tag();
+        let mmap = efi.map_or_else(|| self.info.memory_map_tag(), |_| None);
+        let efi = efi.into_iter().flat_map(|tag| {
+            tag.memory_areas().map(|desc| LimineMemmapEntry {
+                base: desc.phys_start,
+                length: desc.page_count.saturating_mul(EFI_PAGE_SIZE),
+                typ: desc.ty.into(),
+            })
+        });
+        let mmap = mmap.into_iter().flat_map(|tag| {
+            tag.memory_areas().iter().map(|area| LimineMemmapEntry {
+                base: area.start_address(),
+                length: area.size(),
+                typ: MemoryAreaType::from(area.typ()).into(),
+            })
+        });
+        efi.chain(mmap)
+    }
+
+    /// The modules.
+    pub fn modules(&self) -> impl Iterator<Item = LimineFile<'a>> + 'a {
+        self.info.module_tags().map(|tag| LimineFile {
+            address: tag.start_address().into(),
+            size: tag.module_size().into(),
+            cmdline: tag.cmdline().unwrap_or_default(),
+        })
+    }
+
+    /// The framebuffer. Returns `None` if there is none or if it isn't an
+    /// RGB framebuffer, as Limine knows no other kind.
+    #[must_use]
+    pub fn framebuffer(&self) -> Option<LimineFramebuffer> {
+        let tag = self.info.framebuffer_tag()?.ok()?;
+        let FramebufferType::RGB { red, green, blue } = tag.buffer_type().ok()? else {
+            return None;
+        };
+        Some(LimineFramebuffer {
+            address: tag.address(),
+            width: tag.width().into(),
+            height: tag.height().into(),
+            pitch: tag.pitch().into(),
+            bpp: tag.bpp().into(),
+            memory_model: 1,
+            red_mask_size: red.size,
+            red_mask_shift: red.position,
+            green_mask_size: green.size,
+            green_mask_shift: green.position,
+            blue_mask_size: blue.size,
+            blue_mask_shift: blue.position,
+        })
+    }
+
+    /// Address of the RSDP. This is the copy inside the boot information, as
+    /// the Multiboot2 tags don't report the address of the original. The
+    /// RSDP of ACPI 2.0 or later is preferred.
+    #[must_use]
+    pub fn rsdp_address(&self) -> Option<u64> {
+        let tag = self.info.rsdp_v2_tag().map_or_else(
+            || {
+                self.info
+                    .rsdp_v1_tag()
+                    .map(|tag| (tag as *const RsdpV1Tag).cast::<u8>())
+            },
+            |tag| Some((tag as *const RsdpV2Tag).cast::<u8>()),
+        )?;
+        Some(tag as u64 + size_of::<TagHeader>() as u64)
+    }
+
+    /// The kernel command line.
+    #[must_use]
+    pub fn cmdline(&self) -> Option<&'a str> {
+        self.info.command_line_tag()?.cmdline().ok()
+    }
+
+    /// Physical base address of the kernel, like the physical base of the
+    /// Limine executable address response.
+    #[must_use]
+    pub fn executable_physical_base(&self) -> Option<u64> {
+        self.info
+            .load_base_addr_tag()
+            .map(|tag| tag.load_base_addr().into())
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{
+        Builder, CommandLineTag, FramebufferField, FramebufferTag, MemoryArea, MemoryMapTag,
+    };
+    use alloc::vec::Vec;
+
+    #[test]
+    fn translate() {
+        let field = |position, size| FramebufferField { position, size };
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("console=ttyS0"))
+            .add_module(0x20_0000, 0x20_1000, "initrd")
+            .mmap(MemoryMapTag::new(&[
+                MemoryArea::new(0, 0x9_f000, MemoryAreaType::Available),
+                MemoryArea::new(0xe_0000, 0x2_0000, MemoryAreaType::Reserved),
+                MemoryArea::new(0x10_0000, 0x10_0000, MemoryAreaType::AcpiAvailable),
+            ]))
+            .framebuffer(FramebufferTag::new(
+                0xfd00_0000,
+                4096,
+                1024,
+                768,
+                32,
+                FramebufferType::RGB {
+                    red: field(16, 8),
+                    green: field(8, 8),
+                    blue: field(0, 8),
+                },
+            ))
+            .rsdpv1(RsdpV1Tag::new(0, *b"OEMOEM", 0, 0x7fe_0000))
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        let limine = LimineHandoff::new(&mbi);
+
+        assert_eq!(limine.cmdline(), Some("console=ttyS0"));
+        assert_eq!(
+            limine.modules().collect::<Vec<_>>(),
+            [LimineFile {
+                address: 0x20_0000,
+                size: 0x1000,
+                cmdline: "initrd",
+            }]
+        );
+        assert_eq!(
+            limine
+                .memmap()
+                .map(|entry| (entry.base, entry.length, entry.typ))
+                .collect::<Vec<_>>(),
+            [
+                (0, 0x9_f000, LimineMemoryType::Usable),
+                (0xe_0000, 0x2_0000, LimineMemoryType::Reserved),
+                (0x10_0000, 0x10_0000, LimineMemoryType::AcpiReclaimable),
+            ]
+        );
+
+        let fb = limine.framebuffer().unwrap();
+        assert_eq!(fb.address, 0xfd00_0000);
+        assert_eq!(
+            (fb.width, fb.height, fb.pitch, fb.bpp),
+            (1024, 768, 4096, 32)
+        );
+        assert_eq!((fb.red_mask_shift, fb.red_mask_size), (16, 8));
+
+        let rsdp = limine.rsdp_address().unwrap() as *const [u8; 8];
+        assert_eq!(unsafe { *rsdp }, RsdpV1Tag::SIGNATURE);
+        assert_eq!(limine.executable_physical_base(), None);
+    }
+}
@@ -1,18 +1,15 @@
 //! Module for [`MemoryMapTag`], [`EFIMemoryMapTag`] and [`BasicMemoryInfoTag`]
 //! and corresponding helper types.
 
-pub use uefi_raw::table::boot::MemoryAttribute as EFIMemoryAttribute;
-pub use uefi_raw::table::boot::MemoryDescriptor as EFIMemoryDesc;
-pub use uefi_raw::table::boot::MemoryType as EFIMemoryAreaType;
-
-use crate::tag::TagHeader;
-use crate::{TagType, TagTypeId};
+use crate::free_memory::mmap_area_range;
+use crate::tag::{parse_sized_tag, TagHeader, TagParseError};
+use crate::{PhysAddr, PhysRange, TagType};
+use bitflags::bitflags;
 use core::fmt::{Debug, Formatter};
-use core::marker::PhantomData;
-use core::mem;
+use core::{mem, ptr};
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, core::slice, multiboot2_common::new_boxed};
+use {crate::E820Entry, alloc::boxed::Box, core::slice, multiboot2_common::new_boxed};
 
 /// This tag provides an initial host memory map (legacy boot, not UEFI).
 ///
@@ -49,6 +46,14 @@ impl MemoryMapTag {
         new_boxed(header, &[&entry_size, &entry_version, areas])
     }
 
+    /// Constructs a new tag from raw E820 output. See
+    /// [`E820Entry::to_memory_areas`] for how the entries are sanitized.
+    #[cfg(feature = "builder")]
+    #[must_use]
+    pub fn from_e820(entries: &[E820Entry]) -> Box<Self> {
+        Self::new(&E820Entry::to_memory_areas(entries))
+    }
+
     /// Returns the entry size.
     #[must_use]
     pub const fn entry_size(&self) -> u32 {
@@ -65,10 +70,15 @@ impl MemoryMapTag {
     ///
     /// Usually, this should already reflect the memory consumed by the
     /// code running this.
+    ///
+    /// The slice is empty if the reported entry size doesn't match
+    /// [`MemoryArea`].
     #[must_use]
-    pub fn memory_areas(&self) -> &[MemoryArea] {
-        // If this ever fails, we need to model this differently in this crate.
-        assert_eq!(self.entry_size as usize, mem::size_of::<MemoryArea>());
+    pub const fn memory_areas(&self) -> &[MemoryArea] {
+        // If this ever happens, we need to model this differently in this crate.
+        if self.entry_size as usize != mem::size_of::<MemoryArea>() {
+            return &[];
+        }
         &self.areas
     }
 }
@@ -79,10 +89,13 @@ impl MaybeDynSized for MemoryMapTag {
     const BASE_SIZE: usize = mem::size_of::<TagHeader>() + 2 * mem::size_of::<u32>();
 
     fn dst_len(header: &TagHeader) -> usize {
-        assert!(header.size as usize >= Self::BASE_SIZE);
-        let size = header.size as usize - Self::BASE_SIZE;
-        assert_eq!(size % mem::size_of::<MemoryArea>(), 0);
-        size / mem::size_of::<MemoryArea>()
+        Self::try_dst_len(header)
+            .expect("tag should be BASE_SIZE bytes plus a whole number of memory areas")
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        let size = (header.size as usize).checked_sub(Self::BASE_SIZE)?;
+        (size % mem::size_of::<MemoryArea>() == 0).then(|| size / mem::size_of::<MemoryArea>())
     }
 }
 
@@ -131,6 +144,13 @@ impl MemoryArea {
         self.length
     }
 
+    /// The physical memory covered by the region, from
+    /// [`Self::start_address`] to [`Self::end_address`].
+    #[must_use]
+    pub const fn range(&self) -> PhysRange {
+        PhysRange::from_start_size(PhysAddr::new(self.base_addr), self.length)
+    }
+
     /// The type of the memory region.
     #[must_use]
     pub const fn typ(&self) -> MemoryAreaTypeId {
@@ -269,14 +289,44 @@ pub struct BasicMemoryInfoTag {
 impl BasicMemoryInfoTag {
     /// Constructs a new tag.
     #[must_use]
-    pub fn new(memory_lower: u32, memory_upper: u32) -> Self {
+    pub const fn new(memory_lower: u32, memory_upper: u32) -> Self {
         Self {
-            header: TagHeader::new(Self::ID, mem::size_of::<Self>().try_into().unwrap()),
+            header: TagHeader::new_const(Self::ID, mem::size_of::<Self>() as u32),
             memory_lower,
             memory_upper,
         }
     }
 
+    /// Parses the tag by value from unaligned bytes that include its padding.
+    pub const fn from_bytes(bytes: &[u8]) -> Result<Self, TagParseError> {
+        parse_sized_tag(bytes)
+    }
+
+    /// Derives the tag from a sorted memory map without overlaps. Like GRUB,
+    /// the lower memory is the available memory starting at address 0 and the
+    /// upper memory is the contiguous available memory starting at 1 MiB.
+    #[must_use]
+    pub fn from_memory_areas(areas: &[MemoryArea]) -> Self {
+        let contiguous_from = |addr: u64| {
+            let mut end = addr;
+            for area in areas {
+                if MemoryAreaType::from(area.typ()) != MemoryAreaType::Available {
+                    continue;
+                }
+                let range = mmap_area_range(area);
+                if range.start <= end && end < range.end {
+                    end = range.end;
+                }
+            }
+            end - addr
+        };
+        let kib = |size: u64| u32::try_from(size / 1024).unwrap_or(u32::MAX);
+        Self::new(
+            kib(contiguous_from(0).min(0xa0000)),
+            kib(contiguous_from(0x100000)),
+        )
+    }
+
     #[must_use]
     /// Returns the lower memory bound.
     pub const fn memory_lower(&self) -> u32 {
@@ -361,19 +411,10 @@ impl EFIMemoryMapTag {
     /// Returns an iterator over the provided memory areas.
     ///
     /// Usually, this should already reflect the memory consumed by the
-    /// code running this.
+    /// code running this. The iterator is empty if the descriptor version or
+    /// size reported by the bootloader is unusable.
     #[must_use]
-    pub fn memory_areas(&self) -> EFIMemoryAreaIter {
-        // If this ever fails, this needs to be refactored in a joint-effort
-        // with the uefi-rs project to have all corresponding typings.
-        assert_eq!(self.desc_version, EFIMemoryDesc::VERSION);
-        assert_eq!(
-            self.memory_map
-                .as_ptr()
-                .align_offset(mem::align_of::<EFIMemoryDesc>()),
-            0
-        );
-
+    pub const fn memory_areas(&self) -> EFIMemoryAreaIter<'_> {
         EFIMemoryAreaIter::new(self)
     }
 }
@@ -395,11 +436,14 @@ impl Debug for EFIMemoryMapTag {
 impl MaybeDynSized for EFIMemoryMapTag {
     type Header = TagHeader;
 
-    const BASE_SIZE: usize = mem::size_of::<TagTypeId>() + 3 * mem::size_of::<u32>();
+    const BASE_SIZE: usize = mem::size_of::<TagHeader>() + 2 * mem::size_of::<u32>();
 
     fn dst_len(header: &TagHeader) -> usize {
-        assert!(header.size as usize >= Self::BASE_SIZE);
-        header.size as usize - Self::BASE_SIZE
+        Self::try_dst_len(header).expect("tag should be at least BASE_SIZE bytes")
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
     }
 }
 
@@ -409,66 +453,275 @@ impl Tag for EFIMemoryMapTag {
     const ID: TagType = TagType::EfiMmap;
 }
 
+/// The type of an EFI memory range.
+///
+/// This mirrors `EFI_MEMORY_TYPE` of the UEFI spec. Firmware and OS loaders
+/// may use values outside of the known constants, which is why this is not a
+/// Rust enum.
+///
+/// The type is defined here rather than re-exported from `uefi-raw`, as that
+/// crate doesn't compile on targets without the `efiapi` ABI, such as
+/// `wasm32`. With the `uefi` feature, conversions from and into the `uefi-raw`
+/// equivalent are available.
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EFIMemoryAreaType(pub u32);
+
+macro_rules! efi_memory_area_types {
+    ($($(#[$attr:meta])* $name:ident = $value:expr,)*) => {
+        impl EFIMemoryAreaType {
+            $($(#[$attr])* pub const $name: Self = Self($value);)*
+        }
+
+        impl Debug for EFIMemoryAreaType {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                match *self {
+                    $(Self::$name => f.write_str(stringify!($name)),)*
+                    Self(unknown) => write!(f, "EFIMemoryAreaType({unknown})"),
+                }
+            }
+        }
+    };
+}
+
+efi_memory_area_types! {
+    /// Not usable.
+    RESERVED = 0,
+    /// The code portions of a loaded UEFI application.
+    LOADER_CODE = 1,
+    /// The data portions of a loaded UEFI application, as well as any memory
+    /// allocated by it.
+    LOADER_DATA = 2,
+    /// Code of the boot drivers. Can be reused after the OS is loaded.
+    BOOT_SERVICES_CODE = 3,
+    /// Data of the boot drivers. Can be reused after the OS is loaded.
+    BOOT_SERVICES_DATA = 4,
+    /// Code of the runtime drivers.
+    RUNTIME_SERVICES_CODE = 5,
+    /// Data of the runtime drivers.
+    RUNTIME_SERVICES_DATA = 6,
+    /// Free usable memory.
+    CONVENTIONAL = 7,
+    /// Memory in which errors have been detected.
+    UNUSABLE = 8,
+    /// Memory that holds ACPI tables. Can be reclaimed after they are parsed.
+    ACPI_RECLAIM = 9,
+    /// Firmware-reserved addresses.
+    ACPI_NON_VOLATILE = 10,
+    /// A region used for memory-mapped I/O.
+    MMIO = 11,
+    /// Address space used for memory-mapped port I/O.
+    MMIO_PORT_SPACE = 12,
+    /// Address space which is part of the processor.
+    PAL_CODE = 13,
+    /// Memory region which is usable and is also non-volatile.
+    PERSISTENT_MEMORY = 14,
+    /// Memory that must be accepted by the boot target before it can be used.
+    UNACCEPTED = 15,
+    /// End of the defined memory types. Higher values are possible though.
+    MAX = 16,
+}
+
+bitflags! {
+    /// Flags describing the capabilities of an EFI memory range
+    /// (`EFI_MEMORY_*` attributes of the UEFI spec).
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct EFIMemoryAttribute: u64 {
+        /// Supports marking as uncacheable.
+        const UNCACHEABLE = 0x1;
+        /// Supports write-combining.
+        const WRITE_COMBINE = 0x2;
+        /// Supports write-through.
+        const WRITE_THROUGH = 0x4;
+        /// Supports write-back.
+        const WRITE_BACK = 0x8;
+        /// Supports marking as uncacheable, exported and supports the
+        /// "fetch and add" semaphore mechanism.
+        const UNCACHABLE_EXPORTED = 0x10;
+        /// Supports write-protection.
+        const WRITE_PROTECT = 0x1000;
+        /// Supports read-protection.
+        const READ_PROTECT = 0x2000;
+        /// Supports disabling code execution.
+        const EXECUTE_PROTECT = 0x4000;
+        /// Persistent memory.
+        const NON_VOLATILE = 0x8000;
+        /// This memory region is more reliable than other memory.
+        const MORE_RELIABLE = 0x10000;
+        /// This memory range can be set as read-only.
+        const READ_ONLY = 0x20000;
+        /// This memory is earmarked for specific purposes.
+        const SPECIAL_PURPOSE = 0x4_0000;
+        /// This memory region can be protected with the CPU's memory
+        /// cryptography capabilities.
+        const CPU_CRYPTO = 0x8_0000;
+        /// This memory must be mapped by the OS when a runtime service is
+        /// called.
+        const RUNTIME = 0x8000_0000_0000_0000;
+        /// This memory region is described with additional ISA-specific
+        /// memory attributes as specified in [`Self::ISA_MASK`].
+        const ISA_VALID = 0x4000_0000_0000_0000;
+        /// Bits reserved for ISA-specific cacheability attributes.
+        const ISA_MASK = 0x0FFF_F000_0000_0000;
+    }
+}
+
+/// An EFI memory descriptor (`EFI_MEMORY_DESCRIPTOR`) in version
+/// [`Self::VERSION`].
+///
+/// Never rely on `size_of::<EFIMemoryDesc>()` when walking a memory map
+/// produced by firmware, but always use the reported descriptor size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct EFIMemoryDesc {
+    /// Type of memory occupying this range.
+    pub ty: EFIMemoryAreaType,
+    // Implicit 32-bit padding.
+    /// Starting physical address.
+    pub phys_start: u64,
+    /// Starting virtual address.
+    pub virt_start: u64,
+    /// Number of 4 KiB pages contained in this range.
+    pub page_count: u64,
+    /// The capability attributes of this memory range.
+    pub att: EFIMemoryAttribute,
+}
+
+impl EFIMemoryDesc {
+    /// Memory descriptor version number.
+    pub const VERSION: u32 = 1;
+}
+
+impl Default for EFIMemoryDesc {
+    fn default() -> Self {
+        Self {
+            ty: EFIMemoryAreaType::RESERVED,
+            phys_start: 0,
+            virt_start: 0,
+            page_count: 0,
+            att: EFIMemoryAttribute::empty(),
+        }
+    }
+}
+
+#[cfg(feature = "uefi")]
+impl From<uefi_raw::table::boot::MemoryType> for EFIMemoryAreaType {
+    fn from(value: uefi_raw::table::boot::MemoryType) -> Self {
+        Self(value.0)
+    }
+}
+
+#[cfg(feature = "uefi")]
+impl From<EFIMemoryAreaType> for uefi_raw::table::boot::MemoryType {
+    fn from(value: EFIMemoryAreaType) -> Self {
+        Self(value.0)
+    }
+}
+
+#[cfg(feature = "uefi")]
+impl From<uefi_raw::table::boot::MemoryDescriptor> for EFIMemoryDesc {
+    fn from(desc: uefi_raw::table::boot::MemoryDescriptor) -> Self {
+        Self {
+            ty: desc.ty.into(),
+            phys_start: desc.phys_start,
+            virt_start: desc.virt_start,
+            page_count: desc.page_count,
+            att: EFIMemoryAttribute::from_bits_retain(desc.att.bits()),
+        }
+    }
+}
+
+#[cfg(feature = "uefi")]
+impl From<EFIMemoryDesc> for uefi_raw::table::boot::MemoryDescriptor {
+    fn from(desc: EFIMemoryDesc) -> Self {
+        Self {
+            ty: desc.ty.into(),
+            phys_start: desc.phys_start,
+            virt_start: desc.virt_start,
+            page_count: desc.page_count,
+            att: uefi_raw::table::boot::MemoryAttribute::from_bits_retain(desc.att.bits()),
+        }
+    }
+}
+
 /// An iterator over the EFI memory areas emitting [`EFIMemoryDesc`] items.
+///
+/// The descriptors are returned by value, as the descriptor size reported by
+/// the bootloader doesn't need to be a multiple of the alignment of
+/// [`EFIMemoryDesc`].
 #[derive(Clone)]
 pub struct EFIMemoryAreaIter<'a> {
-    mmap_tag: &'a EFIMemoryMapTag,
-    i: usize,
-    entries: usize,
-    phantom: PhantomData<&'a EFIMemoryDesc>,
+    /// The descriptors that weren't emitted yet.
+    rest: &'a [u8],
+    /// The size of each descriptor, which may be bigger than
+    /// [`EFIMemoryDesc`].
+    stride: usize,
+    /// The number of descriptors in `rest`.
+    remaining: usize,
 }
 
 impl<'a> EFIMemoryAreaIter<'a> {
-    fn new(mmap_tag: &'a EFIMemoryMapTag) -> Self {
-        let desc_size = mmap_tag.desc_size as usize;
+    const fn new(mmap_tag: &'a EFIMemoryMapTag) -> Self {
+        let stride = mmap_tag.desc_size as usize;
         let mmap_len = mmap_tag.memory_map.len();
-        assert_eq!(mmap_len % desc_size, 0, "memory map length must be a multiple of `desc_size` by definition. The MBI seems to be corrupt.");
+        // A corrupt MBI yields no descriptors rather than truncated ones.
+        let valid = mmap_tag.desc_version == EFIMemoryDesc::VERSION
+            && stride >= mem::size_of::<EFIMemoryDesc>()
+            && mmap_len % stride == 0;
         Self {
-            mmap_tag,
-            i: 0,
-            entries: mmap_len / desc_size,
-            phantom: PhantomData,
+            rest: &mmap_tag.memory_map,
+            stride,
+            remaining: if valid { mmap_len / stride } else { 0 },
         }
     }
 }
 
-impl<'a> Iterator for EFIMemoryAreaIter<'a> {
-    type Item = &'a EFIMemoryDesc;
-    fn next(&mut self) -> Option<&'a EFIMemoryDesc> {
-        if self.i >= self.entries {
+impl Iterator for EFIMemoryAreaIter<'_> {
+    type Item = EFIMemoryDesc;
+    fn next(&mut self) -> Option<EFIMemoryDesc> {
+        if self.remaining == 0 {
             return None;
         }
+        // SAFETY: `rest` consists of exactly `remaining` descriptors that are
+        // `stride` bytes apart, as checked in `EFIMemoryAreaIter::new`. Every
+        // bit pattern is a valid descriptor.
+        let desc = unsafe { ptr::read_unaligned(self.rest.as_ptr().cast::<EFIMemoryDesc>()) };
+        self.rest = unsafe { self.rest.get_unchecked(self.stride..) };
+        self.remaining -= 1;
+        Some(desc)
+    }
 
-        let desc = unsafe {
-            self.mmap_tag
-                .memory_map
-                .as_ptr()
-                .add(self.i * self.mmap_tag.desc_size as usize)
-                .cast::<EFIMemoryDesc>()
-                .as_ref()
-                .unwrap()
-        };
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
 
-        self.i += 1;
+    fn count(self) -> usize {
+        self.remaining
+    }
 
-        Some(desc)
+    fn nth(&mut self, n: usize) -> Option<EFIMemoryDesc> {
+        if n >= self.remaining {
+            self.rest = &self.rest[self.rest.len()..];
+            self.remaining = 0;
+            return None;
+        }
+        // SAFETY: `n` descriptors are skipped, but at least one remains.
+        self.rest = unsafe { self.rest.get_unchecked(n * self.stride..) };
+        self.remaining -= n;
+        self.next()
     }
-}
 
-impl<'a> ExactSizeIterator for EFIMemoryAreaIter<'a> {
-    fn len(&self) -> usize {
-        self.entries
+    fn last(mut self) -> Option<EFIMemoryDesc> {
+        self.remaining.checked_sub(1).and_then(|n| self.nth(n))
     }
 }
 
-impl<'a> Debug for EFIMemoryAreaIter<'a> {
+impl ExactSizeIterator for EFIMemoryAreaIter<'_> {}
+
+impl Debug for EFIMemoryAreaIter<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        let mut debug = f.debug_list();
-        let iter = self.clone();
-        for elem in iter {
-            debug.entry(elem);
-        }
-        debug.finish()
+        f.debug_list().entries(self.clone()).finish()
     }
 }
 
@@ -509,10 +762,67 @@ mod tests {
 
         let mut iter = efi_mmap_tag.memory_areas();
 
-        assert_eq!(iter.next(), Some(&descs[0]));
-        assert_eq!(iter.next(), Some(&descs[1]));
+        assert_eq!(iter.next(), Some(descs[0]));
+        assert_eq!(iter.next(), Some(descs[1]));
+
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn efi_iter_exact_size_and_nth() {
+        let descs = (0..5)
+            .map(|i| EFIMemoryDesc {
+                phys_start: i * 0x1000,
+                page_count: 1,
+                ..Default::default()
+            })
+            .collect::<alloc::vec::Vec<_>>();
+        let efi_mmap_tag = EFIMemoryMapTag::new_from_descs(&descs);
 
+        let mut iter = efi_mmap_tag.memory_areas();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.nth(2), Some(descs[2]));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        assert_eq!(iter.clone().last(), Some(descs[4]));
+        assert_eq!(iter.clone().count(), 2);
+        assert_eq!(iter.next(), Some(descs[3]));
+        assert_eq!(iter.nth(1), None);
+        assert_eq!(iter.len(), 0);
         assert_eq!(iter.next(), None);
+        assert_eq!(efi_mmap_tag.memory_areas().last(), Some(descs[4]));
+    }
+
+    #[test]
+    fn efi_desc_size_too_small() {
+        let tag = EFIMemoryMapTag::new_from_map(16, EFIMemoryDesc::VERSION, &[0; 32]);
+        assert_eq!(tag.memory_areas().count(), 0);
+    }
+
+    #[test]
+    fn efi_unaligned_desc_size() {
+        // Every second descriptor is misaligned.
+        let desc_size = size_of::<EFIMemoryDesc>() + 4;
+        let descs = [
+            EFIMemoryDesc {
+                ty: EFIMemoryAreaType::CONVENTIONAL,
+                phys_start: 0x1000,
+                page_count: 1,
+                ..Default::default()
+            },
+            EFIMemoryDesc {
+                ty: EFIMemoryAreaType::LOADER_DATA,
+                phys_start: 0x10_0000,
+                page_count: 16,
+                ..Default::default()
+            },
+        ];
+        let mut bytes = [0_u8; 2 * (size_of::<EFIMemoryDesc>() + 4)];
+        for (chunk, desc) in bytes.chunks_exact_mut(desc_size).zip(&descs) {
+            unsafe { ptr::write_unaligned(chunk.as_mut_ptr().cast(), *desc) };
+        }
+        let tag = EFIMemoryMapTag::new_from_map(desc_size as u32, EFIMemoryDesc::VERSION, &bytes);
+        assert!(tag.memory_areas().eq(descs));
     }
 
     /// Tests the EFI memory map parsing using a real world efi memory map.
@@ -534,7 +844,7 @@ mod tests {
             core::slice::from_raw_parts(buf.as_ptr().cast::<u8>(), buf.len() * size_of::<u64>())
         };
         let tag = EFIMemoryMapTag::new_from_map(DESC_SIZE, DESC_VERSION, buf);
-        let entries = tag.memory_areas().copied().collect::<alloc::vec::Vec<_>>();
+        let entries = tag.memory_areas().collect::<alloc::vec::Vec<_>>();
         let expected = [
             EFIMemoryDesc {
                 ty: EFIMemoryAreaType::BOOT_SERVICES_CODE,
@@ -639,4 +949,24 @@ mod tests {
         ];
         assert_eq!(entries.as_slice(), &expected);
     }
+
+    #[test]
+    #[cfg(feature = "uefi")]
+    fn efi_desc_uefi_raw_roundtrip() {
+        let desc = EFIMemoryDesc {
+            ty: EFIMemoryAreaType(0x8000_0001),
+            phys_start: 0x1000,
+            virt_start: 0x2000,
+            page_count: 3,
+            att: EFIMemoryAttribute::WRITE_BACK | EFIMemoryAttribute::RUNTIME,
+        };
+        let raw = uefi_raw::table::boot::MemoryDescriptor::from(desc);
+        assert_eq!(raw.ty.0, 0x8000_0001);
+        assert_eq!(raw.att.bits(), desc.att.bits());
+        assert_eq!(EFIMemoryDesc::from(raw), desc);
+        assert_eq!(
+            mem::size_of::<EFIMemoryDesc>(),
+            mem::size_of::<uefi_raw::table::boot::MemoryDescriptor>()
+        );
+    }
 }
@@ -0,0 +1,188 @@
+//! Module for [`BootInformation::bootloader_api_memory_regions`].
+
+use crate::free_memory::{efi_desc_range, efi_is_available, SplitRanges};
+use crate::{BootInformation, MemoryAreaType};
+use bootloader_api::info::{MemoryRegion, MemoryRegionKind, MemoryRegions};
+use core::ops::Range;
+use derive_more::Display;
+
+/// Errors of [`BootInformation::bootloader_api_memory_regions`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MemoryRegionsError {
+    /// The boot information has neither a usable EFI memory map nor a
+    /// Multiboot2 memory map.
+    NoMemoryMap,
+    /// The provided buffer is too small. Contains the number of regions that
+    /// are required.
+    #[display("BufferTooSmall({})", _0)]
+    BufferTooSmall(usize),
+}
+
+impl core::error::Error for MemoryRegionsError {}
+
+impl BootInformation<'_> {
+    /// Converts the memory map into the [`MemoryRegions`] model of the
+    /// `bootloader_api` crate, so that kernels written against the
+    /// `bootloader` crate can also be booted by a Multiboot2 bootloader.
+    ///
+    /// The EFI memory map is preferred if the boot services were exited.
+    /// Otherwise, the Multiboot2 memory map is used. Free memory becomes
+    /// [`MemoryRegionKind::Usable`] and everything else keeps its firmware
+    /// type as [`MemoryRegionKind::UnknownUefi`] or
+    /// [`MemoryRegionKind::UnknownBios`]. EFI loader memory is not considered
+    /// free, as the bootloader may have placed the kernel there.
+    ///
+    /// The boot information itself, the modules, and the `used` ranges, such
+    /// as the kernel image, are cut out of the usable memory and reported as
+    /// [`MemoryRegionKind::Bootloader`].
+    ///
+    /// The regions are written to `buf`. If it is too small,
+    /// [`MemoryRegionsError::BufferTooSmall`] reports the required length.
+    pub fn bootloader_api_memory_regions(
+        &self,
+        used: &[Range<u64>],
+        buf: &'static mut [MemoryRegion],
+    ) -> Result<MemoryRegions, MemoryRegionsError> {
+        let mbi = self.start_address() as u64..self.end_address() as u64;
+        let modules = self
+            .module_tags()
+            .map(|tag| u64::from(tag.start_address())..u64::from(tag.end_address()));
+        let used_ranges = || {
+            core::iter::once(mbi.clone())
+                .chain(modules.clone())
+                .chain(used.iter().cloned())
+        };
+        let mut writer = RegionWriter { buf, len: 0 };
+
+        if let Some(tag) = self.efi_memory_map_tag() {
+            for desc in tag.memory_areas() {
+                let kind = if efi_is_available(desc.ty) {
+                    MemoryRegionKind::Usable
+                } else {
+                    MemoryRegionKind::UnknownUefi(desc.ty.0)
+                };
+                writer.add(efi_desc_range(desc), kind, used_ranges);
+            }
+        } else if let Some(tag) = self.memory_map_tag() {
+            for area in tag.memory_areas() {
+                let kind = match MemoryAreaType::from(area.typ()) {
+                    MemoryAreaType::Available => MemoryRegionKind::Usable,
+                    _ => MemoryRegionKind::UnknownBios(area.typ().into()),
+                };
+                writer.add(area.start_address()..area.end_address(), kind, used_ranges);
+            }
+        } else {
+            return Err(MemoryRegionsError::NoMemoryMap);
+        }
+
+        writer.finish()
+    }
+}
+
+/// Writes regions to the buffer and counts the ones that don't fit.
+struct RegionWriter {
+    buf: &'static mut [MemoryRegion],
+    len: usize,
+}
+
+impl RegionWriter {
+    /// Adds a region. Usable regions are split around the used ranges.
+    fn add<F: Fn() -> I, I: Iterator<Item = Range<u64>>>(
+        &mut self,
+        range: Range<u64>,
+        kind: MemoryRegionKind,
+        used: F,
+    ) {
+        if range.is_empty() {
+            return;
+        }
+        if kind != MemoryRegionKind::Usable {
+            self.push(range, kind);
+            return;
+        }
+        for (part, is_used) in SplitRanges::new(range, used) {
+            let kind = if is_used {
+                MemoryRegionKind::Bootloader
+            } else {
+                MemoryRegionKind::Usable
+            };
+            self.push(part, kind);
+        }
+    }
+
+    fn push(&mut self, range: Range<u64>, kind: MemoryRegionKind) {
+        if let Some(region) = self.buf.get_mut(self.len) {
+            *region = MemoryRegion {
+                start: range.start,
+                end: range.end,
+                kind,
+            };
+        }
+        self.len += 1;
+    }
+
+    fn finish(self) -> Result<MemoryRegions, MemoryRegionsError> {
+        if self.len > self.buf.len() {
+            return Err(MemoryRegionsError::BufferTooSmall(self.len));
+        }
+        Ok(MemoryRegions::from(&mut self.buf[..self.len]))
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{Builder, MemoryArea, MemoryMapTag};
+    use alloc::boxed::Box;
+    use alloc::vec;
+
+    fn regions(len: usize) -> &'static mut [MemoryRegion] {
+        Box::leak(vec![MemoryRegion::empty(); len].into_boxed_slice())
+    }
+
+    #[test]
+    fn multiboot2_memory_map() {
+        let mbi = Builder::new()
+            .mmap(MemoryMapTag::new(&[
+                MemoryArea::new(0, 0x9_f000, MemoryAreaType::Available),
+                MemoryArea::new(0x9_f000, 0x1000, MemoryAreaType::Reserved),
+                MemoryArea::new(0x10_0000, 0x10_0000, MemoryAreaType::Available),
+            ]))
+            .add_module(0x18_0000, 0x18_1000, "mod")
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        let used = [0x10_0000..0x11_0000, 0x18_0800..0x18_2000];
+
+        assert_eq!(
+            mbi.bootloader_api_memory_regions(&used, regions(4)).err(),
+            Some(MemoryRegionsError::BufferTooSmall(7))
+        );
+
+        let regions = mbi
+            .bootloader_api_memory_regions(&used, regions(7))
+            .unwrap();
+        let region = |start, end, kind| MemoryRegion { start, end, kind };
+        assert_eq!(
+            &*regions,
+            &[
+                region(0, 0x9_f000, MemoryRegionKind::Usable),
+                region(0x9_f000, 0xa_0000, MemoryRegionKind::UnknownBios(2)),
+                region(0x10_0000, 0x11_0000, MemoryRegionKind::Bootloader),
+                region(0x11_0000, 0x18_0000, MemoryRegionKind::Usable),
+                region(0x18_0000, 0x18_1000, MemoryRegionKind::Bootloader),
+                region(0x18_1000, 0x18_2000, MemoryRegionKind::Bootloader),
+                region(0x18_2000, 0x20_0000, MemoryRegionKind::Usable),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_memory_map() {
+        let mbi = Builder::new().build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        assert_eq!(
+            mbi.bootloader_api_memory_regions(&[], regions(1)).err(),
+            Some(MemoryRegionsError::NoMemoryMap)
+        );
+    }
+}
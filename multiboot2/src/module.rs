@@ -1,12 +1,21 @@
 //! Module for [`ModuleTag`].
 
 use crate::tag::TagHeader;
-use crate::{parse_slice_as_string, StringError, TagIter, TagType};
+use crate::util::string_bytes;
+use crate::{
+    parse_slice_as_string, parse_slice_as_string_lenient, PhysAddr, PhysRange, StringError,
+    TagIter, TagType,
+};
 use core::fmt::{Debug, Formatter};
 use core::mem;
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
-use {alloc::boxed::Box, multiboot2_common::new_boxed};
+use {
+    crate::builder::{check_string, check_tag_size},
+    crate::BuilderError,
+    alloc::boxed::Box,
+    multiboot2_common::{new_boxed, try_new_boxed},
+};
 
 /// The module tag can occur multiple times and specifies passed boot modules
 /// (blobs in memory). The tag itself doesn't include the blog, but references
@@ -23,6 +32,9 @@ pub struct ModuleTag {
 
 impl ModuleTag {
     /// Constructs a new tag.
+    ///
+    /// # Panics
+    /// Panics if the module is empty, i.e., if `end <= start`.
     #[cfg(feature = "builder")]
     #[must_use]
     pub fn new(start: u32, end: u32, cmdline: &str) -> Box<Self> {
@@ -40,6 +52,24 @@ impl ModuleTag {
         }
     }
 
+    /// Like [`Self::new`], but returns [`BuilderError::EmptyModule`] instead
+    /// of panicking if the module is empty. Unlike [`Self::new`], this also
+    /// fails instead of producing a malformed tag if the command line
+    /// contains a NUL byte before its end or is too long, or if the allocation
+    /// fails.
+    #[cfg(feature = "builder")]
+    pub fn try_new(start: u32, end: u32, cmdline: &str) -> Result<Box<Self>, BuilderError> {
+        if end <= start {
+            return Err(BuilderError::EmptyModule);
+        }
+        let cmdline = check_string(cmdline)?;
+        check_tag_size(2 * mem::size_of::<u32>() + cmdline.len() + 1)?;
+        let header = TagHeader::new(Self::ID, 0);
+        let start = start.to_ne_bytes();
+        let end = end.to_ne_bytes();
+        try_new_boxed(header, &[&start, &end, cmdline, &[0]]).ok_or(BuilderError::OutOfMemory)
+    }
+
     /// Reads the command line of the boot module as Rust string slice without
     /// the null-byte.
     /// This is an null-terminated UTF-8 string. If this returns `Err` then perhaps the memory
@@ -53,6 +83,21 @@ impl ModuleTag {
         parse_slice_as_string(&self.cmdline)
     }
 
+    /// Like [`Self::cmdline`], but treats the whole remaining payload as the
+    /// command line if the terminating NUL is missing, instead of returning
+    /// [`StringError::MissingNul`].
+    pub fn cmdline_lenient(&self) -> Result<&str, StringError> {
+        parse_slice_as_string_lenient(&self.cmdline)
+    }
+
+    /// Returns the raw bytes of the command line without the terminating NUL and
+    /// without checking for valid UTF-8. If the NUL is missing, this is the
+    /// whole payload.
+    #[must_use]
+    pub fn cmdline_bytes(&self) -> &[u8] {
+        string_bytes(&self.cmdline)
+    }
+
     /// Start address of the module.
     #[must_use]
     pub const fn start_address(&self) -> u32 {
@@ -65,6 +110,16 @@ impl ModuleTag {
         self.mod_end
     }
 
+    /// The physical memory occupied by the module, from
+    /// [`Self::start_address`] to [`Self::end_address`].
+    #[must_use]
+    pub const fn range(&self) -> PhysRange {
+        PhysRange::new(
+            PhysAddr::new(self.mod_start as u64),
+            PhysAddr::new(self.mod_end as u64),
+        )
+    }
+
     /// The size of the module/the BLOB in memory.
     #[must_use]
     pub const fn module_size(&self) -> u32 {
@@ -78,8 +133,11 @@ impl MaybeDynSized for ModuleTag {
     const BASE_SIZE: usize = mem::size_of::<TagHeader>() + 2 * mem::size_of::<u32>();
 
     fn dst_len(header: &TagHeader) -> usize {
-        assert!(header.size as usize >= Self::BASE_SIZE);
-        header.size as usize - Self::BASE_SIZE
+        Self::try_dst_len(header).expect("tag should be at least BASE_SIZE bytes")
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
     }
 }
 
@@ -118,30 +176,27 @@ impl<'a> Iterator for ModuleIter<'a> {
 
     fn next(&mut self) -> Option<&'a ModuleTag> {
         self.iter
-            .find(|tag| tag.header().typ == TagType::Module)
-            .map(|tag| tag.cast())
+            .by_ref()
+            .filter(|tag| tag.header().typ == TagType::Module)
+            .find_map(|tag| tag.try_cast().ok())
     }
 }
 
 impl<'a> Debug for ModuleIter<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        let mut list = f.debug_list();
-        self.clone().for_each(|tag| {
-            list.entry(&tag);
-        });
-        list.finish()
+        f.debug_list().entries(self.clone()).finish()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::GenericInfoTag;
+    use crate::GenericTag;
     use core::borrow::Borrow;
     use multiboot2_common::test_utils::AlignedBytes;
 
     #[rustfmt::skip]
-    fn get_bytes() -> AlignedBytes<24> {
+    const fn get_bytes() -> AlignedBytes<24> {
         AlignedBytes::new([
             TagType::Module.val() as u8, 0, 0, 0,
             22, 0, 0, 0,
@@ -159,10 +214,11 @@ mod tests {
     #[test]
     fn test_parse_str() {
         let bytes = get_bytes();
-        let tag = GenericInfoTag::ref_from_slice(bytes.borrow()).unwrap();
-        let tag = tag.cast::<ModuleTag>();
+        let tag = GenericTag::ref_from_slice(bytes.borrow()).unwrap();
+        let tag = tag.downcast::<ModuleTag>().unwrap();
         assert_eq!(tag.header.typ, TagType::Module);
         assert_eq!(tag.cmdline(), Ok("hello"));
+        assert_eq!(tag.cmdline_bytes(), b"hello");
     }
 
     /// Test to generate a tag from a given string.
@@ -188,4 +244,25 @@ mod tests {
         let tag = ModuleTag::new(0, 1, "AbCdEfGhUjK YEAH".repeat(42).as_str());
         assert_eq!(tag.cmdline(), Ok("AbCdEfGhUjK YEAH".repeat(42).as_str()));
     }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_try_new() {
+        use crate::BuilderError;
+
+        assert_eq!(
+            ModuleTag::try_new(0xff00, 0xffff, "hello").unwrap(),
+            ModuleTag::new(0xff00, 0xffff, "hello")
+        );
+        // Both reject empty modules.
+        assert_eq!(
+            ModuleTag::try_new(0x1000, 0x1000, "hello"),
+            Err(BuilderError::EmptyModule)
+        );
+        assert!(std::panic::catch_unwind(|| ModuleTag::new(0x1000, 0x1000, "hello")).is_err());
+        assert_eq!(
+            ModuleTag::try_new(0, 1, "a\0b"),
+            Err(BuilderError::InvalidString)
+        );
+    }
 }
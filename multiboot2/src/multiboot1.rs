@@ -0,0 +1,655 @@
+//! Parsing of the boot information of the legacy Multiboot (version 1)
+//! protocol, so that a kernel can accept either protocol.
+//!
+//! The API mirrors the one for Multiboot2 where possible. Unlike Multiboot2,
+//! the Multiboot boot information is a fixed structure that refers to further
+//! data, such as the memory map or the command line, by physical address.
+//! These addresses are resolved relative to a physical memory offset, which
+//! is `0` for identity-mapped memory.
+//!
+//! ```rust,no_run
+//! use multiboot2::multiboot1::{BootInformation, BootInformationHeader, MAGIC};
+//!
+//! fn kernel_entry(mb_magic: u32, mbi_ptr: u32) {
+//!     if mb_magic == MAGIC {
+//!         let ptr = mbi_ptr as *const BootInformationHeader;
+//!         let boot_info = unsafe { BootInformation::load(ptr).unwrap() };
+//!         let _cmd = boot_info.command_line();
+//!     }
+//! }
+//! ```
+
+use crate::framebuffer::{FramebufferTypeId, UnknownFramebufferType};
+use crate::{FramebufferColor, FramebufferField, FramebufferType, MemoryArea, StringError};
+#[cfg(feature = "vbe")]
+use crate::{VBEControlInfo, VBEModeInfo};
+use core::ffi::CStr;
+use core::fmt;
+use core::mem::align_of;
+use core::slice;
+use multiboot2_common::MemoryError;
+
+/// Magic value passed by a Multiboot bootloader in `EAX` to the kernel.
+pub const MAGIC: u32 = 0x2bad_b002;
+
+bitflags::bitflags! {
+    /// The flags of the boot information, telling which fields are valid.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[repr(transparent)]
+    pub struct InfoFlags: u32 {
+        /// `mem_lower` and `mem_upper` are valid.
+        const MEMORY = 1 << 0;
+        /// `boot_device` is valid.
+        const BOOT_DEVICE = 1 << 1;
+        /// `cmdline` is valid.
+        const CMDLINE = 1 << 2;
+        /// `mods_count` and `mods_addr` are valid.
+        const MODULES = 1 << 3;
+        /// The a.out symbol table is valid.
+        const AOUT_SYMBOLS = 1 << 4;
+        /// The ELF section header table is valid.
+        const ELF_SECTIONS = 1 << 5;
+        /// `mmap_length` and `mmap_addr` are valid.
+        const MEMORY_MAP = 1 << 6;
+        /// `drives_length` and `drives_addr` are valid.
+        const DRIVES = 1 << 7;
+        /// `config_table` is valid.
+        const CONFIG_TABLE = 1 << 8;
+        /// `boot_loader_name` is valid.
+        const BOOT_LOADER_NAME = 1 << 9;
+        /// `apm_table` is valid.
+        const APM_TABLE = 1 << 10;
+        /// The VBE fields are valid.
+        const VBE = 1 << 11;
+        /// The framebuffer fields are valid.
+        const FRAMEBUFFER = 1 << 12;
+    }
+}
+
+/// The Multiboot boot information structure as passed by the bootloader.
+///
+/// The spec only guarantees 4-byte alignment, hence the 64-bit framebuffer
+/// address may be unaligned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C, packed(4))]
+pub struct BootInformationHeader {
+    flags: u32,
+    mem_lower: u32,
+    mem_upper: u32,
+    boot_device: u32,
+    cmdline: u32,
+    mods_count: u32,
+    mods_addr: u32,
+    syms: [u32; 4],
+    mmap_length: u32,
+    mmap_addr: u32,
+    drives_length: u32,
+    drives_addr: u32,
+    config_table: u32,
+    boot_loader_name: u32,
+    apm_table: u32,
+    vbe_control_info: u32,
+    vbe_mode_info: u32,
+    vbe_mode: u16,
+    vbe_interface_seg: u16,
+    vbe_interface_off: u16,
+    vbe_interface_len: u16,
+    framebuffer_addr: u64,
+    framebuffer_pitch: u32,
+    framebuffer_width: u32,
+    framebuffer_height: u32,
+    framebuffer_bpp: u8,
+    framebuffer_type: u8,
+    color_info: [u8; 6],
+}
+
+/// A Multiboot boot information.
+pub struct BootInformation<'a> {
+    header: &'a BootInformationHeader,
+    phys_offset: usize,
+}
+
+impl<'a> BootInformation<'a> {
+    /// Loads the [`BootInformation`] from a pointer in identity-mapped
+    /// memory. The pointer must be valid and aligned to a 4-byte boundary.
+    ///
+    /// ## Safety
+    /// * `ptr` and all physical addresses it refers to must be valid for
+    ///   reading.
+    /// * The memory must not be modified while the returned value lives.
+    pub unsafe fn load(ptr: *const BootInformationHeader) -> Result<Self, MemoryError> {
+        Self::load_with_offset(ptr, 0)
+    }
+
+    /// Like [`Self::load`], but the physical addresses in the boot
+    /// information are accessed at `phys_offset + addr`, such as in a
+    /// higher-half direct map of the physical memory.
+    ///
+    /// ## Safety
+    /// See [`Self::load`].
+    pub unsafe fn load_with_offset(
+        ptr: *const BootInformationHeader,
+        phys_offset: usize,
+    ) -> Result<Self, MemoryError> {
+        if ptr.is_null() {
+            return Err(MemoryError::Null);
+        }
+        if ptr.align_offset(align_of::<u32>()) != 0 {
+            return Err(MemoryError::WrongAlignment);
+        }
+        Ok(Self {
+            header: &*ptr,
+            phys_offset,
+        })
+    }
+
+    /// Get the start address of the boot info.
+    #[must_use]
+    pub fn start_address(&self) -> usize {
+        (self.header as *const BootInformationHeader) as usize
+    }
+
+    /// The flags telling which information is present.
+    #[must_use]
+    pub const fn flags(&self) -> InfoFlags {
+        InfoFlags::from_bits_retain(self.header.flags)
+    }
+
+    const fn has(&self, flag: InfoFlags) -> bool {
+        self.flags().contains(flag)
+    }
+
+    const fn phys_ptr<T>(&self, addr: u64) -> *const T {
+        (self.phys_offset as u64).wrapping_add(addr) as usize as *const T
+    }
+
+    fn string(&self, addr: u32) -> Result<&'a str, StringError> {
+        let cstr = unsafe { CStr::from_ptr(self.phys_ptr(addr.into())) };
+        cstr.to_str().map_err(StringError::Utf8)
+    }
+
+    /// Amount of lower memory in KiB, starting at address 0.
+    #[must_use]
+    pub fn memory_lower(&self) -> Option<u32> {
+        self.has(InfoFlags::MEMORY).then_some(self.header.mem_lower)
+    }
+
+    /// Amount of upper memory in KiB, starting at address 1 MiB.
+    #[must_use]
+    pub fn memory_upper(&self) -> Option<u32> {
+        self.has(InfoFlags::MEMORY).then_some(self.header.mem_upper)
+    }
+
+    /// The BIOS disk device the kernel was loaded from.
+    #[must_use]
+    pub fn boot_device(&self) -> Option<BootDevice> {
+        self.has(InfoFlags::BOOT_DEVICE)
+            .then_some(BootDevice(self.header.boot_device))
+    }
+
+    /// The command line of the kernel.
+    #[must_use]
+    pub fn command_line(&self) -> Option<Result<&'a str, StringError>> {
+        self.has(InfoFlags::CMDLINE)
+            .then(|| self.string(self.header.cmdline))
+    }
+
+    /// The name of the bootloader.
+    #[must_use]
+    pub fn boot_loader_name(&self) -> Option<Result<&'a str, StringError>> {
+        self.has(InfoFlags::BOOT_LOADER_NAME)
+            .then(|| self.string(self.header.boot_loader_name))
+    }
+
+    /// Get an iterator of the modules.
+    #[must_use]
+    pub fn modules(&self) -> ModuleIter<'a> {
+        let modules = if self.has(InfoFlags::MODULES) {
+            let ptr = self.phys_ptr::<RawModule>(self.header.mods_addr.into());
+            unsafe { slice::from_raw_parts(ptr, self.header.mods_count as usize) }
+        } else {
+            &[]
+        };
+        ModuleIter {
+            modules: modules.iter(),
+            phys_offset: self.phys_offset,
+        }
+    }
+
+    /// Get an iterator of the memory areas of the memory map.
+    #[must_use]
+    pub fn memory_areas(&self) -> MemoryAreaIter<'a> {
+        let buffer = if self.has(InfoFlags::MEMORY_MAP) {
+            let ptr = self.phys_ptr::<u8>(self.header.mmap_addr.into());
+            unsafe { slice::from_raw_parts(ptr, self.header.mmap_length as usize) }
+        } else {
+            &[]
+        };
+        MemoryAreaIter { buffer }
+    }
+
+    /// The current VBE mode.
+    #[must_use]
+    pub fn vbe_mode(&self) -> Option<u16> {
+        self.has(InfoFlags::VBE).then_some(self.header.vbe_mode)
+    }
+
+    /// The VBE controller information returned by VBE function `00h`.
+    #[cfg(feature = "vbe")]
+    #[must_use]
+    pub fn vbe_control_info(&self) -> Option<&'a VBEControlInfo> {
+        self.has(InfoFlags::VBE)
+            .then(|| unsafe { &*self.phys_ptr(self.header.vbe_control_info.into()) })
+    }
+
+    /// The VBE mode information returned by VBE function `01h`.
+    #[cfg(feature = "vbe")]
+    #[must_use]
+    pub fn vbe_mode_info(&self) -> Option<&'a VBEModeInfo> {
+        self.has(InfoFlags::VBE)
+            .then(|| unsafe { &*self.phys_ptr(self.header.vbe_mode_info.into()) })
+    }
+
+    /// The framebuffer.
+    #[must_use]
+    pub fn framebuffer(&self) -> Option<Framebuffer<'a>> {
+        self.has(InfoFlags::FRAMEBUFFER).then_some(Framebuffer {
+            header: self.header,
+            phys_offset: self.phys_offset,
+        })
+    }
+}
+
+impl fmt::Debug for BootInformation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BootInformation")
+            .field("start_address", &self.start_address())
+            .field("flags", &self.flags())
+            .field("memory_lower", &self.memory_lower())
+            .field("memory_upper", &self.memory_upper())
+            .field("boot_device", &self.boot_device())
+            .field("command_line", &self.command_line())
+            .field("boot_loader_name", &self.boot_loader_name())
+            .field("modules", &self.modules())
+            .field("memory_areas", &self.memory_areas())
+            .field("vbe_mode", &self.vbe_mode())
+            .field("framebuffer", &self.framebuffer())
+            .finish()
+    }
+}
+
+/// The BIOS disk device the kernel was loaded from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BootDevice(u32);
+
+impl BootDevice {
+    /// The BIOS drive number, such as `0x80` for the first hard disk.
+    #[must_use]
+    pub const fn drive(&self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    /// The top-level partition number, if any.
+    #[must_use]
+    pub const fn partition(&self) -> Option<u8> {
+        Self::part((self.0 >> 16) as u8)
+    }
+
+    /// The sub-partition in the top-level partition, if any.
+    #[must_use]
+    pub const fn sub_partition(&self) -> Option<u8> {
+        Self::part((self.0 >> 8) as u8)
+    }
+
+    /// The partition in the sub-partition, if any.
+    #[must_use]
+    pub const fn sub_sub_partition(&self) -> Option<u8> {
+        Self::part(self.0 as u8)
+    }
+
+    const fn part(value: u8) -> Option<u8> {
+        if value == 0xff {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+/// The spec doesn't guarantee any alignment of the module list.
+#[derive(Copy, Clone, Debug)]
+#[repr(C, packed)]
+struct RawModule {
+    mod_start: u32,
+    mod_end: u32,
+    string: u32,
+    _reserved: u32,
+}
+
+/// A module loaded by the bootloader.
+#[derive(Copy, Clone, Debug)]
+pub struct Module<'a> {
+    raw: &'a RawModule,
+    phys_offset: usize,
+}
+
+impl<'a> Module<'a> {
+    /// Start address of the module.
+    #[must_use]
+    pub const fn start_address(&self) -> u32 {
+        self.raw.mod_start
+    }
+
+    /// End address of the module.
+    #[must_use]
+    pub const fn end_address(&self) -> u32 {
+        self.raw.mod_end
+    }
+
+    /// The size of the module/the BLOB in memory.
+    #[must_use]
+    pub const fn module_size(&self) -> u32 {
+        self.end_address() - self.start_address()
+    }
+
+    /// The command line of the module.
+    pub fn cmdline(&self) -> Result<&'a str, StringError> {
+        let addr = self.phys_offset.wrapping_add(self.raw.string as usize);
+        let cstr = unsafe { CStr::from_ptr(addr as *const _) };
+        cstr.to_str().map_err(StringError::Utf8)
+    }
+}
+
+/// Iterator over the [`Module`]s of a [`BootInformation`].
+#[derive(Clone)]
+pub struct ModuleIter<'a> {
+    modules: slice::Iter<'a, RawModule>,
+    phys_offset: usize,
+}
+
+impl<'a> Iterator for ModuleIter<'a> {
+    type Item = Module<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.modules.next().map(|raw| Module {
+            raw,
+            phys_offset: self.phys_offset,
+        })
+    }
+}
+
+impl fmt::Debug for ModuleIter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// Iterator over the [`MemoryArea`]s of the memory map of a
+/// [`BootInformation`].
+///
+/// The entries of a Multiboot memory map are of variable size and not
+/// necessarily aligned, hence the areas are returned by value.
+#[derive(Clone)]
+pub struct MemoryAreaIter<'a> {
+    buffer: &'a [u8],
+}
+
+impl Iterator for MemoryAreaIter<'_> {
+    type Item = MemoryArea;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Each entry starts with its size, which doesn't include the size
+        // field itself, followed by `base_addr`, `length`, and `type`.
+        const ENTRY_SIZE: usize = 4 + 8 + 8 + 4;
+        if self.buffer.len() < ENTRY_SIZE {
+            return None;
+        }
+        let read_u32 =
+            |offset: usize| u32::from_ne_bytes(self.buffer[offset..offset + 4].try_into().unwrap());
+        let read_u64 =
+            |offset: usize| u64::from_ne_bytes(self.buffer[offset..offset + 8].try_into().unwrap());
+        let area = MemoryArea::new(read_u64(4), read_u64(12), read_u32(20));
+        let size = (read_u32(0) as usize).saturating_add(4);
+        self.buffer = self.buffer.get(size.max(ENTRY_SIZE)..).unwrap_or(&[]);
+        Some(area)
+    }
+}
+
+impl fmt::Debug for MemoryAreaIter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// The framebuffer of a [`BootInformation`].
+#[derive(Copy, Clone)]
+pub struct Framebuffer<'a> {
+    header: &'a BootInformationHeader,
+    phys_offset: usize,
+}
+
+impl<'a> Framebuffer<'a> {
+    /// Contains framebuffer physical address.
+    #[must_use]
+    pub const fn address(&self) -> u64 {
+        self.header.framebuffer_addr
+    }
+
+    /// Contains the pitch in bytes.
+    #[must_use]
+    pub const fn pitch(&self) -> u32 {
+        self.header.framebuffer_pitch
+    }
+
+    /// Contains framebuffer width in pixels.
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.header.framebuffer_width
+    }
+
+    /// Contains framebuffer height in pixels.
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.header.framebuffer_height
+    }
+
+    /// Contains number of bits per pixel.
+    #[must_use]
+    pub const fn bpp(&self) -> u8 {
+        self.header.framebuffer_bpp
+    }
+
+    /// The type of framebuffer, one of: `Indexed`, `RGB` or `Text`.
+    pub fn buffer_type(&self) -> Result<FramebufferType<'a>, UnknownFramebufferType> {
+        let info = &self.header.color_info;
+        match FramebufferTypeId::try_from(self.header.framebuffer_type)? {
+            FramebufferTypeId::Indexed => {
+                let addr = u32::from_ne_bytes([info[0], info[1], info[2], info[3]]);
+                let num_colors = u16::from_ne_bytes([info[4], info[5]]);
+                let ptr = self.phys_offset.wrapping_add(addr as usize) as *const FramebufferColor;
+                let palette = unsafe { slice::from_raw_parts(ptr, num_colors as usize) };
+                Ok(FramebufferType::Indexed { palette })
+            }
+            FramebufferTypeId::RGB => {
+                let field = |i: usize| FramebufferField {
+                    position: info[i],
+                    size: info[i + 1],
+                };
+                Ok(FramebufferType::RGB {
+                    red: field(0),
+                    green: field(2),
+                    blue: field(4),
+                })
+            }
+            FramebufferTypeId::Text => Ok(FramebufferType::Text),
+        }
+    }
+}
+
+impl fmt::Debug for Framebuffer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Framebuffer")
+            .field("address", &self.address())
+            .field("pitch", &self.pitch())
+            .field("width", &self.width())
+            .field("height", &self.height())
+            .field("bpp", &self.bpp())
+            .field("buffer_type", &self.buffer_type())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryAreaType;
+    use multiboot2_common::test_utils::AlignedBytes;
+
+    const INFO_SIZE: usize = size_of::<BootInformationHeader>();
+
+    /// Boot information with a command line, a module, a memory map, and an
+    /// RGB framebuffer. Addresses are relative to the start of the buffer.
+    fn get_bytes() -> AlignedBytes<256> {
+        let mut bytes = [0; 256];
+        let mut put = |offset: usize, data: &[u8]| {
+            bytes[offset..offset + data.len()].copy_from_slice(data);
+        };
+        let flags = InfoFlags::MEMORY
+            | InfoFlags::BOOT_DEVICE
+            | InfoFlags::CMDLINE
+            | InfoFlags::MODULES
+            | InfoFlags::MEMORY_MAP
+            | InfoFlags::BOOT_LOADER_NAME
+            | InfoFlags::FRAMEBUFFER;
+        put(0, &flags.bits().to_ne_bytes());
+        put(4, &640_u32.to_ne_bytes());
+        put(8, &0x1_fc00_u32.to_ne_bytes());
+        put(12, &0x80ff_ffff_u32.to_ne_bytes());
+        // cmdline
+        put(16, &128_u32.to_ne_bytes());
+        put(128, b"console=ttyS0\0");
+        // modules
+        put(20, &1_u32.to_ne_bytes());
+        put(24, &144_u32.to_ne_bytes());
+        put(144, &0x20_0000_u32.to_ne_bytes());
+        put(148, &0x20_1000_u32.to_ne_bytes());
+        put(152, &160_u32.to_ne_bytes());
+        put(160, b"initrd\0");
+        // memory map with two entries, the second not 8-byte aligned
+        put(44, &48_u32.to_ne_bytes());
+        put(48, &168_u32.to_ne_bytes());
+        put(168, &20_u32.to_ne_bytes());
+        put(172, &0_u64.to_ne_bytes());
+        put(180, &0x9_fc00_u64.to_ne_bytes());
+        put(188, &1_u32.to_ne_bytes());
+        put(192, &20_u32.to_ne_bytes());
+        put(196, &0x10_0000_u64.to_ne_bytes());
+        put(204, &0x7ff_0000_u64.to_ne_bytes());
+        put(212, &2_u32.to_ne_bytes());
+        // boot loader name
+        put(64, &224_u32.to_ne_bytes());
+        put(224, b"GRUB\0");
+        // framebuffer
+        put(88, &0xfd00_0000_u64.to_ne_bytes());
+        put(96, &4096_u32.to_ne_bytes());
+        put(100, &1024_u32.to_ne_bytes());
+        put(104, &768_u32.to_ne_bytes());
+        put(108, &[32, 1, 16, 8, 8, 8, 0, 8]);
+        AlignedBytes::new(bytes)
+    }
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(INFO_SIZE, 116);
+    }
+
+    #[test]
+    fn test_parse() {
+        let bytes = get_bytes();
+        let ptr = bytes.0.as_ptr();
+        let info = unsafe { BootInformation::load_with_offset(ptr.cast(), ptr as usize) }.unwrap();
+
+        assert_eq!(info.memory_lower(), Some(640));
+        assert_eq!(info.memory_upper(), Some(0x1_fc00));
+        let boot_device = info.boot_device().unwrap();
+        assert_eq!(boot_device.drive(), 0x80);
+        assert_eq!(boot_device.partition(), None);
+        assert_eq!(info.command_line(), Some(Ok("console=ttyS0")));
+        assert_eq!(info.boot_loader_name(), Some(Ok("GRUB")));
+
+        let mut modules = info.modules();
+        let module = modules.next().unwrap();
+        assert_eq!(module.start_address(), 0x20_0000);
+        assert_eq!(module.module_size(), 0x1000);
+        assert_eq!(module.cmdline(), Ok("initrd"));
+        assert!(modules.next().is_none());
+
+        assert!(info.memory_areas().eq([
+            MemoryArea::new(0, 0x9_fc00, MemoryAreaType::Available),
+            MemoryArea::new(0x10_0000, 0x7ff_0000, MemoryAreaType::Reserved),
+        ]));
+
+        assert_eq!(info.vbe_mode(), None);
+        let fb = info.framebuffer().unwrap();
+        assert_eq!(fb.address(), 0xfd00_0000);
+        assert_eq!((fb.width(), fb.height(), fb.bpp()), (1024, 768, 32));
+        assert_eq!(
+            fb.buffer_type(),
+            Ok(FramebufferType::RGB {
+                red: FramebufferField {
+                    position: 16,
+                    size: 8
+                },
+                green: FramebufferField {
+                    position: 8,
+                    size: 8
+                },
+                blue: FramebufferField {
+                    position: 0,
+                    size: 8
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_unaligned_modules() {
+        let mut bytes = get_bytes();
+        bytes.0.copy_within(144..160, 233);
+        bytes.0[24..28].copy_from_slice(&233_u32.to_ne_bytes());
+        let ptr = bytes.0.as_ptr();
+        let info = unsafe { BootInformation::load_with_offset(ptr.cast(), ptr as usize) }.unwrap();
+
+        let module = info.modules().next().unwrap();
+        assert_eq!(module.start_address(), 0x20_0000);
+        assert_eq!(module.end_address(), 0x20_1000);
+        assert_eq!(module.cmdline(), Ok("initrd"));
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let mut bytes = get_bytes();
+        bytes.0[..4].copy_from_slice(&0_u32.to_ne_bytes());
+        let ptr = bytes.0.as_ptr();
+        let info = unsafe { BootInformation::load_with_offset(ptr.cast(), ptr as usize) }.unwrap();
+        assert_eq!(info.memory_lower(), None);
+        assert_eq!(info.command_line(), None);
+        assert_eq!(info.modules().count(), 0);
+        assert_eq!(info.memory_areas().count(), 0);
+        assert!(info.framebuffer().is_none());
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let bytes = get_bytes();
+        assert_eq!(
+            unsafe { BootInformation::load(core::ptr::null()) }.err(),
+            Some(MemoryError::Null)
+        );
+        let ptr = unsafe { bytes.0.as_ptr().add(1) };
+        assert_eq!(
+            unsafe { BootInformation::load(ptr.cast()) }.err(),
+            Some(MemoryError::WrongAlignment)
+        );
+    }
+}
@@ -1,28 +1,470 @@
 //! Module for [`NetworkTag`].
 
-use crate::{TagHeader, TagType, TagTypeId};
+use crate::{TagHeader, TagType};
+use core::fmt::{Debug, Formatter};
 use core::mem;
 use multiboot2_common::{MaybeDynSized, Tag};
 use ptr_meta::Pointee;
 #[cfg(feature = "builder")]
 use {alloc::boxed::Box, multiboot2_common::new_boxed};
 
-/// The end tag ends the information struct.
-#[derive(Debug, Pointee)]
+/// The network tag contains the DHCP ACK packet the bootloader received when
+/// it was booted via network (PXE).
+#[derive(Pointee, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(C, align(8))]
 pub struct NetworkTag {
-    typ: TagTypeId,
-    size: u32,
+    header: TagHeader,
     dhcpack: [u8],
 }
 
 impl NetworkTag {
-    /// Create a new network tag from the given DHCP package.
+    /// Create a new network tag from the given DHCP ACK packet, as received
+    /// by the bootloader. The size of the tag is derived from the packet.
     #[cfg(feature = "builder")]
     #[must_use]
-    pub fn new(dhcp_pack: &[u8]) -> Box<Self> {
+    pub fn new(dhcp_ack: &[u8]) -> Box<Self> {
         let header = TagHeader::new(Self::ID, 0);
-        new_boxed(header, &[dhcp_pack])
+        new_boxed(header, &[dhcp_ack])
+    }
+
+    /// Returns the raw DHCP ACK packet.
+    #[must_use]
+    pub const fn dhcp_ack(&self) -> &[u8] {
+        &self.dhcpack
+    }
+
+    /// Returns the BOOTP view of the DHCP ACK packet, or `None` if the packet
+    /// is too short to hold the fixed-size BOOTP fields.
+    #[must_use]
+    pub const fn bootp(&self) -> Option<BootpPacket<'_>> {
+        BootpPacket::new(&self.dhcpack)
+    }
+
+    /// Returns the TFTP server to fetch further boot artifacts from.
+    ///
+    /// The TFTP server name option (`66`) takes precedence over the `sname`
+    /// field, which takes precedence over the `siaddr` field. Fields that
+    /// are overloaded with DHCP options or empty are skipped.
+    #[must_use]
+    pub fn tftp_server(&self) -> Option<TftpServer<'_>> {
+        let bootp = self.bootp()?;
+        if let Some(name) = bootp.tftp_server_name().filter(|name| !name.is_empty()) {
+            return Some(TftpServer::Name(name));
+        }
+        if bootp.overload() & 2 == 0 && !bootp.sname().is_empty() {
+            return Some(TftpServer::Name(bootp.sname()));
+        }
+        let siaddr = bootp.siaddr();
+        (siaddr != [0; 4]).then_some(TftpServer::Address(siaddr))
+    }
+
+    /// Returns the name of the boot file on the [TFTP server].
+    ///
+    /// The bootfile name option (`67`) takes precedence over the `file` field.
+    /// The `file` field is skipped if it is overloaded with DHCP options or
+    /// empty.
+    ///
+    /// [TFTP server]: Self::tftp_server
+    #[must_use]
+    pub fn boot_file(&self) -> Option<&[u8]> {
+        let bootp = self.bootp()?;
+        if let Some(name) = bootp.bootfile_name().filter(|name| !name.is_empty()) {
+            return Some(name);
+        }
+        (bootp.overload() & 1 == 0 && !bootp.file().is_empty()).then(|| bootp.file())
+    }
+}
+
+/// The TFTP server of a network boot, as returned by
+/// [`NetworkTag::tftp_server`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TftpServer<'a> {
+    /// Host name or textual address of the server, without terminating NUL.
+    Name(&'a [u8]),
+    /// IPv4 address of the server in network byte order.
+    Address([u8; 4]),
+}
+
+impl Debug for NetworkTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("NetworkTag")
+            .field("typ", &self.header.typ)
+            .field("size", &self.header.size)
+            .field("bootp", &self.bootp())
+            .finish()
+    }
+}
+
+/// Typed view on the fixed-size part of a BOOTP/DHCP packet, as specified in
+/// [RFC 951] and [RFC 2131]. All multi-byte fields are in network byte order.
+///
+/// IPv4 addresses are returned as `[u8; 4]` in network byte order.
+///
+/// [RFC 951]: https://www.rfc-editor.org/rfc/rfc951
+/// [RFC 2131]: https://www.rfc-editor.org/rfc/rfc2131
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BootpPacket<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> BootpPacket<'a> {
+    /// Size of the fixed-size part of the packet, i.e., the offset of the
+    /// options field.
+    pub const FIXED_SIZE: usize = 236;
+
+    /// `op` value of a reply message, such as a DHCP ACK.
+    pub const OP_BOOTREPLY: u8 = 2;
+
+    const OFFSET_CIADDR: usize = 12;
+    const OFFSET_YIADDR: usize = 16;
+    const OFFSET_SIADDR: usize = 20;
+    const OFFSET_GIADDR: usize = 24;
+    const OFFSET_CHADDR: usize = 28;
+    const OFFSET_SNAME: usize = 44;
+    const OFFSET_FILE: usize = 108;
+    const LEN_CHADDR: usize = 16;
+    const LEN_SNAME: usize = 64;
+    const LEN_FILE: usize = 128;
+
+    /// Creates a new view on the given packet, or returns `None` if the packet
+    /// is too short.
+    #[must_use]
+    pub const fn new(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.len() < Self::FIXED_SIZE {
+            None
+        } else {
+            Some(Self { bytes })
+        }
+    }
+
+    const fn ipv4(&self, offset: usize) -> [u8; 4] {
+        [
+            self.bytes[offset],
+            self.bytes[offset + 1],
+            self.bytes[offset + 2],
+            self.bytes[offset + 3],
+        ]
+    }
+
+    /// Returns the bytes of `field` up to the first NUL byte.
+    fn until_nul(field: &'a [u8]) -> &'a [u8] {
+        let len = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        &field[..len]
+    }
+
+    /// Returns the message op code (`1` = request, `2` = reply).
+    #[must_use]
+    pub const fn op(&self) -> u8 {
+        self.bytes[0]
+    }
+
+    /// Returns the hardware address type, e.g., `1` for Ethernet.
+    #[must_use]
+    pub const fn htype(&self) -> u8 {
+        self.bytes[1]
+    }
+
+    /// Returns the hardware address length.
+    #[must_use]
+    pub const fn hlen(&self) -> u8 {
+        self.bytes[2]
+    }
+
+    /// Returns the transaction ID.
+    #[must_use]
+    pub const fn xid(&self) -> u32 {
+        u32::from_be_bytes([self.bytes[4], self.bytes[5], self.bytes[6], self.bytes[7]])
+    }
+
+    /// Returns the client IP address, as filled in by the client.
+    #[must_use]
+    pub const fn ciaddr(&self) -> [u8; 4] {
+        self.ipv4(Self::OFFSET_CIADDR)
+    }
+
+    /// Returns the "your" (client) IP address assigned by the server.
+    #[must_use]
+    pub const fn yiaddr(&self) -> [u8; 4] {
+        self.ipv4(Self::OFFSET_YIADDR)
+    }
+
+    /// Returns the IP address of the next server to use in bootstrap.
+    #[must_use]
+    pub const fn siaddr(&self) -> [u8; 4] {
+        self.ipv4(Self::OFFSET_SIADDR)
+    }
+
+    /// Returns the IP address of the relay agent.
+    #[must_use]
+    pub const fn giaddr(&self) -> [u8; 4] {
+        self.ipv4(Self::OFFSET_GIADDR)
+    }
+
+    /// Returns the client hardware address, truncated to [`Self::hlen`].
+    #[must_use]
+    pub fn chaddr(&self) -> &'a [u8] {
+        let len = usize::from(self.hlen()).min(Self::LEN_CHADDR);
+        &self.bytes[Self::OFFSET_CHADDR..Self::OFFSET_CHADDR + len]
+    }
+
+    fn sname_field(&self) -> &'a [u8] {
+        &self.bytes[Self::OFFSET_SNAME..Self::OFFSET_SNAME + Self::LEN_SNAME]
+    }
+
+    fn file_field(&self) -> &'a [u8] {
+        &self.bytes[Self::OFFSET_FILE..Self::OFFSET_FILE + Self::LEN_FILE]
+    }
+
+    /// Returns the optional server host name, without the terminating NUL.
+    ///
+    /// If the field is overloaded with DHCP options, the returned bytes are
+    /// meaningless. The options are available via [`Self::options`].
+    #[must_use]
+    pub fn sname(&self) -> &'a [u8] {
+        Self::until_nul(self.sname_field())
+    }
+
+    /// Returns the boot file name, without the terminating NUL.
+    ///
+    /// If the field is overloaded with DHCP options, the returned bytes are
+    /// meaningless. The options are available via [`Self::options`].
+    #[must_use]
+    pub fn file(&self) -> &'a [u8] {
+        Self::until_nul(self.file_field())
+    }
+
+    /// Returns the raw options field, which follows the fixed-size part.
+    #[must_use]
+    pub fn options_raw(&self) -> &'a [u8] {
+        &self.bytes[Self::FIXED_SIZE..]
+    }
+
+    /// Returns the value of the option overload option (`52`), or `0` if the
+    /// option is not present.
+    fn overload(&self) -> u8 {
+        DhcpOptionIter::new(self.dhcp_options_field(), [&[], &[]])
+            .find(|opt| opt.code() == DhcpOption::OVERLOAD)
+            .and_then(|opt| opt.data().first().copied())
+            .unwrap_or(0)
+    }
+
+    /// Returns the options field without the magic cookie, or an empty slice
+    /// if the magic cookie is missing.
+    fn dhcp_options_field(&self) -> &'a [u8] {
+        let options = self.options_raw();
+        if options.starts_with(&DhcpOption::MAGIC_COOKIE) {
+            &options[DhcpOption::MAGIC_COOKIE.len()..]
+        } else {
+            &[]
+        }
+    }
+
+    /// Returns an iterator over the DHCP options of the packet.
+    ///
+    /// If the option overload option (`52`) is present, the options stored in
+    /// the `file` and `sname` fields are emitted after the ones of the options
+    /// field, as specified in [RFC 2131]. The iterator is empty if the options
+    /// field doesn't start with the DHCP magic cookie.
+    ///
+    /// [RFC 2131]: https://www.rfc-editor.org/rfc/rfc2131
+    #[must_use]
+    pub fn options(&self) -> DhcpOptionIter<'a> {
+        let overload = self.overload();
+        let file: &[u8] = if overload & 1 != 0 {
+            self.file_field()
+        } else {
+            &[]
+        };
+        let sname: &[u8] = if overload & 2 != 0 {
+            self.sname_field()
+        } else {
+            &[]
+        };
+        DhcpOptionIter::new(self.dhcp_options_field(), [file, sname])
+    }
+
+    /// Returns the data of the first option with the given code.
+    #[must_use]
+    pub fn option(&self, code: u8) -> Option<&'a [u8]> {
+        self.options()
+            .find(|opt| opt.code() == code)
+            .map(|opt| opt.data())
+    }
+
+    fn ipv4_option(&self, code: u8) -> Option<[u8; 4]> {
+        self.option(code)
+            .and_then(|data| data.get(..4))
+            .map(|data| [data[0], data[1], data[2], data[3]])
+    }
+
+    /// Returns the DHCP message type (option `53`), e.g., `5` for DHCPACK.
+    #[must_use]
+    pub fn message_type(&self) -> Option<u8> {
+        self.option(DhcpOption::MESSAGE_TYPE)
+            .and_then(|data| data.first().copied())
+    }
+
+    /// Returns the subnet mask (option `1`).
+    #[must_use]
+    pub fn subnet_mask(&self) -> Option<[u8; 4]> {
+        self.ipv4_option(DhcpOption::SUBNET_MASK)
+    }
+
+    /// Returns the routers (option `3`), in order of preference.
+    #[must_use]
+    pub fn routers(&self) -> Ipv4AddrIter<'a> {
+        Ipv4AddrIter::new(self.option(DhcpOption::ROUTERS).unwrap_or(&[]))
+    }
+
+    /// Returns the DNS servers (option `6`), in order of preference.
+    #[must_use]
+    pub fn dns_servers(&self) -> Ipv4AddrIter<'a> {
+        Ipv4AddrIter::new(self.option(DhcpOption::DNS_SERVERS).unwrap_or(&[]))
+    }
+
+    /// Returns the TFTP server name (option `66`).
+    #[must_use]
+    pub fn tftp_server_name(&self) -> Option<&'a [u8]> {
+        self.option(DhcpOption::TFTP_SERVER_NAME)
+            .map(Self::until_nul)
+    }
+
+    /// Returns the boot file name (option `67`).
+    #[must_use]
+    pub fn bootfile_name(&self) -> Option<&'a [u8]> {
+        self.option(DhcpOption::BOOTFILE_NAME).map(Self::until_nul)
+    }
+}
+
+impl Debug for BootpPacket<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BootpPacket")
+            .field("op", &self.op())
+            .field("xid", &self.xid())
+            .field("yiaddr", &self.yiaddr())
+            .field("siaddr", &self.siaddr())
+            .field("giaddr", &self.giaddr())
+            .field("chaddr", &self.chaddr())
+            .field("sname", &self.sname())
+            .field("file", &self.file())
+            .field("options", &self.options())
+            .finish()
+    }
+}
+
+/// A single DHCP option, as specified in [RFC 2132].
+///
+/// [RFC 2132]: https://www.rfc-editor.org/rfc/rfc2132
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DhcpOption<'a> {
+    code: u8,
+    data: &'a [u8],
+}
+
+impl<'a> DhcpOption<'a> {
+    /// Magic cookie at the beginning of the options field.
+    pub const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+    /// Pad option, which has no length byte.
+    pub const PAD: u8 = 0;
+    /// Subnet mask option.
+    pub const SUBNET_MASK: u8 = 1;
+    /// Router option.
+    pub const ROUTERS: u8 = 3;
+    /// Domain name server option.
+    pub const DNS_SERVERS: u8 = 6;
+    /// Option overload option.
+    pub const OVERLOAD: u8 = 52;
+    /// DHCP message type option.
+    pub const MESSAGE_TYPE: u8 = 53;
+    /// TFTP server name option.
+    pub const TFTP_SERVER_NAME: u8 = 66;
+    /// Bootfile name option.
+    pub const BOOTFILE_NAME: u8 = 67;
+    /// End option, which has no length byte.
+    pub const END: u8 = 255;
+
+    /// Returns the option code.
+    #[must_use]
+    pub const fn code(&self) -> u8 {
+        self.code
+    }
+
+    /// Returns the option data, without code and length.
+    #[must_use]
+    pub const fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// Iterator over the [`DhcpOption`]s of a [`BootpPacket`].
+///
+/// Pad options are skipped. The iteration of a field stops at the end option
+/// or at a truncated option.
+#[derive(Clone)]
+pub struct DhcpOptionIter<'a> {
+    buffer: &'a [u8],
+    /// Overloaded fields that are iterated after `buffer`, in order.
+    overloaded: [&'a [u8]; 2],
+}
+
+impl<'a> DhcpOptionIter<'a> {
+    const fn new(buffer: &'a [u8], overloaded: [&'a [u8]; 2]) -> Self {
+        Self { buffer, overloaded }
+    }
+}
+
+impl<'a> Iterator for DhcpOptionIter<'a> {
+    type Item = DhcpOption<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.buffer {
+                [DhcpOption::PAD, rest @ ..] => self.buffer = rest,
+                [code, len, rest @ ..]
+                    if *code != DhcpOption::END && usize::from(*len) <= rest.len() =>
+                {
+                    let (data, rest) = rest.split_at(usize::from(*len));
+                    self.buffer = rest;
+                    return Some(DhcpOption { code: *code, data });
+                }
+                // End option, truncated option, or end of the field.
+                _ => {
+                    let next = self.overloaded.iter_mut().find(|f| !f.is_empty())?;
+                    self.buffer = mem::take(next);
+                }
+            }
+        }
+    }
+}
+
+impl Debug for DhcpOptionIter<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// Iterator over a list of IPv4 addresses in a [`DhcpOption`]. Each address
+/// is returned as `[u8; 4]` in network byte order.
+#[derive(Clone, Debug)]
+pub struct Ipv4AddrIter<'a> {
+    chunks: core::slice::ChunksExact<'a, u8>,
+}
+
+impl<'a> Ipv4AddrIter<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            chunks: data.chunks_exact(4),
+        }
+    }
+}
+
+impl Iterator for Ipv4AddrIter<'_> {
+    type Item = [u8; 4];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks
+            .next()
+            .map(|addr| [addr[0], addr[1], addr[2], addr[3]])
     }
 }
 
@@ -32,7 +474,11 @@ impl MaybeDynSized for NetworkTag {
     const BASE_SIZE: usize = mem::size_of::<TagHeader>();
 
     fn dst_len(header: &TagHeader) -> usize {
-        header.size as usize - Self::BASE_SIZE
+        Self::try_dst_len(header).expect("tag should be at least BASE_SIZE bytes")
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
     }
 }
 
@@ -41,3 +487,150 @@ impl Tag for NetworkTag {
 
     const ID: TagType = TagType::Network;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GenericTag;
+    use core::borrow::Borrow;
+    use multiboot2_common::test_utils::AlignedBytes;
+
+    /// Returns a DHCP ACK tag with some BOOTP fields filled in.
+    fn get_bytes() -> AlignedBytes<256> {
+        let mut bytes = [0; 256];
+        // header
+        bytes[0..4].copy_from_slice(&16_u32.to_le_bytes());
+        bytes[4..8].copy_from_slice(&256_u32.to_le_bytes());
+        let packet = &mut bytes[8..];
+        packet[0..4].copy_from_slice(&[2, 1, 6, 0]);
+        packet[4..8].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        packet[16..20].copy_from_slice(&[10, 0, 2, 15]);
+        packet[20..24].copy_from_slice(&[10, 0, 2, 2]);
+        packet[24..28].copy_from_slice(&[10, 0, 2, 1]);
+        packet[28..34].copy_from_slice(&[0x52, 0x54, 0x00, 0x12, 0x34, 0x56]);
+        packet[44..50].copy_from_slice(b"server");
+        packet[108..118].copy_from_slice(b"kernel.elf");
+        AlignedBytes(bytes)
+    }
+
+    /// Test to parse the BOOTP fields of a network tag.
+    #[test]
+    fn test_parse_bootp() {
+        let bytes = get_bytes();
+        let tag = GenericTag::ref_from_slice(bytes.borrow()).unwrap();
+        let tag = tag.downcast::<NetworkTag>().unwrap();
+        assert_eq!(tag.header.typ, TagType::Network);
+        assert_eq!(tag.dhcp_ack().len(), 248);
+
+        let bootp = tag.bootp().unwrap();
+        assert_eq!(bootp.op(), BootpPacket::OP_BOOTREPLY);
+        assert_eq!(bootp.htype(), 1);
+        assert_eq!(bootp.xid(), 0xdeadbeef);
+        assert_eq!(bootp.ciaddr(), [0, 0, 0, 0]);
+        assert_eq!(bootp.yiaddr(), [10, 0, 2, 15]);
+        assert_eq!(bootp.siaddr(), [10, 0, 2, 2]);
+        assert_eq!(bootp.giaddr(), [10, 0, 2, 1]);
+        assert_eq!(bootp.chaddr(), &[0x52, 0x54, 0x00, 0x12, 0x34, 0x56]);
+        assert_eq!(bootp.sname(), b"server");
+        assert_eq!(bootp.file(), b"kernel.elf");
+        assert_eq!(bootp.options_raw().len(), 12);
+
+        assert_eq!(tag.tftp_server(), Some(TftpServer::Name(b"server")));
+        assert_eq!(tag.boot_file(), Some(b"kernel.elf".as_slice()));
+
+        assert!(BootpPacket::new(&tag.dhcp_ack()[..235]).is_none());
+        // No magic cookie.
+        assert_eq!(bootp.options().count(), 0);
+    }
+
+    /// Test to build a network tag.
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_build() {
+        let bytes = get_bytes();
+        let bytes: &[u8] = bytes.borrow();
+        let packet = &bytes[8..];
+        let tag = NetworkTag::new(packet);
+        assert_eq!(tag.header().size as usize, bytes.len());
+        assert_eq!(tag.as_bytes().as_ref(), bytes);
+        assert_eq!(tag.dhcp_ack(), packet);
+
+        // Odd sizes are padded to the tag alignment.
+        let tag = NetworkTag::new(&packet[..BootpPacket::FIXED_SIZE + 1]);
+        assert_eq!(tag.header().size, 8 + 237);
+        assert_eq!(tag.as_bytes().as_ref().len(), 8 + 240);
+        assert_eq!(tag.bootp().unwrap().file(), b"kernel.elf");
+    }
+
+    /// Test to iterate the DHCP options, including overloaded fields.
+    #[test]
+    fn test_dhcp_options() {
+        let mut packet = [0; BootpPacket::FIXED_SIZE + 48];
+        packet[0] = BootpPacket::OP_BOOTREPLY;
+        packet[20..24].copy_from_slice(&[10, 0, 2, 2]);
+        #[rustfmt::skip]
+        let options = [
+            99, 130, 83, 99,
+            53, 1, 5,
+            0, 0,
+            1, 4, 255, 255, 255, 0,
+            3, 8, 10, 0, 2, 1, 10, 0, 2, 254,
+            6, 4, 1, 1, 1, 1,
+            52, 1, 1,
+            255,
+        ];
+        packet[BootpPacket::FIXED_SIZE..][..options.len()].copy_from_slice(&options);
+        // The file field is overloaded with options.
+        #[rustfmt::skip]
+        let file = [
+            66, 8, b't', b'f', b't', b'p', b'.', b'l', b'a', b'n',
+            67, 11, b'/', b'k', b'e', b'r', b'n', b'e', b'l', b'.', b'e', b'l', b'f',
+            255,
+        ];
+        packet[108..][..file.len()].copy_from_slice(&file);
+        // sname isn't overloaded and must not be parsed as options.
+        packet[44..48].copy_from_slice(&[67, 2, b'n', b'o']);
+
+        let bootp = BootpPacket::new(&packet).unwrap();
+        let codes = bootp
+            .options()
+            .map(|opt| opt.code())
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(codes, [53, 1, 3, 6, 52, 66, 67]);
+        assert_eq!(bootp.message_type(), Some(5));
+        assert_eq!(bootp.subnet_mask(), Some([255, 255, 255, 0]));
+        assert_eq!(
+            bootp.routers().collect::<std::vec::Vec<_>>(),
+            [[10, 0, 2, 1], [10, 0, 2, 254]]
+        );
+        assert_eq!(
+            bootp.dns_servers().collect::<std::vec::Vec<_>>(),
+            [[1, 1, 1, 1]]
+        );
+        assert_eq!(bootp.tftp_server_name(), Some(b"tftp.lan".as_slice()));
+        assert_eq!(bootp.bootfile_name(), Some(b"/kernel.elf".as_slice()));
+
+        #[cfg(feature = "builder")]
+        {
+            let tag = NetworkTag::new(&packet);
+            assert_eq!(tag.tftp_server(), Some(TftpServer::Name(b"tftp.lan")));
+            assert_eq!(tag.boot_file(), Some(b"/kernel.elf".as_slice()));
+
+            // Without options 66/67, fall back to sname and siaddr. The file
+            // field is overloaded and thus not used.
+            packet[108..][..1].copy_from_slice(&[255]);
+            packet[44..48].copy_from_slice(b"next");
+            let tag = NetworkTag::new(&packet);
+            assert_eq!(tag.tftp_server(), Some(TftpServer::Name(b"next")));
+            assert_eq!(tag.boot_file(), None);
+            packet[44..48].fill(0);
+            let tag = NetworkTag::new(&packet);
+            assert_eq!(tag.tftp_server(), Some(TftpServer::Address([10, 0, 2, 2])));
+        }
+
+        // Truncated option ends the iteration of the field.
+        packet[BootpPacket::FIXED_SIZE + 4..][..2].copy_from_slice(&[53, 200]);
+        let bootp = BootpPacket::new(&packet).unwrap();
+        assert_eq!(bootp.options().count(), 0);
+    }
+}
@@ -0,0 +1,320 @@
+//! Module for [`PhysAddr`] and [`PhysRange`].
+
+use core::fmt::{self, Debug, Display, Formatter, LowerHex, UpperHex};
+use core::ops::Range;
+
+/// A physical address, as reported by the bootloader.
+///
+/// The boot information uses 32-bit and 64-bit fields for addresses. This
+/// type unifies them, so addresses can be compared and combined without
+/// casts. Arithmetic is only offered as `checked_*` methods, as the addresses
+/// come from the bootloader. Unlike `usize`, it doesn't truncate addresses above 4 GiB on 32-bit
+/// targets.
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct PhysAddr(u64);
+
+impl PhysAddr {
+    /// The address zero.
+    pub const ZERO: Self = Self(0);
+
+    /// Creates a new address.
+    #[must_use]
+    pub const fn new(addr: u64) -> Self {
+        Self(addr)
+    }
+
+    /// Returns the address as `u64`.
+    #[must_use]
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Adds `offset` to the address, returning `None` on overflow.
+    #[must_use]
+    pub const fn checked_add(self, offset: u64) -> Option<Self> {
+        match self.0.checked_add(offset) {
+            Some(addr) => Some(Self(addr)),
+            None => None,
+        }
+    }
+
+    /// Subtracts `offset` from the address, returning `None` on underflow.
+    #[must_use]
+    pub const fn checked_sub(self, offset: u64) -> Option<Self> {
+        match self.0.checked_sub(offset) {
+            Some(addr) => Some(Self(addr)),
+            None => None,
+        }
+    }
+
+    /// Aligns the address downwards to the given alignment.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two.
+    #[must_use]
+    pub const fn align_down(self, align: u64) -> Self {
+        assert!(align.is_power_of_two());
+        Self(self.0 & !(align - 1))
+    }
+
+    /// Aligns the address upwards to the given alignment, returning `None` if
+    /// the aligned address doesn't fit into 64 bits.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two.
+    #[must_use]
+    pub const fn align_up(self, align: u64) -> Option<Self> {
+        assert!(align.is_power_of_two());
+        match self.0.checked_add(align - 1) {
+            Some(addr) => Some(Self(addr & !(align - 1))),
+            None => None,
+        }
+    }
+
+    /// Returns whether the address is aligned to the given alignment.
+    ///
+    /// # Panics
+    /// Panics if `align` is not a power of two.
+    #[must_use]
+    pub const fn is_aligned(self, align: u64) -> bool {
+        self.align_down(align).0 == self.0
+    }
+}
+
+impl From<u32> for PhysAddr {
+    fn from(addr: u32) -> Self {
+        Self(addr.into())
+    }
+}
+
+impl From<u64> for PhysAddr {
+    fn from(addr: u64) -> Self {
+        Self(addr)
+    }
+}
+
+impl From<PhysAddr> for u64 {
+    fn from(addr: PhysAddr) -> Self {
+        addr.0
+    }
+}
+
+impl Debug for PhysAddr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "PhysAddr({:#x})", self.0)
+    }
+}
+
+impl Display for PhysAddr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl LowerHex for PhysAddr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl UpperHex for PhysAddr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        UpperHex::fmt(&self.0, f)
+    }
+}
+
+/// A range of physical memory from [`Self::start`] (inclusive) to
+/// [`Self::end`] (exclusive).
+///
+/// Like [`Range`], a range whose end is not above its start is empty. This
+/// way, bogus values of a bootloader don't cause a panic.
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PhysRange {
+    start: PhysAddr,
+    end: PhysAddr,
+}
+
+impl PhysRange {
+    /// Creates a new range.
+    #[must_use]
+    pub const fn new(start: PhysAddr, end: PhysAddr) -> Self {
+        Self { start, end }
+    }
+
+    /// Creates a new range of `size` bytes at `start`. The end saturates at
+    /// [`u64::MAX`].
+    #[must_use]
+    pub const fn from_start_size(start: PhysAddr, size: u64) -> Self {
+        Self::new(start, PhysAddr(start.0.saturating_add(size)))
+    }
+
+    /// The first address of the range.
+    #[must_use]
+    pub const fn start(&self) -> PhysAddr {
+        self.start
+    }
+
+    /// The address after the last address of the range.
+    #[must_use]
+    pub const fn end(&self) -> PhysAddr {
+        self.end
+    }
+
+    /// The size of the range in bytes.
+    #[must_use]
+    pub const fn size(&self) -> u64 {
+        self.end.0.saturating_sub(self.start.0)
+    }
+
+    /// Returns whether the range contains no address.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.end.0 <= self.start.0
+    }
+
+    /// Returns whether the address is within the range.
+    #[must_use]
+    pub const fn contains(&self, addr: PhysAddr) -> bool {
+        self.start.0 <= addr.0 && addr.0 < self.end.0
+    }
+
+    /// Returns whether `other` is entirely within the range. An empty range
+    /// is within every range.
+    #[must_use]
+    pub const fn contains_range(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start.0 <= other.start.0 && other.end.0 <= self.end.0)
+    }
+
+    /// Returns whether the ranges have at least one address in common.
+    #[must_use]
+    pub const fn overlaps(&self, other: &Self) -> bool {
+        // The intersection starts at the later start and ends at the earlier
+        // end.
+        let start = if self.start.0 > other.start.0 {
+            self.start.0
+        } else {
+            other.start.0
+        };
+        let end = if self.end.0 < other.end.0 {
+            self.end.0
+        } else {
+            other.end.0
+        };
+        start < end
+    }
+}
+
+impl From<Range<PhysAddr>> for PhysRange {
+    fn from(range: Range<PhysAddr>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl From<PhysRange> for Range<PhysAddr> {
+    fn from(range: PhysRange) -> Self {
+        range.start..range.end
+    }
+}
+
+impl From<PhysRange> for Range<u64> {
+    fn from(range: PhysRange) -> Self {
+        range.start.0..range.end.0
+    }
+}
+
+impl Debug for PhysRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "PhysRange({:#x}..{:#x})", self.start.0, self.end.0)
+    }
+}
+
+impl Display for PhysRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}..{:#x}", self.start.0, self.end.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phys_addr() {
+        let addr = PhysAddr::new(0x1234);
+        assert_eq!(addr.align_down(0x1000), PhysAddr::new(0x1000));
+        assert_eq!(addr.align_up(0x1000), Some(PhysAddr::new(0x2000)));
+        assert_eq!(PhysAddr::new(u64::MAX).align_up(2), None);
+        assert!(!addr.is_aligned(0x1000));
+        assert!(PhysAddr::new(0x2000).is_aligned(0x1000));
+        assert_eq!(addr.checked_add(4), Some(PhysAddr::new(0x1238)));
+        assert_eq!(addr.checked_sub(0x234), Some(PhysAddr::new(0x1000)));
+        assert_eq!(PhysAddr::new(u64::MAX).checked_add(1), None);
+        assert_eq!(PhysAddr::ZERO.checked_sub(1), None);
+        assert_eq!(PhysAddr::from(0x1234_u32), addr);
+        assert_eq!(
+            std::format!("{addr} {addr:?} {addr:x}"),
+            "0x1234 PhysAddr(0x1234) 1234"
+        );
+    }
+
+    #[test]
+    fn phys_range() {
+        let range = PhysRange::from_start_size(PhysAddr::new(0x1000), 0x1000);
+        assert_eq!(range.end(), PhysAddr::new(0x2000));
+        assert_eq!(range.size(), 0x1000);
+        assert!(range.contains(PhysAddr::new(0x1000)));
+        assert!(range.contains(PhysAddr::new(0x1fff)));
+        assert!(!range.contains(PhysAddr::new(0x2000)));
+
+        let inner = PhysRange::new(PhysAddr::new(0x1800), PhysAddr::new(0x2000));
+        let overlapping = PhysRange::new(PhysAddr::new(0x1800), PhysAddr::new(0x2800));
+        let adjacent = PhysRange::new(PhysAddr::new(0x2000), PhysAddr::new(0x3000));
+        assert!(range.contains_range(&inner));
+        assert!(!range.contains_range(&overlapping));
+        assert!(range.overlaps(&overlapping));
+        assert!(!range.overlaps(&adjacent));
+
+        // A range whose end is below its start is empty.
+        let bogus = PhysRange::new(PhysAddr::new(0x2000), PhysAddr::new(0x1000));
+        assert!(bogus.is_empty());
+        assert_eq!(bogus.size(), 0);
+        assert!(!bogus.overlaps(&range));
+        assert!(range.contains_range(&bogus));
+
+        let saturated = PhysRange::from_start_size(PhysAddr::new(u64::MAX - 1), 4);
+        assert_eq!(saturated.end(), PhysAddr::new(u64::MAX));
+        assert_eq!(Range::<u64>::from(range), 0x1000..0x2000);
+    }
+
+    #[test]
+    fn tag_getters() {
+        use crate::{ImageLoadPhysAddrTag, MemoryArea, MemoryAreaType, RsdpV1Tag, RsdpV2Tag};
+
+        let area = MemoryArea::new(0x10_0000, 0x1000, MemoryAreaType::Available);
+        assert_eq!(
+            area.range(),
+            PhysRange::new(PhysAddr::new(0x10_0000), PhysAddr::new(0x10_1000))
+        );
+        let tag = ImageLoadPhysAddrTag::new(0x20_0000);
+        assert_eq!(tag.load_base_phys(), PhysAddr::new(0x20_0000));
+        let tag = RsdpV1Tag::new(0, *b"BOCHS ", 0, 0xf1000);
+        assert_eq!(tag.rsdt_phys(), PhysAddr::new(0xf1000));
+        let tag = RsdpV2Tag::new(0, *b"BOCHS ", 2, 0xf1000, 36, 0x1_0000_0000, 0);
+        assert_eq!(tag.xsdt_phys(), PhysAddr::new(0x1_0000_0000));
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn dst_tag_getters() {
+        use crate::{FramebufferTag, FramebufferType, ModuleTag};
+
+        let tag = ModuleTag::new(0x1000, 0x3000, "mod");
+        assert_eq!(tag.range().size(), 0x2000);
+        let tag = FramebufferTag::new(0xfd00_0000, 4096, 1024, 768, 32, FramebufferType::Text);
+        assert_eq!(
+            tag.range(),
+            PhysRange::from_start_size(PhysAddr::new(0xfd00_0000), 4096 * 768)
+        );
+    }
+}
@@ -0,0 +1,373 @@
+//! Module for [`BootLayoutPlanner`].
+
+use crate::{Builder, MemoryArea, MemoryAreaType};
+use alloc::vec::Vec;
+use derive_more::Display;
+
+/// Where the kernel image may be placed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KernelPlacement {
+    /// The kernel must be loaded at exactly this physical address.
+    Fixed(u64),
+    /// The kernel is relocatable and may be loaded anywhere in
+    /// `min_addr..max_addr` at the given alignment.
+    Relocatable {
+        /// The lowest address the kernel may start at.
+        min_addr: u64,
+        /// The highest address the kernel may end at (exclusive).
+        max_addr: u64,
+        /// The alignment of the load address.
+        align: u64,
+        /// Whether the highest possible address is preferred.
+        prefer_high: bool,
+    },
+}
+
+/// Errors of [`BootLayoutPlanner::plan`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PlanError {
+    /// The kernel doesn't fit into available memory at the requested
+    /// placement.
+    NoSpaceForKernel,
+    /// The module with the given index doesn't fit into available memory.
+    #[display("NoSpaceForModule({})", _0)]
+    NoSpaceForModule(usize),
+    /// The boot information doesn't fit into available memory.
+    NoSpaceForBootInformation,
+}
+
+impl core::error::Error for PlanError {}
+
+/// The result of [`BootLayoutPlanner::plan`]: non-overlapping physical
+/// placements of the kernel, the modules, and the boot information. All
+/// ranges are `start..end` with an exclusive end.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BootLayout {
+    kernel: (u64, u64),
+    modules: Vec<(u64, u64)>,
+    boot_information: (u64, u64),
+}
+
+impl BootLayout {
+    /// Returns the physical range of the kernel image.
+    #[must_use]
+    pub const fn kernel(&self) -> (u64, u64) {
+        self.kernel
+    }
+
+    /// Returns the physical ranges of the modules, in the order they were
+    /// added to the planner.
+    #[must_use]
+    pub fn modules(&self) -> &[(u64, u64)] {
+        &self.modules
+    }
+
+    /// Returns the physical range of the boot information. Pass the start
+    /// address to [`Builder::build_at`].
+    #[must_use]
+    pub const fn boot_information(&self) -> (u64, u64) {
+        self.boot_information
+    }
+
+    /// Adds a [`ModuleTag`](crate::ModuleTag) for each planned module with
+    /// the corresponding command line to the builder.
+    ///
+    /// # Panics
+    /// Panics if the number of command lines doesn't match the number of
+    /// modules or if a module is placed above 4 GiB, which the module tag
+    /// can't express.
+    #[must_use]
+    pub fn add_modules_to(&self, builder: Builder, cmdlines: &[&str]) -> Builder {
+        assert_eq!(cmdlines.len(), self.modules.len());
+        self.modules
+            .iter()
+            .zip(cmdlines)
+            .fold(builder, |builder, (&(start, end), cmdline)| {
+                let start = u32::try_from(start).expect("module must be below 4 GiB");
+                let end = u32::try_from(end).expect("module must be below 4 GiB");
+                builder.add_module(start, end, cmdline)
+            })
+    }
+}
+
+/// Plans where a loader places the kernel, the modules, and the boot
+/// information in physical memory.
+///
+/// The planner only considers [`MemoryAreaType::Available`] memory at or
+/// above [`Self::min_addr`]. The kernel is placed first, followed by the
+/// modules and the boot information, which are placed at the lowest free
+/// address that fits.
+///
+/// As the size of the boot information doesn't depend on the placement of
+/// the modules, it can be obtained upfront from [`Builder::required_size`].
+#[derive(Clone, Debug)]
+pub struct BootLayoutPlanner {
+    free: Vec<(u64, u64)>,
+    min_addr: u64,
+    kernel: Option<(u64, KernelPlacement)>,
+    modules: Vec<u64>,
+    module_align: u64,
+    boot_information_size: u64,
+}
+
+impl BootLayoutPlanner {
+    /// Alignment of modules, as required by the module alignment header tag.
+    pub const PAGE_SIZE: u64 = 0x1000;
+
+    /// Creates a new planner for the given memory map.
+    #[must_use]
+    pub fn new(memory_areas: &[MemoryArea]) -> Self {
+        let mut free = memory_areas
+            .iter()
+            .filter(|area| MemoryAreaType::from(area.typ()) == MemoryAreaType::Available)
+            .map(|area| (area.start_address(), area.end_address()))
+            .collect::<Vec<_>>();
+        free.sort_unstable();
+        Self {
+            free,
+            min_addr: 0x100000,
+            kernel: None,
+            modules: Vec::new(),
+            module_align: Self::PAGE_SIZE,
+            boot_information_size: 0,
+        }
+    }
+
+    /// Sets the lowest address the planner may use. Defaults to 1 MiB to
+    /// leave the real mode memory alone.
+    #[must_use]
+    pub const fn min_addr(mut self, min_addr: u64) -> Self {
+        self.min_addr = min_addr;
+        self
+    }
+
+    /// Sets the size and placement of the kernel image, i.e., the memory it
+    /// occupies after loading, including the bss.
+    #[must_use]
+    pub const fn kernel(mut self, size: u64, placement: KernelPlacement) -> Self {
+        self.kernel = Some((size, placement));
+        self
+    }
+
+    /// Adds a module of the given size.
+    #[must_use]
+    pub fn add_module(mut self, size: u64) -> Self {
+        self.modules.push(size);
+        self
+    }
+
+    /// Sets the alignment of the modules. Defaults to [`Self::PAGE_SIZE`].
+    #[must_use]
+    pub const fn module_align(mut self, align: u64) -> Self {
+        self.module_align = align;
+        self
+    }
+
+    /// Sets the size of the boot information.
+    #[must_use]
+    pub const fn boot_information_size(mut self, size: u64) -> Self {
+        self.boot_information_size = size;
+        self
+    }
+
+    /// Removes `start..end` from the free regions. Returns `false` if the
+    /// range is not entirely free.
+    fn reserve(&mut self, start: u64, end: u64) -> bool {
+        let Some(idx) = self.free.iter().position(|&(s, e)| s <= start && end <= e) else {
+            return false;
+        };
+        let (s, e) = self.free.remove(idx);
+        if end < e {
+            self.free.insert(idx, (end, e));
+        }
+        if s < start {
+            self.free.insert(idx, (s, start));
+        }
+        true
+    }
+
+    /// Finds and reserves `size` bytes at the given alignment within
+    /// `min..max`.
+    fn allocate(&mut self, size: u64, align: u64, min: u64, max: u64, high: bool) -> Option<u64> {
+        let align = align.max(1);
+        let fits = |&(s, e): &(u64, u64)| {
+            let lo = s.max(min);
+            let hi = e.min(max);
+            if high {
+                let start = hi.checked_sub(size)? / align * align;
+                (start >= lo).then_some(start)
+            } else {
+                let start = lo.checked_next_multiple_of(align)?;
+                (start.checked_add(size)? <= hi).then_some(start)
+            }
+        };
+        let start = if high {
+            self.free.iter().rev().find_map(fits)
+        } else {
+            self.free.iter().find_map(fits)
+        }?;
+        assert!(self.reserve(start, start + size));
+        Some(start)
+    }
+
+    /// Computes the layout.
+    pub fn plan(mut self) -> Result<BootLayout, PlanError> {
+        let min_addr = self.min_addr;
+        let kernel = match self.kernel {
+            None => (0, 0),
+            Some((size, KernelPlacement::Fixed(addr))) => {
+                let end = addr.checked_add(size).ok_or(PlanError::NoSpaceForKernel)?;
+                if !self.reserve(addr, end) {
+                    return Err(PlanError::NoSpaceForKernel);
+                }
+                (addr, end)
+            }
+            Some((
+                size,
+                KernelPlacement::Relocatable {
+                    min_addr: min,
+                    max_addr: max,
+                    align,
+                    prefer_high,
+                },
+            )) => {
+                let start = self
+                    .allocate(size, align, min.max(min_addr), max, prefer_high)
+                    .ok_or(PlanError::NoSpaceForKernel)?;
+                (start, start + size)
+            }
+        };
+
+        let mut modules = Vec::with_capacity(self.modules.len());
+        for (i, size) in core::mem::take(&mut self.modules).into_iter().enumerate() {
+            let start = self
+                .allocate(size, self.module_align, min_addr, u64::MAX, false)
+                .ok_or(PlanError::NoSpaceForModule(i))?;
+            modules.push((start, start + size));
+        }
+
+        let size = self.boot_information_size;
+        let start = self
+            .allocate(size, 8, min_addr, u64::MAX, false)
+            .ok_or(PlanError::NoSpaceForBootInformation)?;
+
+        Ok(BootLayout {
+            kernel,
+            modules,
+            boot_information: (start, start + size),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BootInformation, CommandLineTag};
+
+    fn memory_map() -> [MemoryArea; 3] {
+        [
+            MemoryArea::new(0x0, 0x9fc00, MemoryAreaType::Available),
+            MemoryArea::new(0x100000, 0x100000, MemoryAreaType::Available),
+            MemoryArea::new(0x200000, 0x1000, MemoryAreaType::Reserved),
+        ]
+    }
+
+    #[test]
+    fn test_plan_fixed() {
+        let layout = BootLayoutPlanner::new(&memory_map())
+            .kernel(0x10000, KernelPlacement::Fixed(0x100000))
+            .add_module(0x1800)
+            .add_module(0x10)
+            .boot_information_size(0x100)
+            .plan()
+            .unwrap();
+        assert_eq!(layout.kernel(), (0x100000, 0x110000));
+        assert_eq!(
+            layout.modules(),
+            &[(0x110000, 0x111800), (0x112000, 0x112010)]
+        );
+        // The boot information fills the alignment gap between the modules.
+        assert_eq!(layout.boot_information(), (0x111800, 0x111900));
+
+        // Outside of available memory.
+        assert_eq!(
+            BootLayoutPlanner::new(&memory_map())
+                .kernel(0x1000, KernelPlacement::Fixed(0x200000))
+                .plan(),
+            Err(PlanError::NoSpaceForKernel)
+        );
+    }
+
+    #[test]
+    fn test_plan_relocatable() {
+        let layout = BootLayoutPlanner::new(&memory_map())
+            .kernel(
+                0x10000,
+                KernelPlacement::Relocatable {
+                    min_addr: 0,
+                    max_addr: u64::MAX,
+                    align: 0x200000,
+                    prefer_high: false,
+                },
+            )
+            .plan();
+        // There is no 2 MiB aligned free region above 1 MiB.
+        assert_eq!(layout, Err(PlanError::NoSpaceForKernel));
+
+        let layout = BootLayoutPlanner::new(&memory_map())
+            .kernel(
+                0x10000,
+                KernelPlacement::Relocatable {
+                    min_addr: 0,
+                    max_addr: u64::MAX,
+                    align: 0x1000,
+                    prefer_high: true,
+                },
+            )
+            .add_module(0x200000)
+            .plan();
+        assert_eq!(layout, Err(PlanError::NoSpaceForModule(0)));
+
+        let layout = BootLayoutPlanner::new(&memory_map())
+            .kernel(
+                0x10000,
+                KernelPlacement::Relocatable {
+                    min_addr: 0,
+                    max_addr: u64::MAX,
+                    align: 0x1000,
+                    prefer_high: true,
+                },
+            )
+            .add_module(0x1000)
+            .boot_information_size(0x40)
+            .plan()
+            .unwrap();
+        assert_eq!(layout.kernel(), (0x1f0000, 0x200000));
+        assert_eq!(layout.modules(), &[(0x100000, 0x101000)]);
+        assert_eq!(layout.boot_information(), (0x101000, 0x101040));
+    }
+
+    #[test]
+    fn test_plan_and_build() {
+        // Module addresses don't influence the size.
+        let size = Builder::new()
+            .cmdline(CommandLineTag::new("kernel"))
+            .add_module(0, 1, "module")
+            .required_size();
+        let builder = Builder::new().cmdline(CommandLineTag::new("kernel"));
+        let layout = BootLayoutPlanner::new(&memory_map())
+            .kernel(0x10000, KernelPlacement::Fixed(0x100000))
+            .add_module(0x1000)
+            .boot_information_size(size as u64)
+            .plan()
+            .unwrap();
+        let builder = layout.add_modules_to(builder, &["module"]);
+        assert_eq!(builder.required_size(), size);
+
+        let structure = builder.build();
+        let info = unsafe { BootInformation::load(structure.as_bytes().as_ptr().cast()) }.unwrap();
+        let module = info.module_tags().next().unwrap();
+        assert_eq!(module.start_address(), 0x110000);
+        assert_eq!(module.end_address(), 0x111000);
+    }
+}
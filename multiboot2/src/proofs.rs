@@ -0,0 +1,97 @@
+//! Kani proof harnesses for the memory safety of the parser.
+//!
+//! Run them with `cargo kani -p multiboot2`.
+
+use crate::{
+    BootInformation, BootLoaderNameTag, CommandLineTag, EFIMemoryMapTag, FramebufferTag,
+    GenericTag, LoadLimits, MemoryMapTag, ModuleTag, RsdpV1Tag, RsdpV2Tag, TagHeader,
+};
+use multiboot2_common::test_utils::AlignedBytes;
+use multiboot2_common::MaybeDynSized;
+
+/// The size of the boot information buffers. Small, to keep the number of
+/// tags and thus the loop bounds small.
+const MBI_SIZE: usize = 48;
+
+/// Returns an arbitrary boot information buffer whose reported total size is
+/// within the buffer, as required by the safety contract of
+/// [`BootInformation::load`].
+fn any_mbi() -> AlignedBytes<MBI_SIZE> {
+    let bytes = AlignedBytes::<MBI_SIZE>(kani::any());
+    let total_size = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    kani::assume(total_size as usize <= MBI_SIZE);
+    bytes
+}
+
+/// The layout checks never read outside the total size, even for tags with
+/// arbitrary sizes.
+#[kani::proof]
+#[kani::unwind(7)]
+fn layout_checks_stay_in_bounds() {
+    let bytes = any_mbi();
+    let limits = LoadLimits {
+        max_tags: kani::any(),
+        max_tag_size: kani::any(),
+    };
+    let _ = unsafe { BootInformation::load_with_limits(bytes.as_ptr().cast(), limits) };
+}
+
+/// The tags of a checked boot information lie within its total size, so the
+/// tag walker never panics or reads beyond it.
+#[kani::proof]
+#[kani::unwind(7)]
+fn tag_walker_stays_in_bounds() {
+    let bytes = any_mbi();
+    let Ok(mbi) = (unsafe { BootInformation::load_checked(bytes.as_ptr().cast()) }) else {
+        return;
+    };
+    let start = mbi.start_address();
+    let end = start + mbi.total_size();
+    for tag in mbi.raw_tags() {
+        let tag_start = tag as *const _ as *const u8 as usize;
+        assert!(tag_start >= start + 8);
+        assert!(tag_start + tag.header().size as usize <= end);
+    }
+}
+
+/// Checks that the dynamically sized part of the tag is only computed for
+/// sizes that cover the sized part of the tag, so that it never underflows.
+macro_rules! dst_len_proof {
+    ($name:ident, $tag:ty) => {
+        #[kani::proof]
+        fn $name() {
+            let header = TagHeader::new(kani::any::<u32>(), kani::any());
+            let dst_len = <$tag as MaybeDynSized>::try_dst_len(&header);
+            assert!(
+                dst_len.is_none() || header.size as usize >= <$tag as MaybeDynSized>::BASE_SIZE
+            );
+        }
+    };
+}
+
+dst_len_proof!(generic_dst_len, GenericTag);
+dst_len_proof!(cmdline_dst_len, CommandLineTag);
+dst_len_proof!(boot_loader_name_dst_len, BootLoaderNameTag);
+dst_len_proof!(module_dst_len, ModuleTag);
+dst_len_proof!(memory_map_dst_len, MemoryMapTag);
+dst_len_proof!(efi_memory_map_dst_len, EFIMemoryMapTag);
+dst_len_proof!(framebuffer_dst_len, FramebufferTag);
+#[cfg(feature = "elf")]
+dst_len_proof!(elf_sections_dst_len, crate::ElfSectionsTag);
+#[cfg(feature = "net")]
+dst_len_proof!(network_dst_len, crate::NetworkTag);
+#[cfg(feature = "smbios")]
+dst_len_proof!(smbios_dst_len, crate::SmbiosTag);
+
+/// The RSDP checksums only sum up bytes of the tag, whatever length the tag
+/// reports.
+#[kani::proof]
+fn rsdp_checksums_stay_in_bounds() {
+    let bytes: [u8; 48] = kani::any();
+    if let Ok(tag) = RsdpV1Tag::from_bytes(&bytes) {
+        let _ = tag.checksum_is_valid();
+    }
+    if let Ok(tag) = RsdpV2Tag::from_bytes(&bytes) {
+        let _ = tag.checksum_is_valid();
+    }
+}
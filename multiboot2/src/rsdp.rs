@@ -12,16 +12,27 @@
 //! signature should be manually verified.
 //!
 
-use crate::tag::TagHeader;
-use crate::TagType;
-#[cfg(feature = "builder")]
-use core::mem::size_of;
+use crate::tag::{parse_sized_tag, TagHeader, TagParseError};
+use crate::util::parse_fixed_string;
+use crate::StringError;
+use crate::{PhysAddr, TagType};
 use core::slice;
-use core::str;
-use core::str::Utf8Error;
 use multiboot2_common::{MaybeDynSized, Tag};
 
 const RSDPV1_LENGTH: usize = 20;
+/// The length of the RSDPv2 structure as copied into the tag.
+const RSDPV2_LENGTH: usize = 36;
+
+/// Sums up all bytes starting at `start` with wrapping arithmetic.
+const fn byte_sum(bytes: &[u8], start: usize) -> u8 {
+    let mut sum = 0_u8;
+    let mut i = start;
+    while i < bytes.len() {
+        sum = sum.wrapping_add(bytes[i]);
+        i += 1;
+    }
+    sum
+}
 
 /// This tag contains a copy of RSDP as defined per ACPI 1.0 specification.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -43,9 +54,9 @@ impl RsdpV1Tag {
 
     /// Constructs a new tag.
     #[must_use]
-    pub fn new(checksum: u8, oem_id: [u8; 6], revision: u8, rsdt_address: u32) -> Self {
+    pub const fn new(checksum: u8, oem_id: [u8; 6], revision: u8, rsdt_address: u32) -> Self {
         Self {
-            header: TagHeader::new(Self::ID, Self::BASE_SIZE as u32),
+            header: TagHeader::new_const(Self::ID, Self::BASE_SIZE as u32),
             signature: Self::SIGNATURE,
             checksum,
             oem_id,
@@ -54,27 +65,41 @@ impl RsdpV1Tag {
         }
     }
 
+    /// Parses the tag by value from unaligned bytes that include its padding.
+    pub const fn from_bytes(bytes: &[u8]) -> Result<Self, TagParseError> {
+        parse_sized_tag(bytes)
+    }
+
     /// The "RSD PTR " marker signature.
     ///
     /// This is originally a 8-byte C string (not null terminated!) that must contain "RSD PTR "
-    pub const fn signature(&self) -> Result<&str, Utf8Error> {
-        str::from_utf8(&self.signature)
+    pub const fn signature(&self) -> Result<&str, StringError> {
+        parse_fixed_string(&self.signature)
+    }
+
+    /// The raw bytes of [`Self::signature`].
+    #[must_use]
+    pub const fn signature_bytes(&self) -> &[u8; 8] {
+        &self.signature
     }
 
     /// Validation of the RSDPv1 checksum
     #[must_use]
-    pub fn checksum_is_valid(&self) -> bool {
+    pub const fn checksum_is_valid(&self) -> bool {
         let bytes =
             unsafe { slice::from_raw_parts(self as *const _ as *const u8, RSDPV1_LENGTH + 8) };
-        bytes[8..]
-            .iter()
-            .fold(0u8, |acc, val| acc.wrapping_add(*val))
-            == 0
+        byte_sum(bytes, 8) == 0
     }
 
     /// An OEM-supplied string that identifies the OEM.
-    pub const fn oem_id(&self) -> Result<&str, Utf8Error> {
-        str::from_utf8(&self.oem_id)
+    pub const fn oem_id(&self) -> Result<&str, StringError> {
+        parse_fixed_string(&self.oem_id)
+    }
+
+    /// The raw bytes of [`Self::oem_id`].
+    #[must_use]
+    pub const fn oem_id_bytes(&self) -> &[u8; 6] {
+        &self.oem_id
     }
 
     /// The revision of the ACPI.
@@ -88,6 +113,12 @@ impl RsdpV1Tag {
     pub const fn rsdt_address(&self) -> usize {
         self.rsdt_address as usize
     }
+
+    /// Like [`Self::rsdt_address`], but as [`PhysAddr`].
+    #[must_use]
+    pub const fn rsdt_phys(&self) -> PhysAddr {
+        PhysAddr::new(self.rsdt_address as u64)
+    }
 }
 
 impl MaybeDynSized for RsdpV1Tag {
@@ -131,7 +162,7 @@ impl RsdpV2Tag {
     /// Constructs a new tag.
     #[allow(clippy::too_many_arguments)]
     #[must_use]
-    pub fn new(
+    pub const fn new(
         checksum: u8,
         oem_id: [u8; 6],
         revision: u8,
@@ -141,7 +172,7 @@ impl RsdpV2Tag {
         ext_checksum: u8,
     ) -> Self {
         Self {
-            header: TagHeader::new(Self::ID, Self::BASE_SIZE as u32),
+            header: TagHeader::new_const(Self::ID, Self::BASE_SIZE as u32),
             signature: Self::SIGNATURE,
             checksum,
             oem_id,
@@ -154,28 +185,47 @@ impl RsdpV2Tag {
         }
     }
 
+    /// Parses the tag by value from unaligned bytes that include its padding.
+    pub const fn from_bytes(bytes: &[u8]) -> Result<Self, TagParseError> {
+        parse_sized_tag(bytes)
+    }
+
     /// The "RSD PTR " marker signature.
     ///
     /// This is originally a 8-byte C string (not null terminated!) that must contain "RSD PTR ".
-    pub const fn signature(&self) -> Result<&str, Utf8Error> {
-        str::from_utf8(&self.signature)
+    pub const fn signature(&self) -> Result<&str, StringError> {
+        parse_fixed_string(&self.signature)
+    }
+
+    /// The raw bytes of [`Self::signature`].
+    #[must_use]
+    pub const fn signature_bytes(&self) -> &[u8; 8] {
+        &self.signature
     }
 
     /// Validation of the RSDPv2 extended checksum
+    ///
+    /// Returns `false` if the reported length exceeds the structure copied
+    /// into the tag, as the checksum can't be verified in that case.
     #[must_use]
-    pub fn checksum_is_valid(&self) -> bool {
-        let bytes = unsafe {
-            slice::from_raw_parts(self as *const _ as *const u8, self.length as usize + 8)
-        };
-        bytes[8..]
-            .iter()
-            .fold(0u8, |acc, val| acc.wrapping_add(*val))
-            == 0
+    pub const fn checksum_is_valid(&self) -> bool {
+        let length = self.length as usize;
+        if length > RSDPV2_LENGTH {
+            return false;
+        }
+        let bytes = unsafe { slice::from_raw_parts(self as *const _ as *const u8, length + 8) };
+        byte_sum(bytes, 8) == 0
     }
 
     /// An OEM-supplied string that identifies the OEM.
-    pub const fn oem_id(&self) -> Result<&str, Utf8Error> {
-        str::from_utf8(&self.oem_id)
+    pub const fn oem_id(&self) -> Result<&str, StringError> {
+        parse_fixed_string(&self.oem_id)
+    }
+
+    /// The raw bytes of [`Self::oem_id`].
+    #[must_use]
+    pub const fn oem_id_bytes(&self) -> &[u8; 6] {
+        &self.oem_id
     }
 
     /// The revision of the ACPI.
@@ -192,6 +242,13 @@ impl RsdpV2Tag {
         self.xsdt_address as usize
     }
 
+    /// Like [`Self::xsdt_address`], but as [`PhysAddr`], which isn't
+    /// truncated on x86.
+    #[must_use]
+    pub const fn xsdt_phys(&self) -> PhysAddr {
+        PhysAddr::new(self.xsdt_address)
+    }
+
     /// This field is used to calculate the checksum of the entire table, including both checksum fields.
     #[must_use]
     pub const fn ext_checksum(&self) -> u8 {
@@ -212,3 +269,60 @@ impl Tag for RsdpV2Tag {
 
     const ID: TagType = TagType::AcpiV2;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    const RSDP_V1_BYTES: [u8; 32] = [
+        14, 0, 0, 0,
+        28, 0, 0, 0,
+        b'R', b'S', b'D', b' ', b'P', b'T', b'R', b' ',
+        51,
+        b'B', b'O', b'C', b'H', b'S', b' ',
+        0,
+        0x00, 0x10, 0x0f, 0x00,
+        /* padding */
+        0, 0, 0, 0,
+    ];
+
+    /// The tag is parsed and validated at compile time.
+    const RSDP_V1: RsdpV1Tag = match RsdpV1Tag::from_bytes(&RSDP_V1_BYTES) {
+        Ok(tag) => tag,
+        Err(_) => panic!("invalid RSDP v1 tag"),
+    };
+    const _: () = assert!(RSDP_V1.checksum_is_valid());
+    const _: () = assert!(RSDP_V1.rsdt_address() == 0xf1000);
+
+    #[test]
+    fn test_const_parse() {
+        assert_eq!(RSDP_V1.signature(), Ok("RSD PTR "));
+        assert_eq!(RSDP_V1.oem_id(), Ok("BOCHS "));
+        assert_eq!(RSDP_V1.signature_bytes(), b"RSD PTR ");
+        assert_eq!(RSDP_V1.oem_id_bytes(), b"BOCHS ");
+        assert_eq!(RSDP_V1, RsdpV1Tag::new(51, *b"BOCHS ", 0, 0xf1000));
+        assert_eq!(
+            RsdpV2Tag::from_bytes(&RSDP_V1_BYTES),
+            Err(TagParseError::WrongType)
+        );
+    }
+
+    #[test]
+    fn test_v2_checksum_bogus_length() {
+        let mut tag = RsdpV2Tag::new(0, *b"BOCHS ", 2, 0xf1000, 36, 0xf2000, 0);
+        // Fix up the extended checksum.
+        let sum = {
+            let bytes = unsafe {
+                core::slice::from_raw_parts(&tag as *const _ as *const u8, RSDPV2_LENGTH + 8)
+            };
+            byte_sum(bytes, 8)
+        };
+        tag.ext_checksum = 0_u8.wrapping_sub(sum);
+        assert!(tag.checksum_is_valid());
+
+        // A length beyond the tag must not be read.
+        tag.length = u32::MAX;
+        assert!(!tag.checksum_is_valid());
+    }
+}
@@ -1,8 +1,9 @@
 //! Module for [`SmbiosTag`].
 
 use crate::tag::TagHeader;
-use crate::TagType;
-use core::fmt::Debug;
+use crate::util::parse_fixed_string;
+use crate::{StringError, TagType};
+use core::fmt::{Debug, Formatter};
 use core::mem;
 use multiboot2_common::{MaybeDynSized, Tag};
 #[cfg(feature = "builder")]
@@ -29,13 +30,47 @@ impl SmbiosTag {
         new_boxed(header, &[&[major, minor], &reserved, tables])
     }
 
+    /// Constructs a new tag from the raw SMBIOS firmware tables, i.e., the
+    /// entry point structure and the structure table it references. This is
+    /// what a UEFI bootloader finds via the EFI configuration table.
+    ///
+    /// The version is taken from the entry point. The entry point is
+    /// embedded in front of the structure table, which is truncated to the
+    /// size announced by the entry point.
+    ///
+    /// Returns `None` if `entry_point` is not a valid 32-bit (`_SM_`) or
+    /// 64-bit (`_SM3_`) entry point.
+    #[cfg(feature = "builder")]
+    #[must_use]
+    pub fn from_firmware_tables(entry_point: &[u8], structure_table: &[u8]) -> Option<Box<Self>> {
+        let entry_point = SmbiosEntryPoint::parse(entry_point)?;
+        if !entry_point.checksum_is_valid() {
+            return None;
+        }
+        let max_size =
+            usize::try_from(entry_point.structure_table_max_size()).unwrap_or(usize::MAX);
+        let structure_table = &structure_table[..structure_table.len().min(max_size)];
+
+        let header = TagHeader::new(Self::ID, 0);
+        let reserved = [0, 0, 0, 0, 0, 0];
+        Some(new_boxed(
+            header,
+            &[
+                &[entry_point.major(), entry_point.minor()],
+                &reserved,
+                entry_point.bytes,
+                structure_table,
+            ],
+        ))
+    }
+
     /// Returns the major number.
     #[must_use]
     pub const fn major(&self) -> u8 {
         self.major
     }
 
-    /// Returns the major number.
+    /// Returns the minor number.
     #[must_use]
     pub const fn minor(&self) -> u8 {
         self.minor
@@ -46,6 +81,122 @@ impl SmbiosTag {
     pub const fn tables(&self) -> &[u8] {
         &self.tables
     }
+
+    /// Returns the SMBIOS entry point, if the tables start with either a
+    /// 32-bit (`_SM_`) or a 64-bit (`_SM3_`) entry point structure.
+    #[must_use]
+    pub const fn entry_point(&self) -> Option<SmbiosEntryPoint<'_>> {
+        SmbiosEntryPoint::parse(&self.tables)
+    }
+
+    /// Returns the raw structure table, i.e., the tables without a leading
+    /// entry point structure, if there is one.
+    #[must_use]
+    pub const fn structure_table(&self) -> &[u8] {
+        let offset = match self.entry_point() {
+            Some(ep) => ep.length(),
+            None => 0,
+        };
+        self.tables.split_at(offset).1
+    }
+
+    /// Returns an iterator over the SMBIOS structures in the structure table.
+    ///
+    /// The iterator stops at the end-of-table structure (type `127`), which is
+    /// still emitted, or as soon as a structure is truncated or malformed.
+    #[must_use]
+    pub const fn structures(&self) -> SmbiosStructureIter<'_> {
+        SmbiosStructureIter::new(self.structure_table())
+    }
+
+    /// Returns the BIOS vendor from the BIOS information structure (type 0).
+    #[must_use]
+    pub fn bios_vendor(&self) -> Option<Result<&str, StringError>> {
+        self.identity_string(SmbiosStructure::TYPE_BIOS_INFORMATION, 0x04)
+    }
+
+    /// The raw bytes of [`Self::bios_vendor`].
+    #[must_use]
+    pub fn bios_vendor_bytes(&self) -> Option<&[u8]> {
+        self.identity_string_bytes(SmbiosStructure::TYPE_BIOS_INFORMATION, 0x04)
+    }
+
+    /// Returns the BIOS version from the BIOS information structure (type 0).
+    #[must_use]
+    pub fn bios_version(&self) -> Option<Result<&str, StringError>> {
+        self.identity_string(SmbiosStructure::TYPE_BIOS_INFORMATION, 0x05)
+    }
+
+    /// The raw bytes of [`Self::bios_version`].
+    #[must_use]
+    pub fn bios_version_bytes(&self) -> Option<&[u8]> {
+        self.identity_string_bytes(SmbiosStructure::TYPE_BIOS_INFORMATION, 0x05)
+    }
+
+    /// Returns the system manufacturer from the system information structure
+    /// (type 1).
+    #[must_use]
+    pub fn system_manufacturer(&self) -> Option<Result<&str, StringError>> {
+        self.identity_string(SmbiosStructure::TYPE_SYSTEM_INFORMATION, 0x04)
+    }
+
+    /// The raw bytes of [`Self::system_manufacturer`].
+    #[must_use]
+    pub fn system_manufacturer_bytes(&self) -> Option<&[u8]> {
+        self.identity_string_bytes(SmbiosStructure::TYPE_SYSTEM_INFORMATION, 0x04)
+    }
+
+    /// Returns the system product name from the system information structure
+    /// (type 1).
+    #[must_use]
+    pub fn system_product(&self) -> Option<Result<&str, StringError>> {
+        self.identity_string(SmbiosStructure::TYPE_SYSTEM_INFORMATION, 0x05)
+    }
+
+    /// The raw bytes of [`Self::system_product`].
+    #[must_use]
+    pub fn system_product_bytes(&self) -> Option<&[u8]> {
+        self.identity_string_bytes(SmbiosStructure::TYPE_SYSTEM_INFORMATION, 0x05)
+    }
+
+    /// Returns the raw system UUID from the system information structure
+    /// (type 1).
+    ///
+    /// Since SMBIOS 2.6, the first three fields of the UUID are encoded in
+    /// little-endian byte order. Returns `None` if the structure is too short
+    /// or if the UUID is all `0x00` (not present) or all `0xff` (not set).
+    #[must_use]
+    pub fn system_uuid(&self) -> Option<[u8; 16]> {
+        let structure = self.find_structure(SmbiosStructure::TYPE_SYSTEM_INFORMATION)?;
+        let uuid: [u8; 16] = structure
+            .formatted_area()
+            .get(0x08..0x18)?
+            .try_into()
+            .ok()?;
+        if uuid.iter().all(|&b| b == 0) || uuid.iter().all(|&b| b == 0xff) {
+            return None;
+        }
+        Some(uuid)
+    }
+
+    /// Returns the first structure of the given type.
+    fn find_structure(&self, typ: u8) -> Option<SmbiosStructure<'_>> {
+        self.structures().find(|s| s.typ() == typ)
+    }
+
+    /// Returns the string referenced at `offset` of the first structure of the
+    /// given type.
+    fn identity_string(&self, typ: u8, offset: usize) -> Option<Result<&str, StringError>> {
+        self.identity_string_bytes(typ, offset)
+            .map(parse_fixed_string)
+    }
+
+    /// Like [`Self::identity_string`], but returns the raw bytes.
+    fn identity_string_bytes(&self, typ: u8, offset: usize) -> Option<&[u8]> {
+        let structure = self.find_structure(typ)?;
+        let number = *structure.formatted_area().get(offset)?;
+        structure.string(number)
+    }
 }
 
 impl MaybeDynSized for SmbiosTag {
@@ -54,8 +205,11 @@ impl MaybeDynSized for SmbiosTag {
     const BASE_SIZE: usize = mem::size_of::<TagHeader>() + mem::size_of::<u8>() * 8;
 
     fn dst_len(header: &TagHeader) -> usize {
-        assert!(header.size as usize >= Self::BASE_SIZE);
-        header.size as usize - Self::BASE_SIZE
+        Self::try_dst_len(header).expect("tag should be at least BASE_SIZE bytes")
+    }
+
+    fn try_dst_len(header: &TagHeader) -> Option<usize> {
+        (header.size as usize).checked_sub(Self::BASE_SIZE)
     }
 }
 
@@ -66,20 +220,341 @@ impl Tag for SmbiosTag {
 }
 
 impl Debug for SmbiosTag {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("BootLoaderNameTag")
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SmbiosTag")
             .field("typ", &self.header.typ)
             .field("size", &self.header.size)
             .field("major", &self.major)
             .field("minor", &self.minor)
+            .field("structures", &self.structures())
             .finish()
     }
 }
 
+/// An SMBIOS entry point structure, either in the 32-bit format of SMBIOS 2.x
+/// (`_SM_` anchor) or in the 64-bit format of SMBIOS 3.x (`_SM3_` anchor).
+///
+/// See chapter 5.2 of the [SMBIOS specification] for the layout.
+///
+/// [SMBIOS specification]: https://www.dmtf.org/standards/smbios
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SmbiosEntryPoint<'a> {
+    bytes: &'a [u8],
+}
+
+/// `const` version of [`slice::starts_with`].
+const fn starts_with(bytes: &[u8], prefix: &[u8]) -> bool {
+    if bytes.len() < prefix.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+        if bytes[i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+impl<'a> SmbiosEntryPoint<'a> {
+    /// Anchor string of the 32-bit entry point.
+    pub const ANCHOR_32: [u8; 4] = *b"_SM_";
+
+    /// Anchor string of the 64-bit entry point.
+    pub const ANCHOR_64: [u8; 5] = *b"_SM3_";
+
+    /// Minimum length of the 32-bit entry point.
+    const MIN_LENGTH_32: usize = 0x1f;
+
+    /// Minimum length of the 64-bit entry point.
+    const MIN_LENGTH_64: usize = 0x18;
+
+    /// Parses an entry point from the given bytes, e.g., the firmware's copy
+    /// of the entry point. Returns `None` if the anchor string or the length
+    /// is invalid. The checksum is not verified.
+    #[must_use]
+    pub const fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let (length_offset, min_length) = if starts_with(bytes, &Self::ANCHOR_64) {
+            (0x06, Self::MIN_LENGTH_64)
+        } else if starts_with(bytes, &Self::ANCHOR_32) {
+            (0x05, Self::MIN_LENGTH_32)
+        } else {
+            return None;
+        };
+        if bytes.len() <= length_offset {
+            return None;
+        }
+        let length = bytes[length_offset] as usize;
+        if length < min_length || length > bytes.len() {
+            return None;
+        }
+        Some(Self {
+            bytes: bytes.split_at(length).0,
+        })
+    }
+
+    fn read_u16(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([self.bytes[offset], self.bytes[offset + 1]])
+    }
+
+    fn read_u32(&self, offset: usize) -> u32 {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(&self.bytes[offset..offset + 4]);
+        u32::from_le_bytes(buf)
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(&self.bytes[offset..offset + 8]);
+        u64::from_le_bytes(buf)
+    }
+
+    /// Returns `true` for the 64-bit (`_SM3_`) entry point.
+    #[must_use]
+    pub fn is_64bit(&self) -> bool {
+        self.bytes.starts_with(&Self::ANCHOR_64)
+    }
+
+    /// Returns the length of the entry point structure.
+    #[must_use]
+    pub const fn length(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns the major version of the SMBIOS specification.
+    #[must_use]
+    pub fn major(&self) -> u8 {
+        if self.is_64bit() {
+            self.bytes[0x07]
+        } else {
+            self.bytes[0x06]
+        }
+    }
+
+    /// Returns the minor version of the SMBIOS specification.
+    #[must_use]
+    pub fn minor(&self) -> u8 {
+        if self.is_64bit() {
+            self.bytes[0x08]
+        } else {
+            self.bytes[0x07]
+        }
+    }
+
+    /// Returns the physical address of the structure table.
+    #[must_use]
+    pub fn structure_table_address(&self) -> u64 {
+        if self.is_64bit() {
+            self.read_u64(0x10)
+        } else {
+            u64::from(self.read_u32(0x18))
+        }
+    }
+
+    /// Returns the size of the structure table. For the 32-bit entry point,
+    /// this is the exact length. For the 64-bit entry point, this is the
+    /// maximum size, as the table is terminated by the end-of-table structure.
+    #[must_use]
+    pub fn structure_table_max_size(&self) -> u32 {
+        if self.is_64bit() {
+            self.read_u32(0x0c)
+        } else {
+            u32::from(self.read_u16(0x16))
+        }
+    }
+
+    /// Returns the number of structures in the structure table. Only the
+    /// 32-bit entry point contains this information.
+    #[must_use]
+    pub fn number_of_structures(&self) -> Option<u16> {
+        (!self.is_64bit()).then(|| self.read_u16(0x1c))
+    }
+
+    /// Validates the checksum of the entry point. For the 32-bit entry point,
+    /// this also validates the intermediate checksum of the `_DMI_` part.
+    #[must_use]
+    pub fn checksum_is_valid(&self) -> bool {
+        let sum = |bytes: &[u8]| bytes.iter().fold(0u8, |acc, val| acc.wrapping_add(*val));
+        if self.is_64bit() {
+            sum(self.bytes) == 0
+        } else {
+            sum(self.bytes) == 0 && sum(&self.bytes[0x10..0x1f]) == 0
+        }
+    }
+}
+
+impl Debug for SmbiosEntryPoint<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SmbiosEntryPoint")
+            .field("is_64bit", &self.is_64bit())
+            .field("major", &self.major())
+            .field("minor", &self.minor())
+            .field(
+                "structure_table_address",
+                &(self.structure_table_address() as *const u8),
+            )
+            .field("structure_table_max_size", &self.structure_table_max_size())
+            .field("checksum_is_valid", &self.checksum_is_valid())
+            .finish()
+    }
+}
+
+/// A single SMBIOS structure, consisting of the formatted area and the
+/// trailing string-set.
+///
+/// See chapter 6.1 of the [SMBIOS specification] for the layout.
+///
+/// [SMBIOS specification]: https://www.dmtf.org/standards/smbios
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SmbiosStructure<'a> {
+    /// Formatted area including the 4-byte structure header.
+    formatted: &'a [u8],
+    /// Strings separated by NUL bytes, without the final double NUL.
+    strings: &'a [u8],
+}
+
+impl<'a> SmbiosStructure<'a> {
+    /// Size of the header every structure starts with.
+    pub const HEADER_SIZE: usize = 4;
+
+    /// Type of the BIOS information structure.
+    pub const TYPE_BIOS_INFORMATION: u8 = 0;
+
+    /// Type of the system information structure.
+    pub const TYPE_SYSTEM_INFORMATION: u8 = 1;
+
+    /// Type of the end-of-table structure.
+    pub const END_OF_TABLE: u8 = 127;
+
+    /// Returns the structure type, such as `0` for BIOS information or `1`
+    /// for system information.
+    #[must_use]
+    pub const fn typ(&self) -> u8 {
+        self.formatted[0]
+    }
+
+    /// Returns the length of the formatted area, including the header.
+    #[must_use]
+    pub const fn length(&self) -> u8 {
+        self.formatted[1]
+    }
+
+    /// Returns the handle of the structure.
+    #[must_use]
+    pub const fn handle(&self) -> u16 {
+        u16::from_le_bytes([self.formatted[2], self.formatted[3]])
+    }
+
+    /// Returns the formatted area, including the structure header.
+    #[must_use]
+    pub const fn formatted_area(&self) -> &'a [u8] {
+        self.formatted
+    }
+
+    /// Returns an iterator over the raw strings of the string-set, without
+    /// their terminating NUL bytes.
+    #[must_use]
+    pub const fn strings(&self) -> SmbiosStringIter<'a> {
+        SmbiosStringIter {
+            strings: self.strings,
+        }
+    }
+
+    /// Returns the string referenced by the given 1-based string number, as
+    /// used by string fields in the formatted area. `0` means "no string".
+    #[must_use]
+    pub fn string(&self, number: u8) -> Option<&'a [u8]> {
+        let index = usize::from(number).checked_sub(1)?;
+        self.strings().nth(index)
+    }
+}
+
+impl Debug for SmbiosStructure<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SmbiosStructure")
+            .field("typ", &self.typ())
+            .field("length", &self.length())
+            .field("handle", &self.handle())
+            .field("strings", &self.strings().count())
+            .finish()
+    }
+}
+
+/// Iterator over the [`SmbiosStructure`]s of a [`SmbiosTag`].
+#[derive(Clone)]
+pub struct SmbiosStructureIter<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> SmbiosStructureIter<'a> {
+    const fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+}
+
+impl<'a> Iterator for SmbiosStructureIter<'a> {
+    type Item = SmbiosStructure<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffer = mem::take(&mut self.buffer);
+        if buffer.len() < SmbiosStructure::HEADER_SIZE {
+            return None;
+        }
+
+        let length = usize::from(buffer[1]);
+        if length < SmbiosStructure::HEADER_SIZE || length > buffer.len() {
+            return None;
+        }
+        let (formatted, rest) = buffer.split_at(length);
+
+        // The string-set is terminated by two NUL bytes. If there are no
+        // strings, the set consists of only these two bytes.
+        let strings_len = rest.windows(2).position(|w| w == [0, 0])?;
+        let strings = &rest[..strings_len];
+
+        let structure = SmbiosStructure { formatted, strings };
+        if structure.typ() != SmbiosStructure::END_OF_TABLE {
+            self.buffer = &rest[strings_len + 2..];
+        }
+        Some(structure)
+    }
+}
+
+impl Debug for SmbiosStructureIter<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// Iterator over the raw strings of a [`SmbiosStructure`].
+#[derive(Clone, Debug)]
+pub struct SmbiosStringIter<'a> {
+    strings: &'a [u8],
+}
+
+impl<'a> Iterator for SmbiosStringIter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.strings.is_empty() {
+            return None;
+        }
+        let strings = mem::take(&mut self.strings);
+        match strings.iter().position(|&b| b == 0) {
+            Some(pos) => {
+                self.strings = &strings[pos + 1..];
+                Some(&strings[..pos])
+            }
+            None => Some(strings),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::GenericInfoTag;
+    use crate::GenericTag;
     use core::borrow::Borrow;
     use multiboot2_common::test_utils::AlignedBytes;
 
@@ -105,14 +580,241 @@ mod tests {
     #[test]
     fn test_parse() {
         let bytes = get_bytes();
-        let tag = GenericInfoTag::ref_from_slice(bytes.borrow()).unwrap();
-        let tag = tag.cast::<SmbiosTag>();
+        let tag = GenericTag::ref_from_slice(bytes.borrow()).unwrap();
+        let tag = tag.downcast::<SmbiosTag>().unwrap();
         assert_eq!(tag.header.typ, TagType::Smbios);
         assert_eq!(tag.major, 7);
         assert_eq!(tag.minor, 42);
         assert_eq!(&tag.tables, [0, 1, 2, 3, 4, 5, 6, 7, 8]);
     }
 
+    #[rustfmt::skip]
+    const STRUCTURES: [u8; 40] = [
+        /* type 0, length 8, handle 0x0000 */
+        0, 8, 0x00, 0x00,
+        /* vendor: string 1, version: string 2, padding */
+        1, 2, 0, 0,
+        b'A', b'C', b'M', b'E', 0,
+        b'1', b'.', b'0', 0,
+        0,
+        /* type 1, length 4, handle 0x0102, no strings */
+        1, 4, 0x02, 0x01,
+        0, 0,
+        /* type 127, end-of-table */
+        127, 4, 0xff, 0xff,
+        0, 0,
+        /* trailing garbage that must be ignored */
+        0xde, 0xad, 0xbe, 0xef, 0, 0, 0, 0, 0, 0,
+    ];
+
+    /// Test to walk the structures of a table.
+    #[test]
+    fn test_walk_structures() {
+        let mut iter = SmbiosStructureIter::new(&STRUCTURES);
+
+        let bios = iter.next().unwrap();
+        assert_eq!(bios.typ(), 0);
+        assert_eq!(bios.length(), 8);
+        assert_eq!(bios.handle(), 0);
+        assert_eq!(bios.formatted_area(), &STRUCTURES[..8]);
+        assert_eq!(bios.strings().count(), 2);
+        assert_eq!(bios.string(0), None);
+        assert_eq!(bios.string(1), Some(b"ACME".as_slice()));
+        assert_eq!(bios.string(2), Some(b"1.0".as_slice()));
+        assert_eq!(bios.string(3), None);
+
+        let system = iter.next().unwrap();
+        assert_eq!(system.typ(), 1);
+        assert_eq!(system.handle(), 0x0102);
+        assert_eq!(system.strings().count(), 0);
+
+        let end = iter.next().unwrap();
+        assert_eq!(end.typ(), SmbiosStructure::END_OF_TABLE);
+        assert_eq!(iter.next(), None);
+    }
+
+    /// Test that truncated or malformed structures end the iteration.
+    #[test]
+    fn test_walk_malformed_structures() {
+        // Missing double NUL.
+        let mut iter = SmbiosStructureIter::new(&STRUCTURES[..13]);
+        assert_eq!(iter.next(), None);
+
+        // Formatted area longer than the buffer.
+        let mut iter = SmbiosStructureIter::new(&[0, 8, 0, 0, 0, 0]);
+        assert_eq!(iter.next(), None);
+
+        // Length smaller than the header.
+        let mut iter = SmbiosStructureIter::new(&[0, 2, 0, 0, 0, 0]);
+        assert_eq!(iter.next(), None);
+    }
+
+    /// Fixes the checksum at `checksum_offset` so that `bytes` sums up to zero.
+    fn fix_checksum(bytes: &mut [u8], checksum_offset: usize) {
+        bytes[checksum_offset] = 0;
+        let sum = bytes.iter().fold(0u8, |acc, val| acc.wrapping_add(*val));
+        bytes[checksum_offset] = 0u8.wrapping_sub(sum);
+    }
+
+    /// Test to parse a 64-bit entry point.
+    #[test]
+    fn test_entry_point_64() {
+        #[rustfmt::skip]
+        let mut bytes = [
+            b'_', b'S', b'M', b'3', b'_',
+            /* checksum, length, major, minor, docrev, revision, reserved */
+            0, 0x18, 3, 2, 0, 1, 0,
+            /* structure table maximum size */
+            0x00, 0x10, 0, 0,
+            /* structure table address */
+            0x00, 0x00, 0x0f, 0x00, 0x01, 0, 0, 0,
+        ];
+        fix_checksum(&mut bytes, 5);
+
+        let ep = SmbiosEntryPoint::parse(&bytes).unwrap();
+        assert!(ep.is_64bit());
+        assert_eq!(ep.length(), 0x18);
+        assert_eq!(ep.major(), 3);
+        assert_eq!(ep.minor(), 2);
+        assert_eq!(ep.structure_table_max_size(), 0x1000);
+        assert_eq!(ep.structure_table_address(), 0x1_000f_0000);
+        assert_eq!(ep.number_of_structures(), None);
+        assert!(ep.checksum_is_valid());
+
+        bytes[0x10] = 0xff;
+        let ep = SmbiosEntryPoint::parse(&bytes).unwrap();
+        assert!(!ep.checksum_is_valid());
+
+        // Truncated.
+        assert_eq!(SmbiosEntryPoint::parse(&bytes[..0x17]), None);
+    }
+
+    /// Test to parse a 32-bit entry point.
+    #[test]
+    fn test_entry_point_32() {
+        #[rustfmt::skip]
+        let mut bytes = [
+            b'_', b'S', b'M', b'_',
+            /* checksum, length, major, minor */
+            0, 0x1f, 2, 8,
+            /* max structure size, revision, formatted area */
+            0x80, 0, 0, 0, 0, 0, 0, 0,
+            b'_', b'D', b'M', b'I', b'_',
+            /* intermediate checksum */
+            0,
+            /* structure table length */
+            0x34, 0x12,
+            /* structure table address */
+            0x00, 0x00, 0x0e, 0x00,
+            /* number of structures, BCD revision */
+            42, 0, 0x28,
+        ];
+        fix_checksum(&mut bytes[0x10..], 0x05);
+        fix_checksum(&mut bytes, 4);
+
+        let ep = SmbiosEntryPoint::parse(&bytes).unwrap();
+        assert!(!ep.is_64bit());
+        assert_eq!(ep.major(), 2);
+        assert_eq!(ep.minor(), 8);
+        assert_eq!(ep.structure_table_max_size(), 0x1234);
+        assert_eq!(ep.structure_table_address(), 0xe_0000);
+        assert_eq!(ep.number_of_structures(), Some(42));
+        assert!(ep.checksum_is_valid());
+
+        assert_eq!(SmbiosEntryPoint::parse(&STRUCTURES), None);
+    }
+
+    /// Test that structures following an embedded entry point are found.
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_structures_after_entry_point() {
+        let mut tables = std::vec::Vec::new();
+        tables.extend_from_slice(b"_SM3_");
+        tables.extend_from_slice(&[0, 0x18, 3, 0, 0, 1, 0]);
+        tables.extend_from_slice(&(STRUCTURES.len() as u32).to_le_bytes());
+        tables.extend_from_slice(&0_u64.to_le_bytes());
+        tables.extend_from_slice(&STRUCTURES);
+
+        let tag = SmbiosTag::new(3, 0, &tables);
+        assert!(tag.entry_point().unwrap().is_64bit());
+        assert_eq!(tag.structure_table(), &STRUCTURES);
+        assert_eq!(tag.structures().count(), 3);
+    }
+
+    /// Test to build the tag from raw firmware tables.
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_from_firmware_tables() {
+        let mut entry_point = [0; 0x18];
+        entry_point[..5].copy_from_slice(b"_SM3_");
+        entry_point[6] = 0x18;
+        entry_point[7] = 3;
+        entry_point[8] = 4;
+        entry_point[0x0c] = STRUCTURES.len() as u8;
+        fix_checksum(&mut entry_point, 5);
+
+        let mut structure_table = STRUCTURES.to_vec();
+        structure_table.extend_from_slice(&[0xff; 8]);
+
+        let tag = SmbiosTag::from_firmware_tables(&entry_point, &structure_table).unwrap();
+        assert_eq!(tag.major(), 3);
+        assert_eq!(tag.minor(), 4);
+        assert_eq!(tag.entry_point().unwrap().length(), 0x18);
+        assert_eq!(tag.structure_table(), &STRUCTURES);
+        assert_eq!(tag.structures().count(), 3);
+
+        entry_point[0x10] = 1;
+        assert!(SmbiosTag::from_firmware_tables(&entry_point, &structure_table).is_none());
+        assert!(SmbiosTag::from_firmware_tables(&STRUCTURES, &structure_table).is_none());
+    }
+
+    /// Test the convenience getters for identity strings.
+    #[test]
+    #[cfg(feature = "builder")]
+    fn test_identity_getters() {
+        #[rustfmt::skip]
+        let tables = [
+            /* type 0, length 8 */
+            0, 8, 0, 0,
+            1, 2, 0, 0,
+            b'A', b'C', b'M', b'E', 0,
+            b'1', b'.', b'0', 0,
+            0,
+            /* type 1, length 0x19 */
+            1, 0x19, 1, 0,
+            /* manufacturer, product, version, serial */
+            1, 2, 0, 0,
+            /* uuid */
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff,
+            /* wake-up type */
+            6,
+            b'Q', b'E', b'M', b'U', 0,
+            b'S', b't', b'd', 0xff, 0,
+            0,
+        ];
+        let tag = SmbiosTag::new(3, 0, &tables);
+        assert_eq!(tag.bios_vendor(), Some(Ok("ACME")));
+        assert_eq!(tag.bios_version(), Some(Ok("1.0")));
+        assert_eq!(tag.system_manufacturer(), Some(Ok("QEMU")));
+        assert!(matches!(
+            tag.system_product(),
+            Some(Err(StringError::Utf8(_)))
+        ));
+        assert_eq!(tag.system_product_bytes(), Some(&b"Std\xff"[..]));
+        assert_eq!(
+            tag.system_uuid(),
+            Some([
+                0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+                0xee, 0xff
+            ])
+        );
+
+        let tag = SmbiosTag::new(3, 0, &[]);
+        assert_eq!(tag.bios_vendor(), None);
+        assert_eq!(tag.system_uuid(), None);
+    }
+
     /// Test to generate a tag.
     #[test]
     #[cfg(feature = "builder")]
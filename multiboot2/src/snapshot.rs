@@ -0,0 +1,115 @@
+//! Module for [`Snapshot`].
+
+use crate::BootInformation;
+#[cfg(feature = "builder")]
+use crate::{BootInformationBuffer, LoadError};
+use serde::{Deserialize, Serialize};
+
+/// Compact binary snapshot of a boot information, e.g., for remote debugging.
+///
+/// A kernel encodes the snapshot into a small buffer with [`Self::encode`]
+/// and sends it over a serial line or a virtio console. The host decodes it
+/// with [`Self::decode`] and parses the boot information from
+/// [`Self::to_buffer`], as if it ran on the target.
+///
+/// The snapshot is serialized with `postcard` and framed with COBS, so that
+/// a zero byte marks the end of each snapshot in a stream.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Snapshot<'a> {
+    version: u8,
+    address: u64,
+    bytes: &'a [u8],
+}
+
+impl<'a> Snapshot<'a> {
+    /// Version of the snapshot format.
+    pub const VERSION: u8 = 1;
+
+    /// Creates a snapshot of the boot information.
+    #[must_use]
+    pub fn new(info: &BootInformation<'a>) -> Self {
+        // SAFETY: The boot information is valid for its total size.
+        let bytes =
+            unsafe { core::slice::from_raw_parts(info.as_ptr().cast::<u8>(), info.total_size()) };
+        Self {
+            version: Self::VERSION,
+            address: info.start_address() as u64,
+            bytes,
+        }
+    }
+
+    /// Returns the address of the boot information on the target.
+    #[must_use]
+    pub const fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Returns the raw bytes of the boot information.
+    #[must_use]
+    pub const fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Encodes the snapshot as COBS frame into `buf` and returns the used
+    /// part of it. This doesn't need the `alloc` feature.
+    pub fn encode<'b>(&self, buf: &'b mut [u8]) -> Result<&'b mut [u8], postcard::Error> {
+        postcard::to_slice_cobs(self, buf)
+    }
+
+    /// Decodes a snapshot from a COBS frame. The frame is decoded in place.
+    pub fn decode(frame: &'a mut [u8]) -> Result<Self, postcard::Error> {
+        let snapshot = postcard::from_bytes_cobs::<Self>(frame)?;
+        if snapshot.version != Self::VERSION {
+            return Err(postcard::Error::DeserializeBadEncoding);
+        }
+        Ok(snapshot)
+    }
+
+    /// Copies the boot information into an aligned buffer, which can be
+    /// parsed with [`BootInformation::load`].
+    #[cfg(feature = "builder")]
+    pub fn to_buffer(&self) -> Result<BootInformationBuffer, LoadError> {
+        BootInformationBuffer::from_bytes(self.bytes)
+    }
+}
+
+impl BootInformation<'_> {
+    /// Creates a [`Snapshot`] of the boot information.
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot<'_> {
+        Snapshot::new(self)
+    }
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{Builder, CommandLineTag};
+
+    #[test]
+    fn roundtrip() {
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("console=ttyS0"))
+            .add_module(0x1000, 0x2000, "initrd")
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+
+        let mut buf = [0; 256];
+        let frame = mbi.snapshot().encode(&mut buf).unwrap();
+        assert_eq!(frame.last(), Some(&0));
+        assert!(!frame[..frame.len() - 1].contains(&0));
+
+        let snapshot = Snapshot::decode(frame).unwrap();
+        assert_eq!(snapshot.address(), mbi.start_address() as u64);
+        let buffer = snapshot.to_buffer().unwrap();
+        let copy = unsafe { BootInformation::load(buffer.as_ptr()) }.unwrap();
+        assert_eq!(
+            copy.command_line_tag().unwrap().cmdline(),
+            Ok("console=ttyS0")
+        );
+        assert_eq!(copy.module_tags().count(), 1);
+
+        let mut small = [0; 16];
+        assert!(mbi.snapshot().encode(&mut small).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! Module for [`SpecCompat`].
+
+use crate::{BootInformation, GenericTag, TagType};
+use derive_more::Display;
+
+/// A revision of the Multiboot2 specification.
+///
+/// The spec is versioned as 2.0 throughout, but it was extended with new tags
+/// over time. The revisions are named after the GRUB release that implemented
+/// them first.
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpecRevision {
+    /// The initial revision, as implemented by GRUB 2.00, with the tags up to
+    /// and including [`TagType::Network`].
+    #[display("GRUB 2.00")]
+    Grub200,
+    /// The revision that added the EFI memory map, EFI boot services not
+    /// exited, EFI image handle, and load base address tags, as implemented by
+    /// GRUB 2.02.
+    #[display("GRUB 2.02")]
+    Grub202,
+}
+
+impl SpecRevision {
+    /// The latest revision known to this crate.
+    pub const LATEST: Self = Self::Grub202;
+
+    /// Returns the revision that introduced the given tag type, or `None` for
+    /// [`TagType::Custom`].
+    #[must_use]
+    pub const fn introducing(typ: TagType) -> Option<Self> {
+        match typ {
+            TagType::EfiMmap
+            | TagType::EfiBs
+            | TagType::Efi32Ih
+            | TagType::Efi64Ih
+            | TagType::LoadBaseAddr => Some(Self::Grub202),
+            TagType::Custom(_) => None,
+            _ => Some(Self::Grub200),
+        }
+    }
+}
+
+/// A compatibility problem of a [`BootInformation`] with a target
+/// [`SpecRevision`], see [`SpecCompat::findings`].
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpecFinding {
+    /// The tag type was introduced after the target revision. A kernel
+    /// written against the target revision ignores it.
+    #[display("tag {typ:?} requires {since}")]
+    PostdatesTarget {
+        /// The type of the tag.
+        typ: TagType,
+        /// The revision that introduced the tag type.
+        since: SpecRevision,
+    },
+    /// The boot information looks like it was created by a bootloader
+    /// predating the target revision: an EFI system table is passed, but
+    /// neither an EFI memory map nor the EFI boot services not exited tag,
+    /// one of which a bootloader of [`SpecRevision::Grub202`] always passes
+    /// on EFI.
+    #[display("EFI boot without EFI memory map, predating {target}")]
+    PredatesTarget {
+        /// The target revision.
+        target: SpecRevision,
+    },
+}
+
+/// Report of the Multiboot2 spec revision features that a
+/// [`BootInformation`] uses, see [`BootInformation::spec_compat`].
+///
+/// Useful for compatibility matrices of bootloaders and kernels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpecCompat {
+    /// Bitmap of the non-custom tag types present.
+    tags: u32,
+}
+
+impl SpecCompat {
+    /// Returns whether a tag of the given type is present. Always `false`
+    /// for [`TagType::Custom`].
+    #[must_use]
+    pub const fn contains(&self, typ: TagType) -> bool {
+        match typ {
+            TagType::Custom(_) => false,
+            typ => self.tags & (1 << typ.val()) != 0,
+        }
+    }
+
+    /// Returns whether an EFI system table or EFI image handle tag is present.
+    #[must_use]
+    pub const fn uses_efi_tags(&self) -> bool {
+        self.contains(TagType::Efi32)
+            || self.contains(TagType::Efi64)
+            || self.contains(TagType::Efi32Ih)
+            || self.contains(TagType::Efi64Ih)
+    }
+
+    /// Returns whether the EFI memory map tag is present.
+    #[must_use]
+    pub const fn uses_efi_memory_map(&self) -> bool {
+        self.contains(TagType::EfiMmap)
+    }
+
+    /// Returns whether the load base address tag is present.
+    #[must_use]
+    pub const fn uses_load_base_addr(&self) -> bool {
+        self.contains(TagType::LoadBaseAddr)
+    }
+
+    /// Returns the oldest revision that defines all tags present.
+    #[must_use]
+    pub fn required_revision(&self) -> SpecRevision {
+        self.tag_types()
+            .filter_map(SpecRevision::introducing)
+            .max()
+            .unwrap_or(SpecRevision::Grub200)
+    }
+
+    /// Returns the compatibility problems with the given target revision.
+    ///
+    /// An empty iterator means that the boot information matches what a
+    /// bootloader of the target revision produces.
+    pub fn findings(&self, target: SpecRevision) -> impl Iterator<Item = SpecFinding> {
+        let postdating = self.tag_types().filter_map(move |typ| {
+            let since = SpecRevision::introducing(typ)?;
+            (since > target).then_some(SpecFinding::PostdatesTarget { typ, since })
+        });
+        let efi_system_table = self.contains(TagType::Efi32) || self.contains(TagType::Efi64);
+        let predating = (target >= SpecRevision::Grub202
+            && efi_system_table
+            && !self.contains(TagType::EfiMmap)
+            && !self.contains(TagType::EfiBs))
+        .then_some(SpecFinding::PredatesTarget { target });
+        postdating.chain(predating)
+    }
+
+    /// Returns the tag types present in ascending order.
+    fn tag_types(&self) -> impl Iterator<Item = TagType> {
+        let tags = self.tags;
+        (0..u32::BITS)
+            .filter(move |i| tags & (1 << i) != 0)
+            .map(TagType::from)
+    }
+}
+
+impl BootInformation<'_> {
+    /// Returns a [`SpecCompat`] report of the spec revision features in use.
+    #[must_use]
+    pub fn spec_compat(&self) -> SpecCompat {
+        let tags = self
+            .tags()
+            .map(GenericTag::typ)
+            .filter(|typ| !matches!(typ, TagType::Custom(_)))
+            .fold(0, |tags, typ| tags | (1 << typ.val()));
+        SpecCompat { tags }
+    }
+}
+
+#[cfg(all(test, feature = "builder", feature = "efi"))]
+mod tests {
+    use super::*;
+    use crate::{
+        Builder, CommandLineTag, EFIImageHandle64Tag, EFIMemoryMapTag, EFISdt64Tag,
+        ImageLoadPhysAddrTag,
+    };
+
+    #[test]
+    fn spec_compat() {
+        let mbi = Builder::new()
+            .cmdline(CommandLineTag::new("foo"))
+            .efi64(EFISdt64Tag::new(0x1000))
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr().cast()) }.unwrap();
+        let compat = mbi.spec_compat();
+        assert!(compat.uses_efi_tags());
+        assert!(!compat.uses_efi_memory_map());
+        assert_eq!(compat.required_revision(), SpecRevision::Grub200);
+        assert_eq!(compat.findings(SpecRevision::Grub200).count(), 0);
+        assert_eq!(
+            compat
+                .findings(SpecRevision::Grub202)
+                .collect::<std::vec::Vec<_>>(),
+            [SpecFinding::PredatesTarget {
+                target: SpecRevision::Grub202
+            }]
+        );
+
+        let mbi = Builder::new()
+            .efi64(EFISdt64Tag::new(0x1000))
+            .efi64_ih(EFIImageHandle64Tag::new(0x2000))
+            .efi_mmap(EFIMemoryMapTag::new_from_descs(&[]))
+            .image_load_addr(ImageLoadPhysAddrTag::new(0x20_0000))
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr().cast()) }.unwrap();
+        let compat = mbi.spec_compat();
+        assert!(compat.uses_load_base_addr());
+        assert_eq!(compat.required_revision(), SpecRevision::Grub202);
+        assert_eq!(compat.findings(SpecRevision::LATEST).count(), 0);
+        assert_eq!(
+            compat
+                .findings(SpecRevision::Grub200)
+                .collect::<std::vec::Vec<_>>(),
+            [
+                SpecFinding::PostdatesTarget {
+                    typ: TagType::EfiMmap,
+                    since: SpecRevision::Grub202
+                },
+                SpecFinding::PostdatesTarget {
+                    typ: TagType::Efi64Ih,
+                    since: SpecRevision::Grub202
+                },
+                SpecFinding::PostdatesTarget {
+                    typ: TagType::LoadBaseAddr,
+                    since: SpecRevision::Grub202
+                },
+            ]
+        );
+    }
+}
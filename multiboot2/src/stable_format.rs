@@ -0,0 +1,408 @@
+//! Module for [`StableFormat`].
+
+#[cfg(feature = "elf")]
+use crate::ElfSectionsTag;
+use crate::{
+    ApmTag, BasicMemoryInfoTag, BootInformation, BootLoaderNameTag, BootdevTag, CommandLineTag,
+    FramebufferTag, FramebufferType, GenericTag, ImageLoadPhysAddrTag, MemoryArea, MemoryMapTag,
+    ModuleTag, RsdpV1Tag, RsdpV2Tag, TagType,
+};
+#[cfg(feature = "efi")]
+use crate::{EFIImageHandle32Tag, EFIImageHandle64Tag, EFIMemoryMapTag, EFISdt32Tag, EFISdt64Tag};
+use core::fmt::{self, Display, Formatter, Write};
+use core::mem;
+
+/// Version of the output of [`StableFormat`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum StableFormatVersion {
+    /// The first version of the format.
+    V1,
+}
+
+impl StableFormatVersion {
+    /// The latest version of the format.
+    pub const LATEST: Self = Self::V1;
+}
+
+/// Text representation of a [`BootInformation`] with a stable, versioned
+/// format, see [`BootInformation::stable_format`].
+///
+/// Unlike the `Debug` output, which follows the internals of the crate, the
+/// output of a given [`StableFormatVersion`] never changes across releases.
+/// Changes to the format result in a new version. This makes the output
+/// suitable for snapshot tests, e.g., with `insta`.
+///
+/// The output starts with a line that names the version, followed by one
+/// line per tag with its offset, type, and size, and by the decoded fields of
+/// the tag on indented lines. It doesn't contain the address of the boot
+/// information. Tags that can't be decoded, including tags of disabled crate
+/// features, are shown as hex.
+#[derive(Copy, Clone, Debug)]
+pub struct StableFormat<'a, 'b> {
+    info: &'b BootInformation<'a>,
+    version: StableFormatVersion,
+}
+
+impl BootInformation<'_> {
+    /// Returns a [`StableFormat`] of the boot information in the given
+    /// version, for snapshot tests.
+    #[must_use]
+    pub const fn stable_format(&self, version: StableFormatVersion) -> StableFormat<'_, '_> {
+        StableFormat {
+            info: self,
+            version,
+        }
+    }
+}
+
+impl Display for StableFormat<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.version {
+            StableFormatVersion::V1 => self.fmt_v1(f),
+        }
+    }
+}
+
+impl StableFormat<'_, '_> {
+    fn fmt_v1(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "multiboot2 boot information, format v1")?;
+        writeln!(f, "total_size: {:#x}", self.info.total_size())?;
+        for tag in self.info.generic_tags() {
+            let offset = tag as *const GenericTag as *const u8 as usize - self.info.start_address();
+            writeln!(
+                f,
+                "tag {offset:#06x}: type {} ({}), size {}",
+                tag.typ().val(),
+                type_name(tag.typ()),
+                tag.size()
+            )?;
+            if !self.fmt_tag_v1(tag, f)? {
+                write_hex(f, "payload", tag.payload())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the fields of the tag and returns whether it could be decoded.
+    fn fmt_tag_v1(&self, tag: &GenericTag, f: &mut Formatter<'_>) -> Result<bool, fmt::Error> {
+        match tag.typ() {
+            TagType::End => {}
+            TagType::Cmdline => match tag.downcast::<CommandLineTag>().map(|t| t.cmdline()) {
+                Some(Ok(cmdline)) => write_str(f, "cmdline", cmdline)?,
+                _ => return Ok(false),
+            },
+            TagType::BootLoaderName => {
+                match tag.downcast::<BootLoaderNameTag>().map(|t| t.name()) {
+                    Some(Ok(name)) => write_str(f, "name", name)?,
+                    _ => return Ok(false),
+                }
+            }
+            TagType::Module => match tag.downcast::<ModuleTag>() {
+                Some(module) if module.cmdline().is_ok() => {
+                    writeln!(f, "  start: {:#x}", module.start_address())?;
+                    writeln!(f, "  end: {:#x}", module.end_address())?;
+                    write_str(f, "cmdline", module.cmdline().unwrap_or_default())?;
+                }
+                _ => return Ok(false),
+            },
+            TagType::BasicMeminfo => match tag.downcast::<BasicMemoryInfoTag>() {
+                Some(meminfo) => {
+                    writeln!(f, "  lower: {}", meminfo.memory_lower())?;
+                    writeln!(f, "  upper: {}", meminfo.memory_upper())?;
+                }
+                None => return Ok(false),
+            },
+            TagType::Bootdev => match tag.downcast::<BootdevTag>() {
+                Some(bootdev) => {
+                    writeln!(f, "  biosdev: {:#x}", bootdev.biosdev())?;
+                    writeln!(f, "  slice: {:#x}", bootdev.slice())?;
+                    writeln!(f, "  part: {:#x}", bootdev.part())?;
+                }
+                None => return Ok(false),
+            },
+            TagType::Mmap => match tag.downcast::<MemoryMapTag>() {
+                Some(mmap) if mmap.entry_size() as usize == mem::size_of::<MemoryArea>() => {
+                    writeln!(f, "  entry_size: {}", mmap.entry_size())?;
+                    writeln!(f, "  entry_version: {}", mmap.entry_version())?;
+                    for area in mmap.memory_areas() {
+                        writeln!(
+                            f,
+                            "  area: base {:#x}, length {:#x}, type {}",
+                            area.start_address(),
+                            area.size(),
+                            u32::from(area.typ())
+                        )?;
+                    }
+                }
+                _ => return Ok(false),
+            },
+            TagType::Framebuffer => {
+                let Some((fb, buffer_type)) = tag
+                    .downcast::<FramebufferTag>()
+                    .and_then(|fb| Some((fb, fb.buffer_type().ok()?)))
+                else {
+                    return Ok(false);
+                };
+                writeln!(f, "  address: {:#x}", fb.address())?;
+                writeln!(f, "  pitch: {}", fb.pitch())?;
+                writeln!(f, "  width: {}", fb.width())?;
+                writeln!(f, "  height: {}", fb.height())?;
+                writeln!(f, "  bpp: {}", fb.bpp())?;
+                match buffer_type {
+                    FramebufferType::Indexed { palette } => {
+                        writeln!(f, "  type: indexed, {} colors", palette.len())?;
+                        for color in palette {
+                            writeln!(
+                                f,
+                                "  color: {:02x}{:02x}{:02x}",
+                                color.red, color.green, color.blue
+                            )?;
+                        }
+                    }
+                    FramebufferType::RGB { red, green, blue } => {
+                        writeln!(f, "  type: rgb")?;
+                        for (name, field) in [("red", red), ("green", green), ("blue", blue)] {
+                            writeln!(
+                                f,
+                                "  {name}: position {}, size {}",
+                                field.position, field.size
+                            )?;
+                        }
+                    }
+                    FramebufferType::Text => writeln!(f, "  type: text")?,
+                }
+            }
+            #[cfg(feature = "elf")]
+            TagType::ElfSections => match tag.downcast::<ElfSectionsTag>() {
+                Some(elf) => {
+                    writeln!(f, "  number_of_sections: {}", elf.number_of_sections())?;
+                    writeln!(f, "  entry_size: {}", elf.entry_size())?;
+                    writeln!(f, "  shndx: {}", elf.shndx())?;
+                    for section in elf.sections_iter() {
+                        writeln!(
+                            f,
+                            "  section: type {:#x}, flags {:#x}, address {:#x}, size {:#x}, align {:#x}",
+                            section.section_type_raw(),
+                            section.flags().bits(),
+                            section.start_address(),
+                            section.size(),
+                            section.addralign()
+                        )?;
+                    }
+                }
+                None => return Ok(false),
+            },
+            TagType::Apm => match tag.downcast::<ApmTag>() {
+                Some(apm) => {
+                    writeln!(f, "  version: {:#x}", apm.version())?;
+                    writeln!(f, "  cseg: {:#x}", apm.cseg())?;
+                    writeln!(f, "  offset: {:#x}", apm.offset())?;
+                    writeln!(f, "  cseg_16: {:#x}", apm.cseg_16())?;
+                    writeln!(f, "  dseg: {:#x}", apm.dseg())?;
+                    writeln!(f, "  flags: {:#x}", apm.flags().bits())?;
+                    writeln!(f, "  cseg_len: {:#x}", apm.cseg_len())?;
+                    writeln!(f, "  cseg_16_len: {:#x}", apm.cseg_16_len())?;
+                    writeln!(f, "  dseg_len: {:#x}", apm.dseg_len())?;
+                }
+                None => return Ok(false),
+            },
+            TagType::AcpiV1 => match tag.downcast::<RsdpV1Tag>() {
+                Some(rsdp) => {
+                    match rsdp.oem_id() {
+                        Ok(oem_id) => write_str(f, "oem_id", oem_id)?,
+                        Err(_) => write_hex(f, "oem_id", &tag.payload()[9..15])?,
+                    }
+                    writeln!(f, "  revision: {}", rsdp.revision())?;
+                    writeln!(f, "  rsdt_address: {:#x}", rsdp.rsdt_address())?;
+                    writeln!(f, "  checksum_valid: {}", rsdp.checksum_is_valid())?;
+                }
+                None => return Ok(false),
+            },
+            TagType::AcpiV2 => match tag.downcast::<RsdpV2Tag>() {
+                Some(rsdp) => {
+                    match rsdp.oem_id() {
+                        Ok(oem_id) => write_str(f, "oem_id", oem_id)?,
+                        Err(_) => write_hex(f, "oem_id", &tag.payload()[9..15])?,
+                    }
+                    writeln!(f, "  revision: {}", rsdp.revision())?;
+                    writeln!(f, "  xsdt_address: {:#x}", rsdp.xsdt_address())?;
+                    writeln!(f, "  checksum_valid: {}", rsdp.checksum_is_valid())?;
+                }
+                None => return Ok(false),
+            },
+            #[cfg(feature = "efi")]
+            TagType::Efi32 => match tag.downcast::<EFISdt32Tag>() {
+                Some(sdt) => writeln!(f, "  address: {:#x}", sdt.sdt_address())?,
+                None => return Ok(false),
+            },
+            #[cfg(feature = "efi")]
+            TagType::Efi64 => match tag.downcast::<EFISdt64Tag>() {
+                Some(sdt) => writeln!(f, "  address: {:#x}", sdt.sdt_address())?,
+                None => return Ok(false),
+            },
+            #[cfg(feature = "efi")]
+            TagType::Efi32Ih => match tag.downcast::<EFIImageHandle32Tag>() {
+                Some(ih) => writeln!(f, "  image_handle: {:#x}", ih.image_handle())?,
+                None => return Ok(false),
+            },
+            #[cfg(feature = "efi")]
+            TagType::Efi64Ih => match tag.downcast::<EFIImageHandle64Tag>() {
+                Some(ih) => writeln!(f, "  image_handle: {:#x}", ih.image_handle())?,
+                None => return Ok(false),
+            },
+            #[cfg(feature = "efi")]
+            TagType::EfiMmap => match tag.downcast::<EFIMemoryMapTag>() {
+                Some(mmap) if mmap.memory_areas().count() > 0 || tag.payload().len() == 8 => {
+                    for desc in mmap.memory_areas() {
+                        writeln!(
+                            f,
+                            "  descriptor: type {}, base {:#x}, pages {:#x}, attributes {:#x}",
+                            desc.ty.0,
+                            desc.phys_start,
+                            desc.page_count,
+                            desc.att.bits()
+                        )?;
+                    }
+                }
+                _ => return Ok(false),
+            },
+            TagType::EfiBs => {}
+            TagType::LoadBaseAddr => match tag.downcast::<ImageLoadPhysAddrTag>() {
+                Some(addr) => writeln!(f, "  load_base_addr: {:#x}", addr.load_base_addr())?,
+                None => return Ok(false),
+            },
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// Returns the name of the tag type in the stable format.
+const fn type_name(typ: TagType) -> &'static str {
+    match typ {
+        TagType::End => "end",
+        TagType::Cmdline => "cmdline",
+        TagType::BootLoaderName => "boot_loader_name",
+        TagType::Module => "module",
+        TagType::BasicMeminfo => "basic_meminfo",
+        TagType::Bootdev => "bootdev",
+        TagType::Mmap => "mmap",
+        TagType::Vbe => "vbe",
+        TagType::Framebuffer => "framebuffer",
+        TagType::ElfSections => "elf_sections",
+        TagType::Apm => "apm",
+        TagType::Efi32 => "efi32",
+        TagType::Efi64 => "efi64",
+        TagType::Smbios => "smbios",
+        TagType::AcpiV1 => "acpi_v1",
+        TagType::AcpiV2 => "acpi_v2",
+        TagType::Network => "network",
+        TagType::EfiMmap => "efi_mmap",
+        TagType::EfiBs => "efi_bs",
+        TagType::Efi32Ih => "efi32_ih",
+        TagType::Efi64Ih => "efi64_ih",
+        TagType::LoadBaseAddr => "load_base_addr",
+        TagType::Custom(_) => "custom",
+    }
+}
+
+/// Writes the string quoted, with quotes, backslashes, and control
+/// characters escaped.
+fn write_str(f: &mut Formatter<'_>, name: &str, s: &str) -> fmt::Result {
+    write!(f, "  {name}: \"")?;
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => write!(f, "\\{c}")?,
+            c if c.is_control() => write!(f, "\\u{{{:x}}}", u32::from(c))?,
+            c => f.write_char(c)?,
+        }
+    }
+    writeln!(f, "\"")
+}
+
+/// Writes the bytes as hex, 32 bytes per line.
+fn write_hex(f: &mut Formatter<'_>, name: &str, bytes: &[u8]) -> fmt::Result {
+    write!(f, "  {name}:")?;
+    for (i, byte) in bytes.iter().enumerate() {
+        if i > 0 && i % 32 == 0 {
+            write!(f, "\n   ")?;
+        }
+        write!(f, " {byte:02x}")?;
+    }
+    writeln!(f)
+}
+
+#[cfg(all(test, feature = "builder"))]
+mod tests {
+    use super::*;
+    use crate::{Builder, FramebufferField, MemoryAreaType, TagOrder};
+    use std::string::ToString;
+
+    #[test]
+    fn stable_format_v1() {
+        let mbi = Builder::new()
+            .tag_order(TagOrder::Insertion)
+            .cmdline(CommandLineTag::new("a=\"b\"\tc"))
+            .add_module(0x10_0000, 0x10_1000, "initrd")
+            .mmap(MemoryMapTag::new(&[
+                MemoryArea::new(0, 0x9_fc00, MemoryAreaType::Available),
+                MemoryArea::new(0x10_0000, 0x100_0000, 0x1337),
+            ]))
+            .framebuffer(FramebufferTag::new(
+                0xfd00_0000,
+                4096,
+                1024,
+                768,
+                32,
+                FramebufferType::RGB {
+                    red: FramebufferField {
+                        position: 16,
+                        size: 8,
+                    },
+                    green: FramebufferField {
+                        position: 8,
+                        size: 8,
+                    },
+                    blue: FramebufferField {
+                        position: 0,
+                        size: 8,
+                    },
+                },
+            ))
+            .add_raw_tag(0x1337, &[0xde, 0xad, 0xbe, 0xef])
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        // This output must never change, see `StableFormat`.
+        assert_eq!(
+            mbi.stable_format(StableFormatVersion::V1).to_string(),
+            r#"multiboot2 boot information, format v1
+total_size: 0xb0
+tag 0x0008: type 1 (cmdline), size 16
+  cmdline: "a=\"b\"\u{9}c"
+tag 0x0018: type 3 (module), size 23
+  start: 0x100000
+  end: 0x101000
+  cmdline: "initrd"
+tag 0x0030: type 6 (mmap), size 64
+  entry_size: 24
+  entry_version: 0
+  area: base 0x0, length 0x9fc00, type 1
+  area: base 0x100000, length 0x1000000, type 4919
+tag 0x0070: type 8 (framebuffer), size 38
+  address: 0xfd000000
+  pitch: 4096
+  width: 1024
+  height: 768
+  bpp: 32
+  type: rgb
+  red: position 16, size 8
+  green: position 8, size 8
+  blue: position 0, size 8
+tag 0x0098: type 4919 (custom), size 12
+  payload: de ad be ef
+tag 0x00a8: type 0 (end), size 8
+"#
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Module for [`StaticBootInformation`].
+
+use crate::{BootInformationHeader, MemoryAreaType, TagType};
+use core::mem::size_of;
+use multiboot2_common::increase_to_alignment;
+
+/// A boot information that is assembled at compile time in a fixed-size,
+/// 8-byte aligned byte array.
+///
+/// This enables embedding a small, known boot information into a `static`,
+/// e.g., to unit-test kernel code in `no_std` environments without a heap.
+/// After every step, the structure is a valid boot information including the
+/// end tag. Construction panics, i.e., fails to compile in const contexts,
+/// if the capacity `N` is too small.
+///
+/// # Example
+///
+/// ```rust
+/// use multiboot2::{BootInformation, MemoryAreaType, StaticBootInformation};
+///
+/// static MBI: StaticBootInformation<256> = StaticBootInformation::new()
+///     .cmdline("console=ttyS0")
+///     .basic_memory_info(639, 130048)
+///     .memory_map(&[(0x100000, 0x7f00000, MemoryAreaType::Available)])
+///     .add_module(0x200000, 0x300000, "initrd");
+///
+/// let info = unsafe { BootInformation::load(MBI.as_ptr()) }.unwrap();
+/// assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("console=ttyS0"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(C, align(8))]
+pub struct StaticBootInformation<const N: usize> {
+    bytes: [u8; N],
+    /// Offset of the end tag.
+    end: usize,
+}
+
+impl<const N: usize> StaticBootInformation<N> {
+    const END_TAG_SIZE: usize = 8;
+
+    /// Creates a boot information that only consists of the header and the
+    /// end tag.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            bytes: [0; N],
+            end: size_of::<BootInformationHeader>(),
+        }
+        .write_end()
+    }
+
+    /// Writes the end tag and updates the total size in the header.
+    const fn write_end(mut self) -> Self {
+        assert!(
+            self.end + Self::END_TAG_SIZE <= N,
+            "capacity of the static boot information exceeded"
+        );
+        let end = self.end;
+        self = self.write_u32(end, 0);
+        self = self.write_u32(end + 4, Self::END_TAG_SIZE as u32);
+        self.write_u32(0, (end + Self::END_TAG_SIZE) as u32)
+    }
+
+    const fn write_u32(self, offset: usize, val: u32) -> Self {
+        self.write(offset, &val.to_ne_bytes())
+    }
+
+    const fn write(mut self, offset: usize, src: &[u8]) -> Self {
+        assert!(
+            offset + src.len() <= N,
+            "capacity of the static boot information exceeded"
+        );
+        let mut i = 0;
+        while i < src.len() {
+            self.bytes[offset + i] = src[i];
+            i += 1;
+        }
+        self
+    }
+
+    /// Appends a tag of the given type consisting of the concatenated
+    /// payload parts. Strings are not NUL-terminated automatically.
+    #[must_use]
+    pub const fn add_tag(mut self, typ: u32, payload: &[&[u8]]) -> Self {
+        let start = self.end;
+        let mut offset = start + 8;
+        let mut i = 0;
+        while i < payload.len() {
+            self = self.write(offset, payload[i]);
+            offset += payload[i].len();
+            i += 1;
+        }
+        self = self.write_u32(start, typ);
+        self = self.write_u32(start + 4, (offset - start) as u32);
+        // Zero the padding, which may contain the previous end tag.
+        let padded = increase_to_alignment(offset);
+        while offset < padded {
+            self.bytes[offset] = 0;
+            offset += 1;
+        }
+        self.end = padded;
+        self.write_end()
+    }
+
+    /// Appends a tag that consists of a NUL-terminated string, preceded by
+    /// the fixed-size fields in `prefix`.
+    const fn add_string_tag(self, typ: TagType, prefix: &[u8], string: &str) -> Self {
+        let string = string.as_bytes();
+        let nul: &[u8] = if !string.is_empty() && string[string.len() - 1] == 0 {
+            &[]
+        } else {
+            &[0]
+        };
+        self.add_tag(tag_type_val(typ), &[prefix, string, nul])
+    }
+
+    /// Appends a command line tag.
+    #[must_use]
+    pub const fn cmdline(self, cmdline: &str) -> Self {
+        self.add_string_tag(TagType::Cmdline, &[], cmdline)
+    }
+
+    /// Appends a boot loader name tag.
+    #[must_use]
+    pub const fn boot_loader_name(self, name: &str) -> Self {
+        self.add_string_tag(TagType::BootLoaderName, &[], name)
+    }
+
+    /// Appends a module tag.
+    #[must_use]
+    pub const fn add_module(self, start: u32, end: u32, cmdline: &str) -> Self {
+        let start = start.to_ne_bytes();
+        let end = end.to_ne_bytes();
+        let prefix = [
+            start[0], start[1], start[2], start[3], end[0], end[1], end[2], end[3],
+        ];
+        self.add_string_tag(TagType::Module, &prefix, cmdline)
+    }
+
+    /// Appends a basic memory information tag.
+    #[must_use]
+    pub const fn basic_memory_info(self, memory_lower: u32, memory_upper: u32) -> Self {
+        self.add_tag(
+            tag_type_val(TagType::BasicMeminfo),
+            &[&memory_lower.to_ne_bytes(), &memory_upper.to_ne_bytes()],
+        )
+    }
+
+    /// Appends a memory map tag with the given `(base_addr, length, type)`
+    /// areas.
+    #[must_use]
+    pub const fn memory_map(mut self, areas: &[(u64, u64, MemoryAreaType)]) -> Self {
+        const ENTRY_SIZE: usize = 24;
+        let start = self.end;
+        self = self.add_tag(
+            tag_type_val(TagType::Mmap),
+            &[&(ENTRY_SIZE as u32).to_ne_bytes(), &0_u32.to_ne_bytes()],
+        );
+        let mut offset = start + 16;
+        let mut i = 0;
+        while i < areas.len() {
+            let (base_addr, length, typ) = areas[i];
+            self = self.write(offset, &base_addr.to_ne_bytes());
+            self = self.write(offset + 8, &length.to_ne_bytes());
+            self = self.write_u32(offset + 16, memory_area_type_val(typ));
+            self = self.write_u32(offset + 20, 0);
+            offset += ENTRY_SIZE;
+            i += 1;
+        }
+        self = self.write_u32(start + 4, (offset - start) as u32);
+        self.end = increase_to_alignment(offset);
+        self.write_end()
+    }
+
+    /// Returns the bytes of the boot information.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.end + Self::END_TAG_SIZE]
+    }
+
+    /// Returns the pointer to pass to [`BootInformation::load`].
+    ///
+    /// [`BootInformation::load`]: crate::BootInformation::load
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const BootInformationHeader {
+        self.bytes.as_ptr().cast()
+    }
+}
+
+impl<const N: usize> Default for StaticBootInformation<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Const version of `u32::from(TagType)`.
+const fn tag_type_val(typ: TagType) -> u32 {
+    match typ {
+        TagType::Cmdline => 1,
+        TagType::BootLoaderName => 2,
+        TagType::Module => 3,
+        TagType::BasicMeminfo => 4,
+        TagType::Mmap => 6,
+        _ => panic!("unsupported tag type"),
+    }
+}
+
+/// Const version of `u32::from(MemoryAreaTypeId::from(MemoryAreaType))`.
+const fn memory_area_type_val(typ: MemoryAreaType) -> u32 {
+    match typ {
+        MemoryAreaType::Available => 1,
+        MemoryAreaType::Reserved => 2,
+        MemoryAreaType::AcpiAvailable => 3,
+        MemoryAreaType::ReservedHibernate => 4,
+        MemoryAreaType::Defective => 5,
+        MemoryAreaType::Custom(val) => val,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BootInformation, TagType};
+
+    static MBI: StaticBootInformation<256> = StaticBootInformation::new()
+        .cmdline("cmdline")
+        .boot_loader_name("loader\0")
+        .basic_memory_info(639, 1024)
+        .memory_map(&[
+            (0, 0x9fc00, MemoryAreaType::Available),
+            (0x100000, 0x100000, MemoryAreaType::Reserved),
+        ])
+        .add_module(0x1000, 0x2000, "module")
+        .add_tag(0x1337, &[b"custom"]);
+
+    #[test]
+    fn test_static() {
+        assert_eq!(MBI.as_ptr().align_offset(8), 0);
+        let info = unsafe { BootInformation::load(MBI.as_ptr()) }.unwrap();
+        assert_eq!(info.total_size(), MBI.as_bytes().len());
+        assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("cmdline"));
+        assert_eq!(info.boot_loader_name_tag().unwrap().name(), Ok("loader"));
+        let meminfo = info.basic_memory_info_tag().unwrap();
+        assert_eq!(meminfo.memory_lower(), 639);
+        assert_eq!(meminfo.memory_upper(), 1024);
+        let areas = info.memory_map_tag().unwrap().memory_areas();
+        assert_eq!(areas.len(), 2);
+        assert_eq!(areas[1].start_address(), 0x100000);
+        assert_eq!(
+            MemoryAreaType::from(areas[1].typ()),
+            MemoryAreaType::Reserved
+        );
+        let module = info.module_tags().next().unwrap();
+        assert_eq!(module.end_address(), 0x2000);
+        assert_eq!(module.cmdline(), Ok("module"));
+        let custom = info.get_generic_tag(TagType::Custom(0x1337)).unwrap();
+        assert_eq!(custom.payload(), b"custom");
+    }
+
+    #[test]
+    fn test_empty() {
+        const EMPTY: StaticBootInformation<16> = StaticBootInformation::new();
+        assert_eq!(
+            EMPTY.as_bytes(),
+            &[16, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity")]
+    #[allow(clippy::missing_const_for_fn)] // Flagged by older clippy.
+    fn test_capacity() {
+        let _ = StaticBootInformation::<16>::new().cmdline("x");
+    }
+}
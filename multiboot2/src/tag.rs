@@ -1,16 +1,26 @@
 //! Module for the base tag definition [`TagHeader`].
 
-use crate::TagTypeId;
+use crate::{TagType, TagTypeId};
+use core::error::Error;
 use core::fmt::Debug;
-use core::mem;
-use multiboot2_common::Header;
+use core::ops::Range;
+use core::{mem, ptr};
+use derive_more::Display;
+use multiboot2_common::{increase_to_alignment, Header, MemoryError, Tag, ALIGNMENT};
 
 /// The common header that all tags have in common. This type is ABI compatible.
 ///
 /// Not to be confused with Multiboot header tags, which are something
 /// different.
 ///
-/// It is the sized counterpart of `GenericTag`, an internal type.
+/// It is the sized counterpart of [`GenericTag`].
+///
+/// Custom tags use it as the [`Header`] of their [`MaybeDynSized`]
+/// implementation. See [`BootInformation::get_tag`] for an example.
+///
+/// [`GenericTag`]: crate::GenericTag
+/// [`MaybeDynSized`]: multiboot2_common::MaybeDynSized
+/// [`BootInformation::get_tag`]: crate::BootInformation::get_tag
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[repr(C, align(8))] // Alignment also propagates to all tag types using this.
 pub struct TagHeader {
@@ -24,6 +34,9 @@ pub struct TagHeader {
 }
 
 impl TagHeader {
+    /// The size of the header in bytes.
+    pub const SIZE: usize = mem::size_of::<Self>();
+
     /// Creates a new header.
     pub fn new(typ: impl Into<TagTypeId>, size: u32) -> Self {
         Self {
@@ -31,15 +44,165 @@ impl TagHeader {
             size,
         }
     }
+
+    /// Like [`Self::new`], but usable in `const` contexts.
+    #[must_use]
+    pub const fn new_const(typ: TagType, size: u32) -> Self {
+        Self {
+            typ: TagTypeId::new(typ.val()),
+            size,
+        }
+    }
+
+    /// The total size of the tag including the padding to the next 8-byte
+    /// boundary, i.e., the offset of the next tag. It is a `u64`, as the
+    /// padded size of a tag close to 4 GiB doesn't fit into a 32-bit `usize`.
+    #[must_use]
+    pub const fn padded_size(&self) -> u64 {
+        (self.size as u64).next_multiple_of(ALIGNMENT as u64)
+    }
+
+    /// The range of the payload, relative to the beginning of the tag.
+    ///
+    /// Returns `None` if the reported size is smaller than the header.
+    #[must_use]
+    pub const fn payload_range(&self) -> Option<Range<usize>> {
+        let size = self.size as usize;
+        if size < Self::SIZE {
+            None
+        } else {
+            Some(Self::SIZE..size)
+        }
+    }
 }
 
 impl Header for TagHeader {
     fn payload_len(&self) -> usize {
-        assert!(self.size as usize >= mem::size_of::<Self>());
-        self.size as usize - mem::size_of::<Self>()
+        assert!(self.size as usize >= Self::SIZE);
+        self.size as usize - Self::SIZE
     }
 
     fn set_size(&mut self, total_size: usize) {
         self.size = total_size as u32
     }
 }
+
+/// Errors that occur when a fixed-size tag can't be parsed from raw bytes,
+/// for example via [`RsdpV1Tag::from_bytes`].
+///
+/// [`RsdpV1Tag::from_bytes`]: crate::RsdpV1Tag::from_bytes
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TagParseError {
+    /// The bytes can't be parsed as the tag. See [`MemoryError`].
+    Memory(MemoryError),
+    /// The type in the header doesn't belong to the requested tag.
+    WrongType,
+}
+
+impl Error for TagParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Memory(inner) => Some(inner),
+            Self::WrongType => None,
+        }
+    }
+}
+
+/// Parses a fixed-size tag by value from raw bytes, which don't need to be
+/// aligned.
+///
+/// The reported size of the tag must round up to the size of `T`. As tags
+/// are padded to the next 8-byte boundary, `bytes` must also cover that
+/// padding.
+///
+/// This is a `const fn` so that layout invariants and sample tags can be
+/// checked at compile time. It backs the `from_bytes` constructors of all
+/// fixed-size tags.
+pub const fn parse_sized_tag<T>(bytes: &[u8]) -> Result<T, TagParseError>
+where
+    T: Tag<IDType = TagType, Header = TagHeader> + Copy,
+{
+    if bytes.len() < mem::size_of::<TagHeader>() {
+        return Err(TagParseError::Memory(MemoryError::ShorterThanHeader));
+    }
+    // SAFETY: The length was checked and `TagHeader` is valid for all bit
+    // patterns.
+    let header = unsafe { ptr::read_unaligned(bytes.as_ptr().cast::<TagHeader>()) };
+    if header.typ.val() != T::ID.val() {
+        return Err(TagParseError::WrongType);
+    }
+    let size = header.size as usize;
+    if size < mem::size_of::<TagHeader>() || increase_to_alignment(size) != mem::size_of::<T>() {
+        return Err(TagParseError::Memory(MemoryError::InvalidReportedTotalSize));
+    }
+    if bytes.len() < mem::size_of::<T>() {
+        return Err(TagParseError::Memory(MemoryError::MissingPadding));
+    }
+    // SAFETY: The length was checked and all fixed-size tags are plain old
+    // data that is valid for all bit patterns.
+    Ok(unsafe { ptr::read_unaligned(bytes.as_ptr().cast::<T>()) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ImageLoadPhysAddrTag;
+
+    #[rustfmt::skip]
+    const BYTES: [u8; 17] = [
+        /* unaligned start */
+        0,
+        21, 0, 0, 0,
+        12, 0, 0, 0,
+        0xef, 0xcd, 0xab, 0x00,
+        /* padding */
+        0, 0, 0, 0,
+    ];
+
+    #[test]
+    fn test_parse_sized_tag() {
+        let tag = ImageLoadPhysAddrTag::from_bytes(&BYTES[1..]).unwrap();
+        assert_eq!(tag, ImageLoadPhysAddrTag::new(0xabcdef));
+
+        assert_eq!(
+            ImageLoadPhysAddrTag::from_bytes(&BYTES[1..8]),
+            Err(TagParseError::Memory(MemoryError::ShorterThanHeader))
+        );
+        assert_eq!(
+            ImageLoadPhysAddrTag::from_bytes(&BYTES[1..13]),
+            Err(TagParseError::Memory(MemoryError::MissingPadding))
+        );
+
+        let mut bytes = BYTES;
+        bytes[5] = 24;
+        assert_eq!(
+            ImageLoadPhysAddrTag::from_bytes(&bytes[1..]),
+            Err(TagParseError::Memory(MemoryError::InvalidReportedTotalSize))
+        );
+        bytes[1] = 20;
+        assert_eq!(
+            ImageLoadPhysAddrTag::from_bytes(&bytes[1..]),
+            Err(TagParseError::WrongType)
+        );
+    }
+
+    #[test]
+    fn test_header_accessors() {
+        let header = TagHeader::new_const(TagType::Custom(0x1337), 13);
+        assert_eq!(header, TagHeader::new(TagType::Custom(0x1337), 13));
+        assert_eq!(header.typ, TagTypeId::new(0x1337));
+        assert_eq!(header.size, 13);
+        assert_eq!(header.padded_size(), 16);
+        assert_eq!(header.payload_range(), Some(8..13));
+        assert_eq!(header.payload_len(), 5);
+
+        let header = TagHeader::new(TagType::End, 8);
+        assert_eq!(header.padded_size(), 8);
+        assert_eq!(header.payload_range(), Some(8..8));
+        assert_eq!(TagHeader::new(TagType::End, 4).payload_range(), None);
+        assert_eq!(
+            TagHeader::new(TagType::End, u32::MAX).padded_size(),
+            0x1_0000_0000
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Module for [`TagCache`], the lazily populated tag offset table used by
+//! [`BootInformation`] with the `tag_cache` feature.
+//!
+//! [`BootInformation`]: crate::BootInformation
+
+use crate::{TagIter, TagTypeId};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
+
+/// Number of slots of the open-addressed table. Real-world boot information
+/// structures contain far fewer distinct tag types.
+const SLOTS: usize = 32;
+
+/// Marks an unused slot. No tag can start at this offset, as the total size
+/// of the boot information is a `u32`.
+const EMPTY: u32 = u32::MAX;
+
+const STATE_EMPTY: u8 = 0;
+const STATE_POPULATING: u8 = 1;
+const STATE_READY: u8 = 2;
+
+/// Open-addressed table mapping a tag type to the offset of its first
+/// occurrence in the tags area of the boot information.
+///
+/// The table is populated with a single scan over all tags on the first
+/// lookup. Afterwards, lookups are answered without walking the tags again,
+/// which matters for big MBIs, e.g., with large EFI memory maps.
+///
+/// Only the thread that wins the race to populate the table writes to it.
+/// Lookups that happen while the table is populated fall back to a scan, so
+/// the table stays `Sync` without any locking.
+pub struct TagCache {
+    state: AtomicU8,
+    /// More distinct tag types were found than the table has slots. In that
+    /// case, a miss doesn't mean that the tag is absent.
+    overflow: AtomicBool,
+    types: [AtomicU32; SLOTS],
+    offsets: [AtomicU32; SLOTS],
+}
+
+impl TagCache {
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(STATE_EMPTY),
+            overflow: AtomicBool::new(false),
+            types: [const { AtomicU32::new(0) }; SLOTS],
+            offsets: [const { AtomicU32::new(EMPTY) }; SLOTS],
+        }
+    }
+
+    /// Looks up the offset of the first tag of the given type, relative to
+    /// the beginning of `tags`.
+    ///
+    /// Returns `Some(None)` if there is no such tag and `None` if the cache
+    /// can't tell, so the caller has to scan the tags itself.
+    pub fn lookup(&self, typ: TagTypeId, tags: &[u8]) -> Option<Option<usize>> {
+        if self.state.load(Ordering::Acquire) != STATE_READY {
+            self.try_populate(tags)?;
+        }
+
+        let typ = u32::from(typ);
+        let start = typ as usize % SLOTS;
+        for i in 0..SLOTS {
+            let slot = (start + i) % SLOTS;
+            let offset = self.offsets[slot].load(Ordering::Relaxed);
+            if offset == EMPTY {
+                break;
+            }
+            if self.types[slot].load(Ordering::Relaxed) == typ {
+                return Some(Some(offset as usize));
+            }
+        }
+        (!self.overflow.load(Ordering::Relaxed)).then_some(None)
+    }
+
+    /// Populates the table, unless another thread is already doing so, in
+    /// which case `None` is returned.
+    fn try_populate(&self, tags: &[u8]) -> Option<()> {
+        match self.state.compare_exchange(
+            STATE_EMPTY,
+            STATE_POPULATING,
+            Ordering::Acquire,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => self.populate(tags),
+            Err(STATE_READY) => {}
+            Err(_) => return None,
+        }
+        Some(())
+    }
+
+    fn populate(&self, tags: &[u8]) {
+        let base = tags.as_ptr() as usize;
+        for tag in TagIter::new(tags) {
+            let offset = (tag.header() as *const _ as usize - base) as u32;
+            self.insert(u32::from(tag.header().typ), offset);
+        }
+        self.state.store(STATE_READY, Ordering::Release);
+    }
+
+    /// Inserts the offset unless the type is already known, as only the first
+    /// occurrence of a tag type is relevant.
+    fn insert(&self, typ: u32, offset: u32) {
+        let start = typ as usize % SLOTS;
+        for i in 0..SLOTS {
+            let slot = (start + i) % SLOTS;
+            if self.offsets[slot].load(Ordering::Relaxed) == EMPTY {
+                self.types[slot].store(typ, Ordering::Relaxed);
+                self.offsets[slot].store(offset, Ordering::Relaxed);
+                return;
+            }
+            if self.types[slot].load(Ordering::Relaxed) == typ {
+                return;
+            }
+        }
+        self.overflow.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_lookup_slots() {
+        let cache = TagCache::new();
+        cache.state.store(STATE_READY, Ordering::Relaxed);
+        cache.insert(1, 8);
+        // Collides with type 1 and is probed into the next slot.
+        cache.insert(33, 16);
+        // Only the first occurrence counts.
+        cache.insert(1, 24);
+        assert_eq!(cache.lookup(1.into(), &[]), Some(Some(8)));
+        assert_eq!(cache.lookup(33.into(), &[]), Some(Some(16)));
+        assert_eq!(cache.lookup(2.into(), &[]), Some(None));
+    }
+
+    #[test]
+    fn overflow_falls_back_to_scan() {
+        let cache = TagCache::new();
+        cache.state.store(STATE_READY, Ordering::Relaxed);
+        for typ in 0..=SLOTS as u32 {
+            cache.insert(typ, typ * 8);
+        }
+        assert!(cache.overflow.load(Ordering::Relaxed));
+        assert_eq!(cache.lookup(5.into(), &[]), Some(Some(40)));
+        assert_eq!(cache.lookup((SLOTS as u32).into(), &[]), None);
+    }
+
+    #[test]
+    fn lookup_while_populating_falls_back_to_scan() {
+        let cache = TagCache::new();
+        cache.state.store(STATE_POPULATING, Ordering::Relaxed);
+        assert_eq!(cache.lookup(1.into(), &[]), None);
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn boot_information_lookups() {
+        use crate::{BootInformation, Builder, CommandLineTag, TagType};
+
+        let mbi = Builder::new()
+            .add_module(0x1000, 0x2000, "first")
+            .add_module(0x3000, 0x4000, "second")
+            .cmdline(CommandLineTag::new("hello"))
+            .build();
+        let info = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+
+        // Repeated lookups are served from the table.
+        for _ in 0..2 {
+            assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("hello"));
+            assert_eq!(
+                info.get_generic_tag(TagType::Module).unwrap().typ(),
+                TagType::Module
+            );
+            assert!(info.memory_map_tag().is_none());
+        }
+        assert_eq!(
+            info.module_tags().next().unwrap().cmdline(),
+            Ok("first"),
+            "the first module must be returned"
+        );
+    }
+}
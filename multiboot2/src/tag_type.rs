@@ -23,6 +23,12 @@ impl TagTypeId {
     pub const fn new(val: u32) -> Self {
         Self(val)
     }
+
+    /// Returns the underlying `u32` representation.
+    #[must_use]
+    pub const fn val(&self) -> u32 {
+        self.0
+    }
 }
 
 impl Debug for TagTypeId {
@@ -140,8 +146,44 @@ pub enum TagType {
 impl TagType {
     /// Convenient wrapper to get the underlying `u32` representation of the tag.
     #[must_use]
-    pub fn val(&self) -> u32 {
-        u32::from(*self)
+    pub const fn val(&self) -> u32 {
+        match *self {
+            Self::End => 0,
+            Self::Cmdline => 1,
+            Self::BootLoaderName => 2,
+            Self::Module => 3,
+            Self::BasicMeminfo => 4,
+            Self::Bootdev => 5,
+            Self::Mmap => 6,
+            Self::Vbe => 7,
+            Self::Framebuffer => 8,
+            Self::ElfSections => 9,
+            Self::Apm => 10,
+            Self::Efi32 => 11,
+            Self::Efi64 => 12,
+            Self::Smbios => 13,
+            Self::AcpiV1 => 14,
+            Self::AcpiV2 => 15,
+            Self::Network => 16,
+            Self::EfiMmap => 17,
+            Self::EfiBs => 18,
+            Self::Efi32Ih => 19,
+            Self::Efi64Ih => 20,
+            Self::LoadBaseAddr => 21,
+            Self::Custom(c) => c,
+        }
+    }
+
+    /// Whether tags of this type may occur multiple times in the boot
+    /// information: one [`Self::Module`] tag per module, one [`Self::Smbios`]
+    /// tag per table, one [`Self::Network`] tag per network card, and any
+    /// number of [`Self::Custom`] tags.
+    #[must_use]
+    pub const fn may_repeat(&self) -> bool {
+        matches!(
+            self,
+            Self::Module | Self::Smbios | Self::Network | Self::Custom(_)
+        )
     }
 }
 
@@ -195,31 +237,7 @@ mod primitive_conversion_impls {
 
     impl From<TagType> for u32 {
         fn from(value: TagType) -> Self {
-            match value {
-                TagType::End => 0,
-                TagType::Cmdline => 1,
-                TagType::BootLoaderName => 2,
-                TagType::Module => 3,
-                TagType::BasicMeminfo => 4,
-                TagType::Bootdev => 5,
-                TagType::Mmap => 6,
-                TagType::Vbe => 7,
-                TagType::Framebuffer => 8,
-                TagType::ElfSections => 9,
-                TagType::Apm => 10,
-                TagType::Efi32 => 11,
-                TagType::Efi64 => 12,
-                TagType::Smbios => 13,
-                TagType::AcpiV1 => 14,
-                TagType::AcpiV2 => 15,
-                TagType::Network => 16,
-                TagType::EfiMmap => 17,
-                TagType::EfiBs => 18,
-                TagType::Efi32Ih => 19,
-                TagType::Efi64Ih => 20,
-                TagType::LoadBaseAddr => 21,
-                TagType::Custom(c) => c,
-            }
+            value.val()
         }
     }
 }
@@ -365,4 +383,14 @@ mod tests {
             assert_eq!(tag_type_from_id, tag_type_from_u16)
         }
     }
+
+    #[test]
+    fn test_may_repeat() {
+        for i in 0..=21 {
+            let typ = TagType::from(i);
+            let expected = matches!(i, 3 | 13 | 16);
+            assert_eq!(typ.may_repeat(), expected, "{typ:?}");
+        }
+        assert!(TagType::Custom(0x1337).may_repeat());
+    }
 }
@@ -0,0 +1,1043 @@
+//! Robustness harness and adversarial corpus for fuzzers and CI.
+//!
+//! [`CORPUS`] contains known-nasty boot information blobs, such as truncated
+//! tags, huge sizes, bad UTF-8, or a zero-size end tag. Each of them
+//! once caused or could cause a panic or UB in a parser. The crate's own tests
+//! feed every blob to [`check_robustness`]. Downstream fuzzers can use the
+//! corpus as seed inputs and the harness as their fuzz target, so that both
+//! sides share one regression corpus that grows over time.
+//!
+//! New blobs go into the `corpus` directory of the crate and into [`CORPUS`].
+//! The crate's own `cargo fuzz` target lives in the `fuzz` directory.
+//!
+//! [`FixtureBuilder`] creates realistic boot information for the unit tests
+//! of kernels, without the need for QEMU.
+//!
+//! The `arbitrary_*` functions generate valid tags and boot information with
+//! boundary-case values from a [`Gen`]. They are deterministic in the seed of
+//! the [`Gen`] and have no dependencies, so they can back the strategies of
+//! any property testing framework. With the `proptest` feature, the
+//! `strategy` module provides them as `proptest` strategies.
+
+use crate::free_memory::mmap_area_range;
+use crate::{
+    BasicMemoryInfoTag, BootInformation, BootInformationBuffer, BootLoaderNameTag, BootdevTag,
+    Builder, CommandLineTag, FramebufferColor, FramebufferField, FramebufferTag, FramebufferType,
+    GenericTag, ImageLoadPhysAddrTag, LoadError, MemoryArea, MemoryAreaType, MemoryMapTag,
+    ModuleTag, TagOrder, TagType,
+};
+#[cfg(feature = "efi")]
+use crate::{EFIMemoryAreaType, EFIMemoryAttribute, EFIMemoryDesc, EFIMemoryMapTag, EFISdt64Tag};
+use alloc::string::ToString;
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{self, Write};
+use derive_more::Display;
+
+/// Includes a blob of the `corpus` directory as a [`CORPUS`] entry.
+macro_rules! blob {
+    ($name:literal) => {
+        (
+            $name,
+            include_bytes!(concat!("../corpus/", $name, ".bin")).as_slice(),
+        )
+    };
+}
+
+/// Known-nasty boot information blobs as `(name, bytes)` pairs.
+///
+/// The bytes are not necessarily aligned. [`check_robustness`] must return
+/// without panicking for each of them.
+pub const CORPUS: &[(&str, &[u8])] = &[
+    blob!("bad_utf8_cmdline"),
+    blob!("efi_desc_size_zero"),
+    blob!("efi_desc_version_2"),
+    blob!("elf_bad_entry_size"),
+    blob!("elf_huge_count"),
+    blob!("framebuffer_huge_palette"),
+    blob!("framebuffer_truncated_rgb"),
+    blob!("huge_tag_size"),
+    blob!("missing_end_tag"),
+    blob!("mmap_entry_size_7"),
+    blob!("network_garbage"),
+    blob!("smbios_garbage"),
+    blob!("tag_smaller_than_header"),
+    blob!("total_size_exceeds_buffer"),
+    blob!("total_size_unaligned"),
+    blob!("total_size_zero"),
+    blob!("truncated_tag"),
+    blob!("undersized_module"),
+    blob!("zero_size_end_tag"),
+];
+
+/// A [`Write`] sink that discards everything, used to run the `Debug`
+/// implementations.
+struct Discard;
+
+impl Write for Discard {
+    fn write_str(&mut self, _s: &str) -> fmt::Result {
+        Ok(())
+    }
+}
+
+/// Loads `bytes` as boot information with
+/// [`BootInformation::load_checked`] and exercises all accessors that only
+/// read memory within the boot information.
+///
+/// Returns the error if the blob is rejected. A panic or, when running under
+/// Miri, UB is a bug in this crate. The bytes are copied into an aligned
+/// buffer first, so they may have any alignment.
+///
+/// Accessors that dereference physical addresses reported by the boot
+/// information, such as [`crate::ElfSection::name`], are not exercised.
+/// Neither is the VBE mode info, as its memory model is an enum that only
+/// accepts the values of the VBE specification.
+pub fn check_robustness(bytes: &[u8]) -> Result<(), LoadError> {
+    let buffer = BootInformationBuffer::from_bytes(bytes)?;
+    // SAFETY: The buffer is valid and aligned.
+    let mbi = unsafe { BootInformation::load_checked(buffer.as_ptr()) }?;
+    exercise(&mbi);
+    Ok(())
+}
+
+/// Runs the accessors of [`check_robustness`] on a loaded boot information.
+fn exercise(mbi: &BootInformation) {
+    let mut sink = Discard;
+
+    for tag in mbi.generic_tags() {
+        let _ = write!(sink, "{:?} {:?}", tag.typ(), tag.vendor_id());
+    }
+    if let Some(tag) = mbi.command_line_tag() {
+        let _ = write!(
+            sink,
+            "{:?} {:?} {:?}",
+            tag,
+            tag.cmdline(),
+            tag.cmdline_lenient()
+        );
+    }
+    if let Some(tag) = mbi.boot_loader_name_tag() {
+        let _ = write!(sink, "{:?} {:?} {:?}", tag, tag.name(), tag.name_lenient());
+    }
+    for module in mbi.module_tags() {
+        let _ = write!(
+            sink,
+            "{:?} {:?} {}",
+            module.cmdline(),
+            module.cmdline_lenient(),
+            module.module_size()
+        );
+    }
+    if let Some(tag) = mbi.memory_map_tag() {
+        for area in tag.memory_areas() {
+            let _ = write!(sink, "{:?} {:?}", area, mmap_area_range(area));
+        }
+    }
+    if let Some(tag) = mbi.efi_memory_map_tag() {
+        let _ = write!(sink, "{:?}", tag);
+        for desc in tag.memory_areas() {
+            let _ = write!(sink, "{:?}", desc);
+        }
+    }
+    if let Some(tag) = mbi.framebuffer_tag() {
+        if let Ok(tag) = tag {
+            let _ = write!(sink, "{:?} {:?}", tag, tag.buffer_type());
+        }
+        let _ = mbi.check_framebuffer();
+    }
+    let _ = write!(
+        sink,
+        "{:?} {:?} {:?} {:?} {:?} {:?} {:?}",
+        mbi.apm_tag(),
+        mbi.basic_memory_info_tag(),
+        mbi.bootdev_tag(),
+        mbi.efi_bs_not_exited_tag(),
+        mbi.load_base_addr_tag(),
+        mbi.rsdp_v1_tag().map(|t| (t.signature(), t.oem_id())),
+        mbi.rsdp_v2_tag().map(|t| (t.signature(), t.oem_id())),
+    );
+    #[cfg(feature = "efi")]
+    let _ = write!(
+        sink,
+        "{:?} {:?} {:?} {:?}",
+        mbi.efi_sdt32_tag(),
+        mbi.efi_sdt64_tag(),
+        mbi.efi_ih32_tag(),
+        mbi.efi_ih64_tag()
+    );
+    #[cfg(feature = "elf")]
+    if let Some(sections) = mbi.elf_sections() {
+        for section in sections {
+            let _ = write!(
+                sink,
+                "{:?} {:?} {}",
+                section.section_type(),
+                section.flags(),
+                section.size()
+            );
+        }
+    }
+    #[cfg(feature = "net")]
+    if let Some(tag) = mbi.network_tag() {
+        let _ = write!(
+            sink,
+            "{:?} {:?} {:?}",
+            tag.bootp().map(|p| p.xid()),
+            tag.tftp_server().is_some(),
+            tag.boot_file()
+        );
+    }
+    #[cfg(feature = "smbios")]
+    if let Some(tag) = mbi.smbios_tag() {
+        let _ = write!(
+            sink,
+            "{:?} {:?} {:?} {:?}",
+            tag.entry_point()
+                .map(|e| (e.checksum_is_valid(), e.number_of_structures())),
+            tag.bios_vendor(),
+            tag.system_product(),
+            tag.system_uuid()
+        );
+        for structure in tag.structures() {
+            let _ = write!(sink, "{}", structure.strings().count());
+        }
+    }
+    #[cfg(feature = "vbe")]
+    if let Some(tag) = mbi.vbe_info_tag() {
+        let _ = write!(sink, "{}", tag.mode());
+    }
+}
+
+/// A semantic difference between two boot informations, see [`differences`].
+#[derive(Display, Clone, Debug, PartialEq, Eq)]
+pub enum Difference {
+    /// The `index`-th tag of the type is only present in the first boot
+    /// information.
+    #[display("{typ:?} #{index}: only present in the first boot information")]
+    OnlyInFirst {
+        /// The type of the tag.
+        typ: TagType,
+        /// The index of the tag among the tags of its type.
+        index: usize,
+    },
+    /// The `index`-th tag of the type is only present in the second boot
+    /// information.
+    #[display("{typ:?} #{index}: only present in the second boot information")]
+    OnlyInSecond {
+        /// The type of the tag.
+        typ: TagType,
+        /// The index of the tag among the tags of its type.
+        index: usize,
+    },
+    /// The payloads of the `index`-th tags of the type differ.
+    #[display(
+        "{typ:?} #{index}: payloads differ at byte {offset} ({} vs. {})",
+        DisplayByte(*first),
+        DisplayByte(*second)
+    )]
+    Payload {
+        /// The type of the tags.
+        typ: TagType,
+        /// The index of the tags among the tags of their type.
+        index: usize,
+        /// The offset of the first differing byte in the payloads.
+        offset: usize,
+        /// The byte of the first payload, or `None` if it is shorter.
+        first: Option<u8>,
+        /// The byte of the second payload, or `None` if it is shorter.
+        second: Option<u8>,
+    },
+}
+
+/// Formats a byte of [`Difference::Payload`].
+struct DisplayByte(Option<u8>);
+
+impl fmt::Display for DisplayByte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(byte) => write!(f, "{byte:#04x}"),
+            None => write!(f, "end"),
+        }
+    }
+}
+
+/// Compares two boot informations tag by tag and returns the semantic
+/// differences.
+///
+/// Tags of different types may appear in any order, while the order of tags of
+/// the same type, such as modules, is significant. Trailing NUL bytes of
+/// strings are ignored, as is the padding between tags. The end tags are not
+/// compared.
+#[must_use]
+pub fn differences(first: &BootInformation, second: &BootInformation) -> Vec<Difference> {
+    let first_tags = tags_by_type(first);
+    let second_tags = tags_by_type(second);
+    let mut differences = Vec::new();
+    for (typ, tags) in &first_tags {
+        let others = second_tags
+            .iter()
+            .find(|(other, _)| other == typ)
+            .map_or(&[][..], |(_, tags)| tags.as_slice());
+        for (index, tag) in tags.iter().enumerate() {
+            let Some(other) = others.get(index) else {
+                differences.push(Difference::OnlyInFirst { typ: *typ, index });
+                continue;
+            };
+            let (a, b) = (semantic_payload(tag), semantic_payload(other));
+            if a != b {
+                let offset = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+                differences.push(Difference::Payload {
+                    typ: *typ,
+                    index,
+                    offset,
+                    first: a.get(offset).copied(),
+                    second: b.get(offset).copied(),
+                });
+            }
+        }
+        for index in tags.len()..others.len() {
+            differences.push(Difference::OnlyInSecond { typ: *typ, index });
+        }
+    }
+    for (typ, tags) in &second_tags {
+        if !first_tags.iter().any(|(other, _)| other == typ) {
+            differences
+                .extend((0..tags.len()).map(|index| Difference::OnlyInSecond { typ: *typ, index }));
+        }
+    }
+    differences
+}
+
+/// Asserts that two boot informations are semantically equivalent, see
+/// [`differences`].
+///
+/// # Panics
+/// Panics with a list of all differences if there are any.
+#[track_caller]
+pub fn assert_equivalent(first: &BootInformation, second: &BootInformation) {
+    let differences = differences(first, second);
+    if !differences.is_empty() {
+        let mut message = String::from("boot informations are not equivalent:");
+        for difference in differences {
+            let _ = write!(message, "\n  {difference}");
+        }
+        panic!("{message}");
+    }
+}
+
+/// Returns the tags without the end tag, grouped by type in the order of
+/// their first appearance.
+fn tags_by_type<'a>(mbi: &'a BootInformation) -> Vec<(TagType, Vec<&'a GenericTag>)> {
+    let mut groups: Vec<(TagType, Vec<&GenericTag>)> = Vec::new();
+    for tag in mbi.generic_tags().filter(|tag| tag.typ() != TagType::End) {
+        match groups.iter_mut().find(|(typ, _)| *typ == tag.typ()) {
+            Some((_, tags)) => tags.push(tag),
+            None => groups.push((tag.typ(), vec![tag])),
+        }
+    }
+    groups
+}
+
+/// Returns the payload of the tag without the trailing NUL bytes of strings.
+fn semantic_payload(tag: &GenericTag) -> &[u8] {
+    let payload = tag.payload();
+    let string_start = match tag.typ() {
+        TagType::Cmdline | TagType::BootLoaderName => 0,
+        // The start and end address of a module precede the string.
+        TagType::Module => 8,
+        _ => return payload,
+    };
+    let len = payload.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    &payload[..len.max(string_start.min(payload.len()))]
+}
+
+/// Builder for realistic boot information fixtures, as GRUB creates them on
+/// a BIOS machine.
+///
+/// By default, the boot information has a command line, the bootloader name,
+/// a QEMU-like memory map of 128 MiB RAM with the derived basic memory info,
+/// the BIOS boot device, and a 1024x768 RGB framebuffer. The tags are ordered
+/// like GRUB orders them.
+///
+/// ```rust
+/// use multiboot2::testing::FixtureBuilder;
+/// use multiboot2::BootInformation;
+///
+/// let mbi = FixtureBuilder::new()
+///     .cmdline("console=ttyS0")
+///     .add_module("initrd", 0x4000)
+///     .build();
+/// let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+/// assert_eq!(mbi.module_tags().count(), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixtureBuilder {
+    cmdline: String,
+    bootloader_name: String,
+    memory_size: u64,
+    memory_areas: Option<Vec<MemoryArea>>,
+    /// Size and command line of each module.
+    modules: Vec<(u32, String)>,
+    /// Width, height, and bits per pixel.
+    framebuffer: Option<(u32, u32, u8)>,
+    load_base_addr: Option<u32>,
+}
+
+impl FixtureBuilder {
+    /// Physical address of the first module. The modules follow each other
+    /// page-aligned, as placed by GRUB above the kernel.
+    pub const MODULE_BASE: u32 = 0x100_0000;
+
+    /// Physical address of the framebuffer.
+    pub const FRAMEBUFFER_ADDRESS: u64 = 0xfd00_0000;
+
+    /// Creates a builder with the default fixture.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cmdline: String::new(),
+            bootloader_name: "GRUB 2.06".to_string(),
+            memory_size: 128 * 1024 * 1024,
+            memory_areas: None,
+            modules: Vec::new(),
+            framebuffer: Some((1024, 768, 32)),
+            load_base_addr: None,
+        }
+    }
+
+    /// Sets the kernel command line. Empty by default.
+    #[must_use]
+    pub fn cmdline(mut self, cmdline: &str) -> Self {
+        self.cmdline = cmdline.to_string();
+        self
+    }
+
+    /// Sets the bootloader name. `GRUB 2.06` by default.
+    #[must_use]
+    pub fn bootloader_name(mut self, name: &str) -> Self {
+        self.bootloader_name = name.to_string();
+        self
+    }
+
+    /// Sets the size of the RAM, from which a QEMU-like memory map is
+    /// generated. Must be at least 2 MiB.
+    #[must_use]
+    pub fn memory_size(mut self, size: u64) -> Self {
+        assert!(size >= 0x20_0000, "memory size must be at least 2 MiB");
+        self.memory_size = size;
+        self
+    }
+
+    /// Uses the given memory map instead of the generated one.
+    #[must_use]
+    pub fn memory_areas(mut self, areas: &[MemoryArea]) -> Self {
+        self.memory_areas = Some(areas.to_vec());
+        self
+    }
+
+    /// Adds a module of the given size, placed after the previous one
+    /// starting at [`Self::MODULE_BASE`].
+    #[must_use]
+    pub fn add_module(mut self, cmdline: &str, size: u32) -> Self {
+        self.modules.push((size, cmdline.to_string()));
+        self
+    }
+
+    /// Sets the resolution and bits per pixel of the RGB framebuffer.
+    #[must_use]
+    pub const fn framebuffer(mut self, width: u32, height: u32, bpp: u8) -> Self {
+        self.framebuffer = Some((width, height, bpp));
+        self
+    }
+
+    /// Omits the framebuffer tag, as on a headless machine.
+    #[must_use]
+    pub const fn no_framebuffer(mut self) -> Self {
+        self.framebuffer = None;
+        self
+    }
+
+    /// Adds the load base address tag, as for a relocatable kernel.
+    #[must_use]
+    pub const fn load_base_addr(mut self, addr: u32) -> Self {
+        self.load_base_addr = Some(addr);
+        self
+    }
+
+    /// Returns the memory map of the fixture.
+    #[must_use]
+    pub fn build_memory_areas(&self) -> Vec<MemoryArea> {
+        if let Some(areas) = &self.memory_areas {
+            return areas.clone();
+        }
+        let top = self.memory_size - 0x2_0000;
+        vec![
+            MemoryArea::new(0, 0x9_fc00, MemoryAreaType::Available),
+            MemoryArea::new(0x9_fc00, 0x400, MemoryAreaType::Reserved),
+            MemoryArea::new(0xf_0000, 0x1_0000, MemoryAreaType::Reserved),
+            MemoryArea::new(0x10_0000, top - 0x10_0000, MemoryAreaType::Available),
+            MemoryArea::new(top, 0x2_0000, MemoryAreaType::Reserved),
+            MemoryArea::new(0xfffc_0000, 0x4_0000, MemoryAreaType::Reserved),
+        ]
+    }
+
+    /// Creates the boot information.
+    #[must_use]
+    pub fn build(self) -> BootInformationBuffer {
+        let areas = self.build_memory_areas();
+        let mut builder = Builder::new().tag_order(TagOrder::Insertion);
+        if let Some(addr) = self.load_base_addr {
+            builder = builder.image_load_addr(ImageLoadPhysAddrTag::new(addr));
+        }
+        builder = builder
+            .cmdline(CommandLineTag::new(&self.cmdline))
+            .bootloader(BootLoaderNameTag::new(&self.bootloader_name));
+        let mut start = Self::MODULE_BASE;
+        for (size, cmdline) in &self.modules {
+            builder = builder.add_module_tag(ModuleTag::new(start, start + size, cmdline));
+            start = (start + size).next_multiple_of(0x1000);
+        }
+        builder = builder
+            .mmap(MemoryMapTag::new(&areas))
+            .meminfo(BasicMemoryInfoTag::from_memory_areas(&areas))
+            .bootdev(BootdevTag::new(0x80, u32::MAX, u32::MAX));
+        if let Some((width, height, bpp)) = self.framebuffer {
+            let field = |position| FramebufferField { position, size: 8 };
+            builder = builder.framebuffer(FramebufferTag::new(
+                Self::FRAMEBUFFER_ADDRESS,
+                width * u32::from(bpp).div_ceil(8),
+                width,
+                height,
+                bpp,
+                FramebufferType::RGB {
+                    red: field(16),
+                    green: field(8),
+                    blue: field(0),
+                },
+            ));
+        }
+        builder.build()
+    }
+}
+
+impl Default for FixtureBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Deterministic pseudo-random generator for the `arbitrary_*` functions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Gen(u64);
+
+impl Gen {
+    /// Creates a generator from the given seed.
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        // xorshift must not start with zero.
+        const MIX: u64 = 0x9e37_79b9_7f4a_7c15;
+        match seed ^ MIX {
+            0 => Self(MIX),
+            state => Self(state),
+        }
+    }
+
+    /// Returns the next pseudo-random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a number in `0..n`, or `0` if `n` is `0`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64().checked_rem(n).unwrap_or(0)
+    }
+
+    /// Returns `true` with a probability of one in `n`.
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.below(n) == 0
+    }
+
+    /// Returns a number that is a boundary case, such as zero or the maximum,
+    /// with a probability of one in two.
+    pub fn u64_value(&mut self) -> u64 {
+        match self.below(8) {
+            0 => 0,
+            1 => 1,
+            2 => u64::from(u32::MAX),
+            3 => u64::MAX,
+            _ => self.next_u64(),
+        }
+    }
+
+    /// Returns a number that is a boundary case, such as zero or the maximum,
+    /// with a probability of three in eight.
+    pub fn u32_value(&mut self) -> u32 {
+        match self.below(8) {
+            0 => 0,
+            1 => 1,
+            2 => u32::MAX,
+            _ => self.next_u64() as u32,
+        }
+    }
+
+    /// Returns a string without NUL characters, such as an empty, a long, or
+    /// a non-ASCII string.
+    pub fn string(&mut self) -> String {
+        const CHARS: &[char] = &['a', 'Z', '0', ' ', '=', '/', 'ä', '€', '😀'];
+        let len = match self.below(4) {
+            0 => 0,
+            1 => 300,
+            _ => self.below(32),
+        };
+        (0..len)
+            .map(|_| CHARS[self.below(CHARS.len() as u64) as usize])
+            .collect()
+    }
+}
+
+/// Generates a [`CommandLineTag`].
+pub fn arbitrary_cmdline(g: &mut Gen) -> alloc::boxed::Box<CommandLineTag> {
+    CommandLineTag::new(&g.string())
+}
+
+/// Generates a [`BootLoaderNameTag`].
+pub fn arbitrary_bootloader_name(g: &mut Gen) -> alloc::boxed::Box<BootLoaderNameTag> {
+    BootLoaderNameTag::new(&g.string())
+}
+
+/// Generates a [`ModuleTag`] of at least one byte, which may end at the 4 GiB
+/// boundary.
+pub fn arbitrary_module(g: &mut Gen) -> alloc::boxed::Box<ModuleTag> {
+    let start = g.u32_value().min(u32::MAX - 1);
+    let end = start + 1 + g.below(u64::from(u32::MAX - start)) as u32;
+    ModuleTag::new(start, end, &g.string())
+}
+
+/// Generates a [`MemoryArea`] of a known or unknown type.
+pub fn arbitrary_memory_area(g: &mut Gen) -> MemoryArea {
+    let typ = if g.one_in(4) {
+        g.u32_value()
+    } else {
+        1 + g.below(5) as u32
+    };
+    MemoryArea::new(g.u64_value(), g.u64_value(), typ)
+}
+
+/// Generates a [`MemoryMapTag`], which may be empty.
+pub fn arbitrary_memory_map(g: &mut Gen) -> alloc::boxed::Box<MemoryMapTag> {
+    let areas = (0..g.below(9))
+        .map(|_| arbitrary_memory_area(g))
+        .collect::<Vec<_>>();
+    MemoryMapTag::new(&areas)
+}
+
+/// Generates a [`BasicMemoryInfoTag`].
+pub fn arbitrary_basic_memory_info(g: &mut Gen) -> BasicMemoryInfoTag {
+    BasicMemoryInfoTag::new(g.u32_value(), g.u32_value())
+}
+
+/// Generates a [`BootdevTag`].
+pub fn arbitrary_bootdev(g: &mut Gen) -> BootdevTag {
+    BootdevTag::new(g.u32_value(), g.u32_value(), g.u32_value())
+}
+
+/// Generates a [`FramebufferTag`] of any type, including an empty palette.
+pub fn arbitrary_framebuffer(g: &mut Gen) -> alloc::boxed::Box<FramebufferTag> {
+    let field = |g: &mut Gen| FramebufferField {
+        position: g.next_u64() as u8,
+        size: g.next_u64() as u8,
+    };
+    let palette = (0..g.below(257))
+        .map(|_| FramebufferColor {
+            red: g.next_u64() as u8,
+            green: g.next_u64() as u8,
+            blue: g.next_u64() as u8,
+        })
+        .collect::<Vec<_>>();
+    let buffer_type = match g.below(3) {
+        0 => FramebufferType::Indexed { palette: &palette },
+        1 => FramebufferType::RGB {
+            red: field(g),
+            green: field(g),
+            blue: field(g),
+        },
+        _ => FramebufferType::Text,
+    };
+    FramebufferTag::new(
+        g.u64_value(),
+        g.u32_value(),
+        g.u32_value(),
+        g.u32_value(),
+        g.next_u64() as u8,
+        buffer_type,
+    )
+}
+
+/// Generates an [`EFIMemoryMapTag`], which may be empty.
+#[cfg(feature = "efi")]
+pub fn arbitrary_efi_memory_map(g: &mut Gen) -> alloc::boxed::Box<EFIMemoryMapTag> {
+    let descs = (0..g.below(9))
+        .map(|_| EFIMemoryDesc {
+            ty: EFIMemoryAreaType(g.below(16) as u32),
+            phys_start: g.u64_value() & !0xfff,
+            virt_start: 0,
+            page_count: g.u64_value(),
+            att: EFIMemoryAttribute::from_bits_retain(g.next_u64()),
+        })
+        .collect::<Vec<_>>();
+    EFIMemoryMapTag::new_from_descs(&descs)
+}
+
+/// Generates a [`Builder`] with a random selection of tags, including
+/// multiple modules and custom tags, in a random order.
+pub fn arbitrary_builder(g: &mut Gen) -> Builder {
+    let mut builder = Builder::new();
+    if g.one_in(2) {
+        builder = builder.tag_order(TagOrder::Insertion);
+    }
+    for _ in 0..g.below(16) {
+        builder = match g.below(11) {
+            0 => builder.cmdline(arbitrary_cmdline(g)),
+            1 => builder.bootloader(arbitrary_bootloader_name(g)),
+            2 => builder.add_module_tag(arbitrary_module(g)),
+            3 => builder.mmap(arbitrary_memory_map(g)),
+            4 => builder.meminfo(arbitrary_basic_memory_info(g)),
+            5 => builder.bootdev(arbitrary_bootdev(g)),
+            6 => builder.framebuffer(arbitrary_framebuffer(g)),
+            7 => builder.image_load_addr(ImageLoadPhysAddrTag::new(g.u32_value())),
+            #[cfg(feature = "efi")]
+            8 => builder.efi_mmap(arbitrary_efi_memory_map(g)),
+            #[cfg(feature = "efi")]
+            9 => builder.efi64(EFISdt64Tag::new(g.u64_value())),
+            _ => {
+                let payload = (0..g.below(64))
+                    .map(|_| g.next_u64() as u8)
+                    .collect::<Vec<_>>();
+                builder.add_raw_tag(0x1000 + g.below(0x100) as u32, &payload)
+            }
+        };
+    }
+    builder
+}
+
+/// Generates a valid boot information, see [`arbitrary_builder`].
+pub fn arbitrary_boot_information(g: &mut Gen) -> BootInformationBuffer {
+    arbitrary_builder(g).build()
+}
+
+/// [`proptest`] strategies over the `arbitrary_*` generators.
+///
+/// ```rust,ignore
+/// use multiboot2::testing::strategy::arb_boot_information;
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     #[test]
+///     fn kernel_accepts_any_boot_information(mbi in arb_boot_information()) {
+///         kernel_init(mbi.as_ptr());
+///     }
+/// }
+/// ```
+#[cfg(feature = "proptest")]
+pub mod strategy {
+    use super::*;
+    use proptest::prelude::*;
+
+    prop_compose! {
+        /// Strategy for a [`Gen`] with a seed from `proptest`.
+        pub fn arb_gen()(seed in any::<u64>()) -> Gen {
+            Gen::new(seed)
+        }
+    }
+
+    prop_compose! {
+        /// Strategy for [`arbitrary_cmdline`].
+        pub fn arb_cmdline()(mut g in arb_gen()) -> alloc::boxed::Box<CommandLineTag> {
+            arbitrary_cmdline(&mut g)
+        }
+    }
+
+    prop_compose! {
+        /// Strategy for [`arbitrary_module`].
+        pub fn arb_module()(mut g in arb_gen()) -> alloc::boxed::Box<ModuleTag> {
+            arbitrary_module(&mut g)
+        }
+    }
+
+    prop_compose! {
+        /// Strategy for [`arbitrary_memory_map`].
+        pub fn arb_memory_map()(mut g in arb_gen()) -> alloc::boxed::Box<MemoryMapTag> {
+            arbitrary_memory_map(&mut g)
+        }
+    }
+
+    prop_compose! {
+        /// Strategy for [`arbitrary_framebuffer`].
+        pub fn arb_framebuffer()(mut g in arb_gen()) -> alloc::boxed::Box<FramebufferTag> {
+            arbitrary_framebuffer(&mut g)
+        }
+    }
+
+    prop_compose! {
+        /// Strategy for [`arbitrary_boot_information`].
+        pub fn arb_boot_information()(mut g in arb_gen()) -> BootInformationBuffer {
+            arbitrary_boot_information(&mut g)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LayoutViolation, MemoryError};
+
+    #[test]
+    fn corpus_is_handled() {
+        for (name, bytes) in CORPUS {
+            // Also misaligned, to exercise the copy into an aligned buffer.
+            let mut shifted = vec![0_u8; bytes.len() + 1];
+            shifted[1..].copy_from_slice(bytes);
+            let result = check_robustness(bytes);
+            assert_eq!(check_robustness(&shifted[1..]), result, "{name}");
+        }
+    }
+
+    #[test]
+    fn corpus_results() {
+        let result = |name: &str| {
+            let (_, bytes) = CORPUS.iter().find(|(n, _)| *n == name).unwrap();
+            check_robustness(bytes)
+        };
+
+        assert_eq!(
+            result("total_size_exceeds_buffer"),
+            Err(LoadError::Memory(MemoryError::InvalidReportedTotalSize))
+        );
+        assert_eq!(result("missing_end_tag"), Err(LoadError::NoEndTag));
+        assert_eq!(result("zero_size_end_tag"), Err(LoadError::NoEndTag));
+        assert_eq!(
+            result("total_size_zero"),
+            Err(LoadError::Memory(MemoryError::InvalidReportedTotalSize))
+        );
+        assert_eq!(
+            result("total_size_unaligned"),
+            Err(LoadError::Memory(MemoryError::MissingPadding))
+        );
+        assert!(matches!(
+            result("huge_tag_size"),
+            Err(LoadError::LimitExceeded(_))
+        ));
+        assert!(matches!(
+            result("tag_smaller_than_header"),
+            Err(LoadError::Layout(e)) if e.violation == LayoutViolation::Overlapping
+        ));
+        assert!(matches!(
+            result("truncated_tag"),
+            Err(LoadError::Layout(e)) if e.violation == LayoutViolation::OutOfBounds
+                && e.typ == TagType::Cmdline
+        ));
+
+        for name in [
+            "bad_utf8_cmdline",
+            "efi_desc_size_zero",
+            "efi_desc_version_2",
+            "elf_bad_entry_size",
+            "elf_huge_count",
+            "framebuffer_huge_palette",
+            "framebuffer_truncated_rgb",
+            "mmap_entry_size_7",
+            "network_garbage",
+            "smbios_garbage",
+            "undersized_module",
+        ] {
+            assert_eq!(result(name), Ok(()), "{name}");
+        }
+    }
+
+    #[test]
+    fn fixture_builder() {
+        let fixture = FixtureBuilder::new()
+            .cmdline("console=ttyS0")
+            .add_module("initrd", 0x1800)
+            .add_module("config", 0x10)
+            .load_base_addr(0x20_0000)
+            .build();
+        let mbi = unsafe { BootInformation::load_checked(fixture.as_ptr()) }.unwrap();
+        assert_eq!(
+            mbi.generic_tags().map(|t| t.typ()).collect::<Vec<_>>(),
+            [
+                TagType::LoadBaseAddr,
+                TagType::Cmdline,
+                TagType::BootLoaderName,
+                TagType::Module,
+                TagType::Module,
+                TagType::Mmap,
+                TagType::BasicMeminfo,
+                TagType::Bootdev,
+                TagType::Framebuffer,
+                TagType::End,
+            ]
+        );
+        assert_eq!(
+            mbi.command_line_tag().unwrap().cmdline(),
+            Ok("console=ttyS0")
+        );
+        let modules = mbi
+            .module_tags()
+            .map(|m| (m.start_address(), m.end_address()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            modules,
+            [(0x100_0000, 0x100_1800), (0x100_2000, 0x100_2010)]
+        );
+        let meminfo = mbi.basic_memory_info_tag().unwrap();
+        assert_eq!(meminfo.memory_lower(), 639);
+        assert_eq!(meminfo.memory_upper(), 128 * 1024 - 1024 - 128);
+        assert_eq!(mbi.check_framebuffer(), Ok(()));
+        assert!(mbi.warnings().next().is_none());
+
+        let fixture = FixtureBuilder::new()
+            .memory_size(0x40_0000)
+            .no_framebuffer()
+            .build();
+        let mbi = unsafe { BootInformation::load(fixture.as_ptr()) }.unwrap();
+        assert!(mbi.framebuffer_tag().is_none());
+        assert_eq!(
+            mbi.memory_map_tag().unwrap().memory_areas()[3].end_address(),
+            0x3e_0000
+        );
+    }
+
+    #[test]
+    fn gen_never_sticks_at_zero() {
+        let mut g = Gen::new(0x9e37_79b9_7f4a_7c15);
+        assert_ne!(g.next_u64(), 0);
+        assert_ne!(g.next_u64(), 0);
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn arbitrary_round_trip(mbi in strategy::arb_boot_information()) {
+            proptest::prop_assert_eq!(check_robustness(mbi.as_bytes()), Ok(()));
+            let parsed = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+            proptest::prop_assert!(parsed.warnings().next().is_none());
+            let rebuilt = Builder::from_boot_information(&parsed).build();
+            proptest::prop_assert_eq!(rebuilt.as_bytes(), mbi.as_bytes());
+        }
+
+        #[test]
+        fn arbitrary_cmdline_round_trip(tag in strategy::arb_cmdline()) {
+            proptest::prop_assert_eq!(CommandLineTag::new(tag.cmdline().unwrap()), tag);
+        }
+
+        #[test]
+        fn arbitrary_module_round_trip(tag in strategy::arb_module()) {
+            let rebuilt = ModuleTag::new(
+                tag.start_address(),
+                tag.end_address(),
+                tag.cmdline().unwrap(),
+            );
+            proptest::prop_assert_eq!(rebuilt, tag);
+        }
+
+        #[test]
+        fn arbitrary_memory_map_round_trip(tag in strategy::arb_memory_map()) {
+            proptest::prop_assert_eq!(MemoryMapTag::new(tag.memory_areas()), tag);
+        }
+
+        #[test]
+        fn arbitrary_framebuffer_round_trip(tag in strategy::arb_framebuffer()) {
+            let rebuilt = FramebufferTag::new(
+                tag.address(),
+                tag.pitch(),
+                tag.width(),
+                tag.height(),
+                tag.bpp(),
+                tag.buffer_type().unwrap(),
+            );
+            proptest::prop_assert_eq!(rebuilt, tag);
+        }
+    }
+
+    #[test]
+    fn equivalence() {
+        let first = Builder::new()
+            .cmdline(CommandLineTag::new("foo"))
+            .add_module(0x1000, 0x2000, "a")
+            .add_module(0x2000, 0x3000, "b")
+            .meminfo(BasicMemoryInfoTag::new(639, 1024))
+            .build();
+        let second = Builder::new()
+            .tag_order(TagOrder::Insertion)
+            .meminfo(BasicMemoryInfoTag::new(639, 1024))
+            .add_module(0x1000, 0x2000, "a")
+            .add_raw_tag(TagType::Cmdline, b"foo\0\0\0")
+            .add_module(0x2000, 0x3000, "b")
+            .build();
+        let first = unsafe { BootInformation::load(first.as_ptr()) }.unwrap();
+        let second = unsafe { BootInformation::load(second.as_ptr()) }.unwrap();
+        assert_equivalent(&first, &second);
+
+        let third = Builder::new()
+            .cmdline(CommandLineTag::new("fop"))
+            .add_module(0x2000, 0x3000, "b")
+            .add_module(0x1000, 0x2000, "a")
+            .bootdev(BootdevTag::new(0x80, 0, 0))
+            .build();
+        let third = unsafe { BootInformation::load(third.as_ptr()) }.unwrap();
+        let differences = differences(&first, &third);
+        assert_eq!(
+            differences,
+            [
+                Difference::Payload {
+                    typ: TagType::Cmdline,
+                    index: 0,
+                    offset: 2,
+                    first: Some(b'o'),
+                    second: Some(b'p'),
+                },
+                Difference::Payload {
+                    typ: TagType::Module,
+                    index: 0,
+                    offset: 1,
+                    first: Some(0x10),
+                    second: Some(0x20),
+                },
+                Difference::Payload {
+                    typ: TagType::Module,
+                    index: 1,
+                    offset: 1,
+                    first: Some(0x20),
+                    second: Some(0x10),
+                },
+                Difference::OnlyInFirst {
+                    typ: TagType::BasicMeminfo,
+                    index: 0,
+                },
+                Difference::OnlyInSecond {
+                    typ: TagType::Bootdev,
+                    index: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            differences[0].to_string(),
+            "Cmdline #0: payloads differ at byte 2 (0x6f vs. 0x70)"
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "boot informations are not equivalent:\n  Cmdline #0: payloads differ at byte 3 (end vs. 0x6f)"
+    )]
+    fn assert_equivalent_panics() {
+        let first = Builder::new().cmdline(CommandLineTag::new("foo")).build();
+        let second = Builder::new().cmdline(CommandLineTag::new("fooo")).build();
+        let first = unsafe { BootInformation::load(first.as_ptr()) }.unwrap();
+        let second = unsafe { BootInformation::load(second.as_ptr()) }.unwrap();
+        assert_equivalent(&first, &second);
+    }
+}
@@ -0,0 +1,603 @@
+//! Module for [`UefiCollector`].
+
+use crate::free_memory::{efi_desc_range, efi_is_available};
+use crate::{
+    BootInformation, Builder, EFIMemoryAreaType, EFIMemoryDesc, EFIMemoryMapTag, EFISdt32Tag,
+    EFISdt64Tag, FramebufferField, FramebufferTag, FramebufferType, MemoryArea, MemoryAreaType,
+    MemoryMapTag, SmbiosTag, TagType,
+};
+use alloc::boxed::Box;
+use alloc::vec;
+use core::mem::size_of;
+use core::{ptr, slice};
+use uefi_raw::protocol::console::{
+    GraphicsOutputModeInformation, GraphicsOutputProtocol, GraphicsPixelFormat,
+};
+use uefi_raw::table::boot::BootServices;
+use uefi_raw::table::configuration::ConfigurationTable;
+use uefi_raw::table::runtime::RuntimeServices;
+use uefi_raw::table::system::SystemTable;
+use uefi_raw::{guid, Guid, Status};
+
+/// GUID of the ACPI 1.0 RSDP in the EFI configuration table.
+pub const ACPI_GUID: Guid = guid!("eb9d2d30-2d88-11d3-9a16-0090273fc14d");
+/// GUID of the ACPI 2.0+ RSDP in the EFI configuration table.
+pub const ACPI2_GUID: Guid = guid!("8868e871-e4f1-11d3-bc22-0080c73c8881");
+/// GUID of the 32-bit SMBIOS entry point in the EFI configuration table.
+pub const SMBIOS_GUID: Guid = guid!("eb9d2d31-2d88-11d3-9a16-0090273fc14d");
+/// GUID of the 64-bit SMBIOS entry point in the EFI configuration table.
+pub const SMBIOS3_GUID: Guid = guid!("f2fd1544-9794-4a2c-992e-e5bbcf20e394");
+
+/// The EFI memory map obtained from the boot services.
+#[derive(Debug)]
+pub struct UefiMemoryMap {
+    buffer: vec::Vec<u64>,
+    size: usize,
+    desc_size: usize,
+    desc_version: u32,
+    map_key: usize,
+}
+
+impl UefiMemoryMap {
+    /// Returns the key to pass to `ExitBootServices()`.
+    #[must_use]
+    pub const fn map_key(&self) -> usize {
+        self.map_key
+    }
+
+    /// Returns the raw memory map.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: The buffer is at least `size` bytes long.
+        unsafe { slice::from_raw_parts(self.buffer.as_ptr().cast(), self.size) }
+    }
+
+    /// Returns an iterator over the memory descriptors.
+    ///
+    /// The descriptors are returned by value, as the descriptor size reported
+    /// by the firmware doesn't need to be a multiple of the alignment of
+    /// [`EFIMemoryDesc`].
+    pub fn descriptors(&self) -> impl Iterator<Item = EFIMemoryDesc> + '_ {
+        self.as_bytes()
+            .chunks_exact(self.desc_size.max(1))
+            .filter(|chunk| chunk.len() >= size_of::<EFIMemoryDesc>())
+            // SAFETY: The chunk is big enough and every bit pattern is a
+            // valid descriptor.
+            .map(|chunk| unsafe { ptr::read_unaligned(chunk.as_ptr().cast::<EFIMemoryDesc>()) })
+    }
+
+    /// Returns the [`EFIMemoryMapTag`] for the memory map.
+    #[must_use]
+    pub fn to_efi_mmap_tag(&self) -> Box<EFIMemoryMapTag> {
+        EFIMemoryMapTag::new_from_map(self.desc_size as u32, self.desc_version, self.as_bytes())
+    }
+
+    /// Returns the [`MemoryMapTag`] for the memory map. Memory used by the
+    /// boot services is reported as available, whereas loader memory is
+    /// reserved, as it holds the loaded kernel and modules.
+    #[must_use]
+    pub fn to_mmap_tag(&self) -> Box<MemoryMapTag> {
+        let areas = self
+            .descriptors()
+            .map(|desc| {
+                let range = efi_desc_range(desc);
+                MemoryArea::new(
+                    range.start,
+                    range.end - range.start,
+                    efi_to_mmap_type(desc.ty),
+                )
+            })
+            .collect::<vec::Vec<_>>();
+        MemoryMapTag::new(&areas)
+    }
+}
+
+/// Translates an EFI memory type to the corresponding Multiboot2 memory type.
+const fn efi_to_mmap_type(typ: EFIMemoryAreaType) -> MemoryAreaType {
+    match typ {
+        ty if efi_is_available(ty) => MemoryAreaType::Available,
+        EFIMemoryAreaType::ACPI_RECLAIM => MemoryAreaType::AcpiAvailable,
+        EFIMemoryAreaType::ACPI_NON_VOLATILE => MemoryAreaType::ReservedHibernate,
+        EFIMemoryAreaType::UNUSABLE => MemoryAreaType::Defective,
+        _ => MemoryAreaType::Reserved,
+    }
+}
+
+/// Creates the [`FramebufferTag`] for the current mode of the GOP. Returns
+/// `None` if the mode has no linear framebuffer.
+fn framebuffer_tag(
+    address: u64,
+    info: &GraphicsOutputModeInformation,
+) -> Option<Box<FramebufferTag>> {
+    let field = |mask: u32| FramebufferField {
+        position: mask.trailing_zeros() as u8,
+        size: mask.count_ones() as u8,
+    };
+    let (red, green, blue, bpp) = match info.pixel_format {
+        GraphicsPixelFormat::PIXEL_RED_GREEN_BLUE_RESERVED_8_BIT_PER_COLOR => {
+            (field(0xff), field(0xff00), field(0xff_0000), 32)
+        }
+        GraphicsPixelFormat::PIXEL_BLUE_GREEN_RED_RESERVED_8_BIT_PER_COLOR => {
+            (field(0xff_0000), field(0xff00), field(0xff), 32)
+        }
+        GraphicsPixelFormat::PIXEL_BIT_MASK => {
+            let mask = info.pixel_information;
+            let all = mask.red | mask.green | mask.blue | mask.reserved;
+            let bpp = (32 - all.leading_zeros()).next_multiple_of(8) as u8;
+            (field(mask.red), field(mask.green), field(mask.blue), bpp)
+        }
+        _ => return None,
+    };
+    Some(FramebufferTag::new(
+        address,
+        info.pixels_per_scan_line * u32::from(bpp / 8),
+        info.horizontal_resolution,
+        info.vertical_resolution,
+        bpp,
+        FramebufferType::RGB { red, green, blue },
+    ))
+}
+
+/// Returns the entries of the configuration table of a valid system table.
+fn configuration_table(st: &SystemTable) -> &[ConfigurationTable] {
+    if st.configuration_table.is_null() {
+        return &[];
+    }
+    // SAFETY: The caller guarantees that the system table is valid.
+    unsafe {
+        slice::from_raw_parts(
+            st.configuration_table,
+            st.number_of_configuration_table_entries,
+        )
+    }
+}
+
+/// Typed view of the EFI system table passed to the kernel in the boot
+/// information. See [`BootInformation::uefi_system_table`].
+///
+/// The boot services are only accessible if they were not exited by the
+/// bootloader, whereas the runtime services are always accessible.
+///
+/// The view is built on the `uefi-raw` definitions, which the `uefi` crate
+/// uses as well. Kernels using the `uefi` crate hand the table over with
+/// [`Self::as_ptr`]:
+///
+/// ```rust,ignore
+/// let st = unsafe { mbi.uefi_system_table() }.unwrap();
+/// unsafe { uefi::table::set_system_table(st.as_ptr().cast()) };
+/// let time = uefi::runtime::get_time()?;
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct UefiSystemTable<'a> {
+    table: &'a SystemTable,
+    boot_services_active: bool,
+}
+
+impl<'a> UefiSystemTable<'a> {
+    /// Returns the raw system table.
+    #[must_use]
+    pub const fn as_raw(&self) -> &'a SystemTable {
+        self.table
+    }
+
+    /// Returns the pointer to the system table, e.g., for
+    /// `uefi::table::set_system_table`.
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const SystemTable {
+        self.table
+    }
+
+    /// Returns whether the boot services are still active, i.e., whether the
+    /// bootloader passed the [`crate::EFIBootServicesNotExitedTag`].
+    #[must_use]
+    pub const fn boot_services_active(&self) -> bool {
+        self.boot_services_active
+    }
+
+    /// Returns the boot services, if they were not exited.
+    #[must_use]
+    pub fn boot_services(&self) -> Option<&'a BootServices> {
+        if !self.boot_services_active {
+            return None;
+        }
+        // SAFETY: Guaranteed by the caller of `uefi_system_table`.
+        unsafe { self.table.boot_services.as_ref() }
+    }
+
+    /// Returns the runtime services.
+    #[must_use]
+    pub fn runtime_services(&self) -> Option<&'a RuntimeServices> {
+        // SAFETY: Guaranteed by the caller of `uefi_system_table`.
+        unsafe { self.table.runtime_services.as_ref() }
+    }
+
+    /// Returns the entries of the EFI configuration table.
+    #[must_use]
+    pub fn configuration_table(&self) -> &'a [ConfigurationTable] {
+        configuration_table(self.table)
+    }
+}
+
+impl BootInformation<'_> {
+    /// Returns a typed view of the EFI system table, which lets UEFI-aware
+    /// kernels call the runtime services, and the boot services if they were
+    /// not exited.
+    ///
+    /// The 64-bit system table is used on 64-bit targets and the 32-bit one
+    /// otherwise. Returns `None` if there is no system table for the target
+    /// or if its signature is invalid.
+    ///
+    /// # Safety
+    /// The system table and all tables it refers to must be valid and
+    /// identity-mapped.
+    #[must_use]
+    pub unsafe fn uefi_system_table(&self) -> Option<UefiSystemTable<'_>> {
+        #[cfg(target_pointer_width = "64")]
+        let address = self.efi_sdt64_tag()?.sdt_address();
+        #[cfg(not(target_pointer_width = "64"))]
+        let address = self.efi_sdt32_tag()?.sdt_address();
+
+        let table = (address as *const SystemTable).as_ref()?;
+        if table.header.signature != SystemTable::SIGNATURE {
+            return None;
+        }
+        Some(UefiSystemTable {
+            table,
+            boot_services_active: self.efi_bs_not_exited_tag().is_some(),
+        })
+    }
+}
+
+/// Collects the information a UEFI bootloader passes to a Multiboot2 kernel
+/// from the EFI system table and adds the corresponding tags to a [`Builder`].
+///
+/// The collector gathers
+/// - the EFI system table pointer,
+/// - the ACPI RSDPs and the SMBIOS tables from the configuration table,
+/// - the memory map from the boot services, and
+/// - the framebuffer from the Graphics Output Protocol (GOP).
+///
+/// As the memory map changes with every allocation, the boot information
+/// should be built right before `ExitBootServices()` is called with
+/// [`UefiMemoryMap::map_key`].
+#[derive(Debug)]
+pub struct UefiCollector<'a> {
+    system_table: &'a SystemTable,
+}
+
+impl<'a> UefiCollector<'a> {
+    /// Creates a new collector.
+    ///
+    /// # Safety
+    /// The system table must be valid and the boot services must not have
+    /// been exited. All tables referenced by the configuration table must be
+    /// identity-mapped.
+    #[must_use]
+    pub const unsafe fn new(system_table: &'a SystemTable) -> Self {
+        Self { system_table }
+    }
+
+    /// Returns the entries of the EFI configuration table.
+    #[must_use]
+    pub fn configuration_table(&self) -> &'a [ConfigurationTable] {
+        configuration_table(self.system_table)
+    }
+
+    /// Returns the address of the configuration table entry with the given
+    /// GUID.
+    #[must_use]
+    pub fn find_table(&self, guid: Guid) -> Option<*const u8> {
+        self.configuration_table()
+            .iter()
+            .find(|entry| entry.vendor_guid == guid)
+            .map(|entry| entry.vendor_table.cast_const().cast())
+    }
+
+    /// Returns the ACPI 1.0 RSDP.
+    #[must_use]
+    pub fn rsdp_v1(&self) -> Option<&'a [u8]> {
+        // SAFETY: Guaranteed by the caller of `new`.
+        self.find_table(ACPI_GUID)
+            .map(|rsdp| unsafe { slice::from_raw_parts(rsdp, 20) })
+    }
+
+    /// Returns the ACPI 2.0+ RSDP.
+    #[must_use]
+    pub fn rsdp_v2(&self) -> Option<&'a [u8]> {
+        // SAFETY: Guaranteed by the caller of `new`.
+        self.find_table(ACPI2_GUID)
+            .map(|rsdp| unsafe { slice::from_raw_parts(rsdp, 36) })
+    }
+
+    /// Returns the [`SmbiosTag`] built from the SMBIOS tables. The 64-bit
+    /// entry point is preferred.
+    #[must_use]
+    pub fn smbios_tag(&self) -> Option<Box<SmbiosTag>> {
+        let (entry_point, len_offset) = self
+            .find_table(SMBIOS3_GUID)
+            .map(|ep| (ep, 6))
+            .or_else(|| self.find_table(SMBIOS_GUID).map(|ep| (ep, 5)))?;
+        // SAFETY: Guaranteed by the caller of `new`. The length is validated
+        // when the entry point is parsed.
+        let entry_point = unsafe {
+            let len = usize::from(*entry_point.add(len_offset)).max(len_offset + 1);
+            slice::from_raw_parts(entry_point, len)
+        };
+        let parsed = crate::SmbiosEntryPoint::parse(entry_point)?;
+        let structure_table = unsafe {
+            slice::from_raw_parts(
+                parsed.structure_table_address() as usize as *const u8,
+                parsed.structure_table_max_size() as usize,
+            )
+        };
+        SmbiosTag::from_firmware_tables(entry_point, structure_table)
+    }
+
+    /// Returns the current memory map.
+    pub fn memory_map(&self) -> Result<UefiMemoryMap, Status> {
+        // SAFETY: Guaranteed by the caller of `new`.
+        let bs = unsafe { &*self.system_table.boot_services };
+        let mut size = 0;
+        let mut map_key = 0;
+        let mut desc_size = 0;
+        let mut desc_version = 0;
+        loop {
+            // Leave room for the descriptors of the allocation itself.
+            let mut buffer = vec![0_u64; (size + 2 * desc_size) / size_of::<u64>() + 1];
+            size = buffer.len() * size_of::<u64>();
+            let status = unsafe {
+                (bs.get_memory_map)(
+                    &mut size,
+                    buffer.as_mut_ptr().cast(),
+                    &mut map_key,
+                    &mut desc_size,
+                    &mut desc_version,
+                )
+            };
+            match status {
+                Status::SUCCESS => {
+                    return Ok(UefiMemoryMap {
+                        buffer,
+                        size,
+                        desc_size,
+                        desc_version,
+                        map_key,
+                    })
+                }
+                Status::BUFFER_TOO_SMALL => continue,
+                status => return Err(status),
+            }
+        }
+    }
+
+    /// Returns the [`FramebufferTag`] of the current GOP mode, if there is a
+    /// GOP with a linear framebuffer.
+    #[must_use]
+    pub fn framebuffer_tag(&self) -> Option<Box<FramebufferTag>> {
+        // SAFETY: Guaranteed by the caller of `new`.
+        let bs = unsafe { &*self.system_table.boot_services };
+        let mut gop = ptr::null_mut();
+        let status = unsafe {
+            (bs.locate_protocol)(&GraphicsOutputProtocol::GUID, ptr::null_mut(), &mut gop)
+        };
+        if status != Status::SUCCESS || gop.is_null() {
+            return None;
+        }
+        let gop = unsafe { &*gop.cast::<GraphicsOutputProtocol>() };
+        let mode = unsafe { gop.mode.as_ref()? };
+        let info = unsafe { mode.info.as_ref()? };
+        framebuffer_tag(mode.frame_buffer_base, info)
+    }
+
+    /// Adds all collected tags to the builder.
+    ///
+    /// Returns the builder together with the memory map, whose key must be
+    /// passed to `ExitBootServices()`.
+    pub fn collect(&self, mut builder: Builder) -> Result<(Builder, UefiMemoryMap), Status> {
+        let st = ptr::addr_of!(*self.system_table) as usize;
+        builder = if cfg!(target_pointer_width = "64") {
+            builder.efi64(EFISdt64Tag::new(st as u64))
+        } else {
+            builder.efi32(EFISdt32Tag::new(st as u32))
+        };
+        if let Some(rsdp) = self.rsdp_v1() {
+            builder = builder.add_raw_tag(TagType::AcpiV1, rsdp);
+        }
+        if let Some(rsdp) = self.rsdp_v2() {
+            builder = builder.add_raw_tag(TagType::AcpiV2, rsdp);
+        }
+        if let Some(smbios) = self.smbios_tag() {
+            builder = builder.add_smbios(smbios);
+        }
+        if let Some(framebuffer) = self.framebuffer_tag() {
+            builder = builder.framebuffer(framebuffer);
+        }
+        // Last, as the allocations above change the memory map.
+        let mmap = self.memory_map()?;
+        builder = builder
+            .mmap(mmap.to_mmap_tag())
+            .efi_mmap(mmap.to_efi_mmap_tag());
+        Ok((builder, mmap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EFIBootServicesNotExitedTag;
+    use core::ffi::c_void;
+    use uefi_raw::protocol::console::PixelBitmask;
+
+    #[test]
+    fn test_configuration_table() {
+        #[rustfmt::skip]
+        let rsdp: [u8; 20] = [
+            b'R', b'S', b'D', b' ', b'P', b'T', b'R', b' ',
+            0x42,
+            b'B', b'O', b'C', b'H', b'S', b' ',
+            0,
+            0x00, 0x10, 0xfe, 0x07,
+        ];
+        let mut tables = [ConfigurationTable {
+            vendor_guid: ACPI_GUID,
+            vendor_table: rsdp.as_ptr().cast_mut().cast::<c_void>(),
+        }];
+        let st = SystemTable {
+            number_of_configuration_table_entries: tables.len(),
+            configuration_table: tables.as_mut_ptr(),
+            ..Default::default()
+        };
+        let collector = unsafe { UefiCollector::new(&st) };
+        assert_eq!(collector.rsdp_v1(), Some(&rsdp[..]));
+        assert_eq!(collector.rsdp_v2(), None);
+        assert!(collector.smbios_tag().is_none());
+    }
+
+    #[test]
+    fn test_memory_map_unaligned_descriptors() {
+        // A descriptor size that isn't a multiple of eight, so that every
+        // second descriptor is misaligned.
+        let desc_size = size_of::<EFIMemoryDesc>() + 4;
+        let descs = [
+            EFIMemoryDesc {
+                ty: EFIMemoryAreaType::CONVENTIONAL,
+                phys_start: 0x1000,
+                page_count: 1,
+                ..Default::default()
+            },
+            EFIMemoryDesc {
+                ty: EFIMemoryAreaType::LOADER_DATA,
+                phys_start: 0x10_0000,
+                page_count: 16,
+                ..Default::default()
+            },
+        ];
+        let mut buffer = vec![0_u64; 2 * desc_size / size_of::<u64>()];
+        let bytes =
+            unsafe { slice::from_raw_parts_mut(buffer.as_mut_ptr().cast::<u8>(), 2 * desc_size) };
+        for (chunk, desc) in bytes.chunks_exact_mut(desc_size).zip(&descs) {
+            unsafe { ptr::write_unaligned(chunk.as_mut_ptr().cast(), *desc) };
+        }
+        let mmap = UefiMemoryMap {
+            buffer,
+            size: 2 * desc_size,
+            desc_size,
+            desc_version: EFIMemoryDesc::VERSION,
+            map_key: 0,
+        };
+
+        assert!(mmap.descriptors().eq(descs));
+        assert_eq!(
+            mmap.to_mmap_tag().memory_areas(),
+            [
+                MemoryArea::new(0x1000, 0x1000, MemoryAreaType::Available),
+                MemoryArea::new(0x10_0000, 0x1_0000, MemoryAreaType::Reserved),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_uefi_system_table() {
+        let mut boot_services = core::mem::MaybeUninit::<BootServices>::zeroed();
+        let st = SystemTable {
+            boot_services: boot_services.as_mut_ptr(),
+            ..Default::default()
+        };
+        let address = ptr::addr_of!(st) as u64;
+
+        let mbi = Builder::new().efi64(EFISdt64Tag::new(address)).build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        let view = unsafe { mbi.uefi_system_table() }.unwrap();
+        assert!(ptr::eq(view.as_raw(), &st));
+        assert_eq!(view.as_ptr(), ptr::addr_of!(st));
+        assert!(!view.boot_services_active());
+        assert!(view.boot_services().is_none());
+        assert!(view.runtime_services().is_none());
+        assert!(view.configuration_table().is_empty());
+
+        let mbi = Builder::new()
+            .efi64(EFISdt64Tag::new(address))
+            .efi_bs(EFIBootServicesNotExitedTag::new())
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        let view = unsafe { mbi.uefi_system_table() }.unwrap();
+        assert!(view.boot_services_active());
+        assert!(ptr::eq(
+            view.boot_services().unwrap(),
+            boot_services.as_ptr()
+        ));
+
+        // Invalid signature.
+        let mut bad = SystemTable::default();
+        bad.header.signature = 0;
+        let mbi = Builder::new()
+            .efi64(EFISdt64Tag::new(ptr::addr_of!(bad) as u64))
+            .build();
+        let mbi = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+        assert!(unsafe { mbi.uefi_system_table() }.is_none());
+    }
+
+    #[test]
+    fn test_framebuffer_tag() {
+        let mut info = GraphicsOutputModeInformation {
+            version: 0,
+            horizontal_resolution: 1024,
+            vertical_resolution: 768,
+            pixel_format: GraphicsPixelFormat::PIXEL_BLUE_GREEN_RED_RESERVED_8_BIT_PER_COLOR,
+            pixel_information: PixelBitmask::default(),
+            pixels_per_scan_line: 1280,
+        };
+        let tag = framebuffer_tag(0x8000_0000, &info).unwrap();
+        assert_eq!(tag.address(), 0x8000_0000);
+        assert_eq!(tag.pitch(), 1280 * 4);
+        assert_eq!(tag.width(), 1024);
+        assert_eq!(tag.bpp(), 32);
+        assert_eq!(
+            tag.buffer_type(),
+            Ok(FramebufferType::RGB {
+                red: FramebufferField {
+                    position: 16,
+                    size: 8
+                },
+                green: FramebufferField {
+                    position: 8,
+                    size: 8
+                },
+                blue: FramebufferField {
+                    position: 0,
+                    size: 8
+                },
+            })
+        );
+
+        info.pixel_format = GraphicsPixelFormat::PIXEL_BIT_MASK;
+        info.pixel_information = PixelBitmask {
+            red: 0xf800,
+            green: 0x07e0,
+            blue: 0x001f,
+            reserved: 0,
+        };
+        let tag = framebuffer_tag(0x8000_0000, &info).unwrap();
+        assert_eq!(tag.bpp(), 16);
+        assert_eq!(tag.pitch(), 1280 * 2);
+
+        info.pixel_format = GraphicsPixelFormat::PIXEL_BLT_ONLY;
+        assert!(framebuffer_tag(0x8000_0000, &info).is_none());
+    }
+
+    #[test]
+    fn test_efi_to_mmap_type() {
+        assert_eq!(
+            efi_to_mmap_type(EFIMemoryAreaType::BOOT_SERVICES_DATA),
+            MemoryAreaType::Available
+        );
+        assert_eq!(
+            efi_to_mmap_type(EFIMemoryAreaType::ACPI_RECLAIM),
+            MemoryAreaType::AcpiAvailable
+        );
+        assert_eq!(
+            efi_to_mmap_type(EFIMemoryAreaType::RUNTIME_SERVICES_DATA),
+            MemoryAreaType::Reserved
+        );
+    }
+}
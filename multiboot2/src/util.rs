@@ -3,58 +3,176 @@
 use core::fmt;
 use core::fmt::{Display, Formatter};
 use core::str::Utf8Error;
+use core::{mem, ptr};
 
 /// Error type describing failures when parsing the string from a tag.
+///
+/// The variants allow a kernel to log what exactly is wrong and to decide
+/// whether a lossy display of the string is still useful.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum StringError {
     /// There is no terminating NUL character, although the specification
     /// requires one.
     MissingNul(core::ffi::FromBytesUntilNulError),
-    /// The sequence until the first NUL character is not valid UTF-8.
+    /// The sequence until the first NUL character is not valid UTF-8. See
+    /// [`Utf8Error::valid_up_to`] for the position of the first invalid byte.
     Utf8(Utf8Error),
+    /// The tag is too small to contain a string, not even the terminating
+    /// NUL character.
+    Truncated,
+}
+
+impl StringError {
+    /// Returns the number of leading bytes that form valid UTF-8 if the
+    /// error is [`StringError::Utf8`].
+    #[must_use]
+    pub const fn valid_up_to(&self) -> Option<usize> {
+        match self {
+            Self::Utf8(e) => Some(e.valid_up_to()),
+            _ => None,
+        }
+    }
 }
 
 impl Display for StringError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::MissingNul(_) => write!(f, "string misses the terminating NUL character"),
+            Self::Utf8(e) => write!(
+                f,
+                "string is not valid UTF-8 starting at byte {}",
+                e.valid_up_to()
+            ),
+            Self::Truncated => write!(f, "tag is too small to contain a string"),
+        }
     }
 }
 
-#[cfg(feature = "unstable")]
 impl core::error::Error for StringError {
     fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self {
             Self::MissingNul(e) => Some(e),
             Self::Utf8(e) => Some(e),
+            Self::Truncated => None,
         }
     }
 }
 
 /// Parses the provided byte sequence as Multiboot string, which maps to a
 /// [`str`].
+///
+/// An empty byte sequence is reported as [`StringError::Truncated`].
 pub fn parse_slice_as_string(bytes: &[u8]) -> Result<&str, StringError> {
+    if bytes.is_empty() {
+        return Err(StringError::Truncated);
+    }
     let cstr = core::ffi::CStr::from_bytes_until_nul(bytes).map_err(StringError::MissingNul)?;
     cstr.to_str().map_err(StringError::Utf8)
 }
 
+/// Lenient variant of [`parse_slice_as_string`].
+///
+/// Treats the whole byte sequence as the string if the terminating NUL
+/// character is missing, as some bootloaders don't emit one. Hence, this never
+/// returns [`StringError::MissingNul`].
+pub fn parse_slice_as_string_lenient(bytes: &[u8]) -> Result<&str, StringError> {
+    core::str::from_utf8(string_bytes(bytes)).map_err(StringError::Utf8)
+}
+
+/// Returns the bytes of a Multiboot string up to, but excluding, the first NUL
+/// character, or all bytes if there is none. This backs the `_bytes()`
+/// accessors of the tags.
+pub fn string_bytes(bytes: &[u8]) -> &[u8] {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    &bytes[..len]
+}
+
+/// Parses a fixed-size string field without a terminating NUL character, such
+/// as the OEM ID of the RSDP.
+pub const fn parse_fixed_string(bytes: &[u8]) -> Result<&str, StringError> {
+    match core::str::from_utf8(bytes) {
+        Ok(string) => Ok(string),
+        Err(e) => Err(StringError::Utf8(e)),
+    }
+}
+
+/// Copies `len` bytes from `src` to `dst` in aligned 8-byte words instead of
+/// single bytes.
+///
+/// # Safety
+/// Both pointers must be 8-byte aligned and valid for `len` bytes, which must
+/// be a multiple of eight. The regions must not overlap.
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) unsafe fn copy_words(src: *const u8, dst: *mut u8, len: usize) {
+    debug_assert_eq!(len % mem::size_of::<u64>(), 0);
+    ptr::copy_nonoverlapping(
+        src.cast::<u64>(),
+        dst.cast::<u64>(),
+        len / mem::size_of::<u64>(),
+    );
+}
+
+/// Implements [`ToOwned`](alloc::borrow::ToOwned) and the comparison with
+/// borrowed tags for dynamically sized tags, which can't implement [`Clone`].
+#[cfg(feature = "builder")]
+macro_rules! impl_dst_owned {
+    ($($typ:ty),+) => {
+        $(
+            impl alloc::borrow::ToOwned for $typ {
+                type Owned = alloc::boxed::Box<Self>;
+
+                fn to_owned(&self) -> Self::Owned {
+                    multiboot2_common::clone_dyn(self)
+                }
+            }
+
+            impl PartialEq<&$typ> for alloc::boxed::Box<$typ> {
+                fn eq(&self, other: &&$typ) -> bool {
+                    **self == **other
+                }
+            }
+
+            impl PartialEq<alloc::boxed::Box<$typ>> for &$typ {
+                fn eq(&self, other: &alloc::boxed::Box<$typ>) -> bool {
+                    **self == **other
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "builder")]
+impl_dst_owned!(
+    crate::BootLoaderNameTag,
+    crate::CommandLineTag,
+    crate::EFIMemoryMapTag,
+    crate::FramebufferTag,
+    crate::GenericTag,
+    crate::MemoryMapTag,
+    crate::ModuleTag
+);
+#[cfg(all(feature = "builder", feature = "elf"))]
+impl_dst_owned!(crate::ElfSectionsTag);
+#[cfg(all(feature = "builder", feature = "net"))]
+impl_dst_owned!(crate::NetworkTag);
+#[cfg(all(feature = "builder", feature = "smbios"))]
+impl_dst_owned!(crate::SmbiosTag);
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::string::ToString;
 
     #[test]
     fn test_parse_slice_as_string() {
         // empty slice is invalid
-        assert!(matches!(
-            parse_slice_as_string(&[]),
-            Err(StringError::MissingNul(_))
-        ));
+        assert_eq!(parse_slice_as_string(&[]), Err(StringError::Truncated));
         // empty string is fine
         assert_eq!(parse_slice_as_string(&[0x00]), Ok(""));
-        // reject invalid utf8
-        assert!(matches!(
-            parse_slice_as_string(&[0xff, 0x00]),
-            Err(StringError::Utf8(_))
-        ));
+        // reject invalid utf8 and report the position
+        let err = parse_slice_as_string(b"ab\xff\x00").unwrap_err();
+        assert!(matches!(err, StringError::Utf8(_)));
+        assert_eq!(err.valid_up_to(), Some(2));
         // reject missing null
         assert!(matches!(
             parse_slice_as_string(b"hello"),
@@ -66,4 +184,29 @@ mod tests {
         // must skip everytihng after first null
         assert_eq!(parse_slice_as_string(b"hello\0foo"), Ok("hello"));
     }
+
+    #[test]
+    fn test_string_error_display() {
+        let err = parse_slice_as_string(b"ab\xff\x00").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "string is not valid UTF-8 starting at byte 2"
+        );
+        assert_eq!(
+            StringError::Truncated.to_string(),
+            "tag is too small to contain a string"
+        );
+        assert_eq!(StringError::Truncated.valid_up_to(), None);
+    }
+
+    #[test]
+    fn test_parse_slice_as_string_lenient() {
+        assert_eq!(parse_slice_as_string_lenient(&[]), Ok(""));
+        assert_eq!(parse_slice_as_string_lenient(b"hello"), Ok("hello"));
+        assert_eq!(parse_slice_as_string_lenient(b"hello\0foo"), Ok("hello"));
+        assert!(matches!(
+            parse_slice_as_string_lenient(&[b'a', 0xff]),
+            Err(StringError::Utf8(_))
+        ));
+    }
 }
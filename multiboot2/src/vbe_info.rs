@@ -1,6 +1,7 @@
 //! Module for [`VBEInfoTag`].
 
 use crate::{TagHeader, TagType};
+use bitflags::bitflags;
 use core::fmt;
 use core::mem;
 use multiboot2_common::{MaybeDynSized, Tag};
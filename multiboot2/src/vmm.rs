@@ -0,0 +1,310 @@
+//! Module for [`DirectBootPreset`].
+
+use crate::{
+    BasicMemoryInfoTag, BootInformationBuffer, BootLoaderNameTag, Builder, CommandLineTag,
+    FramebufferTag, MemoryArea, MemoryAreaType, MemoryMapTag, TagType,
+};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use derive_more::Display;
+
+/// Error when the bytes passed to [`DirectBootPreset::rsdp`] are not a valid
+/// RSDP.
+#[derive(Display, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RsdpError {
+    /// The bytes don't start with the `"RSD PTR "` signature.
+    #[display("missing RSDP signature")]
+    InvalidSignature,
+    /// The bytes are shorter than the RSDP of the given revision.
+    #[display("RSDP is truncated")]
+    Truncated,
+}
+
+impl core::error::Error for RsdpError {}
+
+/// Preset that assembles the boot information a virtual machine monitor (VMM)
+/// passes to a Multiboot2 kernel it boots directly, i.e., without firmware or
+/// bootloader in the guest.
+///
+/// The resulting boot information mirrors what QEMU's multiboot loader
+/// provides: the basic memory information, a memory map derived from the
+/// guest RAM size, the command line, the modules, and optionally the ACPI RSDP
+/// and a framebuffer.
+///
+/// # Example
+///
+/// ```rust
+/// use multiboot2::{BootInformation, DirectBootPreset};
+///
+/// let mbi = DirectBootPreset::new(512 * 1024 * 1024)
+///     .cmdline("console=ttyS0")
+///     .add_module(0x200000, 0x300000, "initrd")
+///     .build();
+/// let info = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+/// assert_eq!(info.command_line_tag().unwrap().cmdline(), Ok("console=ttyS0"));
+/// ```
+#[derive(Debug)]
+pub struct DirectBootPreset {
+    ram_size: u64,
+    low_ram_limit: u64,
+    cmdline: Option<String>,
+    bootloader_name: Option<String>,
+    modules: Vec<(u32, u32, String)>,
+    rsdp: Option<Vec<u8>>,
+    framebuffer: Option<Box<FramebufferTag>>,
+}
+
+impl DirectBootPreset {
+    /// End of the usable conventional memory below 1 MiB. The remainder up to
+    /// 640 KiB is the extended BIOS data area.
+    pub const LOW_MEMORY_END: u64 = 0x9fc00;
+
+    /// Start of the extended memory.
+    pub const HIGH_MEMORY_START: u64 = 0x100000;
+
+    /// Default end of the RAM below 4 GiB. RAM beyond that is mapped above
+    /// 4 GiB to leave room for the PCI hole, as QEMU does for its `pc`
+    /// machine.
+    pub const DEFAULT_LOW_RAM_LIMIT: u64 = 0xc000_0000;
+
+    /// Creates a new preset for a guest with the given RAM size in bytes.
+    ///
+    /// # Panics
+    /// Panics if the guest has less than 1 MiB of RAM.
+    #[must_use]
+    pub fn new(ram_size: u64) -> Self {
+        assert!(
+            ram_size >= Self::HIGH_MEMORY_START,
+            "guest must have at least 1 MiB of RAM"
+        );
+        Self {
+            ram_size,
+            low_ram_limit: Self::DEFAULT_LOW_RAM_LIMIT,
+            cmdline: None,
+            bootloader_name: None,
+            modules: Vec::new(),
+            rsdp: None,
+            framebuffer: None,
+        }
+    }
+
+    /// Sets the end of the RAM below 4 GiB. Defaults to
+    /// [`Self::DEFAULT_LOW_RAM_LIMIT`]. Limits below
+    /// [`Self::HIGH_MEMORY_START`] are raised to it.
+    #[must_use]
+    pub const fn low_ram_limit(mut self, limit: u64) -> Self {
+        self.low_ram_limit = if limit < Self::HIGH_MEMORY_START {
+            Self::HIGH_MEMORY_START
+        } else {
+            limit
+        };
+        self
+    }
+
+    /// Sets the kernel command line.
+    #[must_use]
+    pub fn cmdline(mut self, cmdline: &str) -> Self {
+        self.cmdline = Some(cmdline.into());
+        self
+    }
+
+    /// Sets the name of the bootloader, i.e., the VMM.
+    #[must_use]
+    pub fn bootloader_name(mut self, name: &str) -> Self {
+        self.bootloader_name = Some(name.into());
+        self
+    }
+
+    /// Adds a module that the VMM has placed at `start..end` in guest memory.
+    #[must_use]
+    pub fn add_module(mut self, start: u32, end: u32, cmdline: &str) -> Self {
+        self.modules.push((start, end, cmdline.into()));
+        self
+    }
+
+    /// Sets the ACPI RSDP the VMM has placed in guest memory. Depending on the
+    /// revision, an ACPI 1.0 or ACPI 2.0+ tag is created.
+    pub fn rsdp(mut self, rsdp: &[u8]) -> Result<Self, RsdpError> {
+        if !rsdp.starts_with(b"RSD PTR ") {
+            return Err(RsdpError::InvalidSignature);
+        }
+        let len = match rsdp.get(15) {
+            Some(0) => 20,
+            Some(_) => 36,
+            None => return Err(RsdpError::Truncated),
+        };
+        let rsdp = rsdp.get(..len).ok_or(RsdpError::Truncated)?;
+        self.rsdp = Some(rsdp.into());
+        Ok(self)
+    }
+
+    /// Sets the framebuffer of the guest.
+    #[must_use]
+    pub fn framebuffer(mut self, framebuffer: Box<FramebufferTag>) -> Self {
+        self.framebuffer = Some(framebuffer);
+        self
+    }
+
+    /// Returns the memory map of the guest.
+    #[must_use]
+    pub fn memory_areas(&self) -> Vec<MemoryArea> {
+        let low_end = self.ram_size.min(self.low_ram_limit);
+        let mut areas = Vec::from([
+            MemoryArea::new(0, Self::LOW_MEMORY_END, MemoryAreaType::Available),
+            MemoryArea::new(
+                Self::LOW_MEMORY_END,
+                0xa0000 - Self::LOW_MEMORY_END,
+                MemoryAreaType::Reserved,
+            ),
+            // System BIOS.
+            MemoryArea::new(0xf0000, 0x10000, MemoryAreaType::Reserved),
+            MemoryArea::new(
+                Self::HIGH_MEMORY_START,
+                low_end - Self::HIGH_MEMORY_START,
+                MemoryAreaType::Available,
+            ),
+        ]);
+        // The extended memory below 4 GiB is empty for a minimal low RAM limit.
+        areas.retain(|area| area.size() != 0);
+        if self.ram_size > low_end {
+            areas.push(MemoryArea::new(
+                0x1_0000_0000,
+                self.ram_size - low_end,
+                MemoryAreaType::Available,
+            ));
+        }
+        areas
+    }
+
+    /// Returns a [`Builder`] with all tags of the preset, which can be
+    /// extended by further tags.
+    #[must_use]
+    pub fn into_builder(self) -> Builder {
+        let low_end = self.ram_size.min(self.low_ram_limit);
+        let mem_upper = (low_end - Self::HIGH_MEMORY_START) / 1024;
+        let mut builder = Builder::new()
+            .meminfo(BasicMemoryInfoTag::new(
+                (Self::LOW_MEMORY_END / 1024) as u32,
+                mem_upper.try_into().unwrap_or(u32::MAX),
+            ))
+            .mmap(MemoryMapTag::new(&self.memory_areas()));
+        if let Some(cmdline) = &self.cmdline {
+            builder = builder.cmdline(CommandLineTag::new(cmdline));
+        }
+        if let Some(name) = &self.bootloader_name {
+            builder = builder.bootloader(BootLoaderNameTag::new(name));
+        }
+        for (start, end, cmdline) in &self.modules {
+            builder = builder.add_module(*start, *end, cmdline);
+        }
+        if let Some(rsdp) = &self.rsdp {
+            let typ = if rsdp.len() == 20 {
+                TagType::AcpiV1
+            } else {
+                TagType::AcpiV2
+            };
+            builder = builder.add_raw_tag(typ, rsdp);
+        }
+        if let Some(framebuffer) = self.framebuffer {
+            builder = builder.framebuffer(framebuffer);
+        }
+        builder
+    }
+
+    /// Builds the boot information.
+    #[must_use]
+    pub fn build(self) -> BootInformationBuffer {
+        self.into_builder().build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BootInformation, FramebufferType};
+
+    #[test]
+    fn test_build() {
+        #[rustfmt::skip]
+        let rsdp = [
+            b'R', b'S', b'D', b' ', b'P', b'T', b'R', b' ',
+            0x42,
+            b'B', b'O', b'C', b'H', b'S', b' ',
+            0,
+            0x00, 0x10, 0xfe, 0x07,
+        ];
+        let mbi = DirectBootPreset::new(0x1_4000_0000)
+            .cmdline("console=ttyS0")
+            .bootloader_name("vmm")
+            .add_module(0x200000, 0x201000, "initrd")
+            .rsdp(&rsdp)
+            .unwrap()
+            .framebuffer(FramebufferTag::new(
+                0xfd000000,
+                4096,
+                1024,
+                768,
+                32,
+                FramebufferType::Text,
+            ))
+            .build();
+        let info = unsafe { BootInformation::load(mbi.as_ptr()) }.unwrap();
+
+        assert_eq!(
+            info.command_line_tag().unwrap().cmdline(),
+            Ok("console=ttyS0")
+        );
+        assert_eq!(info.boot_loader_name_tag().unwrap().name(), Ok("vmm"));
+        let module = info.module_tags().next().unwrap();
+        assert_eq!(module.start_address(), 0x200000);
+        assert_eq!(module.cmdline(), Ok("initrd"));
+        let meminfo = info.basic_memory_info_tag().unwrap();
+        assert_eq!(meminfo.memory_lower(), 639);
+        assert_eq!(meminfo.memory_upper(), (0xc000_0000 - 0x100000) / 1024);
+        let rsdp = info.rsdp_v1_tag().unwrap();
+        assert_eq!(rsdp.rsdt_address(), 0x07fe1000);
+        assert!(info.rsdp_v2_tag().is_none());
+        assert!(info.framebuffer_tag().is_some());
+
+        let areas = info.memory_map_tag().unwrap().memory_areas();
+        assert_eq!(areas.len(), 5);
+        assert_eq!(areas[3].end_address(), 0xc000_0000);
+        assert_eq!(areas[4].start_address(), 0x1_0000_0000);
+        assert_eq!(areas[4].size(), 0x8000_0000);
+    }
+
+    #[test]
+    fn test_memory_areas_small() {
+        let areas = DirectBootPreset::new(0x800_0000).memory_areas();
+        assert_eq!(areas.len(), 4);
+        assert_eq!(areas[3].end_address(), 0x800_0000);
+    }
+
+    #[test]
+    fn test_low_ram_limit_below_high_memory() {
+        let areas = DirectBootPreset::new(0x800_0000)
+            .low_ram_limit(0x1000)
+            .memory_areas();
+        assert_eq!(areas.len(), 4);
+        assert_eq!(areas[3].start_address(), 0x1_0000_0000);
+        assert_eq!(areas[3].size(), 0x800_0000 - 0x100000);
+    }
+
+    #[test]
+    fn test_invalid_rsdp() {
+        let preset = || DirectBootPreset::new(0x800_0000);
+        assert_eq!(
+            preset().rsdp(&[0; 20]).unwrap_err(),
+            RsdpError::InvalidSignature
+        );
+        assert_eq!(
+            preset().rsdp(b"RSD PTR ").unwrap_err(),
+            RsdpError::Truncated
+        );
+        assert_eq!(
+            preset().rsdp(b"RSD PTR \0\0\0\0\0\0\0\x02").unwrap_err(),
+            RsdpError::Truncated
+        );
+    }
+}
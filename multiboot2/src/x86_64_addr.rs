@@ -0,0 +1,138 @@
+//! Getters returning the address types of the [`x86_64`] crate.
+//!
+//! Addresses that are only 32 bits wide in the boot information are always
+//! valid physical addresses. Wider addresses are checked and `None` is
+//! returned if the bootloader reported a value that doesn't fit.
+
+#[cfg(feature = "elf")]
+use crate::ElfSection;
+use crate::{FramebufferTag, ImageLoadPhysAddrTag, MemoryArea, ModuleTag, RsdpV1Tag, RsdpV2Tag};
+use core::ops::Range;
+use x86_64::PhysAddr;
+#[cfg(feature = "elf")]
+use x86_64::VirtAddr;
+
+impl TryFrom<crate::PhysAddr> for PhysAddr {
+    type Error = x86_64::addr::PhysAddrNotValid;
+
+    /// Fails if the address has bits set above bit 51.
+    fn try_from(addr: crate::PhysAddr) -> Result<Self, Self::Error> {
+        Self::try_new(addr.as_u64())
+    }
+}
+
+impl FramebufferTag {
+    /// Like [`Self::address`], but as [`PhysAddr`].
+    ///
+    /// Returns `None` if the address has bits set above bit 51.
+    #[must_use]
+    pub fn phys_addr(&self) -> Option<PhysAddr> {
+        PhysAddr::try_new(self.address()).ok()
+    }
+}
+
+impl ModuleTag {
+    /// The physical memory occupied by the module, from
+    /// [`Self::start_address`] to [`Self::end_address`].
+    #[must_use]
+    pub fn phys_range(&self) -> Range<PhysAddr> {
+        PhysAddr::new(self.start_address().into())..PhysAddr::new(self.end_address().into())
+    }
+}
+
+impl RsdpV1Tag {
+    /// Like [`Self::rsdt_address`], but as [`PhysAddr`].
+    #[must_use]
+    pub const fn rsdt_phys_addr(&self) -> PhysAddr {
+        PhysAddr::new(self.rsdt_address() as u64)
+    }
+}
+
+impl RsdpV2Tag {
+    /// Like [`Self::xsdt_address`], but as [`PhysAddr`].
+    ///
+    /// Returns `None` if the address has bits set above bit 51.
+    #[must_use]
+    pub fn xsdt_phys_addr(&self) -> Option<PhysAddr> {
+        PhysAddr::try_new(self.xsdt_address() as u64).ok()
+    }
+}
+
+impl ImageLoadPhysAddrTag {
+    /// Like [`Self::load_base_addr`], but as [`PhysAddr`].
+    #[must_use]
+    pub fn load_base_phys_addr(&self) -> PhysAddr {
+        PhysAddr::new(self.load_base_addr().into())
+    }
+}
+
+impl MemoryArea {
+    /// The physical memory covered by the area, from [`Self::start_address`]
+    /// to [`Self::end_address`].
+    ///
+    /// Returns `None` if the area reaches beyond the 52-bit physical address
+    /// space.
+    #[must_use]
+    pub fn phys_range(&self) -> Option<Range<PhysAddr>> {
+        let start = PhysAddr::try_new(self.start_address()).ok()?;
+        let end = PhysAddr::try_new(self.end_address()).ok()?;
+        Some(start..end)
+    }
+}
+
+#[cfg(feature = "elf")]
+impl ElfSection<'_> {
+    /// The virtual memory covered by the section, from
+    /// [`Self::start_address`] to [`Self::end_address`].
+    ///
+    /// Returns `None` if one of the addresses is not canonical.
+    #[must_use]
+    pub fn virt_range(&self) -> Option<Range<VirtAddr>> {
+        let start = VirtAddr::try_new(self.start_address()).ok()?;
+        let end = VirtAddr::try_new(self.end_address()).ok()?;
+        Some(start..end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryAreaType;
+
+    #[test]
+    fn memory_area_phys_range() {
+        let area = MemoryArea::new(0x10_0000, 0x1000, MemoryAreaType::Available);
+        assert_eq!(
+            area.phys_range(),
+            Some(PhysAddr::new(0x10_0000)..PhysAddr::new(0x10_1000))
+        );
+
+        let area = MemoryArea::new(1 << 52, 0x1000, MemoryAreaType::Available);
+        assert_eq!(area.phys_range(), None);
+    }
+
+    #[test]
+    fn from_crate_phys_addr() {
+        assert_eq!(
+            PhysAddr::try_from(crate::PhysAddr::new(0x1000)).ok(),
+            Some(PhysAddr::new(0x1000))
+        );
+        assert!(PhysAddr::try_from(crate::PhysAddr::new(1 << 52)).is_err());
+    }
+
+    #[test]
+    fn image_load_base_phys_addr() {
+        let tag = ImageLoadPhysAddrTag::new(0x20_0000);
+        assert_eq!(tag.load_base_phys_addr(), PhysAddr::new(0x20_0000));
+    }
+
+    #[test]
+    #[cfg(feature = "builder")]
+    fn module_phys_range() {
+        let tag = ModuleTag::new(0x1000, 0x3000, "mod");
+        assert_eq!(
+            tag.phys_range(),
+            PhysAddr::new(0x1000)..PhysAddr::new(0x3000)
+        );
+    }
+}